@@ -1,8 +1,10 @@
-use crate::D3DResult;
+use crate::{D3DError, D3DResult};
 use std::{
     fmt,
     hash::{Hash, Hasher},
+    mem,
     ops::Deref,
+    panic::Location,
     ptr,
 };
 use winapi::{ctypes::c_void, um::unknwnbase::IUnknown, Interface};
@@ -15,6 +17,9 @@ impl<T> WeakPtr<T> {
         WeakPtr(ptr::null_mut())
     }
 
+    /// # Safety
+    ///
+    /// `raw` must be null or point to a live object of type `T`.
     pub unsafe fn from_raw(raw: *mut T) -> Self {
         WeakPtr(raw)
     }
@@ -41,12 +46,20 @@ impl<T> WeakPtr<T> {
 }
 
 impl<T: Interface> WeakPtr<T> {
+    /// # Safety
+    ///
+    /// The pointer must not be null and the object still alive.
     pub unsafe fn as_unknown(&self) -> &IUnknown {
         debug_assert!(!self.is_null());
         &*(self.0 as *mut IUnknown)
     }
 
-    // Cast creates a new WeakPtr requiring explicit destroy call.
+    /// Cast creates a new WeakPtr requiring explicit destroy call.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must not be null and the object still alive.
+    #[track_caller]
     pub unsafe fn cast<U>(&self) -> D3DResult<WeakPtr<U>>
     where
         U: Interface,
@@ -55,19 +68,26 @@ impl<T: Interface> WeakPtr<T> {
         let hr = self
             .as_unknown()
             .QueryInterface(&U::uuidof(), obj.mut_void());
+        crate::ledger::track_created(&obj);
         (obj, hr)
     }
 
-    // Destroying one instance of the WeakPtr will invalidate all
-    // copies and clones.
+    /// Destroying one instance of the WeakPtr will invalidate all
+    /// copies and clones.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must not be null and must own a reference, which is released.
+    #[track_caller]
     pub unsafe fn destroy(&self) {
+        crate::ledger::release(self.0 as usize, Location::caller());
         self.as_unknown().Release();
     }
 }
 
 impl<T> Clone for WeakPtr<T> {
     fn clone(&self) -> Self {
-        WeakPtr(self.0)
+        *self
     }
 }
 
@@ -105,6 +125,290 @@ impl<T> Hash for WeakPtr<T> {
     }
 }
 
+/// Interfaces which may be used from a thread other than the one that created them,
+/// as long as only one thread uses them at a time.
+///
+/// Marks `UniquePtr<T>` as `Send`. Pointers which can be copied or cloned are only
+/// `Send` for `ComSync` interfaces.
+///
+/// # Safety
+///
+/// The object must not have thread affinity.
+pub unsafe trait ComSend {}
+
+/// Free-threaded interfaces, which may be called from multiple threads concurrently.
+///
+/// Marks `WeakPtr<T>` and `ComPtr<T>` as `Send` and `Sync`. Interfaces which need external
+/// synchronisation, like command lists and allocators, are only `ComSend` and can be moved
+/// to another thread in a `UniquePtr`:
+///
+/// ```
+/// # #[cfg(not(windows))]
+/// # extern crate winapi_headers as winapi;
+/// use winapi::um::d3d12::{ID3D12CommandAllocator, ID3D12GraphicsCommandList};
+///
+/// fn assert_send_sync<T: Send + Sync>() {}
+/// assert_send_sync::<d3d12::Device>();
+/// assert_send_sync::<d3d12::CommandQueue>();
+/// assert_send_sync::<d3d12::Fence>();
+///
+/// fn assert_send<T: Send>() {}
+/// assert_send::<d3d12::UniquePtr<ID3D12GraphicsCommandList>>();
+/// assert_send::<d3d12::UniquePtr<ID3D12CommandAllocator>>();
+/// ```
+///
+/// ```compile_fail,E0277
+/// fn assert_send<T: Send>() {}
+/// assert_send::<d3d12::GraphicsCommandList>();
+/// ```
+///
+/// ```compile_fail,E0277
+/// # #[cfg(not(windows))]
+/// # extern crate winapi_headers as winapi;
+/// fn assert_send<T: Send>() {}
+/// assert_send::<d3d12::ComPtr<winapi::um::d3d12::ID3D12CommandAllocator>>();
+/// ```
+///
+/// ```compile_fail,E0277
+/// # #[cfg(not(windows))]
+/// # extern crate winapi_headers as winapi;
+/// fn assert_sync<T: Sync>() {}
+/// assert_sync::<d3d12::UniquePtr<winapi::um::d3d12::ID3D12GraphicsCommandList>>();
+/// ```
+///
+/// ```compile_fail,E0277
+/// # #[cfg(not(windows))]
+/// # extern crate winapi_headers as winapi;
+/// fn assert_send<T: Send>() {}
+/// assert_send::<d3d12::WeakPtr<winapi::um::unknwnbase::IUnknown>>();
+/// ```
+///
+/// # Safety
+///
+/// All methods of the interface must be safe to call concurrently.
+pub unsafe trait ComSync: ComSend {}
+
+unsafe impl<T: ComSync> Send for WeakPtr<T> {}
+unsafe impl<T: ComSync> Sync for WeakPtr<T> {}
+
+/// Owning, reference counted COM pointer.
+///
+/// Cloning calls `AddRef` and dropping calls `Release`, so unlike `WeakPtr`
+/// every instance is responsible for exactly one reference. It dereferences to
+/// the interface, the wrapper methods are available through `as_weak`.
+///
+/// Constructors hand out owning pointers through `IntoComPtr`:
+///
+/// ```no_run
+/// use d3d12::IntoComPtr;
+///
+/// # fn run(device: d3d12::Device) -> Result<(), d3d12::D3DError> {
+/// // Nothing else references the new fence.
+/// let fence = unsafe { device.try_create_fence(0).into_com_ptr() }?;
+/// let value = unsafe { fence.GetCompletedValue() };
+/// fence.as_weak().signal(value + 1);
+/// // Released here.
+/// # Ok(())
+/// # }
+/// ```
+///
+/// The pointer is null if the constructor failed, dereferencing it then panics.
+#[repr(transparent)]
+pub struct ComPtr<T: Interface>(WeakPtr<T>);
+
+impl<T: Interface> ComPtr<T> {
+    /// Takes ownership of one reference held by `weak`.
+    ///
+    /// # Safety
+    ///
+    /// `weak` (and all of its copies) must not be destroyed afterwards.
+    pub unsafe fn from_weak(weak: WeakPtr<T>) -> Self {
+        ComPtr(weak)
+    }
+
+    /// Takes ownership of the object returned by a `D3DResult` constructor.
+    ///
+    /// # Safety
+    ///
+    /// See `from_weak`, the reference must not be released through another copy.
+    pub unsafe fn from_result((weak, hr): D3DResult<WeakPtr<T>>) -> D3DResult<Self> {
+        (ComPtr(weak), hr)
+    }
+
+    /// Adds a new reference for `weak` without taking over the existing one.
+    ///
+    /// # Safety
+    ///
+    /// `weak` must be null or point to a live COM object.
+    #[track_caller]
+    pub unsafe fn from_weak_add_ref(weak: WeakPtr<T>) -> Self {
+        if !weak.is_null() {
+            weak.as_unknown().AddRef();
+            crate::ledger::track_created(&weak);
+        }
+        ComPtr(weak)
+    }
+
+    /// Gives up ownership, the returned `WeakPtr` requires an explicit destroy call.
+    pub fn into_weak(self) -> WeakPtr<T> {
+        let weak = self.0;
+        mem::forget(self);
+        weak
+    }
+
+    /// Borrows the underlying pointer without touching the reference count.
+    pub fn as_weak(&self) -> WeakPtr<T> {
+        self.0
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.0.is_null()
+    }
+
+    pub fn as_ptr(&self) -> *const T {
+        self.0.as_ptr()
+    }
+
+    pub fn as_mut_ptr(&self) -> *mut T {
+        self.0.as_mut_ptr()
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the pointer is null.
+    #[track_caller]
+    pub fn cast<U: Interface>(&self) -> D3DResult<ComPtr<U>> {
+        assert!(!self.is_null(), "cast of a null ComPtr");
+        let (obj, hr) = unsafe { self.0.cast::<U>() };
+        (ComPtr(obj), hr)
+    }
+}
+
+impl<T: Interface> Clone for ComPtr<T> {
+    #[track_caller]
+    fn clone(&self) -> Self {
+        unsafe { Self::from_weak_add_ref(self.0) }
+    }
+}
+
+impl<T: Interface> Drop for ComPtr<T> {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            unsafe { self.0.destroy() };
+        }
+    }
+}
+
+// Dereferencing to `WeakPtr` would make `destroy` reachable, releasing the reference
+// a second time on drop.
+impl<T: Interface> Deref for ComPtr<T> {
+    type Target = T;
+    #[track_caller]
+    fn deref(&self) -> &T {
+        assert!(!self.is_null(), "dereferenced a null ComPtr");
+        &self.0
+    }
+}
+
+/// Conversion of constructor results into owning pointers.
+pub trait IntoComPtr {
+    type Owned;
+
+    /// Hands the reference created by a constructor over to a `ComPtr`.
+    ///
+    /// # Safety
+    ///
+    /// See `ComPtr::from_result`.
+    unsafe fn into_com_ptr(self) -> Self::Owned;
+}
+
+impl<T: Interface> IntoComPtr for D3DResult<WeakPtr<T>> {
+    type Owned = D3DResult<ComPtr<T>>;
+
+    unsafe fn into_com_ptr(self) -> Self::Owned {
+        ComPtr::from_result(self)
+    }
+}
+
+impl<T: Interface> IntoComPtr for Result<WeakPtr<T>, D3DError> {
+    type Owned = Result<ComPtr<T>, D3DError>;
+
+    unsafe fn into_com_ptr(self) -> Self::Owned {
+        self.map(|weak| ComPtr(weak))
+    }
+}
+
+impl<T: Interface> From<ComPtr<T>> for WeakPtr<T> {
+    fn from(ptr: ComPtr<T>) -> Self {
+        ptr.into_weak()
+    }
+}
+
+impl<T: Interface> fmt::Debug for ComPtr<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ComPtr( ptr: {:?} )", self.0.as_ptr())
+    }
+}
+
+impl<T: Interface> PartialEq for ComPtr<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: Interface> Eq for ComPtr<T> {}
+
+impl<T: Interface> Hash for ComPtr<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+/// Only reference to an object, which can't be copied or cloned.
+///
+/// Objects of `ComSend` interfaces, like command lists, are handed to another thread in
+/// this and used there through the `ComPtr` returned by `into_inner`.
+///
+/// ```no_run
+/// # #[cfg(not(windows))]
+/// # extern crate winapi_headers as winapi;
+/// use d3d12::{ComPtr, UniquePtr};
+/// use winapi::um::d3d12::ID3D12GraphicsCommandList;
+///
+/// # fn run(list: ComPtr<ID3D12GraphicsCommandList>) {
+/// // `list` hasn't been copied or cloned.
+/// let list = unsafe { UniquePtr::new(list) };
+/// std::thread::spawn(move || {
+///     let list = list.into_inner();
+///     list.as_weak().close();
+/// });
+/// # }
+/// ```
+pub struct UniquePtr<T: Interface>(ComPtr<T>);
+
+impl<T: Interface> UniquePtr<T> {
+    /// # Safety
+    ///
+    /// No other pointer to the object may be used as long as the `UniquePtr` exists, in
+    /// particular no copies or clones of `ptr`.
+    pub unsafe fn new(ptr: ComPtr<T>) -> Self {
+        UniquePtr(ptr)
+    }
+
+    /// Gives up uniqueness on the current thread.
+    pub fn into_inner(self) -> ComPtr<T> {
+        self.0
+    }
+}
+
+impl<T: Interface> fmt::Debug for UniquePtr<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "UniquePtr( ptr: {:?} )", self.0.as_ptr())
+    }
+}
+
+unsafe impl<T: Interface + ComSend> Send for UniquePtr<T> {}
+
 /// Macro that allows generation of an easy to use enum for dealing with many different possible versions of a COM object.
 ///
 /// Give the variants so that parents come before children. This often manifests as going up in order (1 -> 2 -> 3). This is vital for safety.
@@ -114,7 +418,12 @@ impl<T> Hash for WeakPtr<T> {
 /// - the as function (`&self -> Option<WeakPtr<actual::ComObject1>>`)
 /// - the unwrap function (`&self -> WeakPtr<actual::ComObject1>` panicing on failure to cast)
 ///
+/// Additionally `probe` constructs the enum from the first interface, querying for the most derived
+/// interface available.
+///
 /// ```rust
+/// # #[cfg(not(windows))]
+/// # extern crate winapi_headers as winapi;
 /// # pub use d3d12::weak_com_inheritance_chain;
 /// # mod actual { 
 /// #     pub struct ComObject; impl winapi::Interface for ComObject { fn uuidof() -> winapi::shared::guiddef::GUID { todo!() } } 
@@ -151,6 +460,10 @@ macro_rules! weak_com_inheritance_chain {
             ),+
         }
         impl $name {
+            /// # Safety
+            ///
+            /// See `WeakPtr::destroy`.
+            #[track_caller]
             $vis unsafe fn destroy(&self) {
                 match *self {
                     Self::$first_variant(v) => v.destroy(),
@@ -160,6 +473,30 @@ macro_rules! weak_com_inheritance_chain {
                 }
             }
 
+            /// Takes ownership of `value` and upgrades it to the newest interface in the chain
+            /// which the object supports. References to intermediate interfaces are released.
+            /// Null pointers, e.g. from failed creation calls, are returned as the first variant.
+            ///
+            /// # Safety
+            ///
+            /// `value` must be null or a valid interface pointer.
+            #[track_caller]
+            $vis unsafe fn probe(value: $crate::WeakPtr<$first_type>) -> Self {
+                let mut probed = Self::$first_variant(value);
+                if value.is_null() {
+                    return probed;
+                }
+                $(
+                    let (next, hr) = probed.$first_unwrap_name().cast::<$type>();
+                    if hr < 0 {
+                        return probed;
+                    }
+                    probed.destroy();
+                    probed = Self::$variant(next);
+                )*
+                probed
+            }
+
             $crate::weak_com_inheritance_chain! {
                 @recursion_logic,
                 $vis,
@@ -222,7 +559,11 @@ macro_rules! weak_com_inheritance_chain {
         $variant:ident($type:ty);
         $($next_variant:ident),*;
     ) => {
-        // Construct this enum from weak pointer to this interface. For best usability, always use the highest constructor you can. This doesn't try to upcast.
+        /// Construct this enum from weak pointer to this interface. For best usability, always use the highest constructor you can. This doesn't try to upcast.
+        ///
+        /// # Safety
+        ///
+        /// `value` must point to an object implementing the interface.
         $vis unsafe fn $from_name(value: $crate::WeakPtr<$type>) -> Self {
             Self::$variant(value)
         }
@@ -237,7 +578,7 @@ macro_rules! weak_com_inheritance_chain {
                 $(
                     Self::$next_variant(ref v) => {
                         // v is &WeakPtr<NextType> and se cast to &WeakPtr<Type>
-                        Some(unsafe { std::mem::transmute(v) })
+                        Some(unsafe { &*(v as *const _ as *const $crate::WeakPtr<$type>) })
                     }
                 )*
             }
@@ -254,10 +595,99 @@ macro_rules! weak_com_inheritance_chain {
                 $(
                     Self::$next_variant(ref v) => {
                         // v is &WeakPtr<NextType> and se cast to &WeakPtr<Type>
-                        unsafe { std::mem::transmute(v) }
+                        unsafe { &*(v as *const _ as *const $crate::WeakPtr<$type>) }
                     }
                 )*
             }
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::com_object::{unimplemented_vtable, ComObject};
+    use winapi::{
+        shared::winerror::{E_OUTOFMEMORY, S_OK},
+        um::d3d12::{ID3D12Fence, ID3D12FenceVtbl},
+    };
+
+    type FenceObject = ComObject<ID3D12FenceVtbl, ()>;
+
+    unsafe extern "system" fn completed_value(_: *mut ID3D12Fence) -> u64 {
+        7
+    }
+
+    // Keeps one extra reference, so the count can still be read after the pointers are gone.
+    fn fence() -> WeakPtr<ID3D12Fence> {
+        unsafe {
+            let mut vtbl: ID3D12FenceVtbl = unimplemented_vtable();
+            vtbl.GetCompletedValue = completed_value;
+            let fence = FenceObject::create::<ID3D12Fence>(vtbl, &[], ());
+            fence.as_unknown().AddRef();
+            fence
+        }
+    }
+
+    fn ref_count(fence: WeakPtr<ID3D12Fence>) -> u32 {
+        unsafe { FenceObject::from_interface(fence.as_mut_ptr()) }.ref_count()
+    }
+
+    #[test]
+    fn owned_constructor_result() {
+        let fence = fence();
+        let (owned, hr) = unsafe { (fence, S_OK).into_com_ptr() };
+        assert_eq!(hr, S_OK);
+        assert_eq!(unsafe { owned.GetCompletedValue() }, 7);
+        assert_eq!(ref_count(fence), 2);
+        drop(owned);
+        assert_eq!(ref_count(fence), 1);
+        unsafe { fence.destroy() };
+
+        let failed: Result<WeakPtr<ID3D12Fence>, D3DError> =
+            Err(D3DError::new("ID3D12Device::CreateFence", E_OUTOFMEMORY));
+        let failed = unsafe { failed.into_com_ptr() };
+        assert_eq!(failed.unwrap_err().hresult(), E_OUTOFMEMORY);
+        let (null, _) = unsafe { (WeakPtr::<ID3D12Fence>::null(), E_OUTOFMEMORY).into_com_ptr() };
+        assert!(null.is_null());
+    }
+
+    #[test]
+    fn clone_and_drop() {
+        let fence = fence();
+        let owned = unsafe { Ok(fence).into_com_ptr() }.unwrap();
+        let clone = owned.clone();
+        assert_eq!(clone, owned);
+        assert_eq!(ref_count(fence), 3);
+        drop(owned);
+        assert_eq!(ref_count(fence), 2);
+        drop(clone);
+        assert_eq!(ref_count(fence), 1);
+        unsafe { fence.destroy() };
+    }
+
+    #[test]
+    fn weak_conversions() {
+        let fence = fence();
+        let owned = unsafe { ComPtr::from_weak_add_ref(fence) };
+        assert_eq!(ref_count(fence), 3);
+        assert_eq!(owned.as_weak(), fence);
+
+        let weak = owned.into_weak();
+        assert_eq!(ref_count(fence), 3);
+        let owned = unsafe { ComPtr::from_weak(weak) };
+        drop(owned);
+        assert_eq!(ref_count(fence), 2);
+        unsafe {
+            fence.destroy();
+            fence.destroy();
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "dereferenced a null ComPtr")]
+    fn null_deref() {
+        let (null, _) = unsafe { (WeakPtr::<ID3D12Fence>::null(), E_OUTOFMEMORY).into_com_ptr() };
+        unsafe { null.GetCompletedValue() };
+    }
+}
@@ -0,0 +1,255 @@
+//! COM objects implemented in Rust
+//!
+//! Allows building stand-ins for D3D12/DXGI interfaces which can be wrapped in
+//! a `WeakPtr` and driven through the regular API, without a driver present.
+
+use crate::com::WeakPtr;
+use std::{
+    mem, process, ptr, slice,
+    sync::atomic::{AtomicU32, Ordering},
+};
+use winapi::{
+    ctypes::c_void,
+    shared::{
+        guiddef::{IsEqualGUID, GUID, REFIID},
+        minwindef::ULONG,
+        winerror::{E_NOINTERFACE, E_POINTER, HRESULT, S_OK},
+    },
+    um::unknwnbase::{IUnknown, IUnknownVtbl},
+    Interface,
+};
+
+/// Reference counted COM object with vtable `V` and user data `D`.
+///
+/// `QueryInterface`, `AddRef` and `Release` are provided, all other vtable
+/// entries are filled in by the user.
+#[repr(C)]
+pub struct ComObject<V, D> {
+    // Must stay the first field, interface pointers point at the object itself.
+    vtable: *const V,
+    ref_count: AtomicU32,
+    interfaces: Vec<GUID>,
+    _owned_vtable: Box<V>,
+    data: D,
+}
+
+impl<V, D> ComObject<V, D> {
+    /// Allocate a new object with a reference count of one.
+    ///
+    /// `QueryInterface` answers `IUnknown`, `I` and any of the additional `interfaces`,
+    /// which should list all parent interfaces of `I`.
+    ///
+    /// # Safety
+    ///
+    /// `V` must be the `#[repr(C)]` vtable of `I`, starting with an `IUnknownVtbl`.
+    pub unsafe fn create<I: Interface>(mut vtable: V, interfaces: &[GUID], data: D) -> WeakPtr<I> {
+        assert!(mem::size_of::<V>() >= mem::size_of::<IUnknownVtbl>());
+        let unknown = &mut *(&mut vtable as *mut V as *mut IUnknownVtbl);
+        unknown.QueryInterface = Self::query_interface;
+        unknown.AddRef = Self::add_ref;
+        unknown.Release = Self::release;
+
+        let mut ids = vec![IUnknown::uuidof(), I::uuidof()];
+        ids.extend_from_slice(interfaces);
+
+        let owned_vtable = Box::new(vtable);
+        let object = Box::new(ComObject {
+            vtable: &*owned_vtable as *const V,
+            ref_count: AtomicU32::new(1),
+            interfaces: ids,
+            _owned_vtable: owned_vtable,
+            data,
+        });
+
+        WeakPtr::from_raw(Box::into_raw(object) as *mut I)
+    }
+
+    /// Recover the object from an interface pointer passed into a vtable entry.
+    ///
+    /// # Safety
+    ///
+    /// `this` must have been created by `ComObject::<V, D>::create` and still be alive.
+    pub unsafe fn from_interface<'a, I>(this: *mut I) -> &'a Self {
+        debug_assert!(!this.is_null());
+        &*(this as *const Self)
+    }
+
+    pub fn data(&self) -> &D {
+        &self.data
+    }
+
+    pub fn ref_count(&self) -> u32 {
+        self.ref_count.load(Ordering::Acquire)
+    }
+
+    unsafe extern "system" fn query_interface(
+        this: *mut IUnknown,
+        riid: REFIID,
+        object: *mut *mut c_void,
+    ) -> HRESULT {
+        if object.is_null() || riid.is_null() {
+            return E_POINTER;
+        }
+
+        let com = Self::from_interface(this);
+        if com.interfaces.iter().any(|id| IsEqualGUID(id, &*riid)) {
+            com.ref_count.fetch_add(1, Ordering::Relaxed);
+            *object = this as *mut c_void;
+            S_OK
+        } else {
+            *object = ptr::null_mut();
+            E_NOINTERFACE
+        }
+    }
+
+    unsafe extern "system" fn add_ref(this: *mut IUnknown) -> ULONG {
+        Self::from_interface(this)
+            .ref_count
+            .fetch_add(1, Ordering::Relaxed)
+            + 1
+    }
+
+    unsafe extern "system" fn release(this: *mut IUnknown) -> ULONG {
+        let count = Self::from_interface(this)
+            .ref_count
+            .fetch_sub(1, Ordering::AcqRel)
+            - 1;
+        if count == 0 {
+            drop(Box::from_raw(this as *mut Self));
+        }
+        count
+    }
+}
+
+/// Vtable with every entry pointing at a stub which aborts the process.
+///
+/// Start from this and overwrite the methods the object actually supports.
+///
+/// # Safety
+///
+/// `V` must consist of function pointers only.
+pub unsafe fn unimplemented_vtable<V>() -> V {
+    assert_eq!(mem::size_of::<V>() % mem::size_of::<usize>(), 0);
+    let mut vtable = mem::MaybeUninit::<V>::uninit();
+    let slots = slice::from_raw_parts_mut(
+        vtable.as_mut_ptr() as *mut usize,
+        mem::size_of::<V>() / mem::size_of::<usize>(),
+    );
+    let stub = unimplemented_method as extern "system" fn() as usize;
+    for slot in slots {
+        *slot = stub;
+    }
+    vtable.assume_init()
+}
+
+// Never returns, so the mismatched signature of the actual vtable entry doesn't matter.
+extern "system" fn unimplemented_method() {
+    eprintln!("called an unimplemented method of a Rust COM object");
+    process::abort();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{atomic::AtomicBool, Arc};
+    use winapi::um::d3d12::{ID3D12Device, ID3D12Fence, ID3D12FenceVtbl, ID3D12Pageable};
+
+    // Sets the flag when the object is freed.
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    type FenceObject = ComObject<ID3D12FenceVtbl, DropFlag>;
+
+    unsafe extern "system" fn completed_value(_: *mut ID3D12Fence) -> u64 {
+        42
+    }
+
+    fn fence(dropped: &Arc<AtomicBool>) -> WeakPtr<ID3D12Fence> {
+        unsafe {
+            let mut vtbl: ID3D12FenceVtbl = unimplemented_vtable();
+            vtbl.GetCompletedValue = completed_value;
+            FenceObject::create::<ID3D12Fence>(
+                vtbl,
+                &[ID3D12Pageable::uuidof()],
+                DropFlag(dropped.clone()),
+            )
+        }
+    }
+
+    fn ref_count(fence: WeakPtr<ID3D12Fence>) -> u32 {
+        unsafe { FenceObject::from_interface(fence.as_mut_ptr()) }.ref_count()
+    }
+
+    #[test]
+    fn vtable_methods() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let fence = fence(&dropped);
+        assert_eq!(unsafe { fence.GetCompletedValue() }, 42);
+        unsafe { fence.destroy() };
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn add_ref_release() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let fence = fence(&dropped);
+        assert_eq!(ref_count(fence), 1);
+
+        let unknown = unsafe { fence.as_unknown() };
+        assert_eq!(unsafe { unknown.AddRef() }, 2);
+        assert_eq!(unsafe { unknown.AddRef() }, 3);
+        assert_eq!(unsafe { unknown.Release() }, 2);
+        assert_eq!(unsafe { unknown.Release() }, 1);
+        assert!(!dropped.load(Ordering::SeqCst));
+
+        assert_eq!(unsafe { unknown.Release() }, 0);
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn query_interface() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let fence = fence(&dropped);
+
+        // The object itself, its parents and IUnknown are answered with the same pointer.
+        let (same, hr) = unsafe { fence.cast::<ID3D12Fence>() };
+        assert_eq!(hr, S_OK);
+        assert_eq!(same.as_ptr(), fence.as_ptr());
+        let (pageable, hr) = unsafe { fence.cast::<ID3D12Pageable>() };
+        assert_eq!(hr, S_OK);
+        assert_eq!(pageable.as_ptr() as usize, fence.as_ptr() as usize);
+        let (unknown, hr) = unsafe { fence.cast::<IUnknown>() };
+        assert_eq!(hr, S_OK);
+        assert_eq!(ref_count(fence), 4);
+
+        let (device, hr) = unsafe { fence.cast::<ID3D12Device>() };
+        assert_eq!(hr, E_NOINTERFACE);
+        assert!(device.is_null());
+        assert_eq!(ref_count(fence), 4);
+
+        unsafe {
+            same.destroy();
+            pageable.destroy();
+            unknown.destroy();
+        }
+        assert_eq!(ref_count(fence), 1);
+        unsafe { fence.destroy() };
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn query_interface_null_pointers() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let fence = fence(&dropped);
+        let unknown = unsafe { fence.as_unknown() };
+        let hr = unsafe { unknown.QueryInterface(&ID3D12Fence::uuidof(), ptr::null_mut()) };
+        assert_eq!(hr, E_POINTER);
+        assert_eq!(ref_count(fence), 1);
+        unsafe { fence.destroy() };
+    }
+}
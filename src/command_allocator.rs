@@ -1,10 +1,12 @@
 //! Command Allocator
 
-use crate::com::WeakPtr;
+use crate::com::{ComSend, WeakPtr};
 use winapi::um::d3d12;
 
 pub type CommandAllocator = WeakPtr<d3d12::ID3D12CommandAllocator>;
 
+unsafe impl ComSend for d3d12::ID3D12CommandAllocator {}
+
 impl CommandAllocator {
     pub fn reset(&self) {
         unsafe {
@@ -1,9 +1,11 @@
 //! Graphics command list
 
 use crate::{
-    com::WeakPtr, resource::DiscardRegion, CommandAllocator, CpuDescriptor, DescriptorHeap, Format,
-    GpuAddress, GpuDescriptor, IndexCount, InstanceCount, PipelineState, Rect, Resource, RootIndex,
-    RootSignature, Subresource, VertexCount, VertexOffset, WorkGroupCount, HRESULT,
+    com::{ComSend, WeakPtr},
+    resource::DiscardRegion,
+    CommandAllocator, CpuDescriptor, DescriptorHeap, Format, GpuAddress, GpuDescriptor, IndexCount,
+    InstanceCount, PipelineState, Rect, Resource, RootIndex, RootSignature, Subresource,
+    VertexCount, VertexOffset, WorkGroupCount, HRESULT,
 };
 use std::{mem, ptr};
 use winapi::um::d3d12;
@@ -143,6 +145,9 @@ pub type CommandSignature = WeakPtr<d3d12::ID3D12CommandSignature>;
 pub type CommandList = WeakPtr<d3d12::ID3D12CommandList>;
 pub type GraphicsCommandList = WeakPtr<d3d12::ID3D12GraphicsCommandList>;
 
+unsafe impl ComSend for d3d12::ID3D12CommandList {}
+unsafe impl ComSend for d3d12::ID3D12GraphicsCommandList {}
+
 impl GraphicsCommandList {
     pub fn as_list(&self) -> CommandList {
         unsafe { CommandList::from_raw(self.as_mut_ptr() as *mut _) }
@@ -243,7 +248,7 @@ impl GraphicsCommandList {
         let ibv = d3d12::D3D12_INDEX_BUFFER_VIEW {
             BufferLocation: gpu_address,
             SizeInBytes: size,
-            Format: format,
+            Format: format as _,
         };
         unsafe {
             self.IASetIndexBuffer(&ibv);
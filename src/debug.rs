@@ -1,4 +1,6 @@
 use crate::com::WeakPtr;
+#[cfg(any(feature = "libloading", feature = "implicit-link"))]
+use crate::{D3DError, IntoResult};
 use winapi::um::d3d12sdklayers;
 #[cfg(any(feature = "libloading", feature = "implicit-link"))]
 use winapi::Interface as _;
@@ -7,6 +9,7 @@ pub type Debug = WeakPtr<d3d12sdklayers::ID3D12Debug>;
 
 #[cfg(feature = "libloading")]
 impl crate::D3D12Lib {
+    #[track_caller]
     pub fn get_debug_interface(&self) -> Result<crate::D3DResult<Debug>, libloading::Error> {
         type Fun = extern "system" fn(
             winapi::shared::guiddef::REFIID,
@@ -19,12 +22,20 @@ impl crate::D3D12Lib {
             func(&d3d12sdklayers::ID3D12Debug::uuidof(), debug.mut_void())
         };
 
+        crate::ledger::track_created(&debug);
         Ok((debug, hr))
     }
+
+    #[track_caller]
+    pub fn try_get_debug_interface(&self) -> Result<Result<Debug, D3DError>, libloading::Error> {
+        self.get_debug_interface()
+            .map(|result| result.into_result("D3D12GetDebugInterface"))
+    }
 }
 
 impl Debug {
     #[cfg(feature = "implicit-link")]
+    #[track_caller]
     pub fn get_interface() -> crate::D3DResult<Self> {
         let mut debug = Debug::null();
         let hr = unsafe {
@@ -34,9 +45,16 @@ impl Debug {
             )
         };
 
+        crate::ledger::track_created(&debug);
         (debug, hr)
     }
 
+    #[cfg(feature = "implicit-link")]
+    #[track_caller]
+    pub fn try_get_interface() -> Result<Self, D3DError> {
+        Self::get_interface().into_result("D3D12GetDebugInterface")
+    }
+
     pub fn enable_layer(&self) {
         unsafe { self.EnableDebugLayer() }
     }
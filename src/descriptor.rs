@@ -1,18 +1,21 @@
-use crate::{com::WeakPtr, Blob, D3DResult, Error, TextureAddressMode};
-use std::{fmt, mem, ops::Range};
-use winapi::{shared::dxgiformat, um::d3d12};
+use crate::{
+    com::{ComSend, ComSync, WeakPtr},
+    Blob, D3DResult, Device, Error, TextureAddressMode,
+};
+use std::{fmt, marker::PhantomData, mem, ops::Range};
+use winapi::um::d3d12;
 
 pub type CpuDescriptor = d3d12::D3D12_CPU_DESCRIPTOR_HANDLE;
 pub type GpuDescriptor = d3d12::D3D12_GPU_DESCRIPTOR_HANDLE;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Binding {
     pub space: u32,
     pub register: u32,
 }
 
 #[repr(u32)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum DescriptorHeapType {
     CbvSrvUav = d3d12::D3D12_DESCRIPTOR_HEAP_TYPE_CBV_SRV_UAV,
     Sampler = d3d12::D3D12_DESCRIPTOR_HEAP_TYPE_SAMPLER,
@@ -20,6 +23,17 @@ pub enum DescriptorHeapType {
     Dsv = d3d12::D3D12_DESCRIPTOR_HEAP_TYPE_DSV,
 }
 
+impl DescriptorHeapType {
+    fn from_raw(raw: d3d12::D3D12_DESCRIPTOR_HEAP_TYPE) -> Self {
+        match raw {
+            d3d12::D3D12_DESCRIPTOR_HEAP_TYPE_SAMPLER => DescriptorHeapType::Sampler,
+            d3d12::D3D12_DESCRIPTOR_HEAP_TYPE_RTV => DescriptorHeapType::Rtv,
+            d3d12::D3D12_DESCRIPTOR_HEAP_TYPE_DSV => DescriptorHeapType::Dsv,
+            _ => DescriptorHeapType::CbvSrvUav,
+        }
+    }
+}
+
 bitflags! {
     pub struct DescriptorHeapFlags: u32 {
         const SHADER_VISIBLE = d3d12::D3D12_DESCRIPTOR_HEAP_FLAG_SHADER_VISIBLE;
@@ -36,10 +50,125 @@ impl DescriptorHeap {
     pub fn start_gpu_descriptor(&self) -> GpuDescriptor {
         unsafe { self.GetGPUDescriptorHandleForHeapStart() }
     }
+
+    pub fn num_descriptors(&self) -> u32 {
+        unsafe { self.GetDesc() }.NumDescriptors
+    }
+
+    pub fn heap_type(&self) -> DescriptorHeapType {
+        DescriptorHeapType::from_raw(unsafe { self.GetDesc() }.Type)
+    }
+
+    pub fn flags(&self) -> DescriptorHeapFlags {
+        DescriptorHeapFlags::from_bits_truncate(unsafe { self.GetDesc() }.Flags)
+    }
+
+    /// Handle of the descriptor at `index`, `None` if it's out of bounds.
+    pub fn handle(&self, device: &Device, index: u32) -> Option<DescriptorHandle> {
+        self.start_handle(device).at(index)
+    }
+
+    pub fn start_handle(&self, device: &Device) -> DescriptorHandle {
+        let desc = unsafe { self.GetDesc() };
+        let heap_type = DescriptorHeapType::from_raw(desc.Type);
+        let shader_visible = desc.Flags & d3d12::D3D12_DESCRIPTOR_HEAP_FLAG_SHADER_VISIBLE != 0;
+        DescriptorHandle {
+            heap: *self,
+            heap_type,
+            cpu_start: self.start_cpu_descriptor().ptr,
+            gpu_start: if shader_visible {
+                self.start_gpu_descriptor().ptr
+            } else {
+                0
+            },
+            index: 0,
+            capacity: desc.NumDescriptors,
+            increment: device.get_descriptor_increment_size(heap_type),
+        }
+    }
+}
+
+/// Descriptor inside of a `DescriptorHeap`, which knows its position for offsetting.
+#[derive(Clone, Copy, Debug, PartialEq, Hash)]
+pub struct DescriptorHandle {
+    heap: DescriptorHeap,
+    heap_type: DescriptorHeapType,
+    cpu_start: usize,
+    // Zero for heaps which aren't shader visible.
+    gpu_start: u64,
+    index: u32,
+    capacity: u32,
+    increment: u32,
+}
+
+impl DescriptorHandle {
+    pub fn heap(&self) -> DescriptorHeap {
+        self.heap
+    }
+
+    pub fn heap_type(&self) -> DescriptorHeapType {
+        self.heap_type
+    }
+
+    /// Index in the heap.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Number of descriptors in the heap.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Number of descriptors from this one to the end of the heap.
+    pub fn remaining(&self) -> u32 {
+        self.capacity - self.index
+    }
+
+    pub fn increment(&self) -> u32 {
+        self.increment
+    }
+
+    pub fn is_shader_visible(&self) -> bool {
+        self.gpu_start != 0
+    }
+
+    pub fn cpu(&self) -> CpuDescriptor {
+        CpuDescriptor {
+            ptr: self.cpu_start + self.index as usize * self.increment as usize,
+        }
+    }
+
+    /// `None` if the heap isn't shader visible.
+    pub fn gpu(&self) -> Option<GpuDescriptor> {
+        if self.is_shader_visible() {
+            Some(GpuDescriptor {
+                ptr: self.gpu_start + self.index as u64 * self.increment as u64,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Handle `count` descriptors further, `None` if it's out of bounds.
+    pub fn offset(&self, count: u32) -> Option<Self> {
+        self.index
+            .checked_add(count)
+            .and_then(|index| self.at(index))
+    }
+
+    /// Handle of the descriptor at `index` in the same heap.
+    pub fn at(&self, index: u32) -> Option<Self> {
+        if index < self.capacity {
+            Some(DescriptorHandle { index, ..*self })
+        } else {
+            None
+        }
+    }
 }
 
 #[repr(u32)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum ShaderVisibility {
     All = d3d12::D3D12_SHADER_VISIBILITY_ALL,
     VS = d3d12::D3D12_SHADER_VISIBILITY_VERTEX,
@@ -47,10 +176,32 @@ pub enum ShaderVisibility {
     DS = d3d12::D3D12_SHADER_VISIBILITY_DOMAIN,
     GS = d3d12::D3D12_SHADER_VISIBILITY_GEOMETRY,
     PS = d3d12::D3D12_SHADER_VISIBILITY_PIXEL,
+    AS = D3D12_SHADER_VISIBILITY_AMPLIFICATION,
+    MS = D3D12_SHADER_VISIBILITY_MESH,
+}
+
+impl ShaderVisibility {
+    pub(crate) fn from_raw(raw: d3d12::D3D12_SHADER_VISIBILITY) -> Option<Self> {
+        Some(match raw {
+            d3d12::D3D12_SHADER_VISIBILITY_ALL => ShaderVisibility::All,
+            d3d12::D3D12_SHADER_VISIBILITY_VERTEX => ShaderVisibility::VS,
+            d3d12::D3D12_SHADER_VISIBILITY_HULL => ShaderVisibility::HS,
+            d3d12::D3D12_SHADER_VISIBILITY_DOMAIN => ShaderVisibility::DS,
+            d3d12::D3D12_SHADER_VISIBILITY_GEOMETRY => ShaderVisibility::GS,
+            d3d12::D3D12_SHADER_VISIBILITY_PIXEL => ShaderVisibility::PS,
+            D3D12_SHADER_VISIBILITY_AMPLIFICATION => ShaderVisibility::AS,
+            D3D12_SHADER_VISIBILITY_MESH => ShaderVisibility::MS,
+            _ => return None,
+        })
+    }
 }
 
+// Not yet part of winapi.
+const D3D12_SHADER_VISIBILITY_AMPLIFICATION: u32 = 6;
+const D3D12_SHADER_VISIBILITY_MESH: u32 = 7;
+
 #[repr(u32)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum DescriptorRangeType {
     SRV = d3d12::D3D12_DESCRIPTOR_RANGE_TYPE_SRV,
     UAV = d3d12::D3D12_DESCRIPTOR_RANGE_TYPE_UAV,
@@ -58,6 +209,18 @@ pub enum DescriptorRangeType {
     Sampler = d3d12::D3D12_DESCRIPTOR_RANGE_TYPE_SAMPLER,
 }
 
+impl DescriptorRangeType {
+    pub(crate) fn from_raw(raw: d3d12::D3D12_DESCRIPTOR_RANGE_TYPE) -> Option<Self> {
+        Some(match raw {
+            d3d12::D3D12_DESCRIPTOR_RANGE_TYPE_SRV => DescriptorRangeType::SRV,
+            d3d12::D3D12_DESCRIPTOR_RANGE_TYPE_UAV => DescriptorRangeType::UAV,
+            d3d12::D3D12_DESCRIPTOR_RANGE_TYPE_CBV => DescriptorRangeType::CBV,
+            d3d12::D3D12_DESCRIPTOR_RANGE_TYPE_SAMPLER => DescriptorRangeType::Sampler,
+            _ => return None,
+        })
+    }
+}
+
 #[repr(transparent)]
 pub struct DescriptorRange(d3d12::D3D12_DESCRIPTOR_RANGE);
 impl DescriptorRange {
@@ -85,11 +248,69 @@ impl fmt::Debug for DescriptorRange {
     }
 }
 
+bitflags! {
+    pub struct DescriptorRangeFlags: u32 {
+        const DESCRIPTORS_VOLATILE = d3d12::D3D12_DESCRIPTOR_RANGE_FLAG_DESCRIPTORS_VOLATILE;
+        const DATA_VOLATILE = d3d12::D3D12_DESCRIPTOR_RANGE_FLAG_DATA_VOLATILE;
+        const DATA_STATIC_WHILE_SET_AT_EXECUTE = d3d12::D3D12_DESCRIPTOR_RANGE_FLAG_DATA_STATIC_WHILE_SET_AT_EXECUTE;
+        const DATA_STATIC = d3d12::D3D12_DESCRIPTOR_RANGE_FLAG_DATA_STATIC;
+        const DESCRIPTORS_STATIC_KEEPING_BUFFER_BOUNDS_CHECKS = 0x10000;
+    }
+}
+
+/// Descriptor range of a version 1.1 root signature.
+#[repr(transparent)]
+pub struct DescriptorRange1(d3d12::D3D12_DESCRIPTOR_RANGE1);
+impl DescriptorRange1 {
+    pub fn new(
+        ty: DescriptorRangeType,
+        count: u32,
+        base_binding: Binding,
+        offset: u32,
+        flags: DescriptorRangeFlags,
+    ) -> Self {
+        DescriptorRange1(d3d12::D3D12_DESCRIPTOR_RANGE1 {
+            RangeType: ty as _,
+            NumDescriptors: count,
+            BaseShaderRegister: base_binding.register,
+            RegisterSpace: base_binding.space,
+            Flags: flags.bits(),
+            OffsetInDescriptorsFromTableStart: offset,
+        })
+    }
+}
+
+impl fmt::Debug for DescriptorRange1 {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("DescriptorRange1")
+            .field("range_type", &self.0.RangeType)
+            .field("num", &self.0.NumDescriptors)
+            .field("register_space", &self.0.RegisterSpace)
+            .field("base_register", &self.0.BaseShaderRegister)
+            .field(
+                "flags",
+                &DescriptorRangeFlags::from_bits_truncate(self.0.Flags),
+            )
+            .field("table_offset", &self.0.OffsetInDescriptorsFromTableStart)
+            .finish()
+    }
+}
+
 #[repr(transparent)]
 pub struct RootParameter(d3d12::D3D12_ROOT_PARAMETER);
 impl RootParameter {
-    // TODO: DescriptorRange must outlive Self
+    #[deprecated(
+        since = "0.5.0",
+        note = "the ranges must outlive the parameter without being borrowed by it, \
+                build a `RootSignatureDesc` instead"
+    )]
     pub fn descriptor_table(visibility: ShaderVisibility, ranges: &[DescriptorRange]) -> Self {
+        Self::borrowed_table(visibility, ranges)
+    }
+
+    // The caller keeps `ranges` alive for as long as the parameter is used.
+    pub(crate) fn borrowed_table(visibility: ShaderVisibility, ranges: &[DescriptorRange]) -> Self {
         let mut param = d3d12::D3D12_ROOT_PARAMETER {
             ParameterType: d3d12::D3D12_ROOT_PARAMETER_TYPE_DESCRIPTOR_TABLE,
             ShaderVisibility: visibility as _,
@@ -205,17 +426,192 @@ impl fmt::Debug for RootParameter {
     }
 }
 
+bitflags! {
+    pub struct RootDescriptorFlags: u32 {
+        const DATA_VOLATILE = d3d12::D3D12_ROOT_DESCRIPTOR_FLAG_DATA_VOLATILE;
+        const DATA_STATIC_WHILE_SET_AT_EXECUTE = d3d12::D3D12_ROOT_DESCRIPTOR_FLAG_DATA_STATIC_WHILE_SET_AT_EXECUTE;
+        const DATA_STATIC = d3d12::D3D12_ROOT_DESCRIPTOR_FLAG_DATA_STATIC;
+    }
+}
+
+/// Root parameter of a version 1.1 root signature, borrowing the ranges of a descriptor table.
+#[repr(transparent)]
+pub struct RootParameter1<'a>(
+    d3d12::D3D12_ROOT_PARAMETER1,
+    PhantomData<&'a [DescriptorRange1]>,
+);
+impl<'a> RootParameter1<'a> {
+    pub fn descriptor_table(visibility: ShaderVisibility, ranges: &'a [DescriptorRange1]) -> Self {
+        let mut param = d3d12::D3D12_ROOT_PARAMETER1 {
+            ParameterType: d3d12::D3D12_ROOT_PARAMETER_TYPE_DESCRIPTOR_TABLE,
+            ShaderVisibility: visibility as _,
+            ..unsafe { mem::zeroed() }
+        };
+
+        *unsafe { param.u.DescriptorTable_mut() } = d3d12::D3D12_ROOT_DESCRIPTOR_TABLE1 {
+            NumDescriptorRanges: ranges.len() as _,
+            pDescriptorRanges: ranges.as_ptr() as *const _,
+        };
+
+        RootParameter1(param, PhantomData)
+    }
+
+    pub fn constants(visibility: ShaderVisibility, binding: Binding, num: u32) -> Self {
+        let mut param = d3d12::D3D12_ROOT_PARAMETER1 {
+            ParameterType: d3d12::D3D12_ROOT_PARAMETER_TYPE_32BIT_CONSTANTS,
+            ShaderVisibility: visibility as _,
+            ..unsafe { mem::zeroed() }
+        };
+
+        *unsafe { param.u.Constants_mut() } = d3d12::D3D12_ROOT_CONSTANTS {
+            ShaderRegister: binding.register,
+            RegisterSpace: binding.space,
+            Num32BitValues: num,
+        };
+
+        RootParameter1(param, PhantomData)
+    }
+
+    // `ty` has to be one of the root descriptor types.
+    pub(crate) fn descriptor(
+        ty: d3d12::D3D12_ROOT_PARAMETER_TYPE,
+        visibility: ShaderVisibility,
+        binding: Binding,
+        flags: RootDescriptorFlags,
+    ) -> Self {
+        let mut param = d3d12::D3D12_ROOT_PARAMETER1 {
+            ParameterType: ty,
+            ShaderVisibility: visibility as _,
+            ..unsafe { mem::zeroed() }
+        };
+
+        *unsafe { param.u.Descriptor_mut() } = d3d12::D3D12_ROOT_DESCRIPTOR1 {
+            ShaderRegister: binding.register,
+            RegisterSpace: binding.space,
+            Flags: flags.bits(),
+        };
+
+        RootParameter1(param, PhantomData)
+    }
+
+    pub fn cbv_descriptor(
+        visibility: ShaderVisibility,
+        binding: Binding,
+        flags: RootDescriptorFlags,
+    ) -> Self {
+        Self::descriptor(
+            d3d12::D3D12_ROOT_PARAMETER_TYPE_CBV,
+            visibility,
+            binding,
+            flags,
+        )
+    }
+
+    pub fn srv_descriptor(
+        visibility: ShaderVisibility,
+        binding: Binding,
+        flags: RootDescriptorFlags,
+    ) -> Self {
+        Self::descriptor(
+            d3d12::D3D12_ROOT_PARAMETER_TYPE_SRV,
+            visibility,
+            binding,
+            flags,
+        )
+    }
+
+    pub fn uav_descriptor(
+        visibility: ShaderVisibility,
+        binding: Binding,
+        flags: RootDescriptorFlags,
+    ) -> Self {
+        Self::descriptor(
+            d3d12::D3D12_ROOT_PARAMETER_TYPE_UAV,
+            visibility,
+            binding,
+            flags,
+        )
+    }
+}
+
+impl fmt::Debug for RootParameter1<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        #[derive(Debug)]
+        #[allow(dead_code)] // False-positive
+        enum Inner<'a> {
+            Table(&'a [DescriptorRange1]),
+            Constants { binding: Binding, num: u32 },
+            SingleCbv(Binding, RootDescriptorFlags),
+            SingleSrv(Binding, RootDescriptorFlags),
+            SingleUav(Binding, RootDescriptorFlags),
+        }
+        let kind = match self.0.ParameterType {
+            d3d12::D3D12_ROOT_PARAMETER_TYPE_DESCRIPTOR_TABLE => unsafe {
+                let raw = self.0.u.DescriptorTable();
+                Inner::Table(std::slice::from_raw_parts(
+                    raw.pDescriptorRanges as *const _,
+                    raw.NumDescriptorRanges as usize,
+                ))
+            },
+            d3d12::D3D12_ROOT_PARAMETER_TYPE_32BIT_CONSTANTS => unsafe {
+                let raw = self.0.u.Constants();
+                Inner::Constants {
+                    binding: Binding {
+                        space: raw.RegisterSpace,
+                        register: raw.ShaderRegister,
+                    },
+                    num: raw.Num32BitValues,
+                }
+            },
+            _ => unsafe {
+                let raw = self.0.u.Descriptor();
+                let binding = Binding {
+                    space: raw.RegisterSpace,
+                    register: raw.ShaderRegister,
+                };
+                let flags = RootDescriptorFlags::from_bits_truncate(raw.Flags);
+                match self.0.ParameterType {
+                    d3d12::D3D12_ROOT_PARAMETER_TYPE_CBV => Inner::SingleCbv(binding, flags),
+                    d3d12::D3D12_ROOT_PARAMETER_TYPE_SRV => Inner::SingleSrv(binding, flags),
+                    d3d12::D3D12_ROOT_PARAMETER_TYPE_UAV => Inner::SingleUav(binding, flags),
+                    other => panic!("Unexpected type {:?}", other),
+                }
+            },
+        };
+
+        formatter
+            .debug_struct("RootParameter1")
+            .field("visibility", &self.0.ShaderVisibility)
+            .field("kind", &kind)
+            .finish()
+    }
+}
+
 #[repr(u32)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum StaticBorderColor {
     TransparentBlack = d3d12::D3D12_STATIC_BORDER_COLOR_TRANSPARENT_BLACK,
     OpaqueBlack = d3d12::D3D12_STATIC_BORDER_COLOR_OPAQUE_BLACK,
     OpaqueWhite = d3d12::D3D12_STATIC_BORDER_COLOR_OPAQUE_WHITE,
 }
 
+impl StaticBorderColor {
+    pub(crate) fn from_raw(raw: d3d12::D3D12_STATIC_BORDER_COLOR) -> Option<Self> {
+        Some(match raw {
+            d3d12::D3D12_STATIC_BORDER_COLOR_TRANSPARENT_BLACK => {
+                StaticBorderColor::TransparentBlack
+            }
+            d3d12::D3D12_STATIC_BORDER_COLOR_OPAQUE_BLACK => StaticBorderColor::OpaqueBlack,
+            d3d12::D3D12_STATIC_BORDER_COLOR_OPAQUE_WHITE => StaticBorderColor::OpaqueWhite,
+            _ => return None,
+        })
+    }
+}
+
 #[repr(transparent)]
 pub struct StaticSampler(d3d12::D3D12_STATIC_SAMPLER_DESC);
 impl StaticSampler {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         visibility: ShaderVisibility,
         binding: Binding,
@@ -246,12 +642,23 @@ impl StaticSampler {
 }
 
 #[repr(u32)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RootSignatureVersion {
     V1_0 = d3d12::D3D_ROOT_SIGNATURE_VERSION_1_0,
     V1_1 = d3d12::D3D_ROOT_SIGNATURE_VERSION_1_1,
 }
 
+impl RootSignatureVersion {
+    pub(crate) fn from_raw(raw: d3d12::D3D_ROOT_SIGNATURE_VERSION) -> Option<Self> {
+        Some(match raw {
+            d3d12::D3D_ROOT_SIGNATURE_VERSION_1_0 => RootSignatureVersion::V1_0,
+            d3d12::D3D_ROOT_SIGNATURE_VERSION_1_1 => RootSignatureVersion::V1_1,
+            _ => return None,
+        })
+    }
+}
+
 bitflags! {
     pub struct RootSignatureFlags: u32 {
         const ALLOW_IA_INPUT_LAYOUT = d3d12::D3D12_ROOT_SIGNATURE_FLAG_ALLOW_INPUT_ASSEMBLER_INPUT_LAYOUT;
@@ -260,14 +667,78 @@ bitflags! {
         const DENY_DS_ROOT_ACCESS = d3d12::D3D12_ROOT_SIGNATURE_FLAG_DENY_DOMAIN_SHADER_ROOT_ACCESS;
         const DENY_GS_ROOT_ACCESS = d3d12::D3D12_ROOT_SIGNATURE_FLAG_DENY_GEOMETRY_SHADER_ROOT_ACCESS;
         const DENY_PS_ROOT_ACCESS = d3d12::D3D12_ROOT_SIGNATURE_FLAG_DENY_PIXEL_SHADER_ROOT_ACCESS;
+        const ALLOW_STREAM_OUTPUT = d3d12::D3D12_ROOT_SIGNATURE_FLAG_ALLOW_STREAM_OUTPUT;
+        const LOCAL_ROOT_SIGNATURE = 0x80;
+        const DENY_AS_ROOT_ACCESS = 0x100;
+        const DENY_MS_ROOT_ACCESS = 0x200;
+        const CBV_SRV_UAV_HEAP_DIRECTLY_INDEXED = 0x400;
+        const SAMPLER_HEAP_DIRECTLY_INDEXED = 0x800;
     }
 }
 
 pub type RootSignature = WeakPtr<d3d12::ID3D12RootSignature>;
+
+unsafe impl ComSend for d3d12::ID3D12RootSignature {}
+unsafe impl ComSync for d3d12::ID3D12RootSignature {}
+
 pub type BlobResult = D3DResult<(Blob, Error)>;
 
+// Releases the error blob, keeping its message for a failed call.
+#[cfg(any(feature = "libloading", feature = "implicit-link"))]
+fn blob_into_result(
+    ((blob, error), hr): BlobResult,
+    call: &'static str,
+) -> Result<Blob, crate::D3DError> {
+    let message = if error.is_null() {
+        None
+    } else {
+        let message = unsafe { error.as_c_str() }.to_string_lossy().into_owned();
+        unsafe { error.destroy() };
+        Some(message)
+    };
+
+    if winapi::shared::winerror::SUCCEEDED(hr) {
+        Ok(blob)
+    } else {
+        if !blob.is_null() {
+            unsafe { blob.destroy() };
+        }
+        let error = crate::D3DError::new(call, hr);
+        Err(match message {
+            Some(message) => error.with_message(message),
+            None => error,
+        })
+    }
+}
+
+// The parameters and samplers are borrowed by the returned description.
+#[cfg(any(feature = "libloading", feature = "implicit-link"))]
+pub(crate) fn versioned_desc_1_1(
+    parameters: &[RootParameter1],
+    static_samplers: &[StaticSampler],
+    flags: RootSignatureFlags,
+) -> d3d12::D3D12_VERSIONED_ROOT_SIGNATURE_DESC {
+    let mut desc = d3d12::D3D12_VERSIONED_ROOT_SIGNATURE_DESC {
+        Version: d3d12::D3D_ROOT_SIGNATURE_VERSION_1_1,
+        ..unsafe { mem::zeroed() }
+    };
+    *unsafe { desc.u.Desc_1_1_mut() } = d3d12::D3D12_ROOT_SIGNATURE_DESC1 {
+        NumParameters: parameters.len() as _,
+        pParameters: parameters.as_ptr() as *const _,
+        NumStaticSamplers: static_samplers.len() as _,
+        pStaticSamplers: static_samplers.as_ptr() as _,
+        Flags: flags.bits(),
+    };
+    desc
+}
+
 #[cfg(feature = "libloading")]
 impl crate::D3D12Lib {
+    #[deprecated(
+        since = "0.5.0",
+        note = "use `serialize_root_signature_desc`, which takes an owned `RootSignatureDesc`"
+    )]
+    #[track_caller]
     pub fn serialize_root_signature(
         &self,
         version: RootSignatureVersion,
@@ -303,12 +774,120 @@ impl crate::D3D12Lib {
             )
         };
 
+        crate::ledger::track_created(&blob);
+        crate::ledger::track_created(&error);
         Ok(((blob, error), hr))
     }
+
+    #[deprecated(
+        since = "0.5.0",
+        note = "use `try_serialize_root_signature_desc`, which takes an owned `RootSignatureDesc`"
+    )]
+    #[allow(deprecated)]
+    #[track_caller]
+    pub fn try_serialize_root_signature(
+        &self,
+        version: RootSignatureVersion,
+        parameters: &[RootParameter],
+        static_samplers: &[StaticSampler],
+        flags: RootSignatureFlags,
+    ) -> Result<Result<Blob, crate::D3DError>, libloading::Error> {
+        self.serialize_root_signature(version, parameters, static_samplers, flags)
+            .map(|result| blob_into_result(result, "D3D12SerializeRootSignature"))
+    }
+
+    /// Serialize a version 1.1 root signature.
+    #[track_caller]
+    pub fn serialize_root_signature_1_1(
+        &self,
+        parameters: &[RootParameter1],
+        static_samplers: &[StaticSampler],
+        flags: RootSignatureFlags,
+    ) -> Result<BlobResult, libloading::Error> {
+        use winapi::um::d3dcommon::ID3DBlob;
+        type Fun = extern "system" fn(
+            *const d3d12::D3D12_VERSIONED_ROOT_SIGNATURE_DESC,
+            *mut *mut ID3DBlob,
+            *mut *mut ID3DBlob,
+        ) -> crate::HRESULT;
+
+        let desc = versioned_desc_1_1(parameters, static_samplers, flags);
+
+        let mut blob = Blob::null();
+        let mut error = Error::null();
+        let hr = unsafe {
+            let func: libloading::Symbol<Fun> =
+                self.lib.get(b"D3D12SerializeVersionedRootSignature")?;
+            func(
+                &desc,
+                blob.mut_void() as *mut *mut _,
+                error.mut_void() as *mut *mut _,
+            )
+        };
+
+        crate::ledger::track_created(&blob);
+        crate::ledger::track_created(&error);
+        Ok(((blob, error), hr))
+    }
+
+    #[track_caller]
+    pub fn try_serialize_root_signature_1_1(
+        &self,
+        parameters: &[RootParameter1],
+        static_samplers: &[StaticSampler],
+        flags: RootSignatureFlags,
+    ) -> Result<Result<Blob, crate::D3DError>, libloading::Error> {
+        self.serialize_root_signature_1_1(parameters, static_samplers, flags)
+            .map(|result| blob_into_result(result, "D3D12SerializeVersionedRootSignature"))
+    }
+
+    /// Serialize an owned description, in its version.
+    #[track_caller]
+    pub fn serialize_root_signature_desc(
+        &self,
+        desc: &crate::RootSignatureDesc,
+    ) -> Result<BlobResult, libloading::Error> {
+        use winapi::um::d3dcommon::ID3DBlob;
+        type Fun = extern "system" fn(
+            *const d3d12::D3D12_VERSIONED_ROOT_SIGNATURE_DESC,
+            *mut *mut ID3DBlob,
+            *mut *mut ID3DBlob,
+        ) -> crate::HRESULT;
+
+        let mut blob = Blob::null();
+        let mut error = Error::null();
+        let func: libloading::Symbol<Fun> =
+            unsafe { self.lib.get(b"D3D12SerializeVersionedRootSignature")? };
+        let hr = desc.with_raw(|desc| {
+            func(
+                desc,
+                blob.mut_void() as *mut *mut _,
+                error.mut_void() as *mut *mut _,
+            )
+        });
+
+        crate::ledger::track_created(&blob);
+        crate::ledger::track_created(&error);
+        Ok(((blob, error), hr))
+    }
+
+    #[track_caller]
+    pub fn try_serialize_root_signature_desc(
+        &self,
+        desc: &crate::RootSignatureDesc,
+    ) -> Result<Result<Blob, crate::D3DError>, libloading::Error> {
+        self.serialize_root_signature_desc(desc)
+            .map(|result| blob_into_result(result, "D3D12SerializeVersionedRootSignature"))
+    }
 }
 
 impl RootSignature {
     #[cfg(feature = "implicit-link")]
+    #[deprecated(
+        since = "0.5.0",
+        note = "use `serialize_desc`, which takes an owned `RootSignatureDesc`"
+    )]
+    #[track_caller]
     pub fn serialize(
         version: RootSignatureVersion,
         parameters: &[RootParameter],
@@ -335,26 +914,296 @@ impl RootSignature {
             )
         };
 
+        crate::ledger::track_created(&blob);
+        crate::ledger::track_created(&error);
+        ((blob, error), hr)
+    }
+
+    #[cfg(feature = "implicit-link")]
+    #[deprecated(
+        since = "0.5.0",
+        note = "use `try_serialize_desc`, which takes an owned `RootSignatureDesc`"
+    )]
+    #[allow(deprecated)]
+    #[track_caller]
+    pub fn try_serialize(
+        version: RootSignatureVersion,
+        parameters: &[RootParameter],
+        static_samplers: &[StaticSampler],
+        flags: RootSignatureFlags,
+    ) -> Result<Blob, crate::D3DError> {
+        blob_into_result(
+            Self::serialize(version, parameters, static_samplers, flags),
+            "D3D12SerializeRootSignature",
+        )
+    }
+
+    /// Serialize a version 1.1 root signature.
+    #[cfg(feature = "implicit-link")]
+    #[track_caller]
+    pub fn serialize_1_1(
+        parameters: &[RootParameter1],
+        static_samplers: &[StaticSampler],
+        flags: RootSignatureFlags,
+    ) -> BlobResult {
+        let mut blob = Blob::null();
+        let mut error = Error::null();
+
+        let desc = versioned_desc_1_1(parameters, static_samplers, flags);
+
+        let hr = unsafe {
+            d3d12::D3D12SerializeVersionedRootSignature(
+                &desc,
+                blob.mut_void() as *mut *mut _,
+                error.mut_void() as *mut *mut _,
+            )
+        };
+
+        crate::ledger::track_created(&blob);
+        crate::ledger::track_created(&error);
+        ((blob, error), hr)
+    }
+
+    #[cfg(feature = "implicit-link")]
+    #[track_caller]
+    pub fn try_serialize_1_1(
+        parameters: &[RootParameter1],
+        static_samplers: &[StaticSampler],
+        flags: RootSignatureFlags,
+    ) -> Result<Blob, crate::D3DError> {
+        blob_into_result(
+            Self::serialize_1_1(parameters, static_samplers, flags),
+            "D3D12SerializeVersionedRootSignature",
+        )
+    }
+
+    /// Serialize an owned description, in its version.
+    #[cfg(feature = "implicit-link")]
+    #[track_caller]
+    pub fn serialize_desc(desc: &crate::RootSignatureDesc) -> BlobResult {
+        let mut blob = Blob::null();
+        let mut error = Error::null();
+
+        let hr = desc.with_raw(|desc| unsafe {
+            d3d12::D3D12SerializeVersionedRootSignature(
+                desc,
+                blob.mut_void() as *mut *mut _,
+                error.mut_void() as *mut *mut _,
+            )
+        });
+
+        crate::ledger::track_created(&blob);
+        crate::ledger::track_created(&error);
         ((blob, error), hr)
     }
+
+    #[cfg(feature = "implicit-link")]
+    #[track_caller]
+    pub fn try_serialize_desc(desc: &crate::RootSignatureDesc) -> Result<Blob, crate::D3DError> {
+        blob_into_result(
+            Self::serialize_desc(desc),
+            "D3D12SerializeVersionedRootSignature",
+        )
+    }
 }
 
-#[repr(transparent)]
-pub struct RenderTargetViewDesc(pub(crate) d3d12::D3D12_RENDER_TARGET_VIEW_DESC);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-impl RenderTargetViewDesc {
-    pub fn texture_2d(format: dxgiformat::DXGI_FORMAT, mip_slice: u32, plane_slice: u32) -> Self {
-        let mut desc = d3d12::D3D12_RENDER_TARGET_VIEW_DESC {
-            Format: format,
-            ViewDimension: d3d12::D3D12_RTV_DIMENSION_TEXTURE2D,
-            ..unsafe { mem::zeroed() }
+    #[test]
+    fn root_parameter_1_1_layout() {
+        assert_eq!(
+            mem::size_of::<RootParameter1>(),
+            mem::size_of::<d3d12::D3D12_ROOT_PARAMETER1>()
+        );
+
+        let ranges = [
+            DescriptorRange1::new(
+                DescriptorRangeType::SRV,
+                4,
+                Binding {
+                    space: 1,
+                    register: 2,
+                },
+                0,
+                DescriptorRangeFlags::DATA_VOLATILE,
+            ),
+            DescriptorRange1::new(
+                DescriptorRangeType::UAV,
+                1,
+                Binding {
+                    space: 0,
+                    register: 0,
+                },
+                d3d12::D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND,
+                DescriptorRangeFlags::empty(),
+            ),
+        ];
+        let parameter = RootParameter1::descriptor_table(ShaderVisibility::PS, &ranges);
+        assert_eq!(
+            parameter.0.ParameterType,
+            d3d12::D3D12_ROOT_PARAMETER_TYPE_DESCRIPTOR_TABLE
+        );
+        let table = unsafe { parameter.0.u.DescriptorTable() };
+        assert_eq!(table.NumDescriptorRanges, 2);
+        assert_eq!(table.pDescriptorRanges, ranges.as_ptr() as *const _);
+        assert!(format!("{:?}", parameter).contains("DescriptorRange1"));
+    }
+
+    #[test]
+    fn root_descriptor_1_1_flags() {
+        let binding = Binding {
+            space: 3,
+            register: 7,
         };
+        let parameter = RootParameter1::cbv_descriptor(
+            ShaderVisibility::All,
+            binding,
+            RootDescriptorFlags::DATA_STATIC,
+        );
+        assert_eq!(
+            parameter.0.ParameterType,
+            d3d12::D3D12_ROOT_PARAMETER_TYPE_CBV
+        );
+        let descriptor = unsafe { parameter.0.u.Descriptor() };
+        assert_eq!(descriptor.ShaderRegister, 7);
+        assert_eq!(descriptor.RegisterSpace, 3);
+        assert_eq!(
+            descriptor.Flags,
+            d3d12::D3D12_ROOT_DESCRIPTOR_FLAG_DATA_STATIC
+        );
+    }
 
-        *unsafe { desc.u.Texture2D_mut() } = d3d12::D3D12_TEX2D_RTV {
-            MipSlice: mip_slice,
-            PlaneSlice: plane_slice,
+    #[cfg(feature = "mock")]
+    fn heaps(device: Device, flags: DescriptorHeapFlags) -> (DescriptorHeap, DescriptorHeap) {
+        use crate::IntoResult;
+
+        let create = |count| {
+            device
+                .create_descriptor_heap(count, DescriptorHeapType::CbvSrvUav, flags, 0)
+                .into_result("CreateDescriptorHeap")
+                .unwrap()
         };
+        (create(4), create(8))
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn handles() {
+        use crate::mock::{Recorder, DESCRIPTOR_INCREMENT};
+
+        let recorder = Recorder::new();
+        let device = recorder.create_device();
+        let (visible, _) = heaps(device, DescriptorHeapFlags::SHADER_VISIBLE);
+        let (hidden, _) = heaps(device, DescriptorHeapFlags::empty());
+
+        let start = visible.start_handle(&device);
+        assert_eq!(
+            (start.index(), start.capacity(), start.remaining()),
+            (0, 4, 4)
+        );
+        assert_eq!(start.increment(), DESCRIPTOR_INCREMENT);
+        let last = start.offset(3).unwrap();
+        assert_eq!((last.index(), last.remaining()), (3, 1));
+        assert_eq!(
+            last.cpu().ptr,
+            start.cpu().ptr + 3 * DESCRIPTOR_INCREMENT as usize
+        );
+        assert_eq!(
+            last.gpu().unwrap().ptr,
+            start.gpu().unwrap().ptr + 3 * DESCRIPTOR_INCREMENT as u64
+        );
+        assert_eq!(last.offset(1), None);
+        assert_eq!(last.offset(u32::MAX), None);
+        assert_eq!(last.at(0), Some(start));
+        assert_eq!(start.at(4), None);
+        assert_eq!(visible.handle(&device, 2), start.at(2));
+        assert_eq!(visible.handle(&device, 4), None);
+
+        let hidden_start = hidden.start_handle(&device);
+        assert!(start.is_shader_visible());
+        assert!(!hidden_start.is_shader_visible());
+        assert!(hidden_start.gpu().is_none());
+        assert!(hidden_start.offset(2).unwrap().gpu().is_none());
+
+        for heap in [visible, hidden].iter() {
+            unsafe { heap.destroy() };
+        }
+        unsafe { device.destroy() };
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn copies() {
+        use crate::mock::{Call, Recorder, DESCRIPTOR_INCREMENT};
+
+        let recorder = Recorder::new();
+        let device = recorder.create_device();
+        let (visible, _) = heaps(device, DescriptorHeapFlags::SHADER_VISIBLE);
+        let (small, large) = heaps(device, DescriptorHeapFlags::empty());
+        let dest = visible.start_handle(&device).at(1).unwrap();
+        let src = large.start_handle(&device).at(5).unwrap();
+        recorder.take_calls();
+
+        device.copy_descriptor_range(dest, src, 3);
+        let cpu = |handle: DescriptorHandle| handle.cpu();
+        device.copy_descriptors(
+            &[(cpu(dest), 2), (cpu(dest.offset(2).unwrap()), 1)],
+            &[(cpu(src), 3)],
+            DescriptorHeapType::CbvSrvUav,
+        );
+        assert_eq!(
+            recorder.take_calls(),
+            [
+                Call::CopyDescriptorsSimple {
+                    count: 3,
+                    dest: dest.cpu().ptr,
+                    src: src.cpu().ptr,
+                    heap_type: d3d12::D3D12_DESCRIPTOR_HEAP_TYPE_CBV_SRV_UAV,
+                },
+                Call::CopyDescriptors {
+                    dest_ranges: vec![
+                        (dest.cpu().ptr, 2),
+                        (dest.cpu().ptr + 2 * DESCRIPTOR_INCREMENT as usize, 1),
+                    ],
+                    src_ranges: vec![(src.cpu().ptr, 3)],
+                    heap_type: d3d12::D3D12_DESCRIPTOR_HEAP_TYPE_CBV_SRV_UAV,
+                },
+            ]
+        );
+
+        for heap in [visible, small, large].iter() {
+            unsafe { heap.destroy() };
+        }
+        unsafe { device.destroy() };
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    #[should_panic]
+    fn copy_past_heap_end() {
+        let recorder = crate::mock::Recorder::new();
+        let device = recorder.create_device();
+        let (visible, hidden) = heaps(device, DescriptorHeapFlags::empty());
+        let dest = visible.start_handle(&device).at(2).unwrap();
+        let src = hidden.start_handle(&device);
+        // Only 2 descriptors are left after `dest`.
+        device.copy_descriptor_range(dest, src, 3);
+    }
 
-        RenderTargetViewDesc(desc)
+    #[cfg(feature = "mock")]
+    #[test]
+    #[should_panic]
+    fn copy_from_shader_visible_heap() {
+        let recorder = crate::mock::Recorder::new();
+        let device = recorder.create_device();
+        let (hidden, _) = heaps(device, DescriptorHeapFlags::empty());
+        let (visible, _) = heaps(device, DescriptorHeapFlags::SHADER_VISIBLE);
+        device.copy_descriptor_range(
+            hidden.start_handle(&device),
+            visible.start_handle(&device),
+            1,
+        );
     }
 }
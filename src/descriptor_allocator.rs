@@ -0,0 +1,415 @@
+//! Allocator for descriptors in heaps which aren't shader visible
+//!
+//! Descriptors are handed out from pages of `DescriptorHeap`s, new pages are added once all
+//! existing ones are full. The bookkeeping of a page lives in `DescriptorFreeList`, which
+//! doesn't need a device.
+
+use crate::{
+    D3DError, DescriptorHandle, DescriptorHeap, DescriptorHeapFlags, DescriptorHeapType, Device,
+};
+use std::ops::Range;
+
+/// Free ranges of slots in a page of `capacity` descriptors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DescriptorFreeList {
+    capacity: u32,
+    // Sorted and coalesced.
+    free: Vec<Range<u32>>,
+}
+
+impl DescriptorFreeList {
+    pub fn new(capacity: u32) -> Self {
+        let mut free = Vec::new();
+        if capacity > 0 {
+            free.push(0..capacity);
+        }
+        DescriptorFreeList { capacity, free }
+    }
+
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    pub fn free_count(&self) -> u32 {
+        self.free.iter().map(|range| range.end - range.start).sum()
+    }
+
+    pub fn allocated_count(&self) -> u32 {
+        self.capacity - self.free_count()
+    }
+
+    /// Size of the largest contiguous allocation which would currently succeed.
+    pub fn largest_free_range(&self) -> u32 {
+        self.free
+            .iter()
+            .map(|range| range.end - range.start)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Index of the first slot of `count` contiguous slots, picking the first range
+    /// large enough.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is zero.
+    pub fn allocate(&mut self, count: u32) -> Option<u32> {
+        assert!(count > 0, "allocated zero descriptors");
+        let position = self
+            .free
+            .iter()
+            .position(|range| range.end - range.start >= count)?;
+        let range = &mut self.free[position];
+        let start = range.start;
+        range.start += count;
+        if range.start == range.end {
+            self.free.remove(position);
+        }
+        Some(start)
+    }
+
+    /// Return slots to the free list, merging them with adjacent free ranges.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slots` is empty, out of bounds or overlaps slots which are already free.
+    pub fn free(&mut self, slots: Range<u32>) {
+        assert!(slots.start < slots.end && slots.end <= self.capacity);
+        let position = self
+            .free
+            .iter()
+            .position(|range| range.start >= slots.end)
+            .unwrap_or(self.free.len());
+        // Ranges from `position` on start after `slots`, only the previous one can overlap.
+        assert!(
+            position == 0 || self.free[position - 1].end <= slots.start,
+            "descriptors {:?} freed twice",
+            slots
+        );
+
+        let merge_prev = position > 0 && self.free[position - 1].end == slots.start;
+        let merge_next = position < self.free.len() && self.free[position].start == slots.end;
+        match (merge_prev, merge_next) {
+            (true, true) => {
+                self.free[position - 1].end = self.free[position].end;
+                self.free.remove(position);
+            }
+            (true, false) => self.free[position - 1].end = slots.end,
+            (false, true) => self.free[position].start = slots.start,
+            (false, false) => self.free.insert(position, slots),
+        }
+    }
+}
+
+/// Descriptors handed out by `CpuDescriptorAllocator`.
+#[derive(Clone, Copy, Debug, PartialEq, Hash)]
+pub struct DescriptorAllocation {
+    handle: DescriptorHandle,
+    count: u32,
+    page: usize,
+}
+
+impl DescriptorAllocation {
+    /// First descriptor of the allocation.
+    pub fn handle(&self) -> DescriptorHandle {
+        self.handle
+    }
+
+    pub fn len(&self) -> u32 {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Descriptor at `index` in the allocation, `None` if it's out of bounds.
+    pub fn at(&self, index: u32) -> Option<DescriptorHandle> {
+        if index < self.count {
+            self.handle.offset(index)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DescriptorAllocatorStats {
+    pub pages: usize,
+    /// Descriptors in all pages.
+    pub capacity: u32,
+    pub allocated: u32,
+    pub peak_allocated: u32,
+}
+
+struct Page {
+    heap: DescriptorHeap,
+    start: DescriptorHandle,
+    free_list: DescriptorFreeList,
+}
+
+/// Growing allocator for single descriptors and contiguous ranges of one heap type.
+///
+/// The heaps are released when the allocator is dropped.
+pub struct CpuDescriptorAllocator {
+    device: Device,
+    heap_type: DescriptorHeapType,
+    page_size: u32,
+    pages: Vec<Page>,
+    allocated: u32,
+    peak_allocated: u32,
+}
+
+impl CpuDescriptorAllocator {
+    /// New pages hold `page_size` descriptors, or more for larger ranges.
+    pub fn new(device: Device, heap_type: DescriptorHeapType, page_size: u32) -> Self {
+        assert!(page_size > 0);
+        CpuDescriptorAllocator {
+            device,
+            heap_type,
+            page_size,
+            pages: Vec::new(),
+            allocated: 0,
+            peak_allocated: 0,
+        }
+    }
+
+    pub fn heap_type(&self) -> DescriptorHeapType {
+        self.heap_type
+    }
+
+    pub fn allocate(&mut self) -> Result<DescriptorAllocation, D3DError> {
+        self.allocate_range(1)
+    }
+
+    /// Allocate `count` contiguous descriptors, adding a page if none has enough space.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is zero.
+    pub fn allocate_range(&mut self, count: u32) -> Result<DescriptorAllocation, D3DError> {
+        assert!(count > 0, "allocated zero descriptors");
+        let found = self
+            .pages
+            .iter_mut()
+            .enumerate()
+            .find_map(|(i, page)| page.free_list.allocate(count).map(|slot| (i, slot)));
+
+        let (page, slot) = match found {
+            Some(found) => found,
+            None => {
+                let capacity = self.page_size.max(count);
+                let heap = self.device.try_create_descriptor_heap(
+                    capacity,
+                    self.heap_type,
+                    DescriptorHeapFlags::empty(),
+                    0,
+                )?;
+                let mut free_list = DescriptorFreeList::new(capacity);
+                let slot = free_list.allocate(count).unwrap();
+                self.pages.push(Page {
+                    heap,
+                    start: heap.start_handle(&self.device),
+                    free_list,
+                });
+                (self.pages.len() - 1, slot)
+            }
+        };
+
+        self.allocated += count;
+        self.peak_allocated = self.peak_allocated.max(self.allocated);
+        Ok(DescriptorAllocation {
+            handle: self.pages[page].start.at(slot).unwrap(),
+            count,
+            page,
+        })
+    }
+
+    /// Return descriptors for reuse by later allocations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation was already freed or comes from another allocator.
+    pub fn free(&mut self, allocation: DescriptorAllocation) {
+        let page = self
+            .pages
+            .get_mut(allocation.page)
+            .filter(|page| page.heap == allocation.handle.heap())
+            .expect("descriptors freed to a different allocator");
+        let start = allocation.handle.index();
+        page.free_list.free(start..start + allocation.count);
+        self.allocated -= allocation.count;
+    }
+
+    pub fn stats(&self) -> DescriptorAllocatorStats {
+        DescriptorAllocatorStats {
+            pages: self.pages.len(),
+            capacity: self
+                .pages
+                .iter()
+                .map(|page| page.free_list.capacity())
+                .sum(),
+            allocated: self.allocated,
+            peak_allocated: self.peak_allocated,
+        }
+    }
+}
+
+impl Drop for CpuDescriptorAllocator {
+    fn drop(&mut self) {
+        for page in &self.pages {
+            unsafe { page.heap.destroy() };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn free_ranges(list: &DescriptorFreeList) -> Vec<(u32, u32)> {
+        list.free
+            .iter()
+            .map(|range| (range.start, range.end))
+            .collect()
+    }
+
+    #[test]
+    fn first_fit() {
+        let mut list = DescriptorFreeList::new(16);
+        assert_eq!(list.allocate(17), None);
+        assert_eq!(list.allocate(4), Some(0));
+        assert_eq!(list.allocate(4), Some(4));
+        assert_eq!(list.allocate(4), Some(8));
+        list.free(0..2);
+        list.free(4..8);
+
+        // The first range large enough is picked, not the smallest one.
+        assert_eq!(list.allocate(1), Some(0));
+        assert_eq!(list.allocate(3), Some(4));
+        assert_eq!(list.allocate(2), Some(12));
+        assert_eq!(free_ranges(&list), [(1, 2), (7, 8), (14, 16)]);
+        assert_eq!(list.free_count(), 4);
+        assert_eq!(list.allocated_count(), 12);
+        assert_eq!(list.allocate(3), None);
+    }
+
+    #[test]
+    fn coalescing() {
+        let mut list = DescriptorFreeList::new(10);
+        assert_eq!(list.allocate(10), Some(0));
+        assert!(free_ranges(&list).is_empty());
+
+        // No neighbours.
+        list.free(2..3);
+        list.free(6..7);
+        assert_eq!(free_ranges(&list), [(2, 3), (6, 7)]);
+        // Merged into the previous range.
+        list.free(3..4);
+        assert_eq!(free_ranges(&list), [(2, 4), (6, 7)]);
+        // Merged into the next range.
+        list.free(5..6);
+        assert_eq!(free_ranges(&list), [(2, 4), (5, 7)]);
+        // Joining both.
+        list.free(4..5);
+        assert_eq!(free_ranges(&list), [(2, 7)]);
+
+        list.free(0..2);
+        list.free(7..10);
+        assert_eq!(free_ranges(&list), [(0, 10)]);
+        assert_eq!(list.allocated_count(), 0);
+    }
+
+    #[test]
+    fn largest_free_range() {
+        let mut list = DescriptorFreeList::new(8);
+        assert_eq!(list.largest_free_range(), 8);
+        assert_eq!(list.allocate(8), Some(0));
+        assert_eq!(list.largest_free_range(), 0);
+        list.free(1..2);
+        list.free(4..7);
+        assert_eq!(list.largest_free_range(), 3);
+        assert_eq!(DescriptorFreeList::new(0).largest_free_range(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "allocated zero descriptors")]
+    fn allocate_zero() {
+        DescriptorFreeList::new(16).allocate(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "freed twice")]
+    fn double_free() {
+        let mut list = DescriptorFreeList::new(8);
+        assert_eq!(list.allocate(4), Some(0));
+        list.free(0..4);
+        list.free(1..2);
+    }
+
+    #[test]
+    #[should_panic(expected = "freed twice")]
+    fn overlapping_free() {
+        let mut list = DescriptorFreeList::new(8);
+        assert_eq!(list.allocate(6), Some(0));
+        list.free(2..4);
+        list.free(3..5);
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn pages() {
+        let recorder = crate::mock::Recorder::new();
+        let device = recorder.create_device();
+        let mut allocator = CpuDescriptorAllocator::new(device, DescriptorHeapType::Rtv, 4);
+
+        let first = allocator.allocate_range(3).unwrap();
+        let second = allocator.allocate_range(2).unwrap();
+        let large = allocator.allocate_range(6).unwrap();
+        assert_ne!(first.handle().heap(), second.handle().heap());
+        assert_eq!(
+            allocator.stats(),
+            DescriptorAllocatorStats {
+                pages: 3,
+                capacity: 4 + 4 + 6,
+                allocated: 11,
+                peak_allocated: 11,
+            }
+        );
+
+        allocator.free(first);
+        let reused = allocator.allocate().unwrap();
+        assert_eq!(reused.handle(), first.handle());
+        assert_eq!(reused.at(1), None);
+        allocator.free(large);
+        assert_eq!(allocator.stats().allocated, 3);
+        assert_eq!(allocator.stats().peak_allocated, 11);
+
+        drop(allocator);
+        unsafe { device.destroy() };
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    #[should_panic(expected = "allocated zero descriptors")]
+    fn allocate_zero_range() {
+        let recorder = crate::mock::Recorder::new();
+        let device = recorder.create_device();
+        let mut allocator = CpuDescriptorAllocator::new(device, DescriptorHeapType::Rtv, 4);
+        let _ = allocator.allocate_range(0);
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    #[should_panic(expected = "freed to a different allocator")]
+    fn free_to_other_allocator() {
+        let recorder = crate::mock::Recorder::new();
+        let device = recorder.create_device();
+        let mut first = CpuDescriptorAllocator::new(device, DescriptorHeapType::Rtv, 4);
+        let mut second = CpuDescriptorAllocator::new(device, DescriptorHeapType::Rtv, 4);
+        let allocation = first.allocate().unwrap();
+        second.allocate().unwrap();
+        // Both allocations live in the first page of their allocator.
+        second.free(allocation);
+    }
+}
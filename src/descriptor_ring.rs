@@ -0,0 +1,320 @@
+//! Ring allocator for descriptor tables in a shader visible heap
+//!
+//! Tables are written once per submission and are reclaimed after the GPU has passed the
+//! fence value of the submission. The bookkeeping lives in `DescriptorRing`, which only
+//! needs completed fence values and no device.
+
+use crate::{
+    D3DError, DescriptorHandle, DescriptorHeap, DescriptorHeapFlags, DescriptorHeapType, Device,
+    Fence,
+};
+use std::collections::VecDeque;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Submission {
+    fence_value: u64,
+    // Slots used by the submission, including the ones skipped when wrapping around.
+    size: u32,
+}
+
+/// Slots of a ring of `capacity` descriptors, each allocation tagged with a fence value.
+///
+/// Fence values passed to `allocate` must not decrease.
+///
+/// ```
+/// use d3d12::DescriptorRing;
+///
+/// let mut ring = DescriptorRing::new(8);
+/// assert_eq!(ring.allocate(3, 1), Some(0));
+/// assert_eq!(ring.allocate(3, 2), Some(3));
+/// assert_eq!(ring.allocate(3, 3), None);
+///
+/// // Submission 1 has finished, the table wraps around to the start.
+/// ring.retire(1);
+/// assert_eq!(ring.allocate(3, 3), Some(0));
+/// assert_eq!(ring.used(), 8);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DescriptorRing {
+    capacity: u32,
+    head: u32,
+    used: u32,
+    submissions: VecDeque<Submission>,
+}
+
+impl DescriptorRing {
+    pub fn new(capacity: u32) -> Self {
+        DescriptorRing {
+            capacity,
+            head: 0,
+            used: 0,
+            submissions: VecDeque::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Slots which aren't retired yet, including the ones skipped at the end of the ring.
+    pub fn used(&self) -> u32 {
+        self.used
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.used == 0
+    }
+
+    /// Index of the first slot of `count` contiguous slots, used until `fence_value` is
+    /// retired.
+    ///
+    /// Returns `None` if there isn't enough space before older submissions are retired.
+    pub fn allocate(&mut self, count: u32, fence_value: u64) -> Option<u32> {
+        if count == 0 || count > self.capacity {
+            return None;
+        }
+        let free = self.capacity - self.used;
+        // Tables can't wrap, the remaining slots at the end are skipped instead.
+        let (start, size) = if self.head + count <= self.capacity {
+            (self.head, count)
+        } else {
+            (0, self.capacity - self.head + count)
+        };
+        if size > free {
+            return None;
+        }
+
+        match self.submissions.back_mut() {
+            Some(last) if last.fence_value == fence_value => last.size += size,
+            Some(last) if last.fence_value > fence_value => {
+                panic!(
+                    "fence value {} is older than the last allocation",
+                    fence_value
+                )
+            }
+            _ => self.submissions.push_back(Submission { fence_value, size }),
+        }
+        self.head = (start + count) % self.capacity;
+        self.used += size;
+        Some(start)
+    }
+
+    /// Reclaim the slots of all submissions up to `completed_value`.
+    pub fn retire(&mut self, completed_value: u64) {
+        while let Some(submission) = self.submissions.front() {
+            if submission.fence_value > completed_value {
+                break;
+            }
+            self.used -= submission.size;
+            self.submissions.pop_front();
+        }
+        if self.submissions.is_empty() {
+            self.head = 0;
+        }
+    }
+}
+
+/// Source of the fence value the GPU has completed.
+///
+/// Implemented by `Fence` and by closures, which can stand in for a fence in tests.
+pub trait CompletedValue {
+    fn completed_value(&self) -> u64;
+}
+
+impl CompletedValue for Fence {
+    fn completed_value(&self) -> u64 {
+        self.get_value()
+    }
+}
+
+impl<F: Fn() -> u64> CompletedValue for F {
+    fn completed_value(&self) -> u64 {
+        self()
+    }
+}
+
+/// Linear allocator of descriptor tables over one shader visible `DescriptorHeap`.
+///
+/// Space is reclaimed based on the completed value of `fence`, which has to outlive the
+/// allocator. The heap is released when the allocator is dropped.
+pub struct DescriptorRingAllocator<F = Fence> {
+    heap: DescriptorHeap,
+    start: DescriptorHandle,
+    fence: F,
+    ring: DescriptorRing,
+}
+
+impl<F: CompletedValue> DescriptorRingAllocator<F> {
+    pub fn new(
+        device: &Device,
+        heap_type: DescriptorHeapType,
+        capacity: u32,
+        fence: F,
+    ) -> Result<Self, D3DError> {
+        let heap = device.try_create_descriptor_heap(
+            capacity,
+            heap_type,
+            DescriptorHeapFlags::SHADER_VISIBLE,
+            0,
+        )?;
+        Ok(DescriptorRingAllocator {
+            heap,
+            start: heap.start_handle(device),
+            fence,
+            ring: DescriptorRing::new(capacity),
+        })
+    }
+
+    pub fn heap(&self) -> DescriptorHeap {
+        self.heap
+    }
+
+    pub fn ring(&self) -> &DescriptorRing {
+        &self.ring
+    }
+
+    /// First descriptor of a table of `count` descriptors, which may be reused once the
+    /// fence has reached `fence_value`.
+    ///
+    /// Returns `None` if the ring is full, even after reclaiming completed submissions.
+    pub fn allocate(&mut self, count: u32, fence_value: u64) -> Option<DescriptorHandle> {
+        let slot = match self.ring.allocate(count, fence_value) {
+            Some(slot) => slot,
+            None => {
+                self.ring.retire(self.fence.completed_value());
+                self.ring.allocate(count, fence_value)?
+            }
+        };
+        self.start.at(slot)
+    }
+
+    /// Reclaim the tables of all submissions the GPU has finished.
+    pub fn retire(&mut self) {
+        self.ring.retire(self.fence.completed_value());
+    }
+}
+
+impl<F> Drop for DescriptorRingAllocator<F> {
+    fn drop(&mut self) {
+        unsafe { self.heap.destroy() };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_skips_tail() {
+        let mut ring = DescriptorRing::new(8);
+        assert_eq!(ring.allocate(3, 1), Some(0));
+        assert_eq!(ring.allocate(3, 2), Some(3));
+        ring.retire(1);
+        assert_eq!(ring.used(), 3);
+
+        // Slots 6 and 7 are skipped and belong to submission 3 along with the table.
+        assert_eq!(ring.allocate(3, 3), Some(0));
+        assert_eq!(ring.used(), 8);
+        assert_eq!(ring.allocate(1, 3), None);
+        ring.retire(2);
+        assert_eq!(ring.used(), 5);
+        assert_eq!(ring.allocate(3, 4), Some(3));
+        ring.retire(3);
+        assert_eq!(ring.used(), 3);
+        ring.retire(4);
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn exactly_full() {
+        let mut ring = DescriptorRing::new(8);
+        assert_eq!(ring.allocate(8, 1), Some(0));
+        assert_eq!(ring.used(), 8);
+        assert_eq!(ring.allocate(1, 2), None);
+        ring.retire(1);
+        assert!(ring.is_empty());
+        assert_eq!(ring.allocate(5, 2), Some(0));
+        assert_eq!(ring.allocate(3, 2), Some(5));
+        assert_eq!(ring.used(), 8);
+    }
+
+    #[test]
+    fn whole_ring_while_in_use() {
+        let mut ring = DescriptorRing::new(8);
+        assert_eq!(ring.allocate(0, 1), None);
+        assert_eq!(ring.allocate(9, 1), None);
+        assert_eq!(ring.allocate(2, 1), Some(0));
+        assert_eq!(ring.allocate(8, 2), None);
+        // Failed allocations don't change the ring.
+        assert_eq!(ring.used(), 2);
+        ring.retire(1);
+        assert_eq!(ring.allocate(8, 2), Some(0));
+    }
+
+    #[test]
+    fn shared_fence_value() {
+        let mut ring = DescriptorRing::new(8);
+        assert_eq!(ring.allocate(2, 5), Some(0));
+        assert_eq!(ring.allocate(3, 5), Some(2));
+        assert_eq!(ring.submissions.len(), 1);
+        assert_eq!(ring.allocate(1, 6), Some(5));
+        assert_eq!(ring.submissions.len(), 2);
+
+        ring.retire(4);
+        assert_eq!(ring.used(), 6);
+        ring.retire(5);
+        assert_eq!(ring.used(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "older than the last allocation")]
+    fn decreasing_fence_value() {
+        let mut ring = DescriptorRing::new(8);
+        ring.allocate(1, 2);
+        ring.allocate(1, 1);
+    }
+
+    #[test]
+    fn retire_resets_head() {
+        let mut ring = DescriptorRing::new(8);
+        assert_eq!(ring.allocate(3, 1), Some(0));
+        ring.retire(0);
+        assert_eq!(ring.head, 3);
+        ring.retire(1);
+        assert_eq!(ring.head, 0);
+        // Without the reset this would have skipped slots 3 to 7.
+        assert_eq!(ring.allocate(6, 2), Some(0));
+        assert_eq!(ring.used(), 6);
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn allocator_retires_when_full() {
+        use std::{cell::Cell, rc::Rc};
+
+        let recorder = crate::mock::Recorder::new();
+        let device = recorder.create_device();
+        let completed = Rc::new(Cell::new(0));
+        let fence = {
+            let completed = completed.clone();
+            move || completed.get()
+        };
+        let mut allocator =
+            DescriptorRingAllocator::new(&device, DescriptorHeapType::CbvSrvUav, 8, fence).unwrap();
+
+        let first = allocator.allocate(6, 1).unwrap();
+        assert_eq!(first.index(), 0);
+        assert_eq!(allocator.allocate(4, 2), None);
+
+        // The fence passed submission 1, the full ring reclaims it on its own.
+        completed.set(1);
+        assert_eq!(allocator.allocate(4, 2).unwrap().index(), 0);
+        assert_eq!(allocator.ring().used(), 4);
+        completed.set(2);
+        allocator.retire();
+        assert!(allocator.ring().is_empty());
+
+        drop(allocator);
+        unsafe { device.destroy() };
+    }
+}
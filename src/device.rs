@@ -1,21 +1,37 @@
 //! Device
 
 use crate::{
-    com::WeakPtr,
+    com::{ComSend, ComSync, WeakPtr},
     command_list::{CmdListType, CommandSignature, IndirectArgument},
-    descriptor::{CpuDescriptor, DescriptorHeapFlags, DescriptorHeapType, RenderTargetViewDesc},
+    descriptor::{CpuDescriptor, DescriptorHandle, DescriptorHeapFlags, DescriptorHeapType},
     heap::{Heap, HeapFlags, HeapProperties},
-    pso, query, queue, Blob, CachedPSO, CommandAllocator, CommandQueue, D3DResult, DescriptorHeap,
-    Fence, GraphicsCommandList, NodeMask, PipelineState, QueryHeap, Resource, RootSignature,
-    Shader, TextureAddressMode,
+    pso, query, queue, Blob, CachedPSO, CommandAllocator, CommandQueue, D3DError, D3DResult,
+    DescriptorHeap, Fence, GraphicsCommandList, IntoResult, NodeMask, PipelineState,
+    PipelineStateStream, QueryHeap, Resource, RootSignature, Shader, TextureAddressMode,
 };
-use std::ops::Range;
-use winapi::{um::d3d12, Interface};
+use crate::{
+    ClearValue, ResourceDesc, ResourceDescError, ResourceDimension, ResourceStates, TextureLayout,
+};
+use crate::{
+    ConstantBufferViewDesc, DepthStencilViewDesc, RenderTargetViewDesc, ShaderResourceViewDesc,
+    UnorderedAccessViewDesc,
+};
+use std::{ops::Range, ptr};
+use winapi::{shared::winerror::E_INVALIDARG, um::d3d12, Interface};
 
 pub type Device = WeakPtr<d3d12::ID3D12Device>;
 
+unsafe impl ComSend for d3d12::ID3D12Device {}
+unsafe impl ComSync for d3d12::ID3D12Device {}
+
+pub type Device2 = WeakPtr<d3d12::ID3D12Device2>;
+
+unsafe impl ComSend for d3d12::ID3D12Device2 {}
+unsafe impl ComSync for d3d12::ID3D12Device2 {}
+
 #[cfg(feature = "libloading")]
 impl crate::D3D12Lib {
+    #[track_caller]
     pub fn create_device<I: Interface>(
         &self,
         adapter: WeakPtr<I>,
@@ -39,12 +55,24 @@ impl crate::D3D12Lib {
             )
         };
 
+        crate::ledger::track_created(&device);
         Ok((device, hr))
     }
+
+    #[track_caller]
+    pub fn try_create_device<I: Interface>(
+        &self,
+        adapter: WeakPtr<I>,
+        feature_level: crate::FeatureLevel,
+    ) -> Result<Result<Device, D3DError>, libloading::Error> {
+        self.create_device(adapter, feature_level)
+            .map(|result| result.into_result("D3D12CreateDevice"))
+    }
 }
 
 impl Device {
     #[cfg(feature = "implicit-link")]
+    #[track_caller]
     pub fn create<I: Interface>(
         adapter: WeakPtr<I>,
         feature_level: crate::FeatureLevel,
@@ -59,9 +87,11 @@ impl Device {
             )
         };
 
+        crate::ledger::track_created(&device);
         (device, hr)
     }
 
+    #[track_caller]
     pub fn create_heap(
         &self,
         size_in_bytes: u64,
@@ -80,9 +110,111 @@ impl Device {
 
         let hr = unsafe { self.CreateHeap(&desc, &d3d12::ID3D12Heap::uuidof(), heap.mut_void()) };
 
+        crate::ledger::track_created(&heap);
         (heap, hr)
     }
 
+    /// Create a resource with its own implicit heap.
+    ///
+    /// Descriptions failing `ResourceDesc::validate` return `E_INVALIDARG` without
+    /// reaching the runtime.
+    #[track_caller]
+    pub fn create_committed_resource(
+        &self,
+        properties: HeapProperties,
+        heap_flags: HeapFlags,
+        desc: &ResourceDesc,
+        initial_state: ResourceStates,
+        clear_value: Option<&ClearValue>,
+    ) -> D3DResult<Resource> {
+        let mut resource = Resource::null();
+        if validate_resource(desc, clear_value).is_err() {
+            return (resource, E_INVALIDARG);
+        }
+
+        let clear_value = clear_value.map(|value| value.to_raw());
+        let hr = unsafe {
+            self.CreateCommittedResource(
+                &properties.0,
+                heap_flags.bits(),
+                &desc.to_raw(),
+                initial_state.bits(),
+                clear_value.as_ref().map_or(ptr::null(), |value| value),
+                &d3d12::ID3D12Resource::uuidof(),
+                resource.mut_void(),
+            )
+        };
+
+        crate::ledger::track_created(&resource);
+        (resource, hr)
+    }
+
+    /// Create a resource at `offset` in `heap`, which has to be aligned to
+    /// `ResourceDesc::placement_alignment`.
+    #[track_caller]
+    pub fn create_placed_resource(
+        &self,
+        heap: &Heap,
+        offset: u64,
+        desc: &ResourceDesc,
+        initial_state: ResourceStates,
+        clear_value: Option<&ClearValue>,
+    ) -> D3DResult<Resource> {
+        let mut resource = Resource::null();
+        if validate_resource(desc, clear_value).is_err()
+            || !is_aligned(offset, desc.placement_alignment())
+        {
+            return (resource, E_INVALIDARG);
+        }
+
+        let clear_value = clear_value.map(|value| value.to_raw());
+        let hr = unsafe {
+            self.CreatePlacedResource(
+                heap.as_mut_ptr(),
+                offset,
+                &desc.to_raw(),
+                initial_state.bits(),
+                clear_value.as_ref().map_or(ptr::null(), |value| value),
+                &d3d12::ID3D12Resource::uuidof(),
+                resource.mut_void(),
+            )
+        };
+
+        crate::ledger::track_created(&resource);
+        (resource, hr)
+    }
+
+    /// Create a tiled resource without backing memory.
+    ///
+    /// Textures need one of the 64KB layouts.
+    #[track_caller]
+    pub fn create_reserved_resource(
+        &self,
+        desc: &ResourceDesc,
+        initial_state: ResourceStates,
+        clear_value: Option<&ClearValue>,
+    ) -> D3DResult<Resource> {
+        let mut resource = Resource::null();
+        if validate_reserved_resource(desc, clear_value).is_err() {
+            return (resource, E_INVALIDARG);
+        }
+
+        let clear_value = clear_value.map(|value| value.to_raw());
+        let hr = unsafe {
+            self.CreateReservedResource(
+                &desc.to_raw(),
+                initial_state.bits(),
+                clear_value.as_ref().map_or(ptr::null(), |value| value),
+                &d3d12::ID3D12Resource::uuidof(),
+                resource.mut_void(),
+            )
+        };
+
+        crate::ledger::track_created(&resource);
+        (resource, hr)
+    }
+
+    #[track_caller]
     pub fn create_command_allocator(&self, list_type: CmdListType) -> D3DResult<CommandAllocator> {
         let mut allocator = CommandAllocator::null();
         let hr = unsafe {
@@ -93,9 +225,11 @@ impl Device {
             )
         };
 
+        crate::ledger::track_created(&allocator);
         (allocator, hr)
     }
 
+    #[track_caller]
     pub fn create_command_queue(
         &self,
         list_type: CmdListType,
@@ -119,9 +253,11 @@ impl Device {
             )
         };
 
+        crate::ledger::track_created(&queue);
         (queue, hr)
     }
 
+    #[track_caller]
     pub fn create_descriptor_heap(
         &self,
         num_descriptors: u32,
@@ -145,6 +281,7 @@ impl Device {
             )
         };
 
+        crate::ledger::track_created(&heap);
         (heap, hr)
     }
 
@@ -152,6 +289,58 @@ impl Device {
         unsafe { self.GetDescriptorHandleIncrementSize(heap_type as _) }
     }
 
+    /// Copy `count` descriptors from `src` to `dest`.
+    ///
+    /// The source has to be in a heap which isn't shader visible.
+    pub fn copy_descriptors_simple(
+        &self,
+        count: u32,
+        dest: CpuDescriptor,
+        src: CpuDescriptor,
+        heap_type: DescriptorHeapType,
+    ) {
+        unsafe { self.CopyDescriptorsSimple(count, dest, src, heap_type as _) }
+    }
+
+    /// Copy the `(start, count)` ranges in `src` to the ranges in `dest`, which can be split
+    /// differently but have to contain the same total number of descriptors.
+    pub fn copy_descriptors(
+        &self,
+        dest: &[(CpuDescriptor, u32)],
+        src: &[(CpuDescriptor, u32)],
+        heap_type: DescriptorHeapType,
+    ) {
+        debug_assert_eq!(
+            dest.iter().map(|&(_, count)| count as u64).sum::<u64>(),
+            src.iter().map(|&(_, count)| count as u64).sum::<u64>(),
+        );
+        let (dest_starts, dest_sizes): (Vec<_>, Vec<_>) = dest.iter().cloned().unzip();
+        let (src_starts, src_sizes): (Vec<_>, Vec<_>) = src.iter().cloned().unzip();
+        unsafe {
+            self.CopyDescriptors(
+                dest.len() as _,
+                dest_starts.as_ptr(),
+                dest_sizes.as_ptr(),
+                src.len() as _,
+                src_starts.as_ptr(),
+                src_sizes.as_ptr(),
+                heap_type as _,
+            )
+        }
+    }
+
+    /// Stage `count` descriptors starting at `src` into the heap of `dest`.
+    ///
+    /// Panics if either range exceeds its heap, the heap types differ or the source heap is
+    /// shader visible.
+    pub fn copy_descriptor_range(&self, dest: DescriptorHandle, src: DescriptorHandle, count: u32) {
+        assert!(count <= dest.remaining() && count <= src.remaining());
+        assert_eq!(dest.heap_type(), src.heap_type());
+        assert!(!src.is_shader_visible());
+        self.copy_descriptors_simple(count, dest.cpu(), src.cpu(), dest.heap_type());
+    }
+
+    #[track_caller]
     pub fn create_graphics_command_list(
         &self,
         list_type: CmdListType,
@@ -171,9 +360,11 @@ impl Device {
             )
         };
 
+        crate::ledger::track_created(&command_list);
         (command_list, hr)
     }
 
+    #[track_caller]
     pub fn create_query_heap(
         &self,
         heap_ty: query::QueryHeapType,
@@ -195,24 +386,35 @@ impl Device {
             )
         };
 
+        crate::ledger::track_created(&query_heap);
         (query_heap, hr)
     }
 
+    /// Descriptions with more than 8 render target formats return `E_INVALIDARG` without
+    /// reaching the runtime.
+    #[track_caller]
     pub fn create_graphics_pipeline_state(
         &self,
-        _root_signature: RootSignature,
-        _vs: Shader,
-        _ps: Shader,
-        _gs: Shader,
-        _hs: Shader,
-        _ds: Shader,
-        _node_mask: NodeMask,
-        _cached_pso: CachedPSO,
-        _flags: pso::PipelineStateFlags,
+        desc: &pso::GraphicsPipelineDesc,
     ) -> D3DResult<PipelineState> {
-        unimplemented!()
+        let mut pipeline = PipelineState::null();
+        let raw = match desc.to_raw() {
+            Some(raw) => raw,
+            None => return (pipeline, E_INVALIDARG),
+        };
+        let hr = unsafe {
+            self.CreateGraphicsPipelineState(
+                &raw,
+                &d3d12::ID3D12PipelineState::uuidof(),
+                pipeline.mut_void(),
+            )
+        };
+
+        crate::ledger::track_created(&pipeline);
+        (pipeline, hr)
     }
 
+    #[track_caller]
     pub fn create_compute_pipeline_state(
         &self,
         root_signature: RootSignature,
@@ -238,9 +440,11 @@ impl Device {
             )
         };
 
+        crate::ledger::track_created(&pipeline);
         (pipeline, hr)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn create_sampler(
         &self,
         sampler: CpuDescriptor,
@@ -270,6 +474,7 @@ impl Device {
         }
     }
 
+    #[track_caller]
     pub fn create_root_signature(
         &self,
         blob: Blob,
@@ -286,9 +491,11 @@ impl Device {
             )
         };
 
+        crate::ledger::track_created(&signature);
         (signature, hr)
     }
 
+    #[track_caller]
     pub fn create_command_signature(
         &self,
         root_signature: RootSignature,
@@ -313,6 +520,7 @@ impl Device {
             )
         };
 
+        crate::ledger::track_created(&signature);
         (signature, hr)
     }
 
@@ -327,7 +535,72 @@ impl Device {
         }
     }
 
+    /// Create a view with the default description of `resource` if `desc` is `None`.
+    ///
+    /// Acceleration structure views are created with a null `resource`.
+    pub fn create_shader_resource_view(
+        &self,
+        resource: Resource,
+        desc: Option<&ShaderResourceViewDesc>,
+        descriptor: CpuDescriptor,
+    ) {
+        if let Some(desc) = desc {
+            debug_assert!(!desc.is_acceleration_structure() || resource.is_null());
+        }
+        unsafe {
+            self.CreateShaderResourceView(
+                resource.as_mut_ptr(),
+                desc.map_or(ptr::null(), |desc| &desc.0),
+                descriptor,
+            );
+        }
+    }
+
+    /// `counter` holds the hidden counter of structured buffer views, it may be null.
+    pub fn create_unordered_access_view(
+        &self,
+        resource: Resource,
+        counter: Resource,
+        desc: Option<&UnorderedAccessViewDesc>,
+        descriptor: CpuDescriptor,
+    ) {
+        unsafe {
+            self.CreateUnorderedAccessView(
+                resource.as_mut_ptr(),
+                counter.as_mut_ptr(),
+                desc.map_or(ptr::null(), |desc| &desc.0),
+                descriptor,
+            );
+        }
+    }
+
+    pub fn create_constant_buffer_view(
+        &self,
+        desc: &ConstantBufferViewDesc,
+        descriptor: CpuDescriptor,
+    ) {
+        unsafe {
+            self.CreateConstantBufferView(&desc.0, descriptor);
+        }
+    }
+
+    pub fn create_depth_stencil_view(
+        &self,
+        resource: Resource,
+        desc: Option<&DepthStencilViewDesc>,
+        descriptor: CpuDescriptor,
+    ) {
+        unsafe {
+            self.CreateDepthStencilView(
+                resource.as_mut_ptr(),
+                desc.map_or(ptr::null(), |desc| &desc.0),
+                descriptor,
+            );
+        }
+    }
+
     // TODO: interface not complete
+    #[track_caller]
     pub fn create_fence(&self, initial: u64) -> D3DResult<Fence> {
         let mut fence = Fence::null();
         let hr = unsafe {
@@ -339,6 +612,254 @@ impl Device {
             )
         };
 
+        crate::ledger::track_created(&fence);
         (fence, hr)
     }
 }
+
+// `Result` based variants of the constructors above.
+impl Device {
+    #[cfg(feature = "implicit-link")]
+    #[track_caller]
+    pub fn try_create<I: Interface>(
+        adapter: WeakPtr<I>,
+        feature_level: crate::FeatureLevel,
+    ) -> Result<Self, D3DError> {
+        Self::create(adapter, feature_level).into_result("D3D12CreateDevice")
+    }
+
+    #[track_caller]
+    pub fn try_create_heap(
+        &self,
+        size_in_bytes: u64,
+        properties: HeapProperties,
+        alignment: u64,
+        flags: HeapFlags,
+    ) -> Result<Heap, D3DError> {
+        self.create_heap(size_in_bytes, properties, alignment, flags)
+            .into_result("ID3D12Device::CreateHeap")
+    }
+
+    #[track_caller]
+    pub fn try_create_committed_resource(
+        &self,
+        properties: HeapProperties,
+        heap_flags: HeapFlags,
+        desc: &ResourceDesc,
+        initial_state: ResourceStates,
+        clear_value: Option<&ClearValue>,
+    ) -> Result<Resource, D3DError> {
+        const CALL: &str = "ID3D12Device::CreateCommittedResource";
+        validate_resource(desc, clear_value).map_err(|err| invalid_resource(CALL, err))?;
+        self.create_committed_resource(properties, heap_flags, desc, initial_state, clear_value)
+            .into_result(CALL)
+    }
+
+    #[track_caller]
+    pub fn try_create_placed_resource(
+        &self,
+        heap: &Heap,
+        offset: u64,
+        desc: &ResourceDesc,
+        initial_state: ResourceStates,
+        clear_value: Option<&ClearValue>,
+    ) -> Result<Resource, D3DError> {
+        const CALL: &str = "ID3D12Device::CreatePlacedResource";
+        validate_resource(desc, clear_value).map_err(|err| invalid_resource(CALL, err))?;
+        if !is_aligned(offset, desc.placement_alignment()) {
+            return Err(D3DError::new(CALL, E_INVALIDARG).with_message(format!(
+                "heap offset {} isn't aligned to {}",
+                offset,
+                desc.placement_alignment()
+            )));
+        }
+        self.create_placed_resource(heap, offset, desc, initial_state, clear_value)
+            .into_result(CALL)
+    }
+
+    #[track_caller]
+    pub fn try_create_reserved_resource(
+        &self,
+        desc: &ResourceDesc,
+        initial_state: ResourceStates,
+        clear_value: Option<&ClearValue>,
+    ) -> Result<Resource, D3DError> {
+        const CALL: &str = "ID3D12Device::CreateReservedResource";
+        validate_reserved_resource(desc, clear_value).map_err(|err| invalid_resource(CALL, err))?;
+        self.create_reserved_resource(desc, initial_state, clear_value)
+            .into_result(CALL)
+    }
+
+    #[track_caller]
+    pub fn try_create_command_allocator(
+        &self,
+        list_type: CmdListType,
+    ) -> Result<CommandAllocator, D3DError> {
+        self.create_command_allocator(list_type)
+            .into_result("ID3D12Device::CreateCommandAllocator")
+    }
+
+    #[track_caller]
+    pub fn try_create_command_queue(
+        &self,
+        list_type: CmdListType,
+        priority: queue::Priority,
+        flags: queue::CommandQueueFlags,
+        node_mask: NodeMask,
+    ) -> Result<CommandQueue, D3DError> {
+        self.create_command_queue(list_type, priority, flags, node_mask)
+            .into_result("ID3D12Device::CreateCommandQueue")
+    }
+
+    #[track_caller]
+    pub fn try_create_descriptor_heap(
+        &self,
+        num_descriptors: u32,
+        heap_type: DescriptorHeapType,
+        flags: DescriptorHeapFlags,
+        node_mask: NodeMask,
+    ) -> Result<DescriptorHeap, D3DError> {
+        self.create_descriptor_heap(num_descriptors, heap_type, flags, node_mask)
+            .into_result("ID3D12Device::CreateDescriptorHeap")
+    }
+
+    #[track_caller]
+    pub fn try_create_graphics_command_list(
+        &self,
+        list_type: CmdListType,
+        allocator: CommandAllocator,
+        initial: PipelineState,
+        node_mask: NodeMask,
+    ) -> Result<GraphicsCommandList, D3DError> {
+        self.create_graphics_command_list(list_type, allocator, initial, node_mask)
+            .into_result("ID3D12Device::CreateCommandList")
+    }
+
+    #[track_caller]
+    pub fn try_create_query_heap(
+        &self,
+        heap_ty: query::QueryHeapType,
+        count: u32,
+        node_mask: NodeMask,
+    ) -> Result<QueryHeap, D3DError> {
+        self.create_query_heap(heap_ty, count, node_mask)
+            .into_result("ID3D12Device::CreateQueryHeap")
+    }
+
+    #[track_caller]
+    pub fn try_create_graphics_pipeline_state(
+        &self,
+        desc: &pso::GraphicsPipelineDesc,
+    ) -> Result<PipelineState, D3DError> {
+        const CALL: &str = "ID3D12Device::CreateGraphicsPipelineState";
+        if desc.rtv_formats.len() > 8 {
+            return Err(D3DError::new(CALL, E_INVALIDARG).with_message(format!(
+                "{} render target formats, at most 8 are supported",
+                desc.rtv_formats.len()
+            )));
+        }
+        self.create_graphics_pipeline_state(desc).into_result(CALL)
+    }
+
+    #[track_caller]
+    pub fn try_create_compute_pipeline_state(
+        &self,
+        root_signature: RootSignature,
+        cs: Shader,
+        node_mask: NodeMask,
+        cached_pso: CachedPSO,
+        flags: pso::PipelineStateFlags,
+    ) -> Result<PipelineState, D3DError> {
+        self.create_compute_pipeline_state(root_signature, cs, node_mask, cached_pso, flags)
+            .into_result("ID3D12Device::CreateComputePipelineState")
+    }
+
+    #[track_caller]
+    pub fn try_create_root_signature(
+        &self,
+        blob: Blob,
+        node_mask: NodeMask,
+    ) -> Result<RootSignature, D3DError> {
+        self.create_root_signature(blob, node_mask)
+            .into_result("ID3D12Device::CreateRootSignature")
+    }
+
+    #[track_caller]
+    pub fn try_create_command_signature(
+        &self,
+        root_signature: RootSignature,
+        arguments: &[IndirectArgument],
+        stride: u32,
+        node_mask: NodeMask,
+    ) -> Result<CommandSignature, D3DError> {
+        self.create_command_signature(root_signature, arguments, stride, node_mask)
+            .into_result("ID3D12Device::CreateCommandSignature")
+    }
+
+    #[track_caller]
+    pub fn try_create_fence(&self, initial: u64) -> Result<Fence, D3DError> {
+        self.create_fence(initial)
+            .into_result("ID3D12Device::CreateFence")
+    }
+}
+
+fn validate_resource(
+    desc: &ResourceDesc,
+    clear_value: Option<&ClearValue>,
+) -> Result<(), ResourceDescError> {
+    desc.validate()?;
+    match clear_value {
+        Some(clear_value) => desc.validate_clear_value(clear_value),
+        None => Ok(()),
+    }
+}
+
+fn validate_reserved_resource(
+    desc: &ResourceDesc,
+    clear_value: Option<&ClearValue>,
+) -> Result<(), ResourceDescError> {
+    validate_resource(desc, clear_value)?;
+    match desc.layout() {
+        TextureLayout::Unknown | TextureLayout::RowMajor
+            if desc.dimension() != ResourceDimension::Buffer =>
+        {
+            Err(ResourceDescError::InvalidLayout(desc.layout()))
+        }
+        _ => Ok(()),
+    }
+}
+
+// Placement alignments are powers of two.
+fn is_aligned(offset: u64, alignment: u64) -> bool {
+    offset & (alignment - 1) == 0
+}
+
+fn invalid_resource(call: &'static str, err: ResourceDescError) -> D3DError {
+    D3DError::new(call, E_INVALIDARG).with_message(err.to_string())
+}
+
+impl Device2 {
+    #[track_caller]
+    pub fn create_pipeline_state(&self, stream: &PipelineStateStream) -> D3DResult<PipelineState> {
+        let mut pipeline = PipelineState::null();
+        let hr = unsafe {
+            self.CreatePipelineState(
+                &stream.desc(),
+                &d3d12::ID3D12PipelineState::uuidof(),
+                pipeline.mut_void(),
+            )
+        };
+
+        crate::ledger::track_created(&pipeline);
+        (pipeline, hr)
+    }
+
+    #[track_caller]
+    pub fn try_create_pipeline_state(
+        &self,
+        stream: &PipelineStateStream,
+    ) -> Result<PipelineState, D3DError> {
+        self.create_pipeline_state(stream)
+            .into_result("ID3D12Device2::CreatePipelineState")
+    }
+}
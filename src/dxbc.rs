@@ -0,0 +1,163 @@
+//! DXBC containers
+//!
+//! Container format of compiled shaders and serialized root signatures, a header with a
+//! checksum followed by a list of parts tagged with a four character code.
+
+use std::convert::TryInto;
+
+pub type FourCC = [u8; 4];
+
+/// Part holding a serialized root signature.
+pub const ROOT_SIGNATURE_PART: FourCC = *b"RTS0";
+
+const MAGIC: FourCC = *b"DXBC";
+const HEADER_SIZE: usize = 32;
+// Everything after the magic and the checksum is hashed.
+const HASHED_START: usize = 20;
+
+/// Build a container from parts, in order.
+pub fn build_dxbc_container(parts: &[(FourCC, &[u8])]) -> Vec<u8> {
+    let offsets_size = 4 * parts.len();
+    let parts_size: usize = parts.iter().map(|(_, data)| 8 + data.len()).sum();
+    let size = HEADER_SIZE + offsets_size + parts_size;
+
+    let mut container = Vec::with_capacity(size);
+    container.extend_from_slice(&MAGIC);
+    container.extend_from_slice(&[0; 16]);
+    container.extend_from_slice(&1u16.to_le_bytes());
+    container.extend_from_slice(&0u16.to_le_bytes());
+    container.extend_from_slice(&(size as u32).to_le_bytes());
+    container.extend_from_slice(&(parts.len() as u32).to_le_bytes());
+
+    let mut offset = HEADER_SIZE + offsets_size;
+    for (_, data) in parts {
+        container.extend_from_slice(&(offset as u32).to_le_bytes());
+        offset += 8 + data.len();
+    }
+    for (fourcc, data) in parts {
+        container.extend_from_slice(fourcc);
+        container.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        container.extend_from_slice(data);
+    }
+
+    let checksum = dxbc_checksum(&container[HASHED_START..]);
+    container[4..HASHED_START].copy_from_slice(&checksum);
+    container
+}
+
+/// Parts of a container in order, `None` if `data` isn't a well formed container.
+///
+/// The checksum isn't verified.
+pub fn dxbc_parts(data: &[u8]) -> Option<Vec<(FourCC, &[u8])>> {
+    let word = |offset: usize| -> Option<u32> {
+        let bytes = data.get(offset..offset.checked_add(4)?)?;
+        Some(u32::from_le_bytes(bytes.try_into().unwrap()))
+    };
+
+    if data.get(..4)? != MAGIC || word(24)? as usize != data.len() {
+        return None;
+    }
+    let count = word(28)? as usize;
+    let mut parts = Vec::new();
+    for i in 0..count {
+        let offset = word(HEADER_SIZE + 4 * i)? as usize;
+        let fourcc = data
+            .get(offset..offset.checked_add(4)?)?
+            .try_into()
+            .unwrap();
+        let size = word(offset + 4)? as usize;
+        let start = offset + 8;
+        parts.push((fourcc, data.get(start..start.checked_add(size)?)?));
+    }
+    Some(parts)
+}
+
+/// Contents of the first part tagged with `fourcc`.
+pub fn find_dxbc_part(data: &[u8], fourcc: FourCC) -> Option<&[u8]> {
+    dxbc_parts(data)?
+        .into_iter()
+        .find(|&(part, _)| part == fourcc)
+        .map(|(_, contents)| contents)
+}
+
+const MD5_SHIFTS: [u32; 16] = [7, 12, 17, 22, 5, 9, 14, 20, 4, 11, 16, 23, 6, 10, 15, 21];
+const MD5_CONSTANTS: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+fn md5_block(state: &mut [u32; 4], block: &[u8]) {
+    let mut x = [0u32; 16];
+    for (word, bytes) in x.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().unwrap());
+    }
+
+    let [mut a, mut b, mut c, mut d] = *state;
+    for i in 0..64 {
+        let (f, g) = match i / 16 {
+            0 => ((b & c) | (!b & d), i),
+            1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+            2 => (b ^ c ^ d, (3 * i + 5) % 16),
+            _ => (c ^ (b | !d), (7 * i) % 16),
+        };
+        let rotated = a
+            .wrapping_add(f)
+            .wrapping_add(MD5_CONSTANTS[i])
+            .wrapping_add(x[g])
+            .rotate_left(MD5_SHIFTS[i / 16 * 4 + i % 4]);
+        a = d;
+        d = c;
+        c = b;
+        b = b.wrapping_add(rotated);
+    }
+
+    state[0] = state[0].wrapping_add(a);
+    state[1] = state[1].wrapping_add(b);
+    state[2] = state[2].wrapping_add(c);
+    state[3] = state[3].wrapping_add(d);
+}
+
+/// Checksum of a container, computed over everything after the checksum field.
+///
+/// MD5 with a modified padding: the bit length is stored in the first word of the last
+/// block and `2 * len + 1` in its last word.
+pub fn dxbc_checksum(data: &[u8]) -> [u8; 16] {
+    let mut state = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+    let full = data.len() & !63;
+    for block in data[..full].chunks_exact(64) {
+        md5_block(&mut state, block);
+    }
+
+    let tail = &data[full..];
+    let bits = (data.len() as u32).wrapping_shl(3).to_le_bytes();
+    let end = (data.len() as u32)
+        .wrapping_shl(1)
+        .wrapping_add(1)
+        .to_le_bytes();
+    let mut block = [0u8; 64];
+    if tail.len() < 56 {
+        block[..4].copy_from_slice(&bits);
+        block[4..4 + tail.len()].copy_from_slice(tail);
+        block[4 + tail.len()] = 0x80;
+    } else {
+        block[..tail.len()].copy_from_slice(tail);
+        block[tail.len()] = 0x80;
+        md5_block(&mut state, &block);
+        block = [0; 64];
+        block[..4].copy_from_slice(&bits);
+    }
+    block[60..].copy_from_slice(&end);
+    md5_block(&mut state, &block);
+
+    let mut checksum = [0; 16];
+    for (bytes, word) in checksum.chunks_exact_mut(4).zip(state.iter()) {
+        bytes.copy_from_slice(&word.to_le_bytes());
+    }
+    checksum
+}
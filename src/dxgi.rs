@@ -1,4 +1,4 @@
-use crate::{com::WeakPtr, D3DResult, Resource, SampleDesc, HRESULT};
+use crate::{com::WeakPtr, D3DError, D3DResult, IntoResult, Resource, SampleDesc, HRESULT};
 use std::ptr;
 use winapi::{
     shared::{
@@ -102,30 +102,33 @@ impl DxgiLib {
         unsafe { libloading::Library::new("dxgi.dll").map(|lib| DxgiLib { lib }) }
     }
 
+    #[track_caller]
     pub fn create_factory2(
         &self,
         flags: FactoryCreationFlags,
-    ) -> Result<D3DResult<Factory4>, libloading::Error> {
+    ) -> Result<D3DResult<DxgiFactory>, libloading::Error> {
         type Fun = extern "system" fn(
             winapi::shared::minwindef::UINT,
             winapi::shared::guiddef::REFIID,
             *mut *mut winapi::ctypes::c_void,
         ) -> HRESULT;
 
-        let mut factory = Factory4::null();
+        let mut factory = Factory1::null();
         let hr = unsafe {
             let func: libloading::Symbol<Fun> = self.lib.get(b"CreateDXGIFactory2")?;
             func(
                 flags.bits(),
-                &dxgi1_4::IDXGIFactory4::uuidof(),
+                &dxgi::IDXGIFactory1::uuidof(),
                 factory.mut_void(),
             )
         };
 
-        Ok((factory, hr))
+        crate::ledger::track_created(&factory);
+        Ok((unsafe { DxgiFactory::probe(factory) }, hr))
     }
 
-    pub fn create_factory1(&self) -> Result<D3DResult<Factory1>, libloading::Error> {
+    #[track_caller]
+    pub fn create_factory1(&self) -> Result<D3DResult<DxgiFactory>, libloading::Error> {
         type Fun = extern "system" fn(
             winapi::shared::guiddef::REFIID,
             *mut *mut winapi::ctypes::c_void,
@@ -137,9 +140,11 @@ impl DxgiLib {
             func(&dxgi::IDXGIFactory1::uuidof(), factory.mut_void())
         };
 
-        Ok((factory, hr))
+        crate::ledger::track_created(&factory);
+        Ok((unsafe { DxgiFactory::probe(factory) }, hr))
     }
 
+    #[track_caller]
     pub fn get_debug_interface1(&self) -> Result<D3DResult<InfoQueue>, libloading::Error> {
         type Fun = extern "system" fn(
             winapi::shared::minwindef::UINT,
@@ -152,8 +157,32 @@ impl DxgiLib {
             let func: libloading::Symbol<Fun> = self.lib.get(b"DXGIGetDebugInterface1")?;
             func(0, &dxgidebug::IDXGIInfoQueue::uuidof(), queue.mut_void())
         };
+        crate::ledger::track_created(&queue);
         Ok((queue, hr))
     }
+
+    #[track_caller]
+    pub fn try_create_factory2(
+        &self,
+        flags: FactoryCreationFlags,
+    ) -> Result<Result<DxgiFactory, D3DError>, libloading::Error> {
+        self.create_factory2(flags)
+            .map(|result| result.into_result("CreateDXGIFactory2"))
+    }
+
+    #[track_caller]
+    pub fn try_create_factory1(&self) -> Result<Result<DxgiFactory, D3DError>, libloading::Error> {
+        self.create_factory1()
+            .map(|result| result.into_result("CreateDXGIFactory1"))
+    }
+
+    #[track_caller]
+    pub fn try_get_debug_interface1(
+        &self,
+    ) -> Result<Result<InfoQueue, D3DError>, libloading::Error> {
+        self.get_debug_interface1()
+            .map(|result| result.into_result("DXGIGetDebugInterface1"))
+    }
 }
 
 // TODO: strong types
@@ -192,12 +221,15 @@ impl SwapchainDesc {
 }
 
 impl Factory1 {
+    // `queue` is only passed on to DXGI.
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    #[track_caller]
     pub fn create_swapchain(
         &self,
         queue: *mut IUnknown,
         hwnd: HWND,
         desc: &SwapchainDesc,
-    ) -> D3DResult<SwapChain> {
+    ) -> D3DResult<DxgiSwapchain> {
         let mut desc = dxgi::DXGI_SWAP_CHAIN_DESC {
             BufferDesc: dxgitype::DXGI_MODE_DESC {
                 Width: desc.width,
@@ -226,19 +258,34 @@ impl Factory1 {
         let hr =
             unsafe { self.CreateSwapChain(queue, &mut desc, swapchain.mut_void() as *mut *mut _) };
 
-        (swapchain, hr)
+        crate::ledger::track_created(&swapchain);
+        (unsafe { DxgiSwapchain::probe(swapchain) }, hr)
+    }
+
+    #[track_caller]
+    pub fn try_create_swapchain(
+        &self,
+        queue: *mut IUnknown,
+        hwnd: HWND,
+        desc: &SwapchainDesc,
+    ) -> Result<DxgiSwapchain, D3DError> {
+        self.create_swapchain(queue, hwnd, desc)
+            .into_result("IDXGIFactory::CreateSwapChain")
     }
 }
 
 impl Factory2 {
     // TODO: interface not complete
+    // `queue` is only passed on to DXGI.
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    #[track_caller]
     pub fn create_swapchain_for_hwnd(
         &self,
         queue: *mut IUnknown,
         hwnd: HWND,
         desc: &SwapchainDesc,
-    ) -> D3DResult<SwapChain1> {
-        let mut swap_chain = SwapChain1::null();
+    ) -> D3DResult<DxgiSwapchain> {
+        let mut swap_chain = SwapChain::null();
         let hr = unsafe {
             self.CreateSwapChainForHwnd(
                 queue,
@@ -250,15 +297,19 @@ impl Factory2 {
             )
         };
 
-        (swap_chain, hr)
+        crate::ledger::track_created(&swap_chain);
+        (unsafe { DxgiSwapchain::probe(swap_chain) }, hr)
     }
 
+    // `queue` is only passed on to DXGI.
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    #[track_caller]
     pub fn create_swapchain_for_composition(
         &self,
         queue: *mut IUnknown,
         desc: &SwapchainDesc,
-    ) -> D3DResult<SwapChain1> {
-        let mut swap_chain = SwapChain1::null();
+    ) -> D3DResult<DxgiSwapchain> {
+        let mut swap_chain = SwapChain::null();
         let hr = unsafe {
             self.CreateSwapChainForComposition(
                 queue,
@@ -268,30 +319,70 @@ impl Factory2 {
             )
         };
 
-        (swap_chain, hr)
+        crate::ledger::track_created(&swap_chain);
+        (unsafe { DxgiSwapchain::probe(swap_chain) }, hr)
+    }
+
+    #[track_caller]
+    pub fn try_create_swapchain_for_hwnd(
+        &self,
+        queue: *mut IUnknown,
+        hwnd: HWND,
+        desc: &SwapchainDesc,
+    ) -> Result<DxgiSwapchain, D3DError> {
+        self.create_swapchain_for_hwnd(queue, hwnd, desc)
+            .into_result("IDXGIFactory2::CreateSwapChainForHwnd")
+    }
+
+    #[track_caller]
+    pub fn try_create_swapchain_for_composition(
+        &self,
+        queue: *mut IUnknown,
+        desc: &SwapchainDesc,
+    ) -> Result<DxgiSwapchain, D3DError> {
+        self.create_swapchain_for_composition(queue, desc)
+            .into_result("IDXGIFactory2::CreateSwapChainForComposition")
     }
 }
 
-impl Factory4 {
+impl DxgiFactory {
     #[cfg(feature = "implicit-link")]
+    #[track_caller]
     pub fn create(flags: FactoryCreationFlags) -> D3DResult<Self> {
-        let mut factory = Factory4::null();
+        let mut factory = Factory1::null();
         let hr = unsafe {
             dxgi1_3::CreateDXGIFactory2(
                 flags.bits(),
-                &dxgi1_4::IDXGIFactory4::uuidof(),
+                &dxgi::IDXGIFactory1::uuidof(),
                 factory.mut_void(),
             )
         };
 
-        (factory, hr)
+        crate::ledger::track_created(&factory);
+        (unsafe { Self::probe(factory) }, hr)
     }
 
-    pub fn enumerate_adapters(&self, id: u32) -> D3DResult<Adapter1> {
+    #[cfg(feature = "implicit-link")]
+    #[track_caller]
+    pub fn try_create(flags: FactoryCreationFlags) -> Result<Self, D3DError> {
+        Self::create(flags).into_result("CreateDXGIFactory2")
+    }
+}
+
+impl Factory1 {
+    #[track_caller]
+    pub fn enumerate_adapters(&self, id: u32) -> D3DResult<DxgiAdapter> {
         let mut adapter = Adapter1::null();
         let hr = unsafe { self.EnumAdapters1(id, adapter.mut_void() as *mut *mut _) };
 
-        (adapter, hr)
+        crate::ledger::track_created(&adapter);
+        (unsafe { DxgiAdapter::probe(adapter) }, hr)
+    }
+
+    #[track_caller]
+    pub fn try_enumerate_adapters(&self, id: u32) -> Result<DxgiAdapter, D3DError> {
+        self.enumerate_adapters(id)
+            .into_result("IDXGIFactory1::EnumAdapters1")
     }
 }
 
@@ -310,14 +401,21 @@ bitflags! {
 }
 
 impl SwapChain {
+    #[track_caller]
     pub fn get_buffer(&self, id: u32) -> D3DResult<Resource> {
         let mut resource = Resource::null();
         let hr =
             unsafe { self.GetBuffer(id, &d3d12::ID3D12Resource::uuidof(), resource.mut_void()) };
 
+        crate::ledger::track_created(&resource);
         (resource, hr)
     }
 
+    #[track_caller]
+    pub fn try_get_buffer(&self, id: u32) -> Result<Resource, D3DError> {
+        self.get_buffer(id).into_result("IDXGISwapChain::GetBuffer")
+    }
+
     //TODO: replace by present_flags
     pub fn present(&self, interval: u32, flags: u32) -> HRESULT {
         unsafe { self.Present(interval, flags) }
@@ -333,3 +431,66 @@ impl SwapChain3 {
         unsafe { self.GetCurrentBackBufferIndex() }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::com_object::{unimplemented_vtable, ComObject};
+
+    type FactoryObject = ComObject<dxgi1_6::IDXGIFactory6Vtbl, ()>;
+
+    // Object implementing the factory interfaces up to `interfaces`.
+    fn factory(interfaces: &[winapi::shared::guiddef::GUID]) -> Factory1 {
+        unsafe {
+            FactoryObject::create::<dxgi::IDXGIFactory1>(unimplemented_vtable(), interfaces, ())
+        }
+    }
+
+    fn ref_count(factory: DxgiFactory) -> u32 {
+        unsafe { FactoryObject::from_interface(factory.as_mut_ptr()) }.ref_count()
+    }
+
+    #[test]
+    fn probe() {
+        let ids = [
+            dxgi1_2::IDXGIFactory2::uuidof(),
+            dxgi1_3::IDXGIFactory3::uuidof(),
+            dxgi1_4::IDXGIFactory4::uuidof(),
+            dxgi1_5::IDXGIFactory5::uuidof(),
+            dxgi1_6::IDXGIFactory6::uuidof(),
+        ];
+        for supported in 0..=ids.len() {
+            let factory = unsafe { DxgiFactory::probe(factory(&ids[..supported])) };
+            let level = match factory {
+                DxgiFactory::Factory1(_) => 0,
+                DxgiFactory::Factory2(_) => 1,
+                DxgiFactory::Factory3(_) => 2,
+                DxgiFactory::Factory4(_) => 3,
+                DxgiFactory::Factory5(_) => 4,
+                DxgiFactory::Factory6(_) => 5,
+            };
+            assert_eq!(level, supported);
+            assert_eq!(factory.as_factory2().is_some(), supported >= 1);
+            assert_eq!(factory.as_factory6().is_some(), supported >= 5);
+            // References to the intermediate interfaces are released.
+            assert_eq!(ref_count(factory), 1);
+            unsafe { factory.destroy() };
+        }
+
+        // Stops at the first interface which isn't supported.
+        let factory = unsafe { DxgiFactory::probe(factory(&[ids[0], ids[2]])) };
+        assert!(matches!(factory, DxgiFactory::Factory2(_)));
+        assert_eq!(ref_count(factory), 1);
+        unsafe { factory.destroy() };
+
+        let null = unsafe { DxgiFactory::probe(Factory1::null()) };
+        assert!(matches!(null, DxgiFactory::Factory1(ptr) if ptr.is_null()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to unwrap a Factory1 as a Factory2")]
+    fn unwrap_older() {
+        let factory = unsafe { DxgiFactory::probe(factory(&[])) };
+        factory.unwrap_factory2();
+    }
+}
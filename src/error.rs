@@ -0,0 +1,263 @@
+//! Typed errors
+
+use crate::{D3DResult, HRESULT};
+use std::{error, fmt};
+use winapi::shared::winerror;
+
+const D3D12_ERROR_ADAPTER_NOT_FOUND: HRESULT = 0x887E0001u32 as _;
+const D3D12_ERROR_DRIVER_VERSION_MISMATCH: HRESULT = 0x887E0002u32 as _;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum D3DErrorKind {
+    DeviceRemoved,
+    DeviceHung,
+    DeviceReset,
+    DriverInternalError,
+    AdapterNotFound,
+    DriverVersionMismatch,
+    OutOfMemory,
+    InvalidArg,
+    InvalidCall,
+    NotFound,
+    MoreData,
+    WasStillDrawing,
+    Unsupported,
+    NotCurrentlyAvailable,
+    AccessLost,
+    AccessDenied,
+    WaitTimeout,
+    NoInterface,
+    NotImplemented,
+    Pointer,
+    Fail,
+    Other,
+}
+
+impl D3DErrorKind {
+    pub fn from_hresult(hr: HRESULT) -> Self {
+        match hr {
+            winerror::DXGI_ERROR_DEVICE_REMOVED => Self::DeviceRemoved,
+            winerror::DXGI_ERROR_DEVICE_HUNG => Self::DeviceHung,
+            winerror::DXGI_ERROR_DEVICE_RESET => Self::DeviceReset,
+            winerror::DXGI_ERROR_DRIVER_INTERNAL_ERROR => Self::DriverInternalError,
+            D3D12_ERROR_ADAPTER_NOT_FOUND => Self::AdapterNotFound,
+            D3D12_ERROR_DRIVER_VERSION_MISMATCH => Self::DriverVersionMismatch,
+            winerror::E_OUTOFMEMORY => Self::OutOfMemory,
+            winerror::E_INVALIDARG => Self::InvalidArg,
+            winerror::DXGI_ERROR_INVALID_CALL => Self::InvalidCall,
+            winerror::DXGI_ERROR_NOT_FOUND => Self::NotFound,
+            winerror::DXGI_ERROR_MORE_DATA => Self::MoreData,
+            winerror::DXGI_ERROR_WAS_STILL_DRAWING => Self::WasStillDrawing,
+            winerror::DXGI_ERROR_UNSUPPORTED => Self::Unsupported,
+            winerror::DXGI_ERROR_NOT_CURRENTLY_AVAILABLE => Self::NotCurrentlyAvailable,
+            winerror::DXGI_ERROR_ACCESS_LOST => Self::AccessLost,
+            winerror::DXGI_ERROR_ACCESS_DENIED | winerror::E_ACCESSDENIED => Self::AccessDenied,
+            winerror::DXGI_ERROR_WAIT_TIMEOUT => Self::WaitTimeout,
+            winerror::E_NOINTERFACE => Self::NoInterface,
+            winerror::E_NOTIMPL => Self::NotImplemented,
+            winerror::E_POINTER => Self::Pointer,
+            winerror::E_FAIL => Self::Fail,
+            _ => Self::Other,
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match *self {
+            Self::DeviceRemoved => "the device has been removed",
+            Self::DeviceHung => "the device hung due to badly formed commands",
+            Self::DeviceReset => "the device failed due to a badly formed command",
+            Self::DriverInternalError => "the driver encountered a problem",
+            Self::AdapterNotFound => "the specified cached PSO was created on a different adapter",
+            Self::DriverVersionMismatch => {
+                "the specified cached PSO was created on a different driver version"
+            }
+            Self::OutOfMemory => "out of memory",
+            Self::InvalidArg => "an invalid parameter was passed",
+            Self::InvalidCall => "the method call is invalid",
+            Self::NotFound => "the object was not found",
+            Self::MoreData => "the buffer is not big enough to hold the requested data",
+            Self::WasStillDrawing => "the GPU was busy",
+            Self::Unsupported => "the requested functionality is not supported",
+            Self::NotCurrentlyAvailable => "the resource or request is not currently available",
+            Self::AccessLost => "the desktop duplication interface is invalid",
+            Self::AccessDenied => "access denied",
+            Self::WaitTimeout => "the time-out interval elapsed",
+            Self::NoInterface => "the interface is not supported",
+            Self::NotImplemented => "not implemented",
+            Self::Pointer => "invalid pointer",
+            Self::Fail => "unspecified failure",
+            Self::Other => "unknown error",
+        }
+    }
+}
+
+impl fmt::Display for D3DErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+/// Failed API call together with the returned `HRESULT`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct D3DError {
+    call: &'static str,
+    hr: HRESULT,
+    message: Option<String>,
+}
+
+impl D3DError {
+    pub fn new(call: &'static str, hr: HRESULT) -> Self {
+        D3DError {
+            call,
+            hr,
+            message: None,
+        }
+    }
+
+    /// Attach additional information, e.g. the error blob of the shader compiler.
+    pub fn with_message(mut self, message: String) -> Self {
+        self.message = Some(message);
+        self
+    }
+
+    /// Name of the failing API function.
+    pub fn call(&self) -> &'static str {
+        self.call
+    }
+
+    pub fn hresult(&self) -> HRESULT {
+        self.hr
+    }
+
+    pub fn kind(&self) -> D3DErrorKind {
+        D3DErrorKind::from_hresult(self.hr)
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+}
+
+impl fmt::Display for D3DError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} failed: {} (0x{:08X})",
+            self.call,
+            self.kind(),
+            self.hr as u32
+        )?;
+        if let Some(ref message) = self.message {
+            write!(f, ": {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for D3DError {}
+
+/// Conversion of the `(T, HRESULT)` pairs into a `Result`.
+pub trait IntoResult<T> {
+    fn into_result(self, call: &'static str) -> Result<T, D3DError>;
+}
+
+impl<T> IntoResult<T> for D3DResult<T> {
+    fn into_result(self, call: &'static str) -> Result<T, D3DError> {
+        let (value, hr) = self;
+        if winerror::SUCCEEDED(hr) {
+            Ok(value)
+        } else {
+            Err(D3DError::new(call, hr))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds() {
+        let kinds = [
+            (
+                winerror::DXGI_ERROR_DEVICE_REMOVED,
+                D3DErrorKind::DeviceRemoved,
+            ),
+            (winerror::DXGI_ERROR_DEVICE_HUNG, D3DErrorKind::DeviceHung),
+            (winerror::DXGI_ERROR_DEVICE_RESET, D3DErrorKind::DeviceReset),
+            (
+                winerror::DXGI_ERROR_DRIVER_INTERNAL_ERROR,
+                D3DErrorKind::DriverInternalError,
+            ),
+            (0x887E0001u32 as _, D3DErrorKind::AdapterNotFound),
+            (0x887E0002u32 as _, D3DErrorKind::DriverVersionMismatch),
+            (winerror::E_OUTOFMEMORY, D3DErrorKind::OutOfMemory),
+            (winerror::E_INVALIDARG, D3DErrorKind::InvalidArg),
+            (winerror::DXGI_ERROR_INVALID_CALL, D3DErrorKind::InvalidCall),
+            (winerror::DXGI_ERROR_NOT_FOUND, D3DErrorKind::NotFound),
+            (winerror::DXGI_ERROR_MORE_DATA, D3DErrorKind::MoreData),
+            (
+                winerror::DXGI_ERROR_WAS_STILL_DRAWING,
+                D3DErrorKind::WasStillDrawing,
+            ),
+            (winerror::DXGI_ERROR_UNSUPPORTED, D3DErrorKind::Unsupported),
+            (
+                winerror::DXGI_ERROR_NOT_CURRENTLY_AVAILABLE,
+                D3DErrorKind::NotCurrentlyAvailable,
+            ),
+            (winerror::DXGI_ERROR_ACCESS_LOST, D3DErrorKind::AccessLost),
+            (
+                winerror::DXGI_ERROR_ACCESS_DENIED,
+                D3DErrorKind::AccessDenied,
+            ),
+            (winerror::E_ACCESSDENIED, D3DErrorKind::AccessDenied),
+            (winerror::DXGI_ERROR_WAIT_TIMEOUT, D3DErrorKind::WaitTimeout),
+            (winerror::E_NOINTERFACE, D3DErrorKind::NoInterface),
+            (winerror::E_NOTIMPL, D3DErrorKind::NotImplemented),
+            (winerror::E_POINTER, D3DErrorKind::Pointer),
+            (winerror::E_FAIL, D3DErrorKind::Fail),
+            (0x8000FFFFu32 as _, D3DErrorKind::Other),
+            (winerror::S_OK, D3DErrorKind::Other),
+        ];
+        for &(hr, kind) in kinds.iter() {
+            assert_eq!(D3DErrorKind::from_hresult(hr), kind, "{:08X}", hr as u32);
+        }
+        assert_eq!(
+            D3DErrorKind::DeviceRemoved.to_string(),
+            "the device has been removed"
+        );
+        assert_eq!(D3DErrorKind::Other.to_string(), "unknown error");
+    }
+
+    #[test]
+    fn display() {
+        let error = D3DError::new("ID3D12Device::CreateFence", winerror::E_OUTOFMEMORY);
+        assert_eq!(error.call(), "ID3D12Device::CreateFence");
+        assert_eq!(error.hresult(), winerror::E_OUTOFMEMORY);
+        assert_eq!(error.kind(), D3DErrorKind::OutOfMemory);
+        assert_eq!(error.message(), None);
+        assert_eq!(
+            error.to_string(),
+            "ID3D12Device::CreateFence failed: out of memory (0x8007000E)"
+        );
+
+        let error = D3DError::new("D3DCompile", winerror::E_FAIL)
+            .with_message("error X3000: syntax error".to_string());
+        assert_eq!(error.message(), Some("error X3000: syntax error"));
+        assert_eq!(
+            error.to_string(),
+            "D3DCompile failed: unspecified failure (0x80004005): error X3000: syntax error"
+        );
+    }
+
+    #[test]
+    fn into_result() {
+        assert_eq!((7, winerror::S_OK).into_result("Call"), Ok(7));
+        assert_eq!((7, winerror::S_FALSE).into_result("Call"), Ok(7));
+        assert_eq!(
+            (7, winerror::DXGI_ERROR_DEVICE_REMOVED).into_result("Call"),
+            Err(D3DError::new("Call", winerror::DXGI_ERROR_DEVICE_REMOVED))
+        );
+    }
+}
@@ -0,0 +1,1288 @@
+//! Feature support queries
+
+use crate::{D3DResult, Device, FeatureLevel, Format, RootSignatureVersion};
+use std::{convert::TryFrom, mem};
+use winapi::{
+    shared::{
+        minwindef::{BOOL, INT, UINT},
+        winerror::{E_INVALIDARG, SUCCEEDED},
+    },
+    um::{d3d12, d3dcommon},
+};
+
+// Feature queries and data not yet part of `winapi`.
+
+const D3D12_FEATURE_D3D12_OPTIONS3: d3d12::D3D12_FEATURE = 21;
+const D3D12_FEATURE_D3D12_OPTIONS4: d3d12::D3D12_FEATURE = 23;
+const D3D12_FEATURE_D3D12_OPTIONS5: d3d12::D3D12_FEATURE = 27;
+const D3D12_FEATURE_D3D12_OPTIONS6: d3d12::D3D12_FEATURE = 30;
+const D3D12_FEATURE_D3D12_OPTIONS7: d3d12::D3D12_FEATURE = 32;
+const D3D12_FEATURE_D3D12_OPTIONS8: d3d12::D3D12_FEATURE = 36;
+const D3D12_FEATURE_D3D12_OPTIONS9: d3d12::D3D12_FEATURE = 37;
+const D3D12_FEATURE_D3D12_OPTIONS10: d3d12::D3D12_FEATURE = 39;
+const D3D12_FEATURE_D3D12_OPTIONS11: d3d12::D3D12_FEATURE = 40;
+const D3D12_FEATURE_D3D12_OPTIONS12: d3d12::D3D12_FEATURE = 41;
+
+#[allow(non_camel_case_types)]
+type D3D12_TRI_STATE = INT;
+
+#[allow(non_snake_case)]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct D3D12_FEATURE_DATA_D3D12_OPTIONS3 {
+    CopyQueueTimestampQueriesSupported: BOOL,
+    CastingFullyTypedFormatSupported: BOOL,
+    WriteBufferImmediateSupportFlags: UINT,
+    ViewInstancingTier: UINT,
+    BarycentricsSupported: BOOL,
+}
+
+#[allow(non_snake_case)]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct D3D12_FEATURE_DATA_D3D12_OPTIONS4 {
+    MSAA64KBAlignedTextureSupported: BOOL,
+    SharedResourceCompatibilityTier: UINT,
+    Native16BitShaderOpsSupported: BOOL,
+}
+
+#[allow(non_snake_case)]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct D3D12_FEATURE_DATA_D3D12_OPTIONS5 {
+    SRVOnlyTiledResourceTier3: BOOL,
+    RenderPassesTier: UINT,
+    RaytracingTier: UINT,
+}
+
+#[allow(non_snake_case)]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct D3D12_FEATURE_DATA_D3D12_OPTIONS6 {
+    AdditionalShadingRatesSupported: BOOL,
+    PerPrimitiveShadingRateSupportedWithViewportIndexing: BOOL,
+    VariableShadingRateTier: UINT,
+    ShadingRateImageTileSize: UINT,
+    BackgroundProcessingSupported: BOOL,
+}
+
+#[allow(non_snake_case)]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct D3D12_FEATURE_DATA_D3D12_OPTIONS7 {
+    MeshShaderTier: UINT,
+    SamplerFeedbackTier: UINT,
+}
+
+#[allow(non_snake_case)]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct D3D12_FEATURE_DATA_D3D12_OPTIONS8 {
+    UnalignedBlockTexturesSupported: BOOL,
+}
+
+#[allow(non_snake_case)]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct D3D12_FEATURE_DATA_D3D12_OPTIONS9 {
+    MeshShaderPipelineStatsSupported: BOOL,
+    MeshShaderSupportsFullRangeRenderTargetArrayIndex: BOOL,
+    AtomicInt64OnTypedResourceSupported: BOOL,
+    AtomicInt64OnGroupSharedSupported: BOOL,
+    DerivativesInMeshAndAmplificationShadersSupported: BOOL,
+    WaveMMATier: UINT,
+}
+
+#[allow(non_snake_case)]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct D3D12_FEATURE_DATA_D3D12_OPTIONS10 {
+    VariableRateShadingSumCombinerSupported: BOOL,
+    MeshShaderPerPrimitiveShadingRateSupported: BOOL,
+}
+
+#[allow(non_snake_case)]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct D3D12_FEATURE_DATA_D3D12_OPTIONS11 {
+    AtomicInt64OnDescriptorHeapResourceSupported: BOOL,
+}
+
+#[allow(non_snake_case)]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct D3D12_FEATURE_DATA_D3D12_OPTIONS12 {
+    MSPrimitivesPipelineStatisticIncludesCulledPrimitives: D3D12_TRI_STATE,
+    EnhancedBarriersSupported: BOOL,
+    RelaxedFormatCastingSupported: BOOL,
+}
+
+macro_rules! tiers {
+    ($(
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $first:ident = $first_value:expr,
+            $($variant:ident = $value:expr,)*
+        }
+    )*) => {
+        $(
+            $(#[$meta])*
+            #[repr(u32)]
+            #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+            pub enum $name {
+                $first = $first_value,
+                $($variant = $value,)*
+            }
+
+            impl $name {
+                // Tiers added after this crate map to the highest one known.
+                fn from_raw(raw: u32) -> Self {
+                    #[allow(unused_mut)]
+                    let mut tier = Self::$first;
+                    $(
+                        if raw >= $value {
+                            tier = Self::$variant;
+                        }
+                    )*
+                    tier
+                }
+            }
+        )*
+    };
+}
+
+tiers! {
+    pub enum TiledResourcesTier {
+        NotSupported = 0,
+        Tier1 = 1,
+        Tier2 = 2,
+        Tier3 = 3,
+        Tier4 = 4,
+    }
+    pub enum ResourceBindingTier {
+        Tier1 = 1,
+        Tier2 = 2,
+        Tier3 = 3,
+    }
+    pub enum ConservativeRasterizationTier {
+        NotSupported = 0,
+        Tier1 = 1,
+        Tier2 = 2,
+        Tier3 = 3,
+    }
+    pub enum CrossNodeSharingTier {
+        NotSupported = 0,
+        Tier1Emulated = 1,
+        Tier1 = 2,
+        Tier2 = 3,
+        Tier3 = 4,
+    }
+    pub enum ResourceHeapTier {
+        Tier1 = 1,
+        Tier2 = 2,
+    }
+    pub enum ProgrammableSamplePositionsTier {
+        NotSupported = 0,
+        Tier1 = 1,
+        Tier2 = 2,
+    }
+    pub enum ViewInstancingTier {
+        NotSupported = 0,
+        Tier1 = 1,
+        Tier2 = 2,
+        Tier3 = 3,
+    }
+    pub enum SharedResourceCompatibilityTier {
+        Tier0 = 0,
+        Tier1 = 1,
+        Tier2 = 2,
+    }
+    pub enum RenderPassTier {
+        Tier0 = 0,
+        Tier1 = 1,
+        Tier2 = 2,
+    }
+    pub enum RaytracingTier {
+        NotSupported = 0,
+        Tier1_0 = 10,
+        Tier1_1 = 11,
+    }
+    pub enum VariableShadingRateTier {
+        NotSupported = 0,
+        Tier1 = 1,
+        Tier2 = 2,
+    }
+    pub enum MeshShaderTier {
+        NotSupported = 0,
+        Tier1 = 10,
+    }
+    pub enum SamplerFeedbackTier {
+        NotSupported = 0,
+        Tier0_9 = 90,
+        Tier1_0 = 100,
+    }
+    pub enum WaveMmaTier {
+        NotSupported = 0,
+        Tier1_0 = 10,
+    }
+
+    pub enum ShaderModel {
+        V5_1 = 0x51,
+        V6_0 = 0x60,
+        V6_1 = 0x61,
+        V6_2 = 0x62,
+        V6_3 = 0x63,
+        V6_4 = 0x64,
+        V6_5 = 0x65,
+        V6_6 = 0x66,
+        V6_7 = 0x67,
+        V6_8 = 0x68,
+    }
+}
+
+bitflags! {
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct MinPrecisionSupport: u32 {
+        const BITS_10 = d3d12::D3D12_SHADER_MIN_PRECISION_SUPPORT_10_BIT;
+        const BITS_16 = d3d12::D3D12_SHADER_MIN_PRECISION_SUPPORT_16_BIT;
+    }
+}
+
+bitflags! {
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct CommandListSupportFlags: u32 {
+        const DIRECT = 0x1;
+        const BUNDLE = 0x2;
+        const COMPUTE = 0x4;
+        const COPY = 0x8;
+        const VIDEO_DECODE = 0x10;
+        const VIDEO_PROCESS = 0x20;
+        const VIDEO_ENCODE = 0x40;
+    }
+}
+
+bitflags! {
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct FormatSupport1: u32 {
+        const BUFFER = d3d12::D3D12_FORMAT_SUPPORT1_BUFFER;
+        const IA_VERTEX_BUFFER = d3d12::D3D12_FORMAT_SUPPORT1_IA_VERTEX_BUFFER;
+        const IA_INDEX_BUFFER = d3d12::D3D12_FORMAT_SUPPORT1_IA_INDEX_BUFFER;
+        const SO_BUFFER = d3d12::D3D12_FORMAT_SUPPORT1_SO_BUFFER;
+        const TEXTURE1D = d3d12::D3D12_FORMAT_SUPPORT1_TEXTURE1D;
+        const TEXTURE2D = d3d12::D3D12_FORMAT_SUPPORT1_TEXTURE2D;
+        const TEXTURE3D = d3d12::D3D12_FORMAT_SUPPORT1_TEXTURE3D;
+        const TEXTURECUBE = d3d12::D3D12_FORMAT_SUPPORT1_TEXTURECUBE;
+        const SHADER_LOAD = d3d12::D3D12_FORMAT_SUPPORT1_SHADER_LOAD;
+        const SHADER_SAMPLE = d3d12::D3D12_FORMAT_SUPPORT1_SHADER_SAMPLE;
+        const SHADER_SAMPLE_COMPARISON = d3d12::D3D12_FORMAT_SUPPORT1_SHADER_SAMPLE_COMPARISON;
+        const SHADER_SAMPLE_MONO_TEXT = d3d12::D3D12_FORMAT_SUPPORT1_SHADER_SAMPLE_MONO_TEXT;
+        const MIP = d3d12::D3D12_FORMAT_SUPPORT1_MIP;
+        const RENDER_TARGET = d3d12::D3D12_FORMAT_SUPPORT1_RENDER_TARGET;
+        const BLENDABLE = d3d12::D3D12_FORMAT_SUPPORT1_BLENDABLE;
+        const DEPTH_STENCIL = d3d12::D3D12_FORMAT_SUPPORT1_DEPTH_STENCIL;
+        const MULTISAMPLE_RESOLVE = d3d12::D3D12_FORMAT_SUPPORT1_MULTISAMPLE_RESOLVE;
+        const DISPLAY = d3d12::D3D12_FORMAT_SUPPORT1_DISPLAY;
+        const CAST_WITHIN_BIT_LAYOUT = d3d12::D3D12_FORMAT_SUPPORT1_CAST_WITHIN_BIT_LAYOUT;
+        const MULTISAMPLE_RENDERTARGET = d3d12::D3D12_FORMAT_SUPPORT1_MULTISAMPLE_RENDERTARGET;
+        const MULTISAMPLE_LOAD = d3d12::D3D12_FORMAT_SUPPORT1_MULTISAMPLE_LOAD;
+        const SHADER_GATHER = d3d12::D3D12_FORMAT_SUPPORT1_SHADER_GATHER;
+        const BACK_BUFFER_CAST = d3d12::D3D12_FORMAT_SUPPORT1_BACK_BUFFER_CAST;
+        const TYPED_UNORDERED_ACCESS_VIEW = d3d12::D3D12_FORMAT_SUPPORT1_TYPED_UNORDERED_ACCESS_VIEW;
+        const SHADER_GATHER_COMPARISON = d3d12::D3D12_FORMAT_SUPPORT1_SHADER_GATHER_COMPARISON;
+        const DECODER_OUTPUT = d3d12::D3D12_FORMAT_SUPPORT1_DECODER_OUTPUT;
+        const VIDEO_PROCESSOR_OUTPUT = d3d12::D3D12_FORMAT_SUPPORT1_VIDEO_PROCESSOR_OUTPUT;
+        const VIDEO_PROCESSOR_INPUT = d3d12::D3D12_FORMAT_SUPPORT1_VIDEO_PROCESSOR_INPUT;
+        const VIDEO_ENCODER = d3d12::D3D12_FORMAT_SUPPORT1_VIDEO_ENCODER;
+    }
+}
+
+bitflags! {
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct FormatSupport2: u32 {
+        const UAV_ATOMIC_ADD = d3d12::D3D12_FORMAT_SUPPORT2_UAV_ATOMIC_ADD;
+        const UAV_ATOMIC_BITWISE_OPS = d3d12::D3D12_FORMAT_SUPPORT2_UAV_ATOMIC_BITWISE_OPS;
+        const UAV_ATOMIC_COMPARE_STORE_OR_COMPARE_EXCHANGE = d3d12::D3D12_FORMAT_SUPPORT2_UAV_ATOMIC_COMPARE_STORE_OR_COMPARE_EXCHANGE;
+        const UAV_ATOMIC_EXCHANGE = d3d12::D3D12_FORMAT_SUPPORT2_UAV_ATOMIC_EXCHANGE;
+        const UAV_ATOMIC_SIGNED_MIN_OR_MAX = d3d12::D3D12_FORMAT_SUPPORT2_UAV_ATOMIC_SIGNED_MIN_OR_MAX;
+        const UAV_ATOMIC_UNSIGNED_MIN_OR_MAX = d3d12::D3D12_FORMAT_SUPPORT2_UAV_ATOMIC_UNSIGNED_MIN_OR_MAX;
+        const UAV_TYPED_LOAD = d3d12::D3D12_FORMAT_SUPPORT2_UAV_TYPED_LOAD;
+        const UAV_TYPED_STORE = d3d12::D3D12_FORMAT_SUPPORT2_UAV_TYPED_STORE;
+        const OUTPUT_MERGER_LOGIC_OP = d3d12::D3D12_FORMAT_SUPPORT2_OUTPUT_MERGER_LOGIC_OP;
+        const TILED = d3d12::D3D12_FORMAT_SUPPORT2_TILED;
+        const MULTIPLANE_OVERLAY = d3d12::D3D12_FORMAT_SUPPORT2_MULTIPLANE_OVERLAY;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options {
+    pub double_precision_float_shader_ops: bool,
+    pub output_merger_logic_op: bool,
+    pub min_precision_support: MinPrecisionSupport,
+    pub tiled_resources_tier: TiledResourcesTier,
+    pub resource_binding_tier: ResourceBindingTier,
+    pub ps_specified_stencil_ref_supported: bool,
+    pub typed_uav_load_additional_formats: bool,
+    pub rovs_supported: bool,
+    pub conservative_rasterization_tier: ConservativeRasterizationTier,
+    pub max_gpu_virtual_address_bits_per_resource: u32,
+    pub standard_swizzle_64kb_supported: bool,
+    pub cross_node_sharing_tier: CrossNodeSharingTier,
+    pub cross_adapter_row_major_texture_supported: bool,
+    pub vp_and_rt_array_index_from_any_shader_feeding_rasterizer_supported_without_gs_emulation:
+        bool,
+    pub resource_heap_tier: ResourceHeapTier,
+}
+
+impl From<d3d12::D3D12_FEATURE_DATA_D3D12_OPTIONS> for Options {
+    fn from(raw: d3d12::D3D12_FEATURE_DATA_D3D12_OPTIONS) -> Self {
+        Options {
+            double_precision_float_shader_ops: raw.DoublePrecisionFloatShaderOps != 0,
+            output_merger_logic_op: raw.OutputMergerLogicOp != 0,
+            min_precision_support: MinPrecisionSupport::from_bits_truncate(raw.MinPrecisionSupport as _),
+            tiled_resources_tier: TiledResourcesTier::from_raw(raw.TiledResourcesTier as _),
+            resource_binding_tier: ResourceBindingTier::from_raw(raw.ResourceBindingTier as _),
+            ps_specified_stencil_ref_supported: raw.PSSpecifiedStencilRefSupported != 0,
+            typed_uav_load_additional_formats: raw.TypedUAVLoadAdditionalFormats != 0,
+            rovs_supported: raw.ROVsSupported != 0,
+            conservative_rasterization_tier: ConservativeRasterizationTier::from_raw(raw.ConservativeRasterizationTier as _),
+            max_gpu_virtual_address_bits_per_resource: raw.MaxGPUVirtualAddressBitsPerResource,
+            standard_swizzle_64kb_supported: raw.StandardSwizzle64KBSupported != 0,
+            cross_node_sharing_tier: CrossNodeSharingTier::from_raw(raw.CrossNodeSharingTier as _),
+            cross_adapter_row_major_texture_supported: raw.CrossAdapterRowMajorTextureSupported != 0,
+            vp_and_rt_array_index_from_any_shader_feeding_rasterizer_supported_without_gs_emulation: raw.VPAndRTArrayIndexFromAnyShaderFeedingRasterizerSupportedWithoutGSEmulation != 0,
+            resource_heap_tier: ResourceHeapTier::from_raw(raw.ResourceHeapTier as _),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options1 {
+    pub wave_ops: bool,
+    pub wave_lane_count_min: u32,
+    pub wave_lane_count_max: u32,
+    pub total_lane_count: u32,
+    pub expanded_compute_resource_states: bool,
+    pub int64_shader_ops: bool,
+}
+
+impl From<d3d12::D3D12_FEATURE_DATA_D3D12_OPTIONS1> for Options1 {
+    fn from(raw: d3d12::D3D12_FEATURE_DATA_D3D12_OPTIONS1) -> Self {
+        Options1 {
+            wave_ops: raw.WaveOps != 0,
+            wave_lane_count_min: raw.WaveLaneCountMin,
+            wave_lane_count_max: raw.WaveLaneCountMax,
+            total_lane_count: raw.TotalLaneCount,
+            expanded_compute_resource_states: raw.ExpandedComputeResourceStates != 0,
+            int64_shader_ops: raw.Int64ShaderOps != 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options2 {
+    pub depth_bounds_test_supported: bool,
+    pub programmable_sample_positions_tier: ProgrammableSamplePositionsTier,
+}
+
+impl From<d3d12::D3D12_FEATURE_DATA_D3D12_OPTIONS2> for Options2 {
+    fn from(raw: d3d12::D3D12_FEATURE_DATA_D3D12_OPTIONS2) -> Self {
+        Options2 {
+            depth_bounds_test_supported: raw.DepthBoundsTestSupported != 0,
+            programmable_sample_positions_tier: ProgrammableSamplePositionsTier::from_raw(
+                raw.ProgrammableSamplePositionsTier as _,
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options3 {
+    pub copy_queue_timestamp_queries_supported: bool,
+    pub casting_fully_typed_format_supported: bool,
+    pub write_buffer_immediate_support_flags: CommandListSupportFlags,
+    pub view_instancing_tier: ViewInstancingTier,
+    pub barycentrics_supported: bool,
+}
+
+impl From<D3D12_FEATURE_DATA_D3D12_OPTIONS3> for Options3 {
+    fn from(raw: D3D12_FEATURE_DATA_D3D12_OPTIONS3) -> Self {
+        Options3 {
+            copy_queue_timestamp_queries_supported: raw.CopyQueueTimestampQueriesSupported != 0,
+            casting_fully_typed_format_supported: raw.CastingFullyTypedFormatSupported != 0,
+            write_buffer_immediate_support_flags: CommandListSupportFlags::from_bits_truncate(
+                raw.WriteBufferImmediateSupportFlags as _,
+            ),
+            view_instancing_tier: ViewInstancingTier::from_raw(raw.ViewInstancingTier as _),
+            barycentrics_supported: raw.BarycentricsSupported != 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options4 {
+    pub msaa_64kb_aligned_texture_supported: bool,
+    pub shared_resource_compatibility_tier: SharedResourceCompatibilityTier,
+    pub native_16bit_shader_ops_supported: bool,
+}
+
+impl From<D3D12_FEATURE_DATA_D3D12_OPTIONS4> for Options4 {
+    fn from(raw: D3D12_FEATURE_DATA_D3D12_OPTIONS4) -> Self {
+        Options4 {
+            msaa_64kb_aligned_texture_supported: raw.MSAA64KBAlignedTextureSupported != 0,
+            shared_resource_compatibility_tier: SharedResourceCompatibilityTier::from_raw(
+                raw.SharedResourceCompatibilityTier as _,
+            ),
+            native_16bit_shader_ops_supported: raw.Native16BitShaderOpsSupported != 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options5 {
+    pub srv_only_tiled_resource_tier3: bool,
+    pub render_passes_tier: RenderPassTier,
+    pub raytracing_tier: RaytracingTier,
+}
+
+impl From<D3D12_FEATURE_DATA_D3D12_OPTIONS5> for Options5 {
+    fn from(raw: D3D12_FEATURE_DATA_D3D12_OPTIONS5) -> Self {
+        Options5 {
+            srv_only_tiled_resource_tier3: raw.SRVOnlyTiledResourceTier3 != 0,
+            render_passes_tier: RenderPassTier::from_raw(raw.RenderPassesTier as _),
+            raytracing_tier: RaytracingTier::from_raw(raw.RaytracingTier as _),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options6 {
+    pub additional_shading_rates_supported: bool,
+    pub per_primitive_shading_rate_supported_with_viewport_indexing: bool,
+    pub variable_shading_rate_tier: VariableShadingRateTier,
+    pub shading_rate_image_tile_size: u32,
+    pub background_processing_supported: bool,
+}
+
+impl From<D3D12_FEATURE_DATA_D3D12_OPTIONS6> for Options6 {
+    fn from(raw: D3D12_FEATURE_DATA_D3D12_OPTIONS6) -> Self {
+        Options6 {
+            additional_shading_rates_supported: raw.AdditionalShadingRatesSupported != 0,
+            per_primitive_shading_rate_supported_with_viewport_indexing: raw
+                .PerPrimitiveShadingRateSupportedWithViewportIndexing
+                != 0,
+            variable_shading_rate_tier: VariableShadingRateTier::from_raw(
+                raw.VariableShadingRateTier as _,
+            ),
+            shading_rate_image_tile_size: raw.ShadingRateImageTileSize,
+            background_processing_supported: raw.BackgroundProcessingSupported != 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options7 {
+    pub mesh_shader_tier: MeshShaderTier,
+    pub sampler_feedback_tier: SamplerFeedbackTier,
+}
+
+impl From<D3D12_FEATURE_DATA_D3D12_OPTIONS7> for Options7 {
+    fn from(raw: D3D12_FEATURE_DATA_D3D12_OPTIONS7) -> Self {
+        Options7 {
+            mesh_shader_tier: MeshShaderTier::from_raw(raw.MeshShaderTier as _),
+            sampler_feedback_tier: SamplerFeedbackTier::from_raw(raw.SamplerFeedbackTier as _),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options8 {
+    pub unaligned_block_textures_supported: bool,
+}
+
+impl From<D3D12_FEATURE_DATA_D3D12_OPTIONS8> for Options8 {
+    fn from(raw: D3D12_FEATURE_DATA_D3D12_OPTIONS8) -> Self {
+        Options8 {
+            unaligned_block_textures_supported: raw.UnalignedBlockTexturesSupported != 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options9 {
+    pub mesh_shader_pipeline_stats_supported: bool,
+    pub mesh_shader_supports_full_range_render_target_array_index: bool,
+    pub atomic_int64_on_typed_resource_supported: bool,
+    pub atomic_int64_on_group_shared_supported: bool,
+    pub derivatives_in_mesh_and_amplification_shaders_supported: bool,
+    pub wave_mma_tier: WaveMmaTier,
+}
+
+impl From<D3D12_FEATURE_DATA_D3D12_OPTIONS9> for Options9 {
+    fn from(raw: D3D12_FEATURE_DATA_D3D12_OPTIONS9) -> Self {
+        Options9 {
+            mesh_shader_pipeline_stats_supported: raw.MeshShaderPipelineStatsSupported != 0,
+            mesh_shader_supports_full_range_render_target_array_index: raw
+                .MeshShaderSupportsFullRangeRenderTargetArrayIndex
+                != 0,
+            atomic_int64_on_typed_resource_supported: raw.AtomicInt64OnTypedResourceSupported != 0,
+            atomic_int64_on_group_shared_supported: raw.AtomicInt64OnGroupSharedSupported != 0,
+            derivatives_in_mesh_and_amplification_shaders_supported: raw
+                .DerivativesInMeshAndAmplificationShadersSupported
+                != 0,
+            wave_mma_tier: WaveMmaTier::from_raw(raw.WaveMMATier as _),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options10 {
+    pub variable_rate_shading_sum_combiner_supported: bool,
+    pub mesh_shader_per_primitive_shading_rate_supported: bool,
+}
+
+impl From<D3D12_FEATURE_DATA_D3D12_OPTIONS10> for Options10 {
+    fn from(raw: D3D12_FEATURE_DATA_D3D12_OPTIONS10) -> Self {
+        Options10 {
+            variable_rate_shading_sum_combiner_supported: raw
+                .VariableRateShadingSumCombinerSupported
+                != 0,
+            mesh_shader_per_primitive_shading_rate_supported: raw
+                .MeshShaderPerPrimitiveShadingRateSupported
+                != 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options11 {
+    pub atomic_int64_on_descriptor_heap_resource_supported: bool,
+}
+
+impl From<D3D12_FEATURE_DATA_D3D12_OPTIONS11> for Options11 {
+    fn from(raw: D3D12_FEATURE_DATA_D3D12_OPTIONS11) -> Self {
+        Options11 {
+            atomic_int64_on_descriptor_heap_resource_supported: raw
+                .AtomicInt64OnDescriptorHeapResourceSupported
+                != 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options12 {
+    /// `None` if the driver doesn't report it.
+    pub ms_primitives_pipeline_statistic_includes_culled_primitives: Option<bool>,
+    pub enhanced_barriers_supported: bool,
+    pub relaxed_format_casting_supported: bool,
+}
+
+impl From<D3D12_FEATURE_DATA_D3D12_OPTIONS12> for Options12 {
+    fn from(raw: D3D12_FEATURE_DATA_D3D12_OPTIONS12) -> Self {
+        Options12 {
+            ms_primitives_pipeline_statistic_includes_culled_primitives: match raw
+                .MSPrimitivesPipelineStatisticIncludesCulledPrimitives
+            {
+                0 => Some(false),
+                1 => Some(true),
+                _ => None,
+            },
+            enhanced_barriers_supported: raw.EnhancedBarriersSupported != 0,
+            relaxed_format_casting_supported: raw.RelaxedFormatCastingSupported != 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Architecture {
+    pub node_index: u32,
+    pub tile_based_renderer: bool,
+    pub uma: bool,
+    pub cache_coherent_uma: bool,
+    pub isolated_mmu: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GpuVirtualAddressSupport {
+    pub max_bits_per_resource: u32,
+    pub max_bits_per_process: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FormatSupport {
+    pub support1: FormatSupport1,
+    pub support2: FormatSupport2,
+}
+
+/// All feature queries of a device, fields are `None` if the runtime doesn't know the query.
+///
+/// Collected when creating the device with `Device::create_with_capabilities` or
+/// `D3D12Lib::create_device_with_capabilities`, or later with `Device::capabilities`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capabilities {
+    pub options: Option<Options>,
+    pub options1: Option<Options1>,
+    pub options2: Option<Options2>,
+    pub options3: Option<Options3>,
+    pub options4: Option<Options4>,
+    pub options5: Option<Options5>,
+    pub options6: Option<Options6>,
+    pub options7: Option<Options7>,
+    pub options8: Option<Options8>,
+    pub options9: Option<Options9>,
+    pub options10: Option<Options10>,
+    pub options11: Option<Options11>,
+    pub options12: Option<Options12>,
+    pub architecture: Option<Architecture>,
+    pub shader_model: Option<ShaderModel>,
+    pub root_signature_version: Option<RootSignatureVersion>,
+    pub max_feature_level: Option<FeatureLevel>,
+    pub gpu_virtual_address_support: Option<GpuVirtualAddressSupport>,
+}
+
+fn succeeded<T>((value, hr): D3DResult<T>) -> Option<T> {
+    if SUCCEEDED(hr) {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+// Failed creation calls return a null device, which has no capabilities.
+#[cfg(any(feature = "libloading", feature = "implicit-link"))]
+fn with_capabilities((device, hr): D3DResult<Device>) -> D3DResult<(Device, Capabilities)> {
+    let capabilities = if SUCCEEDED(hr) {
+        device.capabilities()
+    } else {
+        Capabilities::default()
+    };
+    ((device, capabilities), hr)
+}
+
+#[cfg(feature = "libloading")]
+impl crate::D3D12Lib {
+    /// Create a device and collect its `Capabilities` right away.
+    #[track_caller]
+    pub fn create_device_with_capabilities<I: winapi::Interface>(
+        &self,
+        adapter: crate::WeakPtr<I>,
+        feature_level: FeatureLevel,
+    ) -> Result<D3DResult<(Device, Capabilities)>, libloading::Error> {
+        self.create_device(adapter, feature_level)
+            .map(with_capabilities)
+    }
+
+    #[track_caller]
+    pub fn try_create_device_with_capabilities<I: winapi::Interface>(
+        &self,
+        adapter: crate::WeakPtr<I>,
+        feature_level: FeatureLevel,
+    ) -> Result<Result<(Device, Capabilities), crate::D3DError>, libloading::Error> {
+        use crate::IntoResult;
+        self.create_device_with_capabilities(adapter, feature_level)
+            .map(|result| result.into_result("D3D12CreateDevice"))
+    }
+}
+
+impl Device {
+    /// Create a device and collect its `Capabilities` right away.
+    #[cfg(feature = "implicit-link")]
+    #[track_caller]
+    pub fn create_with_capabilities<I: winapi::Interface>(
+        adapter: crate::WeakPtr<I>,
+        feature_level: FeatureLevel,
+    ) -> D3DResult<(Self, Capabilities)> {
+        with_capabilities(Self::create(adapter, feature_level))
+    }
+
+    #[cfg(feature = "implicit-link")]
+    #[track_caller]
+    pub fn try_create_with_capabilities<I: winapi::Interface>(
+        adapter: crate::WeakPtr<I>,
+        feature_level: FeatureLevel,
+    ) -> Result<(Self, Capabilities), crate::D3DError> {
+        use crate::IntoResult;
+        Self::create_with_capabilities(adapter, feature_level).into_result("D3D12CreateDevice")
+    }
+
+    fn check_feature_support<T>(&self, feature: d3d12::D3D12_FEATURE, mut data: T) -> D3DResult<T> {
+        let hr = unsafe {
+            self.CheckFeatureSupport(
+                feature,
+                &mut data as *mut T as *mut _,
+                mem::size_of::<T>() as _,
+            )
+        };
+        (data, hr)
+    }
+
+    pub fn options(&self) -> D3DResult<Options> {
+        let (raw, hr) = self.check_feature_support::<d3d12::D3D12_FEATURE_DATA_D3D12_OPTIONS>(
+            d3d12::D3D12_FEATURE_D3D12_OPTIONS,
+            unsafe { mem::zeroed() },
+        );
+        (raw.into(), hr)
+    }
+
+    pub fn options1(&self) -> D3DResult<Options1> {
+        let (raw, hr) = self.check_feature_support::<d3d12::D3D12_FEATURE_DATA_D3D12_OPTIONS1>(
+            d3d12::D3D12_FEATURE_D3D12_OPTIONS1,
+            unsafe { mem::zeroed() },
+        );
+        (raw.into(), hr)
+    }
+
+    pub fn options2(&self) -> D3DResult<Options2> {
+        let (raw, hr) = self.check_feature_support::<d3d12::D3D12_FEATURE_DATA_D3D12_OPTIONS2>(
+            d3d12::D3D12_FEATURE_D3D12_OPTIONS2,
+            unsafe { mem::zeroed() },
+        );
+        (raw.into(), hr)
+    }
+
+    pub fn options3(&self) -> D3DResult<Options3> {
+        let (raw, hr) = self.check_feature_support::<D3D12_FEATURE_DATA_D3D12_OPTIONS3>(
+            D3D12_FEATURE_D3D12_OPTIONS3,
+            unsafe { mem::zeroed() },
+        );
+        (raw.into(), hr)
+    }
+
+    pub fn options4(&self) -> D3DResult<Options4> {
+        let (raw, hr) = self.check_feature_support::<D3D12_FEATURE_DATA_D3D12_OPTIONS4>(
+            D3D12_FEATURE_D3D12_OPTIONS4,
+            unsafe { mem::zeroed() },
+        );
+        (raw.into(), hr)
+    }
+
+    pub fn options5(&self) -> D3DResult<Options5> {
+        let (raw, hr) = self.check_feature_support::<D3D12_FEATURE_DATA_D3D12_OPTIONS5>(
+            D3D12_FEATURE_D3D12_OPTIONS5,
+            unsafe { mem::zeroed() },
+        );
+        (raw.into(), hr)
+    }
+
+    pub fn options6(&self) -> D3DResult<Options6> {
+        let (raw, hr) = self.check_feature_support::<D3D12_FEATURE_DATA_D3D12_OPTIONS6>(
+            D3D12_FEATURE_D3D12_OPTIONS6,
+            unsafe { mem::zeroed() },
+        );
+        (raw.into(), hr)
+    }
+
+    pub fn options7(&self) -> D3DResult<Options7> {
+        let (raw, hr) = self.check_feature_support::<D3D12_FEATURE_DATA_D3D12_OPTIONS7>(
+            D3D12_FEATURE_D3D12_OPTIONS7,
+            unsafe { mem::zeroed() },
+        );
+        (raw.into(), hr)
+    }
+
+    pub fn options8(&self) -> D3DResult<Options8> {
+        let (raw, hr) = self.check_feature_support::<D3D12_FEATURE_DATA_D3D12_OPTIONS8>(
+            D3D12_FEATURE_D3D12_OPTIONS8,
+            unsafe { mem::zeroed() },
+        );
+        (raw.into(), hr)
+    }
+
+    pub fn options9(&self) -> D3DResult<Options9> {
+        let (raw, hr) = self.check_feature_support::<D3D12_FEATURE_DATA_D3D12_OPTIONS9>(
+            D3D12_FEATURE_D3D12_OPTIONS9,
+            unsafe { mem::zeroed() },
+        );
+        (raw.into(), hr)
+    }
+
+    pub fn options10(&self) -> D3DResult<Options10> {
+        let (raw, hr) = self.check_feature_support::<D3D12_FEATURE_DATA_D3D12_OPTIONS10>(
+            D3D12_FEATURE_D3D12_OPTIONS10,
+            unsafe { mem::zeroed() },
+        );
+        (raw.into(), hr)
+    }
+
+    pub fn options11(&self) -> D3DResult<Options11> {
+        let (raw, hr) = self.check_feature_support::<D3D12_FEATURE_DATA_D3D12_OPTIONS11>(
+            D3D12_FEATURE_D3D12_OPTIONS11,
+            unsafe { mem::zeroed() },
+        );
+        (raw.into(), hr)
+    }
+
+    pub fn options12(&self) -> D3DResult<Options12> {
+        let (raw, hr) = self.check_feature_support::<D3D12_FEATURE_DATA_D3D12_OPTIONS12>(
+            D3D12_FEATURE_D3D12_OPTIONS12,
+            unsafe { mem::zeroed() },
+        );
+        (raw.into(), hr)
+    }
+
+    pub fn architecture(&self, node_index: u32) -> D3DResult<Architecture> {
+        let (raw, hr) = self.check_feature_support(
+            d3d12::D3D12_FEATURE_ARCHITECTURE1,
+            d3d12::D3D12_FEATURE_DATA_ARCHITECTURE1 {
+                NodeIndex: node_index,
+                TileBasedRenderer: 0,
+                UMA: 0,
+                CacheCoherentUMA: 0,
+                IsolatedMMU: 0,
+            },
+        );
+        let architecture = Architecture {
+            node_index: raw.NodeIndex,
+            tile_based_renderer: raw.TileBasedRenderer != 0,
+            uma: raw.UMA != 0,
+            cache_coherent_uma: raw.CacheCoherentUMA != 0,
+            isolated_mmu: raw.IsolatedMMU != 0,
+        };
+        (architecture, hr)
+    }
+
+    /// Highest supported shader model.
+    ///
+    /// Runtimes reject shader models they don't know, so this steps down from the newest one.
+    pub fn shader_model(&self) -> D3DResult<ShaderModel> {
+        let mut hr = E_INVALIDARG;
+        for &model in [
+            ShaderModel::V6_8,
+            ShaderModel::V6_7,
+            ShaderModel::V6_6,
+            ShaderModel::V6_5,
+            ShaderModel::V6_4,
+            ShaderModel::V6_3,
+            ShaderModel::V6_2,
+            ShaderModel::V6_1,
+            ShaderModel::V6_0,
+            ShaderModel::V5_1,
+        ]
+        .iter()
+        {
+            let (raw, result) = self.check_feature_support(
+                d3d12::D3D12_FEATURE_SHADER_MODEL,
+                d3d12::D3D12_FEATURE_DATA_SHADER_MODEL {
+                    HighestShaderModel: model as _,
+                },
+            );
+            hr = result;
+            if SUCCEEDED(hr) {
+                return (ShaderModel::from_raw(raw.HighestShaderModel), hr);
+            }
+            if hr != E_INVALIDARG {
+                break;
+            }
+        }
+        (ShaderModel::V5_1, hr)
+    }
+
+    /// Highest supported root signature version.
+    pub fn root_signature_version(&self) -> D3DResult<RootSignatureVersion> {
+        let (raw, hr) = self.check_feature_support(
+            d3d12::D3D12_FEATURE_ROOT_SIGNATURE,
+            d3d12::D3D12_FEATURE_DATA_ROOT_SIGNATURE {
+                HighestVersion: d3d12::D3D_ROOT_SIGNATURE_VERSION_1_1,
+            },
+        );
+        let version =
+            if SUCCEEDED(hr) && raw.HighestVersion >= d3d12::D3D_ROOT_SIGNATURE_VERSION_1_1 {
+                RootSignatureVersion::V1_1
+            } else {
+                RootSignatureVersion::V1_0
+            };
+        (version, hr)
+    }
+
+    /// Highest of the given feature levels supported by the device.
+    pub fn max_feature_level(&self, levels: &[FeatureLevel]) -> D3DResult<Option<FeatureLevel>> {
+        let levels = levels
+            .iter()
+            .map(|&level| level as d3dcommon::D3D_FEATURE_LEVEL)
+            .collect::<Vec<_>>();
+        let (raw, hr) = self.check_feature_support(
+            d3d12::D3D12_FEATURE_FEATURE_LEVELS,
+            d3d12::D3D12_FEATURE_DATA_FEATURE_LEVELS {
+                NumFeatureLevels: levels.len() as _,
+                pFeatureLevelsRequested: levels.as_ptr(),
+                MaxSupportedFeatureLevel: 0,
+            },
+        );
+        let level = if SUCCEEDED(hr) {
+            FeatureLevel::try_from(raw.MaxSupportedFeatureLevel).ok()
+        } else {
+            None
+        };
+        (level, hr)
+    }
+
+    pub fn gpu_virtual_address_support(&self) -> D3DResult<GpuVirtualAddressSupport> {
+        let (raw, hr) = self
+            .check_feature_support::<d3d12::D3D12_FEATURE_DATA_GPU_VIRTUAL_ADDRESS_SUPPORT>(
+                d3d12::D3D12_FEATURE_GPU_VIRTUAL_ADDRESS_SUPPORT,
+                unsafe { mem::zeroed() },
+            );
+        let support = GpuVirtualAddressSupport {
+            max_bits_per_resource: raw.MaxGPUVirtualAddressBitsPerResource,
+            max_bits_per_process: raw.MaxGPUVirtualAddressBitsPerProcess,
+        };
+        (support, hr)
+    }
+
+    pub fn format_support(&self, format: Format) -> D3DResult<FormatSupport> {
+        let (raw, hr) = self.check_feature_support(
+            d3d12::D3D12_FEATURE_FORMAT_SUPPORT,
+            d3d12::D3D12_FEATURE_DATA_FORMAT_SUPPORT {
+                Format: format as _,
+                Support1: 0,
+                Support2: 0,
+            },
+        );
+        let support = FormatSupport {
+            support1: FormatSupport1::from_bits_truncate(raw.Support1),
+            support2: FormatSupport2::from_bits_truncate(raw.Support2),
+        };
+        (support, hr)
+    }
+
+    /// Run all feature queries, except the per format ones.
+    ///
+    /// This issues close to twenty `CheckFeatureSupport` calls, prefer the capabilities
+    /// collected when creating the device.
+    pub fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            options: succeeded(self.options()),
+            options1: succeeded(self.options1()),
+            options2: succeeded(self.options2()),
+            options3: succeeded(self.options3()),
+            options4: succeeded(self.options4()),
+            options5: succeeded(self.options5()),
+            options6: succeeded(self.options6()),
+            options7: succeeded(self.options7()),
+            options8: succeeded(self.options8()),
+            options9: succeeded(self.options9()),
+            options10: succeeded(self.options10()),
+            options11: succeeded(self.options11()),
+            options12: succeeded(self.options12()),
+            architecture: succeeded(self.architecture(0)),
+            shader_model: succeeded(self.shader_model()),
+            root_signature_version: succeeded(self.root_signature_version()),
+            max_feature_level: succeeded(self.max_feature_level(&[
+                FeatureLevel::L11_0,
+                FeatureLevel::L11_1,
+                FeatureLevel::L12_0,
+                FeatureLevel::L12_1,
+                FeatureLevel::L12_2,
+            ]))
+            .flatten(),
+            gpu_virtual_address_support: succeeded(self.gpu_virtual_address_support()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::com_object::{unimplemented_vtable, ComObject};
+    use std::cell::RefCell;
+    use winapi::{
+        ctypes::c_void,
+        shared::winerror::{E_FAIL, HRESULT, S_OK},
+    };
+
+    // Answers feature queries like a device supporting shader model `highest_shader_model`
+    // on a runtime which knows shader models up to `known_shader_model`.
+    struct Features {
+        known_shader_model: u32,
+        highest_shader_model: u32,
+        shader_model_result: HRESULT,
+        unknown: Vec<d3d12::D3D12_FEATURE>,
+        requested_shader_models: RefCell<Vec<u32>>,
+    }
+
+    type DeviceObject = ComObject<d3d12::ID3D12DeviceVtbl, Features>;
+
+    unsafe fn write<T>(data: *mut c_void, size: UINT, f: impl FnOnce(&mut T)) -> HRESULT {
+        assert_eq!(size as usize, mem::size_of::<T>());
+        f(&mut *(data as *mut T));
+        S_OK
+    }
+
+    unsafe extern "system" fn check_feature_support(
+        this: *mut d3d12::ID3D12Device,
+        feature: d3d12::D3D12_FEATURE,
+        data: *mut c_void,
+        size: UINT,
+    ) -> HRESULT {
+        let features = DeviceObject::from_interface(this).data();
+        if features.unknown.contains(&feature) {
+            return E_INVALIDARG;
+        }
+        match feature {
+            d3d12::D3D12_FEATURE_D3D12_OPTIONS => write(
+                data,
+                size,
+                |raw: &mut d3d12::D3D12_FEATURE_DATA_D3D12_OPTIONS| {
+                    raw.DoublePrecisionFloatShaderOps = 1;
+                    raw.TiledResourcesTier = 3;
+                    raw.ResourceBindingTier = 3;
+                    raw.ResourceHeapTier = 2;
+                },
+            ),
+            D3D12_FEATURE_D3D12_OPTIONS5 => {
+                write(data, size, |raw: &mut D3D12_FEATURE_DATA_D3D12_OPTIONS5| {
+                    raw.RaytracingTier = 11;
+                })
+            }
+            d3d12::D3D12_FEATURE_SHADER_MODEL => {
+                let raw = &mut *(data as *mut d3d12::D3D12_FEATURE_DATA_SHADER_MODEL);
+                let requested = raw.HighestShaderModel;
+                features
+                    .requested_shader_models
+                    .borrow_mut()
+                    .push(requested);
+                if requested > features.known_shader_model {
+                    return E_INVALIDARG;
+                }
+                raw.HighestShaderModel = requested.min(features.highest_shader_model);
+                features.shader_model_result
+            }
+            d3d12::D3D12_FEATURE_FEATURE_LEVELS => write(
+                data,
+                size,
+                |raw: &mut d3d12::D3D12_FEATURE_DATA_FEATURE_LEVELS| {
+                    raw.MaxSupportedFeatureLevel = d3dcommon::D3D_FEATURE_LEVEL_12_1;
+                },
+            ),
+            d3d12::D3D12_FEATURE_GPU_VIRTUAL_ADDRESS_SUPPORT => write(
+                data,
+                size,
+                |raw: &mut d3d12::D3D12_FEATURE_DATA_GPU_VIRTUAL_ADDRESS_SUPPORT| {
+                    raw.MaxGPUVirtualAddressBitsPerResource = 40;
+                    raw.MaxGPUVirtualAddressBitsPerProcess = 44;
+                },
+            ),
+            _ => S_OK,
+        }
+    }
+
+    fn fake_device(features: Features) -> Device {
+        unsafe {
+            let mut vtbl: d3d12::ID3D12DeviceVtbl = unimplemented_vtable();
+            vtbl.CheckFeatureSupport = check_feature_support;
+            DeviceObject::create::<d3d12::ID3D12Device>(vtbl, &[], features)
+        }
+    }
+
+    fn features(known_shader_model: u32, highest_shader_model: u32) -> Features {
+        Features {
+            known_shader_model,
+            highest_shader_model,
+            shader_model_result: S_OK,
+            unknown: Vec::new(),
+            requested_shader_models: RefCell::default(),
+        }
+    }
+
+    fn requested_shader_models(device: Device) -> Vec<u32> {
+        unsafe { DeviceObject::from_interface(device.as_mut_ptr()) }
+            .data()
+            .requested_shader_models
+            .take()
+    }
+
+    #[test]
+    fn tiers() {
+        assert_eq!(
+            TiledResourcesTier::from_raw(0),
+            TiledResourcesTier::NotSupported
+        );
+        assert_eq!(TiledResourcesTier::from_raw(4), TiledResourcesTier::Tier4);
+        // Tiers newer than the crate map to the highest known one.
+        assert_eq!(TiledResourcesTier::from_raw(5), TiledResourcesTier::Tier4);
+        assert_eq!(ResourceBindingTier::from_raw(0), ResourceBindingTier::Tier1);
+        assert_eq!(ResourceHeapTier::from_raw(7), ResourceHeapTier::Tier2);
+        // Values between tiers map to the lower one.
+        assert_eq!(RaytracingTier::from_raw(5), RaytracingTier::NotSupported);
+        assert_eq!(RaytracingTier::from_raw(10), RaytracingTier::Tier1_0);
+        assert_eq!(RaytracingTier::from_raw(12), RaytracingTier::Tier1_1);
+        assert_eq!(
+            SamplerFeedbackTier::from_raw(95),
+            SamplerFeedbackTier::Tier0_9
+        );
+        assert_eq!(ShaderModel::from_raw(0x50), ShaderModel::V5_1);
+        assert_eq!(ShaderModel::from_raw(0x69), ShaderModel::V6_8);
+        assert!(RaytracingTier::Tier1_0 < RaytracingTier::Tier1_1);
+    }
+
+    #[test]
+    fn options() {
+        let mut raw: d3d12::D3D12_FEATURE_DATA_D3D12_OPTIONS = unsafe { mem::zeroed() };
+        raw.OutputMergerLogicOp = 1;
+        raw.MinPrecisionSupport = d3d12::D3D12_SHADER_MIN_PRECISION_SUPPORT_16_BIT;
+        raw.TiledResourcesTier = 2;
+        raw.ResourceBindingTier = 3;
+        raw.ConservativeRasterizationTier = 1;
+        raw.MaxGPUVirtualAddressBitsPerResource = 40;
+        raw.CrossNodeSharingTier = 1;
+        raw.ResourceHeapTier = 2;
+        let options = Options::from(raw);
+        assert!(!options.double_precision_float_shader_ops);
+        assert!(options.output_merger_logic_op);
+        assert_eq!(options.min_precision_support, MinPrecisionSupport::BITS_16);
+        assert_eq!(options.tiled_resources_tier, TiledResourcesTier::Tier2);
+        assert_eq!(options.resource_binding_tier, ResourceBindingTier::Tier3);
+        assert_eq!(
+            options.conservative_rasterization_tier,
+            ConservativeRasterizationTier::Tier1
+        );
+        assert_eq!(options.max_gpu_virtual_address_bits_per_resource, 40);
+        assert_eq!(
+            options.cross_node_sharing_tier,
+            CrossNodeSharingTier::Tier1Emulated
+        );
+        assert_eq!(options.resource_heap_tier, ResourceHeapTier::Tier2);
+
+        let options = Options3::from(D3D12_FEATURE_DATA_D3D12_OPTIONS3 {
+            CopyQueueTimestampQueriesSupported: 1,
+            CastingFullyTypedFormatSupported: 0,
+            WriteBufferImmediateSupportFlags: 0x85,
+            ViewInstancingTier: 2,
+            BarycentricsSupported: 1,
+        });
+        assert!(options.copy_queue_timestamp_queries_supported);
+        assert!(!options.casting_fully_typed_format_supported);
+        // Unknown list types are dropped.
+        assert_eq!(
+            options.write_buffer_immediate_support_flags,
+            CommandListSupportFlags::DIRECT | CommandListSupportFlags::COMPUTE
+        );
+        assert_eq!(options.view_instancing_tier, ViewInstancingTier::Tier2);
+        assert!(options.barycentrics_supported);
+
+        let options = Options7::from(D3D12_FEATURE_DATA_D3D12_OPTIONS7 {
+            MeshShaderTier: 10,
+            SamplerFeedbackTier: 90,
+        });
+        assert_eq!(options.mesh_shader_tier, MeshShaderTier::Tier1);
+        assert_eq!(options.sampler_feedback_tier, SamplerFeedbackTier::Tier0_9);
+
+        let options12 = |state| {
+            Options12::from(D3D12_FEATURE_DATA_D3D12_OPTIONS12 {
+                MSPrimitivesPipelineStatisticIncludesCulledPrimitives: state,
+                EnhancedBarriersSupported: 1,
+                RelaxedFormatCastingSupported: 0,
+            })
+        };
+        assert_eq!(
+            options12(-1).ms_primitives_pipeline_statistic_includes_culled_primitives,
+            None
+        );
+        assert_eq!(
+            options12(0).ms_primitives_pipeline_statistic_includes_culled_primitives,
+            Some(false)
+        );
+        assert_eq!(
+            options12(1).ms_primitives_pipeline_statistic_includes_culled_primitives,
+            Some(true)
+        );
+        assert!(options12(1).enhanced_barriers_supported);
+        assert!(!options12(1).relaxed_format_casting_supported);
+    }
+
+    #[test]
+    fn shader_model() {
+        // Steps down until the runtime knows the requested model.
+        let device = fake_device(features(0x66, 0x65));
+        assert_eq!(device.shader_model(), (ShaderModel::V6_5, S_OK));
+        assert_eq!(requested_shader_models(device), [0x68, 0x67, 0x66]);
+        unsafe { device.destroy() };
+
+        let device = fake_device(features(0x68, 0x68));
+        assert_eq!(device.shader_model(), (ShaderModel::V6_8, S_OK));
+        assert_eq!(requested_shader_models(device), [0x68]);
+        unsafe { device.destroy() };
+
+        // Runtimes without shader model 6 reject every query but 5.1.
+        let device = fake_device(features(0x51, 0x51));
+        assert_eq!(device.shader_model(), (ShaderModel::V5_1, S_OK));
+        assert_eq!(requested_shader_models(device).len(), 10);
+        unsafe { device.destroy() };
+
+        // Other errors end the search.
+        let device = fake_device(Features {
+            shader_model_result: E_FAIL,
+            ..features(0x68, 0x68)
+        });
+        assert_eq!(device.shader_model(), (ShaderModel::V5_1, E_FAIL));
+        assert_eq!(requested_shader_models(device), [0x68]);
+        unsafe { device.destroy() };
+    }
+
+    #[test]
+    fn capabilities() {
+        let device = fake_device(Features {
+            unknown: vec![
+                D3D12_FEATURE_D3D12_OPTIONS12,
+                d3d12::D3D12_FEATURE_ARCHITECTURE1,
+            ],
+            ..features(0x67, 0x66)
+        });
+        let capabilities = device.capabilities();
+
+        let options = capabilities.options.unwrap();
+        assert!(options.double_precision_float_shader_ops);
+        assert_eq!(options.tiled_resources_tier, TiledResourcesTier::Tier3);
+        assert_eq!(options.resource_binding_tier, ResourceBindingTier::Tier3);
+        assert_eq!(
+            capabilities.options5.unwrap().raytracing_tier,
+            RaytracingTier::Tier1_1
+        );
+        assert_eq!(
+            capabilities.options7.unwrap().mesh_shader_tier,
+            MeshShaderTier::NotSupported
+        );
+        assert_eq!(capabilities.options12, None);
+        assert_eq!(capabilities.architecture, None);
+        assert_eq!(capabilities.shader_model, Some(ShaderModel::V6_6));
+        assert_eq!(
+            capabilities.root_signature_version,
+            Some(RootSignatureVersion::V1_1)
+        );
+        assert_eq!(capabilities.max_feature_level, Some(FeatureLevel::L12_1));
+        assert_eq!(
+            capabilities.gpu_virtual_address_support,
+            Some(GpuVirtualAddressSupport {
+                max_bits_per_resource: 40,
+                max_bits_per_process: 44,
+            })
+        );
+
+        #[cfg(any(feature = "libloading", feature = "implicit-link"))]
+        {
+            let ((created, collected), hr) = with_capabilities((device, S_OK));
+            assert_eq!((created, collected, hr), (device, capabilities, S_OK));
+            let ((null, collected), hr) = with_capabilities((Device::null(), E_FAIL));
+            assert!(null.is_null());
+            assert_eq!((collected, hr), (Capabilities::default(), E_FAIL));
+        }
+        unsafe { device.destroy() };
+    }
+}
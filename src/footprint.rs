@@ -0,0 +1,484 @@
+//! Subresource indexing and copyable footprints
+//!
+//! Pure reimplementation of `ID3D12Device::GetCopyableFootprints`, usable without a device
+//! to size and fill upload buffers.
+
+use crate::{Device, Format, ResourceDesc, ResourceDimension, Subresource};
+use std::{convert::TryFrom, mem, ops::Range};
+use winapi::um::d3d12;
+
+/// Index of a subresource, equivalent to `D3D12CalcSubresource`.
+pub fn calc_subresource(
+    mip_slice: u32,
+    array_slice: u32,
+    plane_slice: u32,
+    mip_levels: u32,
+    array_size: u32,
+) -> Subresource {
+    mip_slice + array_slice * mip_levels + plane_slice * mip_levels * array_size
+}
+
+/// Mip, array and plane slice of a subresource, inverse of `calc_subresource`.
+///
+/// Returns `None` if `mip_levels` or `array_size` is zero.
+pub fn decompose_subresource(
+    subresource: Subresource,
+    mip_levels: u32,
+    array_size: u32,
+) -> Option<(u32, u32, u32)> {
+    let mip_slice = subresource.checked_rem(mip_levels)?;
+    let array_slice = (subresource / mip_levels).checked_rem(array_size)?;
+    let plane_slice = subresource / mip_levels / array_size;
+    Some((mip_slice, array_slice, plane_slice))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubresourceFootprint {
+    pub format: Format,
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+    pub row_pitch: u32,
+}
+
+/// Footprint of a subresource at a byte offset inside of a buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlacedSubresourceFootprint {
+    pub offset: u64,
+    pub footprint: SubresourceFootprint,
+}
+
+impl PlacedSubresourceFootprint {
+    pub fn to_raw(self) -> d3d12::D3D12_PLACED_SUBRESOURCE_FOOTPRINT {
+        d3d12::D3D12_PLACED_SUBRESOURCE_FOOTPRINT {
+            Offset: self.offset,
+            Footprint: d3d12::D3D12_SUBRESOURCE_FOOTPRINT {
+                Format: self.footprint.format as _,
+                Width: self.footprint.width,
+                Height: self.footprint.height,
+                Depth: self.footprint.depth,
+                RowPitch: self.footprint.row_pitch,
+            },
+        }
+    }
+
+    fn from_raw(raw: &d3d12::D3D12_PLACED_SUBRESOURCE_FOOTPRINT) -> Self {
+        PlacedSubresourceFootprint {
+            offset: raw.Offset,
+            footprint: SubresourceFootprint {
+                format: Format::try_from(raw.Footprint.Format).unwrap_or(Format::Unknown),
+                width: raw.Footprint.Width,
+                height: raw.Footprint.Height,
+                depth: raw.Footprint.Depth,
+                row_pitch: raw.Footprint.RowPitch,
+            },
+        }
+    }
+}
+
+/// Layout of a range of subresources copied to or from a buffer.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CopyableFootprints {
+    pub layouts: Vec<PlacedSubresourceFootprint>,
+    /// Number of rows of texels, or blocks for block compressed formats, per depth slice.
+    pub num_rows: Vec<u32>,
+    /// Unpadded size of a row.
+    pub row_sizes: Vec<u64>,
+    /// Size of the range, from the first offset to the end of the last row.
+    pub total_bytes: u64,
+}
+
+// Format of a single plane and the log2 of its subsampling in x and y.
+fn plane_format(format: Format, plane: u32) -> (Format, u32, u32) {
+    match (format, plane) {
+        // Any format of a depth stencil family has both planes, whichever one its views read.
+        (Format::R32G8X24Typeless, 0)
+        | (Format::D32FloatS8X24Uint, 0)
+        | (Format::R32FloatX8X24Typeless, 0)
+        | (Format::X32TypelessG8X24Uint, 0)
+        | (Format::R24G8Typeless, 0)
+        | (Format::D24UnormS8Uint, 0)
+        | (Format::R24UnormX8Typeless, 0)
+        | (Format::X24TypelessG8Uint, 0) => (Format::R32Typeless, 0, 0),
+        (Format::R32G8X24Typeless, 1)
+        | (Format::D32FloatS8X24Uint, 1)
+        | (Format::R32FloatX8X24Typeless, 1)
+        | (Format::X32TypelessG8X24Uint, 1)
+        | (Format::R24G8Typeless, 1)
+        | (Format::D24UnormS8Uint, 1)
+        | (Format::R24UnormX8Typeless, 1)
+        | (Format::X24TypelessG8Uint, 1) => (Format::R8Typeless, 0, 0),
+        (Format::Nv12, 0)
+        | (Format::Opaque420, 0)
+        | (Format::Nv11, 0)
+        | (Format::P208, 0)
+        | (Format::V208, 0)
+        | (Format::V408, _) => (Format::R8Typeless, 0, 0),
+        (Format::V208, _) => (Format::R8Typeless, 0, 1),
+        (Format::Nv12, 1) | (Format::Opaque420, 1) => (Format::R8G8Typeless, 1, 1),
+        (Format::Nv11, 1) => (Format::R8G8Typeless, 2, 0),
+        (Format::P208, 1) => (Format::R8G8Typeless, 1, 0),
+        (Format::P010, 0) | (Format::P016, 0) => (Format::R16Typeless, 0, 0),
+        (Format::P010, 1) | (Format::P016, 1) => (Format::R16G16Typeless, 1, 1),
+        _ => (format, 0, 0),
+    }
+}
+
+// Alignments are powers of two.
+fn align(value: u64, alignment: u64) -> u64 {
+    (value + alignment - 1) & !(alignment - 1)
+}
+
+impl ResourceDesc {
+    /// Layout of the subresources in `subresources` inside of a buffer, starting at
+    /// `base_offset`.
+    ///
+    /// Rows are padded to `D3D12_TEXTURE_DATA_PITCH_ALIGNMENT` and subresources placed at
+    /// multiples of `D3D12_TEXTURE_DATA_PLACEMENT_ALIGNMENT`, matching the runtime.
+    /// Returns `None` if the range exceeds the subresources of the resource.
+    ///
+    /// ```
+    /// use d3d12::{Format, ResourceDesc};
+    ///
+    /// let desc = ResourceDesc::texture_2d(Format::R8G8B8A8Unorm, 100, 100);
+    /// let footprints = desc.copyable_footprints(0..1, 0).unwrap();
+    /// assert_eq!(footprints.layouts[0].footprint.row_pitch, 512);
+    /// assert_eq!(footprints.num_rows, [100]);
+    /// assert_eq!(footprints.row_sizes, [400]);
+    /// assert_eq!(footprints.total_bytes, 512 * 99 + 400);
+    /// ```
+    pub fn copyable_footprints(
+        &self,
+        subresources: Range<Subresource>,
+        base_offset: u64,
+    ) -> Option<CopyableFootprints> {
+        if subresources.start > subresources.end || subresources.end > self.subresource_count() {
+            return None;
+        }
+
+        let pitch_alignment = d3d12::D3D12_TEXTURE_DATA_PITCH_ALIGNMENT as u64;
+        let placement_alignment = d3d12::D3D12_TEXTURE_DATA_PLACEMENT_ALIGNMENT as u64;
+        let mut footprints = CopyableFootprints::default();
+        let mut offset = 0;
+        let is_buffer = self.dimension() == ResourceDimension::Buffer;
+
+        for subresource in subresources {
+            let (layout, num_rows, row_size, size) = if is_buffer {
+                let footprint = SubresourceFootprint {
+                    format: Format::Unknown,
+                    width: self.width() as _,
+                    height: 1,
+                    depth: 1,
+                    row_pitch: align(self.width(), pitch_alignment) as _,
+                };
+                let layout = PlacedSubresourceFootprint {
+                    offset: base_offset,
+                    footprint,
+                };
+                (layout, 1, self.width(), self.width())
+            } else {
+                let (mip, _, plane) = decompose_subresource(
+                    subresource,
+                    self.mip_levels() as _,
+                    self.array_size() as _,
+                )?;
+                let (format, shift_x, shift_y) = plane_format(self.format(), plane);
+                let (block_width, block_height) = format.block_dimensions();
+
+                let width = ((self.width() >> mip).max(1) as u32 + (1 << shift_x) - 1) >> shift_x;
+                let height = ((self.height() >> mip).max(1) + (1 << shift_y) - 1) >> shift_y;
+                let depth = (self.depth() as u32 >> mip).max(1);
+                let width = align(width as _, block_width as _) as u32;
+                let height = align(height as _, block_height as _) as u32;
+
+                let num_rows = height / block_height;
+                let row_size = (width / block_width) as u64 * format.bytes_per_block() as u64;
+                let row_pitch = align(row_size, pitch_alignment);
+
+                offset = align(offset, placement_alignment);
+                let layout = PlacedSubresourceFootprint {
+                    offset: base_offset + offset,
+                    footprint: SubresourceFootprint {
+                        format,
+                        width,
+                        height,
+                        depth,
+                        row_pitch: row_pitch as _,
+                    },
+                };
+                let rows = num_rows as u64 * depth as u64;
+                (
+                    layout,
+                    num_rows,
+                    row_size,
+                    row_pitch * (rows - 1) + row_size,
+                )
+            };
+
+            footprints.total_bytes = layout.offset - base_offset + size;
+            offset = footprints.total_bytes;
+            footprints.layouts.push(layout);
+            footprints.num_rows.push(num_rows);
+            footprints.row_sizes.push(row_size);
+        }
+
+        Some(footprints)
+    }
+}
+
+impl Device {
+    /// Layout of the subresources as reported by the runtime.
+    ///
+    /// Equivalent to `ResourceDesc::copyable_footprints`, except for returning `u64::MAX`
+    /// sizes for invalid descriptions.
+    pub fn copyable_footprints(
+        &self,
+        desc: &ResourceDesc,
+        subresources: Range<Subresource>,
+        base_offset: u64,
+    ) -> CopyableFootprints {
+        let count = subresources.end.saturating_sub(subresources.start) as usize;
+        let mut layouts = vec![unsafe { mem::zeroed() }; count];
+        let mut num_rows = vec![0; count];
+        let mut row_sizes = vec![0; count];
+        let mut total_bytes = 0;
+
+        unsafe {
+            self.GetCopyableFootprints(
+                &desc.to_raw(),
+                subresources.start,
+                count as _,
+                base_offset,
+                layouts.as_mut_ptr(),
+                num_rows.as_mut_ptr(),
+                row_sizes.as_mut_ptr(),
+                &mut total_bytes,
+            );
+        }
+
+        CopyableFootprints {
+            layouts: layouts
+                .iter()
+                .map(PlacedSubresourceFootprint::from_raw)
+                .collect(),
+            num_rows,
+            row_sizes,
+            total_bytes,
+        }
+    }
+}
+
+// Expected values follow the rules of `GetCopyableFootprints`: rows padded to 256 bytes,
+// subresources placed at multiples of 512 bytes, block compressed sizes rounded up to whole
+// blocks and planar formats split into their per plane formats. They were worked out from
+// those rules, not captured from a device.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placed(
+        offset: u64,
+        format: Format,
+        (width, height, depth): (u32, u32, u32),
+        row_pitch: u32,
+    ) -> PlacedSubresourceFootprint {
+        PlacedSubresourceFootprint {
+            offset,
+            footprint: SubresourceFootprint {
+                format,
+                width,
+                height,
+                depth,
+                row_pitch,
+            },
+        }
+    }
+
+    #[test]
+    fn subresource_indices() {
+        assert_eq!(calc_subresource(2, 1, 1, 4, 3), 2 + 4 + 12);
+        for subresource in 0..24 {
+            let (mip, array, plane) = decompose_subresource(subresource, 4, 3).unwrap();
+            assert_eq!(calc_subresource(mip, array, plane, 4, 3), subresource);
+        }
+        assert_eq!(decompose_subresource(18, 4, 3), Some((2, 1, 1)));
+        assert_eq!(decompose_subresource(1, 0, 3), None);
+        assert_eq!(decompose_subresource(1, 4, 0), None);
+    }
+
+    #[test]
+    fn buffer() {
+        let desc = ResourceDesc::buffer(1000);
+        let footprints = desc.copyable_footprints(0..1, 256).unwrap();
+        assert_eq!(
+            footprints.layouts,
+            [placed(256, Format::Unknown, (1000, 1, 1), 1024)]
+        );
+        assert_eq!(footprints.num_rows, [1]);
+        assert_eq!(footprints.row_sizes, [1000]);
+        assert_eq!(footprints.total_bytes, 1000);
+    }
+
+    #[test]
+    fn block_compressed_mips() {
+        let desc = ResourceDesc::texture_2d(Format::Bc1Unorm, 16, 16).with_full_mip_chain();
+        let footprints = desc.copyable_footprints(0..5, 0).unwrap();
+        // Mips below 4x4 still take up a whole block.
+        assert_eq!(
+            footprints.layouts,
+            [
+                placed(0, Format::Bc1Unorm, (16, 16, 1), 256),
+                placed(1024, Format::Bc1Unorm, (8, 8, 1), 256),
+                placed(1536, Format::Bc1Unorm, (4, 4, 1), 256),
+                placed(2048, Format::Bc1Unorm, (4, 4, 1), 256),
+                placed(2560, Format::Bc1Unorm, (4, 4, 1), 256),
+            ]
+        );
+        assert_eq!(footprints.num_rows, [4, 2, 1, 1, 1]);
+        assert_eq!(footprints.row_sizes, [32, 16, 8, 8, 8]);
+        assert_eq!(footprints.total_bytes, 2560 + 8);
+
+        let bc7 = ResourceDesc::texture_2d(Format::Bc7Unorm, 8, 4).with_mip_levels(3);
+        let footprints = bc7.copyable_footprints(1..3, 0).unwrap();
+        assert_eq!(footprints.row_sizes, [16, 16]);
+        assert_eq!(footprints.layouts[1].offset, 512);
+        assert_eq!(footprints.total_bytes, 512 + 16);
+    }
+
+    #[test]
+    fn volume() {
+        let desc = ResourceDesc::texture_3d(Format::R8G8B8A8Unorm, 10, 6, 5).with_mip_levels(2);
+        let footprints = desc.copyable_footprints(0..2, 0).unwrap();
+        // Every depth slice is a set of rows, the last row of the last slice isn't padded.
+        let first = 256 * (6 * 5 - 1) + 40;
+        assert_eq!(first, 7464);
+        assert_eq!(
+            footprints.layouts,
+            [
+                placed(0, Format::R8G8B8A8Unorm, (10, 6, 5), 256),
+                placed(7680, Format::R8G8B8A8Unorm, (5, 3, 2), 256),
+            ]
+        );
+        assert_eq!(footprints.num_rows, [6, 3]);
+        assert_eq!(footprints.row_sizes, [40, 20]);
+        assert_eq!(footprints.total_bytes, 7680 + 256 * 5 + 20);
+    }
+
+    #[test]
+    fn array() {
+        let desc = ResourceDesc::texture_2d(Format::R8Unorm, 3, 3)
+            .with_array_size(3)
+            .with_mip_levels(2);
+        let footprints = desc.copyable_footprints(0..6, 0).unwrap();
+        // Subresources go through the mips of one slice before the next slice, each
+        // starting at the next multiple of 512 bytes.
+        assert_eq!(
+            footprints.layouts,
+            [
+                placed(0, Format::R8Unorm, (3, 3, 1), 256),
+                placed(1024, Format::R8Unorm, (1, 1, 1), 256),
+                placed(1536, Format::R8Unorm, (3, 3, 1), 256),
+                placed(2560, Format::R8Unorm, (1, 1, 1), 256),
+                placed(3072, Format::R8Unorm, (3, 3, 1), 256),
+                placed(4096, Format::R8Unorm, (1, 1, 1), 256),
+            ]
+        );
+        assert_eq!(footprints.num_rows, [3, 1, 3, 1, 3, 1]);
+        assert_eq!(footprints.total_bytes, 4096 + 1);
+
+        // A range starting in the middle is laid out from the start of the buffer.
+        let footprints = desc.copyable_footprints(3..5, 0).unwrap();
+        assert_eq!(footprints.layouts[0].offset, 0);
+        assert_eq!(footprints.layouts[1].offset, 512);
+    }
+
+    #[test]
+    fn video_planes() {
+        let nv12 = ResourceDesc::texture_2d(Format::Nv12, 8, 4);
+        assert_eq!(nv12.subresource_count(), 2);
+        let footprints = nv12.copyable_footprints(0..2, 0).unwrap();
+        assert_eq!(
+            footprints.layouts,
+            [
+                placed(0, Format::R8Typeless, (8, 4, 1), 256),
+                placed(1024, Format::R8G8Typeless, (4, 2, 1), 256),
+            ]
+        );
+        assert_eq!(footprints.num_rows, [4, 2]);
+        assert_eq!(footprints.row_sizes, [8, 8]);
+        assert_eq!(footprints.total_bytes, 1024 + 256 + 8);
+
+        let p010 = ResourceDesc::texture_2d(Format::P010, 8, 4);
+        let footprints = p010.copyable_footprints(0..2, 0).unwrap();
+        assert_eq!(
+            footprints.layouts,
+            [
+                placed(0, Format::R16Typeless, (8, 4, 1), 256),
+                placed(1024, Format::R16G16Typeless, (4, 2, 1), 256),
+            ]
+        );
+        assert_eq!(footprints.row_sizes, [16, 16]);
+        assert_eq!(footprints.total_bytes, 1024 + 256 + 16);
+    }
+
+    #[test]
+    fn depth_stencil_planes() {
+        for &format in &[
+            Format::R32G8X24Typeless,
+            Format::D32FloatS8X24Uint,
+            Format::R32FloatX8X24Typeless,
+            Format::X32TypelessG8X24Uint,
+            Format::R24G8Typeless,
+            Format::D24UnormS8Uint,
+            Format::R24UnormX8Typeless,
+            Format::X24TypelessG8Uint,
+        ] {
+            let desc = ResourceDesc::texture_2d(format, 4, 4).with_mip_levels(2);
+            assert_eq!(desc.subresource_count(), 4);
+            let footprints = desc.copyable_footprints(0..4, 0).unwrap();
+            // Depth of every format in both families is copied as 32 bit values, stencil as
+            // 8 bit values, also for formats whose views only read one of the planes.
+            assert_eq!(
+                footprints.layouts,
+                [
+                    placed(0, Format::R32Typeless, (4, 4, 1), 256),
+                    placed(1024, Format::R32Typeless, (2, 2, 1), 256),
+                    placed(1536, Format::R8Typeless, (4, 4, 1), 256),
+                    placed(2560, Format::R8Typeless, (2, 2, 1), 256),
+                ],
+                "{:?}",
+                format
+            );
+            assert_eq!(footprints.row_sizes, [16, 8, 4, 2]);
+            assert_eq!(footprints.total_bytes, 2560 + 256 + 2);
+        }
+    }
+
+    #[test]
+    fn base_offset() {
+        let desc = ResourceDesc::texture_2d(Format::R8G8B8A8Unorm, 100, 100).with_mip_levels(2);
+        let at_zero = desc.copyable_footprints(0..2, 0).unwrap();
+        let moved = desc.copyable_footprints(0..2, 0x1_0000).unwrap();
+
+        assert_eq!(at_zero.layouts[1].offset, 51200);
+        for (moved, at_zero) in moved.layouts.iter().zip(&at_zero.layouts) {
+            assert_eq!(moved.offset, at_zero.offset + 0x1_0000);
+            assert_eq!(moved.footprint, at_zero.footprint);
+        }
+        // The total doesn't include the base offset.
+        assert_eq!(moved.total_bytes, at_zero.total_bytes);
+        assert_eq!(moved.total_bytes, 51200 + 256 * 49 + 200);
+    }
+
+    #[test]
+    fn out_of_range() {
+        let desc = ResourceDesc::texture_2d(Format::R8Unorm, 4, 4).with_mip_levels(3);
+        assert!(desc.copyable_footprints(0..4, 0).is_none());
+        let reversed = Range { start: 2, end: 1 };
+        assert!(desc.copyable_footprints(reversed, 0).is_none());
+        assert_eq!(
+            desc.copyable_footprints(1..1, 0),
+            Some(CopyableFootprints::default())
+        );
+    }
+}
@@ -0,0 +1,1027 @@
+//! DXGI formats
+
+use std::convert::TryFrom;
+use winapi::shared::dxgiformat;
+
+// Not yet part of `winapi`.
+const DXGI_FORMAT_SAMPLER_FEEDBACK_MIN_MIP_OPAQUE: dxgiformat::DXGI_FORMAT = 189;
+const DXGI_FORMAT_SAMPLER_FEEDBACK_MIP_REGION_USED_OPAQUE: dxgiformat::DXGI_FORMAT = 190;
+const DXGI_FORMAT_A4B4G4R4_UNORM: dxgiformat::DXGI_FORMAT = 191;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Channel {
+    R,
+    G,
+    B,
+    A,
+    Depth,
+    Stencil,
+    /// Unused bits.
+    X,
+    SharedExponent,
+    /// Luma.
+    Y,
+    /// Blue-difference chroma.
+    U,
+    /// Red-difference chroma.
+    V,
+    PaletteIndex,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ComponentType {
+    Typeless,
+    Float,
+    UNorm,
+    SNorm,
+    UInt,
+    SInt,
+    UNormSrgb,
+}
+
+/// Single component of a format, in memory order.
+///
+/// `bits` is zero for block compressed formats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Component {
+    pub channel: Channel,
+    pub bits: u8,
+    pub ty: ComponentType,
+}
+
+macro_rules! components {
+    ($($channel:ident $bits:literal $ty:ident),* $(,)?) => {
+        &[$(Component {
+            channel: Channel::$channel,
+            bits: $bits,
+            ty: ComponentType::$ty,
+        }),*]
+    };
+}
+
+#[repr(u32)]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    Unknown = dxgiformat::DXGI_FORMAT_UNKNOWN,
+    R32G32B32A32Typeless = dxgiformat::DXGI_FORMAT_R32G32B32A32_TYPELESS,
+    R32G32B32A32Float = dxgiformat::DXGI_FORMAT_R32G32B32A32_FLOAT,
+    R32G32B32A32Uint = dxgiformat::DXGI_FORMAT_R32G32B32A32_UINT,
+    R32G32B32A32Sint = dxgiformat::DXGI_FORMAT_R32G32B32A32_SINT,
+    R32G32B32Typeless = dxgiformat::DXGI_FORMAT_R32G32B32_TYPELESS,
+    R32G32B32Float = dxgiformat::DXGI_FORMAT_R32G32B32_FLOAT,
+    R32G32B32Uint = dxgiformat::DXGI_FORMAT_R32G32B32_UINT,
+    R32G32B32Sint = dxgiformat::DXGI_FORMAT_R32G32B32_SINT,
+    R16G16B16A16Typeless = dxgiformat::DXGI_FORMAT_R16G16B16A16_TYPELESS,
+    R16G16B16A16Float = dxgiformat::DXGI_FORMAT_R16G16B16A16_FLOAT,
+    R16G16B16A16Unorm = dxgiformat::DXGI_FORMAT_R16G16B16A16_UNORM,
+    R16G16B16A16Uint = dxgiformat::DXGI_FORMAT_R16G16B16A16_UINT,
+    R16G16B16A16Snorm = dxgiformat::DXGI_FORMAT_R16G16B16A16_SNORM,
+    R16G16B16A16Sint = dxgiformat::DXGI_FORMAT_R16G16B16A16_SINT,
+    R32G32Typeless = dxgiformat::DXGI_FORMAT_R32G32_TYPELESS,
+    R32G32Float = dxgiformat::DXGI_FORMAT_R32G32_FLOAT,
+    R32G32Uint = dxgiformat::DXGI_FORMAT_R32G32_UINT,
+    R32G32Sint = dxgiformat::DXGI_FORMAT_R32G32_SINT,
+    R32G8X24Typeless = dxgiformat::DXGI_FORMAT_R32G8X24_TYPELESS,
+    D32FloatS8X24Uint = dxgiformat::DXGI_FORMAT_D32_FLOAT_S8X24_UINT,
+    R32FloatX8X24Typeless = dxgiformat::DXGI_FORMAT_R32_FLOAT_X8X24_TYPELESS,
+    X32TypelessG8X24Uint = dxgiformat::DXGI_FORMAT_X32_TYPELESS_G8X24_UINT,
+    R10G10B10A2Typeless = dxgiformat::DXGI_FORMAT_R10G10B10A2_TYPELESS,
+    R10G10B10A2Unorm = dxgiformat::DXGI_FORMAT_R10G10B10A2_UNORM,
+    R10G10B10A2Uint = dxgiformat::DXGI_FORMAT_R10G10B10A2_UINT,
+    R11G11B10Float = dxgiformat::DXGI_FORMAT_R11G11B10_FLOAT,
+    R8G8B8A8Typeless = dxgiformat::DXGI_FORMAT_R8G8B8A8_TYPELESS,
+    R8G8B8A8Unorm = dxgiformat::DXGI_FORMAT_R8G8B8A8_UNORM,
+    R8G8B8A8UnormSrgb = dxgiformat::DXGI_FORMAT_R8G8B8A8_UNORM_SRGB,
+    R8G8B8A8Uint = dxgiformat::DXGI_FORMAT_R8G8B8A8_UINT,
+    R8G8B8A8Snorm = dxgiformat::DXGI_FORMAT_R8G8B8A8_SNORM,
+    R8G8B8A8Sint = dxgiformat::DXGI_FORMAT_R8G8B8A8_SINT,
+    R16G16Typeless = dxgiformat::DXGI_FORMAT_R16G16_TYPELESS,
+    R16G16Float = dxgiformat::DXGI_FORMAT_R16G16_FLOAT,
+    R16G16Unorm = dxgiformat::DXGI_FORMAT_R16G16_UNORM,
+    R16G16Uint = dxgiformat::DXGI_FORMAT_R16G16_UINT,
+    R16G16Snorm = dxgiformat::DXGI_FORMAT_R16G16_SNORM,
+    R16G16Sint = dxgiformat::DXGI_FORMAT_R16G16_SINT,
+    R32Typeless = dxgiformat::DXGI_FORMAT_R32_TYPELESS,
+    D32Float = dxgiformat::DXGI_FORMAT_D32_FLOAT,
+    R32Float = dxgiformat::DXGI_FORMAT_R32_FLOAT,
+    R32Uint = dxgiformat::DXGI_FORMAT_R32_UINT,
+    R32Sint = dxgiformat::DXGI_FORMAT_R32_SINT,
+    R24G8Typeless = dxgiformat::DXGI_FORMAT_R24G8_TYPELESS,
+    D24UnormS8Uint = dxgiformat::DXGI_FORMAT_D24_UNORM_S8_UINT,
+    R24UnormX8Typeless = dxgiformat::DXGI_FORMAT_R24_UNORM_X8_TYPELESS,
+    X24TypelessG8Uint = dxgiformat::DXGI_FORMAT_X24_TYPELESS_G8_UINT,
+    R8G8Typeless = dxgiformat::DXGI_FORMAT_R8G8_TYPELESS,
+    R8G8Unorm = dxgiformat::DXGI_FORMAT_R8G8_UNORM,
+    R8G8Uint = dxgiformat::DXGI_FORMAT_R8G8_UINT,
+    R8G8Snorm = dxgiformat::DXGI_FORMAT_R8G8_SNORM,
+    R8G8Sint = dxgiformat::DXGI_FORMAT_R8G8_SINT,
+    R16Typeless = dxgiformat::DXGI_FORMAT_R16_TYPELESS,
+    R16Float = dxgiformat::DXGI_FORMAT_R16_FLOAT,
+    D16Unorm = dxgiformat::DXGI_FORMAT_D16_UNORM,
+    R16Unorm = dxgiformat::DXGI_FORMAT_R16_UNORM,
+    R16Uint = dxgiformat::DXGI_FORMAT_R16_UINT,
+    R16Snorm = dxgiformat::DXGI_FORMAT_R16_SNORM,
+    R16Sint = dxgiformat::DXGI_FORMAT_R16_SINT,
+    R8Typeless = dxgiformat::DXGI_FORMAT_R8_TYPELESS,
+    R8Unorm = dxgiformat::DXGI_FORMAT_R8_UNORM,
+    R8Uint = dxgiformat::DXGI_FORMAT_R8_UINT,
+    R8Snorm = dxgiformat::DXGI_FORMAT_R8_SNORM,
+    R8Sint = dxgiformat::DXGI_FORMAT_R8_SINT,
+    A8Unorm = dxgiformat::DXGI_FORMAT_A8_UNORM,
+    R1Unorm = dxgiformat::DXGI_FORMAT_R1_UNORM,
+    R9G9B9E5SharedExp = dxgiformat::DXGI_FORMAT_R9G9B9E5_SHAREDEXP,
+    R8G8B8G8Unorm = dxgiformat::DXGI_FORMAT_R8G8_B8G8_UNORM,
+    G8R8G8B8Unorm = dxgiformat::DXGI_FORMAT_G8R8_G8B8_UNORM,
+    Bc1Typeless = dxgiformat::DXGI_FORMAT_BC1_TYPELESS,
+    Bc1Unorm = dxgiformat::DXGI_FORMAT_BC1_UNORM,
+    Bc1UnormSrgb = dxgiformat::DXGI_FORMAT_BC1_UNORM_SRGB,
+    Bc2Typeless = dxgiformat::DXGI_FORMAT_BC2_TYPELESS,
+    Bc2Unorm = dxgiformat::DXGI_FORMAT_BC2_UNORM,
+    Bc2UnormSrgb = dxgiformat::DXGI_FORMAT_BC2_UNORM_SRGB,
+    Bc3Typeless = dxgiformat::DXGI_FORMAT_BC3_TYPELESS,
+    Bc3Unorm = dxgiformat::DXGI_FORMAT_BC3_UNORM,
+    Bc3UnormSrgb = dxgiformat::DXGI_FORMAT_BC3_UNORM_SRGB,
+    Bc4Typeless = dxgiformat::DXGI_FORMAT_BC4_TYPELESS,
+    Bc4Unorm = dxgiformat::DXGI_FORMAT_BC4_UNORM,
+    Bc4Snorm = dxgiformat::DXGI_FORMAT_BC4_SNORM,
+    Bc5Typeless = dxgiformat::DXGI_FORMAT_BC5_TYPELESS,
+    Bc5Unorm = dxgiformat::DXGI_FORMAT_BC5_UNORM,
+    Bc5Snorm = dxgiformat::DXGI_FORMAT_BC5_SNORM,
+    B5G6R5Unorm = dxgiformat::DXGI_FORMAT_B5G6R5_UNORM,
+    B5G5R5A1Unorm = dxgiformat::DXGI_FORMAT_B5G5R5A1_UNORM,
+    B8G8R8A8Unorm = dxgiformat::DXGI_FORMAT_B8G8R8A8_UNORM,
+    B8G8R8X8Unorm = dxgiformat::DXGI_FORMAT_B8G8R8X8_UNORM,
+    R10G10B10XrBiasA2Unorm = dxgiformat::DXGI_FORMAT_R10G10B10_XR_BIAS_A2_UNORM,
+    B8G8R8A8Typeless = dxgiformat::DXGI_FORMAT_B8G8R8A8_TYPELESS,
+    B8G8R8A8UnormSrgb = dxgiformat::DXGI_FORMAT_B8G8R8A8_UNORM_SRGB,
+    B8G8R8X8Typeless = dxgiformat::DXGI_FORMAT_B8G8R8X8_TYPELESS,
+    B8G8R8X8UnormSrgb = dxgiformat::DXGI_FORMAT_B8G8R8X8_UNORM_SRGB,
+    Bc6hTypeless = dxgiformat::DXGI_FORMAT_BC6H_TYPELESS,
+    Bc6hUf16 = dxgiformat::DXGI_FORMAT_BC6H_UF16,
+    Bc6hSf16 = dxgiformat::DXGI_FORMAT_BC6H_SF16,
+    Bc7Typeless = dxgiformat::DXGI_FORMAT_BC7_TYPELESS,
+    Bc7Unorm = dxgiformat::DXGI_FORMAT_BC7_UNORM,
+    Bc7UnormSrgb = dxgiformat::DXGI_FORMAT_BC7_UNORM_SRGB,
+    Ayuv = dxgiformat::DXGI_FORMAT_AYUV,
+    Y410 = dxgiformat::DXGI_FORMAT_Y410,
+    Y416 = dxgiformat::DXGI_FORMAT_Y416,
+    Nv12 = dxgiformat::DXGI_FORMAT_NV12,
+    P010 = dxgiformat::DXGI_FORMAT_P010,
+    P016 = dxgiformat::DXGI_FORMAT_P016,
+    Opaque420 = dxgiformat::DXGI_FORMAT_420_OPAQUE,
+    Yuy2 = dxgiformat::DXGI_FORMAT_YUY2,
+    Y210 = dxgiformat::DXGI_FORMAT_Y210,
+    Y216 = dxgiformat::DXGI_FORMAT_Y216,
+    Nv11 = dxgiformat::DXGI_FORMAT_NV11,
+    Ai44 = dxgiformat::DXGI_FORMAT_AI44,
+    Ia44 = dxgiformat::DXGI_FORMAT_IA44,
+    P8 = dxgiformat::DXGI_FORMAT_P8,
+    A8P8 = dxgiformat::DXGI_FORMAT_A8P8,
+    B4G4R4A4Unorm = dxgiformat::DXGI_FORMAT_B4G4R4A4_UNORM,
+    P208 = dxgiformat::DXGI_FORMAT_P208,
+    V208 = dxgiformat::DXGI_FORMAT_V208,
+    V408 = dxgiformat::DXGI_FORMAT_V408,
+    SamplerFeedbackMinMipOpaque = DXGI_FORMAT_SAMPLER_FEEDBACK_MIN_MIP_OPAQUE,
+    SamplerFeedbackMipRegionUsedOpaque = DXGI_FORMAT_SAMPLER_FEEDBACK_MIP_REGION_USED_OPAQUE,
+    A4B4G4R4Unorm = DXGI_FORMAT_A4B4G4R4_UNORM,
+}
+
+impl TryFrom<dxgiformat::DXGI_FORMAT> for Format {
+    type Error = ();
+
+    fn try_from(value: dxgiformat::DXGI_FORMAT) -> Result<Self, Self::Error> {
+        Ok(match value {
+            dxgiformat::DXGI_FORMAT_UNKNOWN => Self::Unknown,
+            dxgiformat::DXGI_FORMAT_R32G32B32A32_TYPELESS => Self::R32G32B32A32Typeless,
+            dxgiformat::DXGI_FORMAT_R32G32B32A32_FLOAT => Self::R32G32B32A32Float,
+            dxgiformat::DXGI_FORMAT_R32G32B32A32_UINT => Self::R32G32B32A32Uint,
+            dxgiformat::DXGI_FORMAT_R32G32B32A32_SINT => Self::R32G32B32A32Sint,
+            dxgiformat::DXGI_FORMAT_R32G32B32_TYPELESS => Self::R32G32B32Typeless,
+            dxgiformat::DXGI_FORMAT_R32G32B32_FLOAT => Self::R32G32B32Float,
+            dxgiformat::DXGI_FORMAT_R32G32B32_UINT => Self::R32G32B32Uint,
+            dxgiformat::DXGI_FORMAT_R32G32B32_SINT => Self::R32G32B32Sint,
+            dxgiformat::DXGI_FORMAT_R16G16B16A16_TYPELESS => Self::R16G16B16A16Typeless,
+            dxgiformat::DXGI_FORMAT_R16G16B16A16_FLOAT => Self::R16G16B16A16Float,
+            dxgiformat::DXGI_FORMAT_R16G16B16A16_UNORM => Self::R16G16B16A16Unorm,
+            dxgiformat::DXGI_FORMAT_R16G16B16A16_UINT => Self::R16G16B16A16Uint,
+            dxgiformat::DXGI_FORMAT_R16G16B16A16_SNORM => Self::R16G16B16A16Snorm,
+            dxgiformat::DXGI_FORMAT_R16G16B16A16_SINT => Self::R16G16B16A16Sint,
+            dxgiformat::DXGI_FORMAT_R32G32_TYPELESS => Self::R32G32Typeless,
+            dxgiformat::DXGI_FORMAT_R32G32_FLOAT => Self::R32G32Float,
+            dxgiformat::DXGI_FORMAT_R32G32_UINT => Self::R32G32Uint,
+            dxgiformat::DXGI_FORMAT_R32G32_SINT => Self::R32G32Sint,
+            dxgiformat::DXGI_FORMAT_R32G8X24_TYPELESS => Self::R32G8X24Typeless,
+            dxgiformat::DXGI_FORMAT_D32_FLOAT_S8X24_UINT => Self::D32FloatS8X24Uint,
+            dxgiformat::DXGI_FORMAT_R32_FLOAT_X8X24_TYPELESS => Self::R32FloatX8X24Typeless,
+            dxgiformat::DXGI_FORMAT_X32_TYPELESS_G8X24_UINT => Self::X32TypelessG8X24Uint,
+            dxgiformat::DXGI_FORMAT_R10G10B10A2_TYPELESS => Self::R10G10B10A2Typeless,
+            dxgiformat::DXGI_FORMAT_R10G10B10A2_UNORM => Self::R10G10B10A2Unorm,
+            dxgiformat::DXGI_FORMAT_R10G10B10A2_UINT => Self::R10G10B10A2Uint,
+            dxgiformat::DXGI_FORMAT_R11G11B10_FLOAT => Self::R11G11B10Float,
+            dxgiformat::DXGI_FORMAT_R8G8B8A8_TYPELESS => Self::R8G8B8A8Typeless,
+            dxgiformat::DXGI_FORMAT_R8G8B8A8_UNORM => Self::R8G8B8A8Unorm,
+            dxgiformat::DXGI_FORMAT_R8G8B8A8_UNORM_SRGB => Self::R8G8B8A8UnormSrgb,
+            dxgiformat::DXGI_FORMAT_R8G8B8A8_UINT => Self::R8G8B8A8Uint,
+            dxgiformat::DXGI_FORMAT_R8G8B8A8_SNORM => Self::R8G8B8A8Snorm,
+            dxgiformat::DXGI_FORMAT_R8G8B8A8_SINT => Self::R8G8B8A8Sint,
+            dxgiformat::DXGI_FORMAT_R16G16_TYPELESS => Self::R16G16Typeless,
+            dxgiformat::DXGI_FORMAT_R16G16_FLOAT => Self::R16G16Float,
+            dxgiformat::DXGI_FORMAT_R16G16_UNORM => Self::R16G16Unorm,
+            dxgiformat::DXGI_FORMAT_R16G16_UINT => Self::R16G16Uint,
+            dxgiformat::DXGI_FORMAT_R16G16_SNORM => Self::R16G16Snorm,
+            dxgiformat::DXGI_FORMAT_R16G16_SINT => Self::R16G16Sint,
+            dxgiformat::DXGI_FORMAT_R32_TYPELESS => Self::R32Typeless,
+            dxgiformat::DXGI_FORMAT_D32_FLOAT => Self::D32Float,
+            dxgiformat::DXGI_FORMAT_R32_FLOAT => Self::R32Float,
+            dxgiformat::DXGI_FORMAT_R32_UINT => Self::R32Uint,
+            dxgiformat::DXGI_FORMAT_R32_SINT => Self::R32Sint,
+            dxgiformat::DXGI_FORMAT_R24G8_TYPELESS => Self::R24G8Typeless,
+            dxgiformat::DXGI_FORMAT_D24_UNORM_S8_UINT => Self::D24UnormS8Uint,
+            dxgiformat::DXGI_FORMAT_R24_UNORM_X8_TYPELESS => Self::R24UnormX8Typeless,
+            dxgiformat::DXGI_FORMAT_X24_TYPELESS_G8_UINT => Self::X24TypelessG8Uint,
+            dxgiformat::DXGI_FORMAT_R8G8_TYPELESS => Self::R8G8Typeless,
+            dxgiformat::DXGI_FORMAT_R8G8_UNORM => Self::R8G8Unorm,
+            dxgiformat::DXGI_FORMAT_R8G8_UINT => Self::R8G8Uint,
+            dxgiformat::DXGI_FORMAT_R8G8_SNORM => Self::R8G8Snorm,
+            dxgiformat::DXGI_FORMAT_R8G8_SINT => Self::R8G8Sint,
+            dxgiformat::DXGI_FORMAT_R16_TYPELESS => Self::R16Typeless,
+            dxgiformat::DXGI_FORMAT_R16_FLOAT => Self::R16Float,
+            dxgiformat::DXGI_FORMAT_D16_UNORM => Self::D16Unorm,
+            dxgiformat::DXGI_FORMAT_R16_UNORM => Self::R16Unorm,
+            dxgiformat::DXGI_FORMAT_R16_UINT => Self::R16Uint,
+            dxgiformat::DXGI_FORMAT_R16_SNORM => Self::R16Snorm,
+            dxgiformat::DXGI_FORMAT_R16_SINT => Self::R16Sint,
+            dxgiformat::DXGI_FORMAT_R8_TYPELESS => Self::R8Typeless,
+            dxgiformat::DXGI_FORMAT_R8_UNORM => Self::R8Unorm,
+            dxgiformat::DXGI_FORMAT_R8_UINT => Self::R8Uint,
+            dxgiformat::DXGI_FORMAT_R8_SNORM => Self::R8Snorm,
+            dxgiformat::DXGI_FORMAT_R8_SINT => Self::R8Sint,
+            dxgiformat::DXGI_FORMAT_A8_UNORM => Self::A8Unorm,
+            dxgiformat::DXGI_FORMAT_R1_UNORM => Self::R1Unorm,
+            dxgiformat::DXGI_FORMAT_R9G9B9E5_SHAREDEXP => Self::R9G9B9E5SharedExp,
+            dxgiformat::DXGI_FORMAT_R8G8_B8G8_UNORM => Self::R8G8B8G8Unorm,
+            dxgiformat::DXGI_FORMAT_G8R8_G8B8_UNORM => Self::G8R8G8B8Unorm,
+            dxgiformat::DXGI_FORMAT_BC1_TYPELESS => Self::Bc1Typeless,
+            dxgiformat::DXGI_FORMAT_BC1_UNORM => Self::Bc1Unorm,
+            dxgiformat::DXGI_FORMAT_BC1_UNORM_SRGB => Self::Bc1UnormSrgb,
+            dxgiformat::DXGI_FORMAT_BC2_TYPELESS => Self::Bc2Typeless,
+            dxgiformat::DXGI_FORMAT_BC2_UNORM => Self::Bc2Unorm,
+            dxgiformat::DXGI_FORMAT_BC2_UNORM_SRGB => Self::Bc2UnormSrgb,
+            dxgiformat::DXGI_FORMAT_BC3_TYPELESS => Self::Bc3Typeless,
+            dxgiformat::DXGI_FORMAT_BC3_UNORM => Self::Bc3Unorm,
+            dxgiformat::DXGI_FORMAT_BC3_UNORM_SRGB => Self::Bc3UnormSrgb,
+            dxgiformat::DXGI_FORMAT_BC4_TYPELESS => Self::Bc4Typeless,
+            dxgiformat::DXGI_FORMAT_BC4_UNORM => Self::Bc4Unorm,
+            dxgiformat::DXGI_FORMAT_BC4_SNORM => Self::Bc4Snorm,
+            dxgiformat::DXGI_FORMAT_BC5_TYPELESS => Self::Bc5Typeless,
+            dxgiformat::DXGI_FORMAT_BC5_UNORM => Self::Bc5Unorm,
+            dxgiformat::DXGI_FORMAT_BC5_SNORM => Self::Bc5Snorm,
+            dxgiformat::DXGI_FORMAT_B5G6R5_UNORM => Self::B5G6R5Unorm,
+            dxgiformat::DXGI_FORMAT_B5G5R5A1_UNORM => Self::B5G5R5A1Unorm,
+            dxgiformat::DXGI_FORMAT_B8G8R8A8_UNORM => Self::B8G8R8A8Unorm,
+            dxgiformat::DXGI_FORMAT_B8G8R8X8_UNORM => Self::B8G8R8X8Unorm,
+            dxgiformat::DXGI_FORMAT_R10G10B10_XR_BIAS_A2_UNORM => Self::R10G10B10XrBiasA2Unorm,
+            dxgiformat::DXGI_FORMAT_B8G8R8A8_TYPELESS => Self::B8G8R8A8Typeless,
+            dxgiformat::DXGI_FORMAT_B8G8R8A8_UNORM_SRGB => Self::B8G8R8A8UnormSrgb,
+            dxgiformat::DXGI_FORMAT_B8G8R8X8_TYPELESS => Self::B8G8R8X8Typeless,
+            dxgiformat::DXGI_FORMAT_B8G8R8X8_UNORM_SRGB => Self::B8G8R8X8UnormSrgb,
+            dxgiformat::DXGI_FORMAT_BC6H_TYPELESS => Self::Bc6hTypeless,
+            dxgiformat::DXGI_FORMAT_BC6H_UF16 => Self::Bc6hUf16,
+            dxgiformat::DXGI_FORMAT_BC6H_SF16 => Self::Bc6hSf16,
+            dxgiformat::DXGI_FORMAT_BC7_TYPELESS => Self::Bc7Typeless,
+            dxgiformat::DXGI_FORMAT_BC7_UNORM => Self::Bc7Unorm,
+            dxgiformat::DXGI_FORMAT_BC7_UNORM_SRGB => Self::Bc7UnormSrgb,
+            dxgiformat::DXGI_FORMAT_AYUV => Self::Ayuv,
+            dxgiformat::DXGI_FORMAT_Y410 => Self::Y410,
+            dxgiformat::DXGI_FORMAT_Y416 => Self::Y416,
+            dxgiformat::DXGI_FORMAT_NV12 => Self::Nv12,
+            dxgiformat::DXGI_FORMAT_P010 => Self::P010,
+            dxgiformat::DXGI_FORMAT_P016 => Self::P016,
+            dxgiformat::DXGI_FORMAT_420_OPAQUE => Self::Opaque420,
+            dxgiformat::DXGI_FORMAT_YUY2 => Self::Yuy2,
+            dxgiformat::DXGI_FORMAT_Y210 => Self::Y210,
+            dxgiformat::DXGI_FORMAT_Y216 => Self::Y216,
+            dxgiformat::DXGI_FORMAT_NV11 => Self::Nv11,
+            dxgiformat::DXGI_FORMAT_AI44 => Self::Ai44,
+            dxgiformat::DXGI_FORMAT_IA44 => Self::Ia44,
+            dxgiformat::DXGI_FORMAT_P8 => Self::P8,
+            dxgiformat::DXGI_FORMAT_A8P8 => Self::A8P8,
+            dxgiformat::DXGI_FORMAT_B4G4R4A4_UNORM => Self::B4G4R4A4Unorm,
+            dxgiformat::DXGI_FORMAT_P208 => Self::P208,
+            dxgiformat::DXGI_FORMAT_V208 => Self::V208,
+            dxgiformat::DXGI_FORMAT_V408 => Self::V408,
+            DXGI_FORMAT_SAMPLER_FEEDBACK_MIN_MIP_OPAQUE => Self::SamplerFeedbackMinMipOpaque,
+            DXGI_FORMAT_SAMPLER_FEEDBACK_MIP_REGION_USED_OPAQUE => {
+                Self::SamplerFeedbackMipRegionUsedOpaque
+            }
+            DXGI_FORMAT_A4B4G4R4_UNORM => Self::A4B4G4R4Unorm,
+            _ => return Err(()),
+        })
+    }
+}
+
+impl From<Format> for dxgiformat::DXGI_FORMAT {
+    fn from(format: Format) -> Self {
+        format as _
+    }
+}
+
+impl Format {
+    /// Width and height of a block in texels.
+    ///
+    /// Block compressed formats use 4x4 blocks, packed formats like `YUY2` 2x1.
+    pub fn block_dimensions(self) -> (u32, u32) {
+        match self {
+            Format::R1Unorm => (8, 1),
+            Format::R8G8B8G8Unorm
+            | Format::G8R8G8B8Unorm
+            | Format::Yuy2
+            | Format::Y210
+            | Format::Y216 => (2, 1),
+            Format::Bc1Typeless
+            | Format::Bc1Unorm
+            | Format::Bc1UnormSrgb
+            | Format::Bc2Typeless
+            | Format::Bc2Unorm
+            | Format::Bc2UnormSrgb
+            | Format::Bc3Typeless
+            | Format::Bc3Unorm
+            | Format::Bc3UnormSrgb
+            | Format::Bc4Typeless
+            | Format::Bc4Unorm
+            | Format::Bc4Snorm
+            | Format::Bc5Typeless
+            | Format::Bc5Unorm
+            | Format::Bc5Snorm
+            | Format::Bc6hTypeless
+            | Format::Bc6hUf16
+            | Format::Bc6hSf16
+            | Format::Bc7Typeless
+            | Format::Bc7Unorm
+            | Format::Bc7UnormSrgb => (4, 4),
+            _ => (1, 1),
+        }
+    }
+
+    /// Size of a block in bytes.
+    ///
+    /// Planar formats return the size of an element in the first plane, `Unknown` and opaque
+    /// formats return zero.
+    pub fn bytes_per_block(self) -> u32 {
+        match self {
+            Format::Unknown
+            | Format::SamplerFeedbackMinMipOpaque
+            | Format::SamplerFeedbackMipRegionUsedOpaque => 0,
+            Format::R8Typeless
+            | Format::R8Unorm
+            | Format::R8Uint
+            | Format::R8Snorm
+            | Format::R8Sint
+            | Format::A8Unorm
+            | Format::R1Unorm
+            | Format::Nv12
+            | Format::Opaque420
+            | Format::Nv11
+            | Format::Ai44
+            | Format::Ia44
+            | Format::P8
+            | Format::P208
+            | Format::V208
+            | Format::V408 => 1,
+            Format::R8G8Typeless
+            | Format::R8G8Unorm
+            | Format::R8G8Uint
+            | Format::R8G8Snorm
+            | Format::R8G8Sint
+            | Format::R16Typeless
+            | Format::R16Float
+            | Format::D16Unorm
+            | Format::R16Unorm
+            | Format::R16Uint
+            | Format::R16Snorm
+            | Format::R16Sint
+            | Format::B5G6R5Unorm
+            | Format::B5G5R5A1Unorm
+            | Format::P010
+            | Format::P016
+            | Format::A8P8
+            | Format::B4G4R4A4Unorm
+            | Format::A4B4G4R4Unorm => 2,
+            Format::R10G10B10A2Typeless
+            | Format::R10G10B10A2Unorm
+            | Format::R10G10B10A2Uint
+            | Format::R11G11B10Float
+            | Format::R8G8B8A8Typeless
+            | Format::R8G8B8A8Unorm
+            | Format::R8G8B8A8UnormSrgb
+            | Format::R8G8B8A8Uint
+            | Format::R8G8B8A8Snorm
+            | Format::R8G8B8A8Sint
+            | Format::R16G16Typeless
+            | Format::R16G16Float
+            | Format::R16G16Unorm
+            | Format::R16G16Uint
+            | Format::R16G16Snorm
+            | Format::R16G16Sint
+            | Format::R32Typeless
+            | Format::D32Float
+            | Format::R32Float
+            | Format::R32Uint
+            | Format::R32Sint
+            | Format::R24G8Typeless
+            | Format::D24UnormS8Uint
+            | Format::R24UnormX8Typeless
+            | Format::X24TypelessG8Uint
+            | Format::R9G9B9E5SharedExp
+            | Format::R8G8B8G8Unorm
+            | Format::G8R8G8B8Unorm
+            | Format::B8G8R8A8Unorm
+            | Format::B8G8R8X8Unorm
+            | Format::R10G10B10XrBiasA2Unorm
+            | Format::B8G8R8A8Typeless
+            | Format::B8G8R8A8UnormSrgb
+            | Format::B8G8R8X8Typeless
+            | Format::B8G8R8X8UnormSrgb
+            | Format::Ayuv
+            | Format::Y410
+            | Format::Yuy2 => 4,
+            Format::R16G16B16A16Typeless
+            | Format::R16G16B16A16Float
+            | Format::R16G16B16A16Unorm
+            | Format::R16G16B16A16Uint
+            | Format::R16G16B16A16Snorm
+            | Format::R16G16B16A16Sint
+            | Format::R32G32Typeless
+            | Format::R32G32Float
+            | Format::R32G32Uint
+            | Format::R32G32Sint
+            | Format::R32G8X24Typeless
+            | Format::D32FloatS8X24Uint
+            | Format::R32FloatX8X24Typeless
+            | Format::X32TypelessG8X24Uint
+            | Format::Bc1Typeless
+            | Format::Bc1Unorm
+            | Format::Bc1UnormSrgb
+            | Format::Bc4Typeless
+            | Format::Bc4Unorm
+            | Format::Bc4Snorm
+            | Format::Y416
+            | Format::Y210
+            | Format::Y216 => 8,
+            Format::R32G32B32Typeless
+            | Format::R32G32B32Float
+            | Format::R32G32B32Uint
+            | Format::R32G32B32Sint => 12,
+            Format::R32G32B32A32Typeless
+            | Format::R32G32B32A32Float
+            | Format::R32G32B32A32Uint
+            | Format::R32G32B32A32Sint
+            | Format::Bc2Typeless
+            | Format::Bc2Unorm
+            | Format::Bc2UnormSrgb
+            | Format::Bc3Typeless
+            | Format::Bc3Unorm
+            | Format::Bc3UnormSrgb
+            | Format::Bc5Typeless
+            | Format::Bc5Unorm
+            | Format::Bc5Snorm
+            | Format::Bc6hTypeless
+            | Format::Bc6hUf16
+            | Format::Bc6hSf16
+            | Format::Bc7Typeless
+            | Format::Bc7Unorm
+            | Format::Bc7UnormSrgb => 16,
+        }
+    }
+
+    /// Components in memory order, empty for `Unknown` and opaque formats.
+    pub fn components(self) -> &'static [Component] {
+        match self {
+            Format::Unknown => components![],
+            Format::R32G32B32A32Typeless => {
+                components![R 32 Typeless, G 32 Typeless, B 32 Typeless, A 32 Typeless]
+            }
+            Format::R32G32B32A32Float => {
+                components![R 32 Float, G 32 Float, B 32 Float, A 32 Float]
+            }
+            Format::R32G32B32A32Uint => components![R 32 UInt, G 32 UInt, B 32 UInt, A 32 UInt],
+            Format::R32G32B32A32Sint => components![R 32 SInt, G 32 SInt, B 32 SInt, A 32 SInt],
+            Format::R32G32B32Typeless => components![R 32 Typeless, G 32 Typeless, B 32 Typeless],
+            Format::R32G32B32Float => components![R 32 Float, G 32 Float, B 32 Float],
+            Format::R32G32B32Uint => components![R 32 UInt, G 32 UInt, B 32 UInt],
+            Format::R32G32B32Sint => components![R 32 SInt, G 32 SInt, B 32 SInt],
+            Format::R16G16B16A16Typeless => {
+                components![R 16 Typeless, G 16 Typeless, B 16 Typeless, A 16 Typeless]
+            }
+            Format::R16G16B16A16Float => {
+                components![R 16 Float, G 16 Float, B 16 Float, A 16 Float]
+            }
+            Format::R16G16B16A16Unorm => {
+                components![R 16 UNorm, G 16 UNorm, B 16 UNorm, A 16 UNorm]
+            }
+            Format::R16G16B16A16Uint => components![R 16 UInt, G 16 UInt, B 16 UInt, A 16 UInt],
+            Format::R16G16B16A16Snorm => {
+                components![R 16 SNorm, G 16 SNorm, B 16 SNorm, A 16 SNorm]
+            }
+            Format::R16G16B16A16Sint => components![R 16 SInt, G 16 SInt, B 16 SInt, A 16 SInt],
+            Format::R32G32Typeless => components![R 32 Typeless, G 32 Typeless],
+            Format::R32G32Float => components![R 32 Float, G 32 Float],
+            Format::R32G32Uint => components![R 32 UInt, G 32 UInt],
+            Format::R32G32Sint => components![R 32 SInt, G 32 SInt],
+            Format::R32G8X24Typeless => components![R 32 Typeless, G 8 Typeless, X 24 Typeless],
+            Format::D32FloatS8X24Uint => components![Depth 32 Float, Stencil 8 UInt, X 24 Typeless],
+            Format::R32FloatX8X24Typeless => components![R 32 Float, X 32 Typeless],
+            Format::X32TypelessG8X24Uint => components![X 32 Typeless, G 8 UInt, X 24 Typeless],
+            Format::R10G10B10A2Typeless => {
+                components![R 10 Typeless, G 10 Typeless, B 10 Typeless, A 2 Typeless]
+            }
+            Format::R10G10B10A2Unorm => components![R 10 UNorm, G 10 UNorm, B 10 UNorm, A 2 UNorm],
+            Format::R10G10B10A2Uint => components![R 10 UInt, G 10 UInt, B 10 UInt, A 2 UInt],
+            Format::R11G11B10Float => components![R 11 Float, G 11 Float, B 10 Float],
+            Format::R8G8B8A8Typeless => {
+                components![R 8 Typeless, G 8 Typeless, B 8 Typeless, A 8 Typeless]
+            }
+            Format::R8G8B8A8Unorm => components![R 8 UNorm, G 8 UNorm, B 8 UNorm, A 8 UNorm],
+            Format::R8G8B8A8UnormSrgb => {
+                components![R 8 UNormSrgb, G 8 UNormSrgb, B 8 UNormSrgb, A 8 UNorm]
+            }
+            Format::R8G8B8A8Uint => components![R 8 UInt, G 8 UInt, B 8 UInt, A 8 UInt],
+            Format::R8G8B8A8Snorm => components![R 8 SNorm, G 8 SNorm, B 8 SNorm, A 8 SNorm],
+            Format::R8G8B8A8Sint => components![R 8 SInt, G 8 SInt, B 8 SInt, A 8 SInt],
+            Format::R16G16Typeless => components![R 16 Typeless, G 16 Typeless],
+            Format::R16G16Float => components![R 16 Float, G 16 Float],
+            Format::R16G16Unorm => components![R 16 UNorm, G 16 UNorm],
+            Format::R16G16Uint => components![R 16 UInt, G 16 UInt],
+            Format::R16G16Snorm => components![R 16 SNorm, G 16 SNorm],
+            Format::R16G16Sint => components![R 16 SInt, G 16 SInt],
+            Format::R32Typeless => components![R 32 Typeless],
+            Format::D32Float => components![Depth 32 Float],
+            Format::R32Float => components![R 32 Float],
+            Format::R32Uint => components![R 32 UInt],
+            Format::R32Sint => components![R 32 SInt],
+            Format::R24G8Typeless => components![R 24 Typeless, G 8 Typeless],
+            Format::D24UnormS8Uint => components![Depth 24 UNorm, Stencil 8 UInt],
+            Format::R24UnormX8Typeless => components![R 24 UNorm, X 8 Typeless],
+            Format::X24TypelessG8Uint => components![X 24 Typeless, G 8 UInt],
+            Format::R8G8Typeless => components![R 8 Typeless, G 8 Typeless],
+            Format::R8G8Unorm => components![R 8 UNorm, G 8 UNorm],
+            Format::R8G8Uint => components![R 8 UInt, G 8 UInt],
+            Format::R8G8Snorm => components![R 8 SNorm, G 8 SNorm],
+            Format::R8G8Sint => components![R 8 SInt, G 8 SInt],
+            Format::R16Typeless => components![R 16 Typeless],
+            Format::R16Float => components![R 16 Float],
+            Format::D16Unorm => components![Depth 16 UNorm],
+            Format::R16Unorm => components![R 16 UNorm],
+            Format::R16Uint => components![R 16 UInt],
+            Format::R16Snorm => components![R 16 SNorm],
+            Format::R16Sint => components![R 16 SInt],
+            Format::R8Typeless => components![R 8 Typeless],
+            Format::R8Unorm => components![R 8 UNorm],
+            Format::R8Uint => components![R 8 UInt],
+            Format::R8Snorm => components![R 8 SNorm],
+            Format::R8Sint => components![R 8 SInt],
+            Format::A8Unorm => components![A 8 UNorm],
+            Format::R1Unorm => components![R 1 UNorm],
+            Format::R9G9B9E5SharedExp => {
+                components![R 9 Float, G 9 Float, B 9 Float, SharedExponent 5 Float]
+            }
+            Format::R8G8B8G8Unorm => components![R 8 UNorm, G 8 UNorm, B 8 UNorm, G 8 UNorm],
+            Format::G8R8G8B8Unorm => components![G 8 UNorm, R 8 UNorm, G 8 UNorm, B 8 UNorm],
+            Format::Bc1Typeless => {
+                components![R 0 Typeless, G 0 Typeless, B 0 Typeless, A 0 Typeless]
+            }
+            Format::Bc1Unorm => components![R 0 UNorm, G 0 UNorm, B 0 UNorm, A 0 UNorm],
+            Format::Bc1UnormSrgb => {
+                components![R 0 UNormSrgb, G 0 UNormSrgb, B 0 UNormSrgb, A 0 UNorm]
+            }
+            Format::Bc2Typeless => {
+                components![R 0 Typeless, G 0 Typeless, B 0 Typeless, A 0 Typeless]
+            }
+            Format::Bc2Unorm => components![R 0 UNorm, G 0 UNorm, B 0 UNorm, A 0 UNorm],
+            Format::Bc2UnormSrgb => {
+                components![R 0 UNormSrgb, G 0 UNormSrgb, B 0 UNormSrgb, A 0 UNorm]
+            }
+            Format::Bc3Typeless => {
+                components![R 0 Typeless, G 0 Typeless, B 0 Typeless, A 0 Typeless]
+            }
+            Format::Bc3Unorm => components![R 0 UNorm, G 0 UNorm, B 0 UNorm, A 0 UNorm],
+            Format::Bc3UnormSrgb => {
+                components![R 0 UNormSrgb, G 0 UNormSrgb, B 0 UNormSrgb, A 0 UNorm]
+            }
+            Format::Bc4Typeless => components![R 0 Typeless],
+            Format::Bc4Unorm => components![R 0 UNorm],
+            Format::Bc4Snorm => components![R 0 SNorm],
+            Format::Bc5Typeless => components![R 0 Typeless, G 0 Typeless],
+            Format::Bc5Unorm => components![R 0 UNorm, G 0 UNorm],
+            Format::Bc5Snorm => components![R 0 SNorm, G 0 SNorm],
+            Format::B5G6R5Unorm => components![B 5 UNorm, G 6 UNorm, R 5 UNorm],
+            Format::B5G5R5A1Unorm => components![B 5 UNorm, G 5 UNorm, R 5 UNorm, A 1 UNorm],
+            Format::B8G8R8A8Unorm => components![B 8 UNorm, G 8 UNorm, R 8 UNorm, A 8 UNorm],
+            Format::B8G8R8X8Unorm => components![B 8 UNorm, G 8 UNorm, R 8 UNorm, X 8 Typeless],
+            Format::R10G10B10XrBiasA2Unorm => {
+                components![R 10 UNorm, G 10 UNorm, B 10 UNorm, A 2 UNorm]
+            }
+            Format::B8G8R8A8Typeless => {
+                components![B 8 Typeless, G 8 Typeless, R 8 Typeless, A 8 Typeless]
+            }
+            Format::B8G8R8A8UnormSrgb => {
+                components![B 8 UNormSrgb, G 8 UNormSrgb, R 8 UNormSrgb, A 8 UNorm]
+            }
+            Format::B8G8R8X8Typeless => {
+                components![B 8 Typeless, G 8 Typeless, R 8 Typeless, X 8 Typeless]
+            }
+            Format::B8G8R8X8UnormSrgb => {
+                components![B 8 UNormSrgb, G 8 UNormSrgb, R 8 UNormSrgb, X 8 Typeless]
+            }
+            Format::Bc6hTypeless => components![R 0 Typeless, G 0 Typeless, B 0 Typeless],
+            Format::Bc6hUf16 => components![R 0 Float, G 0 Float, B 0 Float],
+            Format::Bc6hSf16 => components![R 0 Float, G 0 Float, B 0 Float],
+            Format::Bc7Typeless => {
+                components![R 0 Typeless, G 0 Typeless, B 0 Typeless, A 0 Typeless]
+            }
+            Format::Bc7Unorm => components![R 0 UNorm, G 0 UNorm, B 0 UNorm, A 0 UNorm],
+            Format::Bc7UnormSrgb => {
+                components![R 0 UNormSrgb, G 0 UNormSrgb, B 0 UNormSrgb, A 0 UNorm]
+            }
+            Format::Ayuv => components![V 8 UNorm, U 8 UNorm, Y 8 UNorm, A 8 UNorm],
+            Format::Y410 => components![U 10 UNorm, Y 10 UNorm, V 10 UNorm, A 2 UNorm],
+            Format::Y416 => components![U 16 UNorm, Y 16 UNorm, V 16 UNorm, A 16 UNorm],
+            Format::Nv12 => components![Y 8 UNorm, U 8 UNorm, V 8 UNorm],
+            Format::P010 => components![Y 16 UNorm, U 16 UNorm, V 16 UNorm],
+            Format::P016 => components![Y 16 UNorm, U 16 UNorm, V 16 UNorm],
+            Format::Opaque420 => components![],
+            Format::Yuy2 => components![Y 8 UNorm, U 8 UNorm, Y 8 UNorm, V 8 UNorm],
+            Format::Y210 => components![Y 16 UNorm, U 16 UNorm, Y 16 UNorm, V 16 UNorm],
+            Format::Y216 => components![Y 16 UNorm, U 16 UNorm, Y 16 UNorm, V 16 UNorm],
+            Format::Nv11 => components![Y 8 UNorm, U 8 UNorm, V 8 UNorm],
+            Format::Ai44 => components![PaletteIndex 4 UInt, A 4 UNorm],
+            Format::Ia44 => components![A 4 UNorm, PaletteIndex 4 UInt],
+            Format::P8 => components![PaletteIndex 8 UInt],
+            Format::A8P8 => components![PaletteIndex 8 UInt, A 8 UNorm],
+            Format::B4G4R4A4Unorm => components![B 4 UNorm, G 4 UNorm, R 4 UNorm, A 4 UNorm],
+            Format::P208 => components![Y 8 UNorm, U 8 UNorm, V 8 UNorm],
+            Format::V208 => components![Y 8 UNorm, U 8 UNorm, V 8 UNorm],
+            Format::V408 => components![Y 8 UNorm, U 8 UNorm, V 8 UNorm],
+            Format::SamplerFeedbackMinMipOpaque => components![],
+            Format::SamplerFeedbackMipRegionUsedOpaque => components![],
+            Format::A4B4G4R4Unorm => components![A 4 UNorm, B 4 UNorm, G 4 UNorm, R 4 UNorm],
+        }
+    }
+
+    pub fn is_typeless(self) -> bool {
+        matches!(
+            self,
+            Format::R32G32B32A32Typeless
+                | Format::R32G32B32Typeless
+                | Format::R16G16B16A16Typeless
+                | Format::R32G32Typeless
+                | Format::R32G8X24Typeless
+                | Format::R10G10B10A2Typeless
+                | Format::R8G8B8A8Typeless
+                | Format::R16G16Typeless
+                | Format::R32Typeless
+                | Format::R24G8Typeless
+                | Format::R8G8Typeless
+                | Format::R16Typeless
+                | Format::R8Typeless
+                | Format::Bc1Typeless
+                | Format::Bc2Typeless
+                | Format::Bc3Typeless
+                | Format::Bc4Typeless
+                | Format::Bc5Typeless
+                | Format::B8G8R8A8Typeless
+                | Format::B8G8R8X8Typeless
+                | Format::Bc6hTypeless
+                | Format::Bc7Typeless
+        )
+    }
+
+    pub fn is_srgb(self) -> bool {
+        matches!(
+            self,
+            Format::R8G8B8A8UnormSrgb
+                | Format::Bc1UnormSrgb
+                | Format::Bc2UnormSrgb
+                | Format::Bc3UnormSrgb
+                | Format::B8G8R8A8UnormSrgb
+                | Format::B8G8R8X8UnormSrgb
+                | Format::Bc7UnormSrgb
+        )
+    }
+
+    /// Formats usable for depth stencil views.
+    pub fn is_depth_stencil(self) -> bool {
+        matches!(
+            self,
+            Format::D32FloatS8X24Uint
+                | Format::D32Float
+                | Format::D24UnormS8Uint
+                | Format::D16Unorm
+        )
+    }
+
+    pub fn is_block_compressed(self) -> bool {
+        matches!(
+            self,
+            Format::Bc1Typeless
+                | Format::Bc1Unorm
+                | Format::Bc1UnormSrgb
+                | Format::Bc2Typeless
+                | Format::Bc2Unorm
+                | Format::Bc2UnormSrgb
+                | Format::Bc3Typeless
+                | Format::Bc3Unorm
+                | Format::Bc3UnormSrgb
+                | Format::Bc4Typeless
+                | Format::Bc4Unorm
+                | Format::Bc4Snorm
+                | Format::Bc5Typeless
+                | Format::Bc5Unorm
+                | Format::Bc5Snorm
+                | Format::Bc6hTypeless
+                | Format::Bc6hUf16
+                | Format::Bc6hSf16
+                | Format::Bc7Typeless
+                | Format::Bc7Unorm
+                | Format::Bc7UnormSrgb
+        )
+    }
+
+    /// Video formats storing luma and chroma in separate planes.
+    pub fn is_planar(self) -> bool {
+        matches!(
+            self,
+            Format::Nv12
+                | Format::P010
+                | Format::P016
+                | Format::Opaque420
+                | Format::Nv11
+                | Format::P208
+                | Format::V208
+                | Format::V408
+        )
+    }
+
+    pub fn is_video(self) -> bool {
+        matches!(
+            self,
+            Format::Ayuv
+                | Format::Y410
+                | Format::Y416
+                | Format::Nv12
+                | Format::P010
+                | Format::P016
+                | Format::Opaque420
+                | Format::Yuy2
+                | Format::Y210
+                | Format::Y216
+                | Format::Nv11
+                | Format::Ai44
+                | Format::Ia44
+                | Format::P8
+                | Format::A8P8
+                | Format::P208
+                | Format::V208
+                | Format::V408
+        )
+    }
+
+    /// Number of planes in resources of this format.
+    ///
+    /// Depth stencil formats with a stencil component store it in a second plane.
+    pub fn plane_count(self) -> u32 {
+        match self {
+            Format::R32G8X24Typeless
+            | Format::D32FloatS8X24Uint
+            | Format::R32FloatX8X24Typeless
+            | Format::X32TypelessG8X24Uint
+            | Format::R24G8Typeless
+            | Format::D24UnormS8Uint
+            | Format::R24UnormX8Typeless
+            | Format::X24TypelessG8Uint
+            | Format::Nv12
+            | Format::P010
+            | Format::P016
+            | Format::Opaque420
+            | Format::Nv11
+            | Format::P208 => 2,
+            Format::V208 | Format::V408 => 3,
+            _ => 1,
+        }
+    }
+
+    /// Typeless format of the family, which resources can be created with to allow
+    /// creating views of any format in the family.
+    pub fn typeless(self) -> Option<Format> {
+        Some(match self {
+            Format::R32G32B32A32Typeless
+            | Format::R32G32B32A32Float
+            | Format::R32G32B32A32Uint
+            | Format::R32G32B32A32Sint => Format::R32G32B32A32Typeless,
+            Format::R32G32B32Typeless
+            | Format::R32G32B32Float
+            | Format::R32G32B32Uint
+            | Format::R32G32B32Sint => Format::R32G32B32Typeless,
+            Format::R16G16B16A16Typeless
+            | Format::R16G16B16A16Float
+            | Format::R16G16B16A16Unorm
+            | Format::R16G16B16A16Uint
+            | Format::R16G16B16A16Snorm
+            | Format::R16G16B16A16Sint => Format::R16G16B16A16Typeless,
+            Format::R32G32Typeless
+            | Format::R32G32Float
+            | Format::R32G32Uint
+            | Format::R32G32Sint => Format::R32G32Typeless,
+            Format::R32G8X24Typeless
+            | Format::D32FloatS8X24Uint
+            | Format::R32FloatX8X24Typeless
+            | Format::X32TypelessG8X24Uint => Format::R32G8X24Typeless,
+            Format::R10G10B10A2Typeless | Format::R10G10B10A2Unorm | Format::R10G10B10A2Uint => {
+                Format::R10G10B10A2Typeless
+            }
+            Format::R8G8B8A8Typeless
+            | Format::R8G8B8A8Unorm
+            | Format::R8G8B8A8UnormSrgb
+            | Format::R8G8B8A8Uint
+            | Format::R8G8B8A8Snorm
+            | Format::R8G8B8A8Sint => Format::R8G8B8A8Typeless,
+            Format::R16G16Typeless
+            | Format::R16G16Float
+            | Format::R16G16Unorm
+            | Format::R16G16Uint
+            | Format::R16G16Snorm
+            | Format::R16G16Sint => Format::R16G16Typeless,
+            Format::R32Typeless
+            | Format::D32Float
+            | Format::R32Float
+            | Format::R32Uint
+            | Format::R32Sint => Format::R32Typeless,
+            Format::R24G8Typeless
+            | Format::D24UnormS8Uint
+            | Format::R24UnormX8Typeless
+            | Format::X24TypelessG8Uint => Format::R24G8Typeless,
+            Format::R8G8Typeless
+            | Format::R8G8Unorm
+            | Format::R8G8Uint
+            | Format::R8G8Snorm
+            | Format::R8G8Sint => Format::R8G8Typeless,
+            Format::R16Typeless
+            | Format::R16Float
+            | Format::D16Unorm
+            | Format::R16Unorm
+            | Format::R16Uint
+            | Format::R16Snorm
+            | Format::R16Sint => Format::R16Typeless,
+            Format::R8Typeless
+            | Format::R8Unorm
+            | Format::R8Uint
+            | Format::R8Snorm
+            | Format::R8Sint => Format::R8Typeless,
+            Format::Bc1Typeless | Format::Bc1Unorm | Format::Bc1UnormSrgb => Format::Bc1Typeless,
+            Format::Bc2Typeless | Format::Bc2Unorm | Format::Bc2UnormSrgb => Format::Bc2Typeless,
+            Format::Bc3Typeless | Format::Bc3Unorm | Format::Bc3UnormSrgb => Format::Bc3Typeless,
+            Format::Bc4Typeless | Format::Bc4Unorm | Format::Bc4Snorm => Format::Bc4Typeless,
+            Format::Bc5Typeless | Format::Bc5Unorm | Format::Bc5Snorm => Format::Bc5Typeless,
+            Format::B8G8R8A8Unorm | Format::B8G8R8A8Typeless | Format::B8G8R8A8UnormSrgb => {
+                Format::B8G8R8A8Typeless
+            }
+            Format::B8G8R8X8Unorm | Format::B8G8R8X8Typeless | Format::B8G8R8X8UnormSrgb => {
+                Format::B8G8R8X8Typeless
+            }
+            Format::Bc6hTypeless | Format::Bc6hUf16 | Format::Bc6hSf16 => Format::Bc6hTypeless,
+            Format::Bc7Typeless | Format::Bc7Unorm | Format::Bc7UnormSrgb => Format::Bc7Typeless,
+            _ => return None,
+        })
+    }
+
+    /// sRGB counterpart of a linear format, or the format itself if it's already sRGB.
+    pub fn srgb(self) -> Option<Format> {
+        match self {
+            Format::R8G8B8A8Unorm => Some(Format::R8G8B8A8UnormSrgb),
+            Format::Bc1Unorm => Some(Format::Bc1UnormSrgb),
+            Format::Bc2Unorm => Some(Format::Bc2UnormSrgb),
+            Format::Bc3Unorm => Some(Format::Bc3UnormSrgb),
+            Format::B8G8R8A8Unorm => Some(Format::B8G8R8A8UnormSrgb),
+            Format::B8G8R8X8Unorm => Some(Format::B8G8R8X8UnormSrgb),
+            Format::Bc7Unorm => Some(Format::Bc7UnormSrgb),
+            _ if self.is_srgb() => Some(self),
+            _ => None,
+        }
+    }
+
+    /// Linear counterpart of an sRGB format, other formats are returned as is.
+    pub fn linear(self) -> Format {
+        match self {
+            Format::R8G8B8A8UnormSrgb => Format::R8G8B8A8Unorm,
+            Format::Bc1UnormSrgb => Format::Bc1Unorm,
+            Format::Bc2UnormSrgb => Format::Bc2Unorm,
+            Format::Bc3UnormSrgb => Format::Bc3Unorm,
+            Format::B8G8R8A8UnormSrgb => Format::B8G8R8A8Unorm,
+            Format::B8G8R8X8UnormSrgb => Format::B8G8R8X8Unorm,
+            Format::Bc7UnormSrgb => Format::Bc7Unorm,
+            _ => self,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all() -> Vec<Format> {
+        (0..=255)
+            .filter_map(|raw| Format::try_from(raw).ok())
+            .collect()
+    }
+
+    #[test]
+    fn raw_round_trip() {
+        for raw in 0..=255 {
+            if let Ok(format) = Format::try_from(raw) {
+                assert_eq!(dxgiformat::DXGI_FORMAT::from(format), raw, "{:?}", format);
+            }
+        }
+        // Every variant of the enum.
+        assert_eq!(all().len(), 122);
+        assert_eq!(
+            Format::try_from(dxgiformat::DXGI_FORMAT_B4G4R4A4_UNORM + 1),
+            Err(())
+        );
+        assert_eq!(Format::try_from(DXGI_FORMAT_A4B4G4R4_UNORM + 1), Err(()));
+    }
+
+    #[test]
+    fn block_layout() {
+        let cases = [
+            (Format::Unknown, 0, (1, 1), 1),
+            (Format::R8G8B8A8Unorm, 4, (1, 1), 1),
+            (Format::R32G32B32Float, 12, (1, 1), 1),
+            (Format::R32G32B32A32Uint, 16, (1, 1), 1),
+            (Format::R1Unorm, 1, (8, 1), 1),
+            (Format::R9G9B9E5SharedExp, 4, (1, 1), 1),
+            (Format::Bc1Unorm, 8, (4, 4), 1),
+            (Format::Bc4Snorm, 8, (4, 4), 1),
+            (Format::Bc5Unorm, 16, (4, 4), 1),
+            (Format::Bc7UnormSrgb, 16, (4, 4), 1),
+            (Format::Yuy2, 4, (2, 1), 1),
+            (Format::Y216, 8, (2, 1), 1),
+            (Format::Nv12, 1, (1, 1), 2),
+            (Format::P010, 2, (1, 1), 2),
+            (Format::D16Unorm, 2, (1, 1), 1),
+            (Format::D24UnormS8Uint, 4, (1, 1), 2),
+            (Format::D32FloatS8X24Uint, 8, (1, 1), 2),
+            (Format::V408, 1, (1, 1), 3),
+        ];
+        for &(format, bytes, block, planes) in &cases {
+            assert_eq!(format.bytes_per_block(), bytes, "{:?}", format);
+            assert_eq!(format.block_dimensions(), block, "{:?}", format);
+            assert_eq!(format.plane_count(), planes, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn format_families() {
+        for format in all() {
+            if let Some(typeless) = format.typeless() {
+                assert!(typeless.is_typeless(), "{:?}", format);
+                assert_eq!(typeless.typeless(), Some(typeless), "{:?}", format);
+                assert_eq!(
+                    typeless.bytes_per_block(),
+                    format.bytes_per_block(),
+                    "{:?}",
+                    format
+                );
+                assert_eq!(
+                    typeless.block_dimensions(),
+                    format.block_dimensions(),
+                    "{:?}",
+                    format
+                );
+            }
+            if format.is_typeless() && !format.is_video() {
+                assert_eq!(format.typeless(), Some(format), "{:?}", format);
+            }
+
+            assert!(!format.linear().is_srgb(), "{:?}", format);
+            assert_eq!(
+                format.linear().typeless(),
+                format.typeless(),
+                "{:?}",
+                format
+            );
+            if format.is_srgb() {
+                assert_eq!(format.srgb(), Some(format), "{:?}", format);
+                assert_eq!(format.linear().srgb(), Some(format), "{:?}", format);
+            }
+            if let Some(srgb) = format.srgb() {
+                assert!(srgb.is_srgb(), "{:?}", format);
+                assert_eq!(srgb.linear(), format.linear(), "{:?}", format);
+            }
+        }
+        assert_eq!(Format::R8G8B8A8Uint.srgb(), None);
+        assert_eq!(Format::Bc1Unorm.srgb(), Some(Format::Bc1UnormSrgb));
+        assert_eq!(Format::D32Float.typeless(), Some(Format::R32Typeless));
+        assert_eq!(Format::Nv12.typeless(), None);
+    }
+}
@@ -1,8 +1,11 @@
-use crate::com::WeakPtr;
+use crate::com::{ComSend, ComSync, WeakPtr};
 use winapi::um::d3d12;
 
 pub type Heap = WeakPtr<d3d12::ID3D12Heap>;
 
+unsafe impl ComSend for d3d12::ID3D12Heap {}
+unsafe impl ComSync for d3d12::ID3D12Heap {}
+
 #[repr(u32)]
 #[derive(Clone, Copy)]
 pub enum HeapType {
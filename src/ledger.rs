@@ -0,0 +1,317 @@
+//! COM lifetime ledger
+//!
+//! Opt-in bookkeeping of the objects handed out by the creation functions,
+//! matched against `WeakPtr::destroy` calls. Enable it with `ledger::enable`
+//! early on and call `ledger::report` during shutdown to list leaked and
+//! over-released objects.
+
+use crate::com::WeakPtr;
+use std::{
+    any,
+    collections::HashMap,
+    fmt,
+    panic::Location,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static LEDGER: Mutex<Option<Ledger>> = Mutex::new(None);
+
+/// Object which is still referenced when the report is generated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LeakedObject {
+    pub address: usize,
+    pub type_name: &'static str,
+    pub created_at: &'static Location<'static>,
+    pub references: u32,
+}
+
+/// `destroy` call on an object whose tracked references were all released already.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DoubleRelease {
+    pub address: usize,
+    pub type_name: &'static str,
+    pub created_at: &'static Location<'static>,
+    pub released_at: &'static Location<'static>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LedgerReport {
+    pub leaked: Vec<LeakedObject>,
+    pub double_released: Vec<DoubleRelease>,
+}
+
+impl LedgerReport {
+    pub fn is_clean(&self) -> bool {
+        self.leaked.is_empty() && self.double_released.is_empty()
+    }
+}
+
+impl fmt::Display for LedgerReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for leak in &self.leaked {
+            writeln!(
+                f,
+                "leaked {} at {:#x} ({} references), created at {}",
+                leak.type_name, leak.address, leak.references, leak.created_at
+            )?;
+        }
+        for release in &self.double_released {
+            writeln!(
+                f,
+                "released {} at {:#x} once too often at {}, created at {}",
+                release.type_name, release.address, release.released_at, release.created_at
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    type_name: &'static str,
+    created_at: &'static Location<'static>,
+    references: u32,
+}
+
+/// Reference bookkeeping keyed by object address.
+///
+/// Objects which were never tracked are ignored on release.
+#[derive(Debug, Default)]
+pub struct Ledger {
+    alive: HashMap<usize, Entry>,
+    released: HashMap<usize, Entry>,
+    double_released: Vec<DoubleRelease>,
+}
+
+impl Ledger {
+    pub fn new() -> Self {
+        Ledger::default()
+    }
+
+    /// Record one new reference to the object at `address`.
+    pub fn track(
+        &mut self,
+        address: usize,
+        type_name: &'static str,
+        location: &'static Location<'static>,
+    ) {
+        // The address may be reused after the previous object was freed.
+        self.released.remove(&address);
+        self.alive
+            .entry(address)
+            .and_modify(|entry| entry.references += 1)
+            .or_insert(Entry {
+                type_name,
+                created_at: location,
+                references: 1,
+            });
+    }
+
+    /// Record the release of one reference to the object at `address`.
+    pub fn release(&mut self, address: usize, location: &'static Location<'static>) {
+        if let Some(entry) = self.alive.get_mut(&address) {
+            entry.references -= 1;
+            if entry.references == 0 {
+                let entry = self.alive.remove(&address).unwrap();
+                self.released.insert(address, entry);
+            }
+        } else if let Some(entry) = self.released.get(&address) {
+            self.double_released.push(DoubleRelease {
+                address,
+                type_name: entry.type_name,
+                created_at: entry.created_at,
+                released_at: location,
+            });
+        }
+    }
+
+    pub fn report(&self) -> LedgerReport {
+        let mut leaked = self
+            .alive
+            .iter()
+            .map(|(&address, entry)| LeakedObject {
+                address,
+                type_name: entry.type_name,
+                created_at: entry.created_at,
+                references: entry.references,
+            })
+            .collect::<Vec<_>>();
+        leaked.sort_by_key(|leak| leak.address);
+
+        LedgerReport {
+            leaked,
+            double_released: self.double_released.clone(),
+        }
+    }
+}
+
+/// Start tracking objects created from now on.
+pub fn enable() {
+    let mut ledger = LEDGER.lock().unwrap_or_else(|e| e.into_inner());
+    ledger.get_or_insert_with(Ledger::new);
+    ENABLED.store(true, Ordering::Release);
+}
+
+/// Stop tracking, keeping the recorded state for `report`.
+pub fn disable() {
+    ENABLED.store(false, Ordering::Release);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Acquire)
+}
+
+/// Forget everything recorded so far.
+pub fn reset() {
+    if let Some(ref mut ledger) = *LEDGER.lock().unwrap_or_else(|e| e.into_inner()) {
+        *ledger = Ledger::new();
+    }
+}
+
+/// Objects currently alive and all double releases seen since tracking was enabled.
+pub fn report() -> LedgerReport {
+    LEDGER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(Ledger::report)
+        .unwrap_or_default()
+}
+
+fn with_ledger(f: impl FnOnce(&mut Ledger)) {
+    if !is_enabled() {
+        return;
+    }
+    if let Some(ref mut ledger) = *LEDGER.lock().unwrap_or_else(|e| e.into_inner()) {
+        f(ledger);
+    }
+}
+
+/// Track an object returned by a creation function, failed calls leave the pointer null.
+#[track_caller]
+pub(crate) fn track_created<T>(ptr: &WeakPtr<T>) {
+    if !ptr.is_null() {
+        track(
+            ptr.as_mut_ptr() as usize,
+            any::type_name::<T>(),
+            Location::caller(),
+        );
+    }
+}
+
+pub(crate) fn track(address: usize, type_name: &'static str, location: &'static Location<'static>) {
+    with_ledger(|ledger| ledger.track(address, type_name, location));
+}
+
+pub(crate) fn release(address: usize, location: &'static Location<'static>) {
+    with_ledger(|ledger| ledger.release(address, location));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaks() {
+        let created = Location::caller();
+        let mut ledger = Ledger::new();
+        assert!(ledger.report().is_clean());
+
+        ledger.track(0x200, "Fence", created);
+        ledger.track(0x100, "Heap", created);
+        ledger.track(0x200, "Fence", created);
+        ledger.release(0x200, created);
+        assert_eq!(
+            ledger.report().leaked,
+            [
+                LeakedObject {
+                    address: 0x100,
+                    type_name: "Heap",
+                    created_at: created,
+                    references: 1,
+                },
+                LeakedObject {
+                    address: 0x200,
+                    type_name: "Fence",
+                    created_at: created,
+                    references: 1,
+                },
+            ]
+        );
+
+        ledger.release(0x100, created);
+        ledger.release(0x200, created);
+        assert!(ledger.report().is_clean());
+    }
+
+    #[test]
+    fn double_release() {
+        let created = Location::caller();
+        let released = Location::caller();
+        let mut ledger = Ledger::new();
+
+        ledger.track(0x100, "Fence", created);
+        ledger.release(0x100, created);
+        ledger.release(0x100, released);
+        // Untracked objects are ignored.
+        ledger.release(0x300, released);
+
+        let report = ledger.report();
+        assert!(report.leaked.is_empty());
+        assert_eq!(
+            report.double_released,
+            [DoubleRelease {
+                address: 0x100,
+                type_name: "Fence",
+                created_at: created,
+                released_at: released,
+            }]
+        );
+        assert!(report
+            .to_string()
+            .starts_with("released Fence at 0x100 once too often"));
+
+        // A new object at the same address starts over.
+        ledger.track(0x100, "Heap", created);
+        ledger.release(0x100, released);
+        assert_eq!(ledger.report().double_released.len(), 1);
+    }
+
+    // The only test touching the global ledger. Other tests may still release objects
+    // concurrently, so only the addresses used here are looked at.
+    #[test]
+    fn enable_disable() {
+        let location = Location::caller();
+        let tracked = |address| {
+            report()
+                .leaked
+                .iter()
+                .any(|leak: &LeakedObject| leak.address == address)
+        };
+
+        track(0x1000_0001, "Fence", location);
+        assert!(!tracked(0x1000_0001));
+
+        enable();
+        assert!(is_enabled());
+        track(0x1000_0001, "Fence", location);
+        track(0x1000_0002, "Fence", location);
+        release(0x1000_0002, location);
+        assert!(tracked(0x1000_0001));
+        assert!(!tracked(0x1000_0002));
+
+        disable();
+        assert!(!is_enabled());
+        release(0x1000_0001, location);
+        track(0x1000_0003, "Fence", location);
+        assert!(tracked(0x1000_0001));
+        assert!(!tracked(0x1000_0003));
+
+        reset();
+        assert!(!tracked(0x1000_0001));
+    }
+}
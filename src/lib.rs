@@ -1,45 +1,76 @@
 #[macro_use]
 extern crate bitflags;
+#[cfg(not(windows))]
+extern crate winapi_headers as winapi;
 
-use std::{convert::TryFrom, ffi::CStr};
-use winapi::{
-    shared::dxgiformat,
-    um::{d3d12, d3dcommon},
-};
+use std::{convert::TryFrom, ffi::CStr, slice};
+use winapi::um::{d3d12, d3dcommon};
 
 mod com;
+mod com_object;
 mod command_allocator;
 mod command_list;
 mod debug;
 mod descriptor;
+mod descriptor_allocator;
+mod descriptor_ring;
 mod device;
+mod dxbc;
 mod dxgi;
+mod error;
+mod feature;
+mod footprint;
+mod format;
 mod heap;
+pub mod ledger;
+#[cfg(feature = "mock")]
+pub mod mock;
+mod object;
+mod pipeline_stream;
 mod pso;
 mod query;
 mod queue;
 mod resource;
+mod root_signature;
+mod root_signature_hlsl;
+mod root_signature_layout;
+mod root_signature_validate;
 mod sync;
+mod view;
 
 pub use crate::com::*;
+pub use crate::com_object::*;
 pub use crate::command_allocator::*;
 pub use crate::command_list::*;
 pub use crate::debug::*;
 pub use crate::descriptor::*;
+pub use crate::descriptor_allocator::*;
+pub use crate::descriptor_ring::*;
 pub use crate::device::*;
+pub use crate::dxbc::*;
 pub use crate::dxgi::*;
+pub use crate::error::*;
+pub use crate::feature::*;
+pub use crate::footprint::*;
+pub use crate::format::*;
 pub use crate::heap::*;
+pub use crate::object::*;
+pub use crate::pipeline_stream::*;
 pub use crate::pso::*;
 pub use crate::query::*;
 pub use crate::queue::*;
 pub use crate::resource::*;
+pub use crate::root_signature::*;
+pub use crate::root_signature_hlsl::*;
+pub use crate::root_signature_layout::*;
+pub use crate::root_signature_validate::*;
 pub use crate::sync::*;
+pub use crate::view::*;
 
 pub use winapi::shared::winerror::HRESULT;
 
 pub type D3DResult<T> = (T, HRESULT);
 pub type GpuAddress = d3d12::D3D12_GPU_VIRTUAL_ADDRESS;
-pub type Format = dxgiformat::DXGI_FORMAT;
 pub type Rect = d3d12::D3D12_RECT;
 pub type NodeMask = u32;
 
@@ -58,13 +89,19 @@ pub type WorkGroupCount = [u32; 3];
 
 pub type TextureAddressMode = [d3d12::D3D12_TEXTURE_ADDRESS_MODE; 3];
 
+#[derive(Clone, Copy, Debug)]
 pub struct SampleDesc {
     pub count: u32,
     pub quality: u32,
 }
 
+// Not yet part of `winapi`.
+const D3D_FEATURE_LEVEL_12_2: d3dcommon::D3D_FEATURE_LEVEL = 0xc200;
+
 #[repr(u32)]
 #[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FeatureLevel {
     L9_1 = d3dcommon::D3D_FEATURE_LEVEL_9_1,
     L9_2 = d3dcommon::D3D_FEATURE_LEVEL_9_2,
@@ -75,6 +112,7 @@ pub enum FeatureLevel {
     L11_1 = d3dcommon::D3D_FEATURE_LEVEL_11_1,
     L12_0 = d3dcommon::D3D_FEATURE_LEVEL_12_0,
     L12_1 = d3dcommon::D3D_FEATURE_LEVEL_12_1,
+    L12_2 = D3D_FEATURE_LEVEL_12_2,
 }
 
 impl TryFrom<u32> for FeatureLevel {
@@ -91,15 +129,30 @@ impl TryFrom<u32> for FeatureLevel {
             d3dcommon::D3D_FEATURE_LEVEL_11_1 => Self::L11_1,
             d3dcommon::D3D_FEATURE_LEVEL_12_0 => Self::L12_0,
             d3dcommon::D3D_FEATURE_LEVEL_12_1 => Self::L12_1,
+            D3D_FEATURE_LEVEL_12_2 => Self::L12_2,
             _ => return Err(()),
         })
     }
 }
 
 pub type Blob = WeakPtr<d3dcommon::ID3DBlob>;
+impl Blob {
+    /// Contents of the blob.
+    ///
+    /// # Safety
+    ///
+    /// The blob must not be null.
+    pub unsafe fn as_slice(&self) -> &[u8] {
+        debug_assert!(!self.is_null());
+        slice::from_raw_parts(self.GetBufferPointer() as *const u8, self.GetBufferSize())
+    }
+}
 
 pub type Error = WeakPtr<d3dcommon::ID3DBlob>;
 impl Error {
+    /// # Safety
+    ///
+    /// The blob must not be null and hold a null terminated string.
     pub unsafe fn as_c_str(&self) -> &CStr {
         debug_assert!(!self.is_null());
         let data = self.GetBufferPointer();
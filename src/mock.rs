@@ -0,0 +1,1825 @@
+//! Recording mock objects
+//!
+//! `Recorder` creates fake `Device`, `CommandQueue`, `GraphicsCommandList` and `Fence`
+//! objects which log every call made through the wrappers, together with the decoded
+//! arguments. Results of calls returning an `HRESULT` can be scripted per method.
+
+use crate::{
+    com::WeakPtr,
+    com_object::{unimplemented_vtable, ComObject},
+    CommandQueue, DescriptorHeapFlags, Device, Event, Fence, GraphicsCommandList, HeapFlags,
+};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    ffi::CStr,
+    mem, slice,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+};
+use winapi::{
+    ctypes::c_void,
+    shared::{
+        basetsd::SIZE_T,
+        dxgiformat::DXGI_FORMAT,
+        guiddef::{GUID, REFGUID, REFIID},
+        minwindef::{FLOAT, INT, UINT},
+        winerror::{FAILED, HRESULT, S_OK},
+    },
+    um::{d3d12, unknwnbase::IUnknown, winnt::HANDLE},
+    Interface,
+};
+
+/// Increment size reported for every descriptor heap type.
+pub const DESCRIPTOR_INCREMENT: u32 = 32;
+
+// Number of entries in the vtable of objects which are only created but not mocked.
+const STUB_VTABLE_LEN: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Barrier {
+    Transition {
+        resource: usize,
+        subresource: u32,
+        state_before: u32,
+        state_after: u32,
+        flags: u32,
+    },
+    Aliasing {
+        resource_before: usize,
+        resource_after: usize,
+        flags: u32,
+    },
+    Uav {
+        resource: usize,
+        flags: u32,
+    },
+}
+
+/// Decoded `D3D12_RESOURCE_DESC`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResourceInfo {
+    pub dimension: u32,
+    pub alignment: u64,
+    pub width: u64,
+    pub height: u32,
+    pub depth_or_array_size: u16,
+    pub mip_levels: u16,
+    pub format: DXGI_FORMAT,
+    pub sample_count: u32,
+    pub layout: u32,
+    pub flags: u32,
+}
+
+/// Decoded call made on one of the mock objects.
+///
+/// Interface pointers are stored as addresses, rects as `[left, top, right, bottom]`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Call {
+    // ID3D12Device
+    CreateCommandQueue {
+        list_type: u32,
+        priority: i32,
+        flags: u32,
+        node_mask: u32,
+    },
+    CreateCommandAllocator {
+        list_type: u32,
+    },
+    CreateGraphicsPipelineState {
+        root_signature: usize,
+        vs: Vec<u8>,
+        ps: Vec<u8>,
+        input_layout: Vec<(String, u32, DXGI_FORMAT)>,
+        primitive_topology_type: u32,
+        rtv_formats: Vec<DXGI_FORMAT>,
+        dsv_format: DXGI_FORMAT,
+        node_mask: u32,
+        flags: u32,
+    },
+    CreateComputePipelineState {
+        root_signature: usize,
+        cs: Vec<u8>,
+        node_mask: u32,
+        flags: u32,
+    },
+    CreateCommandList {
+        node_mask: u32,
+        list_type: u32,
+        allocator: usize,
+        initial_state: usize,
+    },
+    CreateDescriptorHeap {
+        heap_type: u32,
+        num_descriptors: u32,
+        flags: DescriptorHeapFlags,
+        node_mask: u32,
+    },
+    GetDescriptorHandleIncrementSize {
+        heap_type: u32,
+    },
+    CopyDescriptors {
+        dest_ranges: Vec<(usize, u32)>,
+        src_ranges: Vec<(usize, u32)>,
+        heap_type: u32,
+    },
+    CopyDescriptorsSimple {
+        count: u32,
+        dest: usize,
+        src: usize,
+        heap_type: u32,
+    },
+    CreateRootSignature {
+        node_mask: u32,
+        blob: Vec<u8>,
+    },
+    CreateRenderTargetView {
+        resource: usize,
+        format: Option<DXGI_FORMAT>,
+        view_dimension: Option<u32>,
+        descriptor: usize,
+    },
+    CreateShaderResourceView {
+        resource: usize,
+        format: Option<DXGI_FORMAT>,
+        view_dimension: Option<u32>,
+        descriptor: usize,
+    },
+    CreateUnorderedAccessView {
+        resource: usize,
+        counter: usize,
+        format: Option<DXGI_FORMAT>,
+        view_dimension: Option<u32>,
+        descriptor: usize,
+    },
+    CreateConstantBufferView {
+        buffer_location: u64,
+        size_in_bytes: u32,
+        descriptor: usize,
+    },
+    CreateDepthStencilView {
+        resource: usize,
+        format: Option<DXGI_FORMAT>,
+        view_dimension: Option<u32>,
+        flags: Option<u32>,
+        descriptor: usize,
+    },
+    CreateSampler {
+        filter: u32,
+        address_mode: [u32; 3],
+        mip_lod_bias: f32,
+        max_anisotropy: u32,
+        comparison_op: u32,
+        border_color: [f32; 4],
+        min_lod: f32,
+        max_lod: f32,
+        descriptor: usize,
+    },
+    CreateHeap {
+        size_in_bytes: u64,
+        heap_type: u32,
+        alignment: u64,
+        flags: HeapFlags,
+    },
+    CreateCommittedResource {
+        heap_type: u32,
+        heap_flags: HeapFlags,
+        desc: ResourceInfo,
+        initial_state: u32,
+        clear_format: Option<DXGI_FORMAT>,
+    },
+    CreatePlacedResource {
+        heap: usize,
+        offset: u64,
+        desc: ResourceInfo,
+        initial_state: u32,
+        clear_format: Option<DXGI_FORMAT>,
+    },
+    CreateReservedResource {
+        desc: ResourceInfo,
+        initial_state: u32,
+        clear_format: Option<DXGI_FORMAT>,
+    },
+    CreateFence {
+        initial_value: u64,
+        flags: u32,
+    },
+    CreateQueryHeap {
+        heap_type: u32,
+        count: u32,
+        node_mask: u32,
+    },
+    CreateCommandSignature {
+        byte_stride: u32,
+        num_arguments: u32,
+        node_mask: u32,
+        root_signature: usize,
+    },
+
+    // ID3D12CommandQueue
+    ExecuteCommandLists {
+        lists: Vec<usize>,
+    },
+    QueueSignal {
+        fence: usize,
+        value: u64,
+    },
+    QueueWait {
+        fence: usize,
+        value: u64,
+    },
+
+    // ID3D12GraphicsCommandList
+    Close,
+    Reset {
+        allocator: usize,
+        initial_state: usize,
+    },
+    DrawInstanced {
+        vertex_count: u32,
+        instance_count: u32,
+        start_vertex: u32,
+        start_instance: u32,
+    },
+    DrawIndexedInstanced {
+        index_count: u32,
+        instance_count: u32,
+        start_index: u32,
+        base_vertex: i32,
+        start_instance: u32,
+    },
+    Dispatch {
+        count: [u32; 3],
+    },
+    SetBlendFactor {
+        factor: [f32; 4],
+    },
+    SetStencilRef {
+        reference: u32,
+    },
+    SetPipelineState {
+        pipeline_state: usize,
+    },
+    ResourceBarrier {
+        barriers: Vec<Barrier>,
+    },
+    ExecuteBundle {
+        bundle: usize,
+    },
+    SetDescriptorHeaps {
+        heaps: Vec<usize>,
+    },
+    SetComputeRootSignature {
+        root_signature: usize,
+    },
+    SetGraphicsRootSignature {
+        root_signature: usize,
+    },
+    SetComputeRootDescriptorTable {
+        root_index: u32,
+        base_descriptor: u64,
+    },
+    SetGraphicsRootDescriptorTable {
+        root_index: u32,
+        base_descriptor: u64,
+    },
+    SetComputeRoot32BitConstant {
+        root_index: u32,
+        value: u32,
+        dest_offset_words: u32,
+    },
+    SetGraphicsRoot32BitConstant {
+        root_index: u32,
+        value: u32,
+        dest_offset_words: u32,
+    },
+    SetComputeRootConstantBufferView {
+        root_index: u32,
+        buffer_location: u64,
+    },
+    SetGraphicsRootConstantBufferView {
+        root_index: u32,
+        buffer_location: u64,
+    },
+    SetComputeRootShaderResourceView {
+        root_index: u32,
+        buffer_location: u64,
+    },
+    SetGraphicsRootShaderResourceView {
+        root_index: u32,
+        buffer_location: u64,
+    },
+    SetComputeRootUnorderedAccessView {
+        root_index: u32,
+        buffer_location: u64,
+    },
+    SetGraphicsRootUnorderedAccessView {
+        root_index: u32,
+        buffer_location: u64,
+    },
+    SetIndexBuffer {
+        buffer_location: u64,
+        size_in_bytes: u32,
+        format: DXGI_FORMAT,
+    },
+    ClearDepthStencilView {
+        descriptor: usize,
+        flags: u32,
+        depth: f32,
+        stencil: u8,
+        rects: Vec<[i32; 4]>,
+    },
+    ClearRenderTargetView {
+        descriptor: usize,
+        color: [f32; 4],
+        rects: Vec<[i32; 4]>,
+    },
+    DiscardResource {
+        resource: usize,
+        rects: Vec<[i32; 4]>,
+        first_subresource: u32,
+        num_subresources: u32,
+    },
+
+    // ID3D12Fence
+    GetCompletedValue,
+    SetEventOnCompletion {
+        value: u64,
+    },
+    FenceSignal {
+        value: u64,
+    },
+}
+
+type EventHandler = Arc<dyn Fn(Event) + Send + Sync>;
+
+#[derive(Default)]
+struct State {
+    calls: Vec<Call>,
+    results: HashMap<&'static str, VecDeque<HRESULT>>,
+    fences: HashSet<usize>,
+    next_descriptor_address: usize,
+    event_handler: Option<EventHandler>,
+}
+
+/// Shared log of all calls made on the mock objects created from it.
+#[derive(Clone, Default)]
+pub struct Recorder(Arc<Mutex<State>>);
+
+impl Recorder {
+    pub fn new() -> Self {
+        Recorder::default()
+    }
+
+    /// All calls recorded so far, in order.
+    pub fn calls(&self) -> Vec<Call> {
+        self.state().calls.clone()
+    }
+
+    /// Returns and clears all calls recorded so far.
+    pub fn take_calls(&self) -> Vec<Call> {
+        mem::take(&mut self.state().calls)
+    }
+
+    /// Queue the result for the next call of `method` (e.g. `"CreateFence"`).
+    ///
+    /// Calls without a queued result succeed with `S_OK`.
+    pub fn push_result(&self, method: &'static str, hr: HRESULT) {
+        self.state()
+            .results
+            .entry(method)
+            .or_default()
+            .push_back(hr);
+    }
+
+    /// Replace how events passed to `SetEventOnCompletion` are signaled once the fence
+    /// reaches the awaited value.
+    ///
+    /// By default events are set with `SetEvent` on Windows and ignored elsewhere.
+    pub fn set_event_handler(&self, handler: impl Fn(Event) + Send + Sync + 'static) {
+        self.state().event_handler = Some(Arc::new(handler));
+    }
+
+    pub fn create_device(&self) -> Device {
+        unsafe {
+            let mut vtbl: d3d12::ID3D12DeviceVtbl = unimplemented_vtable();
+            vtbl.CreateCommandQueue = device::create_command_queue;
+            vtbl.CreateCommandAllocator = device::create_command_allocator;
+            vtbl.CreateGraphicsPipelineState = device::create_graphics_pipeline_state;
+            vtbl.CreateComputePipelineState = device::create_compute_pipeline_state;
+            vtbl.CreateCommandList = device::create_command_list;
+            vtbl.CreateDescriptorHeap = device::create_descriptor_heap;
+            vtbl.GetDescriptorHandleIncrementSize = device::get_descriptor_handle_increment_size;
+            vtbl.CopyDescriptors = device::copy_descriptors;
+            vtbl.CopyDescriptorsSimple = device::copy_descriptors_simple;
+            vtbl.CreateRootSignature = device::create_root_signature;
+            vtbl.CreateRenderTargetView = device::create_render_target_view;
+            vtbl.CreateShaderResourceView = device::create_shader_resource_view;
+            vtbl.CreateUnorderedAccessView = device::create_unordered_access_view;
+            vtbl.CreateConstantBufferView = device::create_constant_buffer_view;
+            vtbl.CreateDepthStencilView = device::create_depth_stencil_view;
+            vtbl.CreateSampler = device::create_sampler;
+            vtbl.CreateHeap = device::create_heap;
+            vtbl.CreateCommittedResource = device::create_committed_resource;
+            vtbl.CreatePlacedResource = device::create_placed_resource;
+            vtbl.CreateReservedResource = device::create_reserved_resource;
+            vtbl.CreateFence = device::create_fence;
+            vtbl.CreateQueryHeap = device::create_query_heap;
+            vtbl.CreateCommandSignature = device::create_command_signature;
+
+            ComObject::create::<d3d12::ID3D12Device>(
+                vtbl,
+                &[d3d12::ID3D12Object::uuidof()],
+                self.clone(),
+            )
+        }
+    }
+
+    pub fn create_command_queue(&self) -> CommandQueue {
+        unsafe {
+            let mut vtbl: d3d12::ID3D12CommandQueueVtbl = unimplemented_vtable();
+            vtbl.ExecuteCommandLists = queue::execute_command_lists;
+            vtbl.Signal = queue::signal;
+            vtbl.Wait = queue::wait;
+
+            ComObject::create::<d3d12::ID3D12CommandQueue>(vtbl, &pageable(), self.clone())
+        }
+    }
+
+    pub fn create_graphics_command_list(&self) -> GraphicsCommandList {
+        unsafe {
+            let mut vtbl: d3d12::ID3D12GraphicsCommandListVtbl = unimplemented_vtable();
+            vtbl.Close = list::close;
+            vtbl.Reset = list::reset;
+            vtbl.DrawInstanced = list::draw_instanced;
+            vtbl.DrawIndexedInstanced = list::draw_indexed_instanced;
+            vtbl.Dispatch = list::dispatch;
+            vtbl.OMSetBlendFactor = list::set_blend_factor;
+            vtbl.OMSetStencilRef = list::set_stencil_ref;
+            vtbl.SetPipelineState = list::set_pipeline_state;
+            vtbl.ResourceBarrier = list::resource_barrier;
+            vtbl.ExecuteBundle = list::execute_bundle;
+            vtbl.SetDescriptorHeaps = list::set_descriptor_heaps;
+            vtbl.SetComputeRootSignature = list::set_compute_root_signature;
+            vtbl.SetGraphicsRootSignature = list::set_graphics_root_signature;
+            vtbl.SetComputeRootDescriptorTable = list::set_compute_root_descriptor_table;
+            vtbl.SetGraphicsRootDescriptorTable = list::set_graphics_root_descriptor_table;
+            vtbl.SetComputeRoot32BitConstant = list::set_compute_root_constant;
+            vtbl.SetGraphicsRoot32BitConstant = list::set_graphics_root_constant;
+            vtbl.SetComputeRootConstantBufferView = list::set_compute_root_cbv;
+            vtbl.SetGraphicsRootConstantBufferView = list::set_graphics_root_cbv;
+            vtbl.SetComputeRootShaderResourceView = list::set_compute_root_srv;
+            vtbl.SetGraphicsRootShaderResourceView = list::set_graphics_root_srv;
+            vtbl.SetComputeRootUnorderedAccessView = list::set_compute_root_uav;
+            vtbl.SetGraphicsRootUnorderedAccessView = list::set_graphics_root_uav;
+            vtbl.IASetIndexBuffer = list::set_index_buffer;
+            vtbl.ClearDepthStencilView = list::clear_depth_stencil_view;
+            vtbl.ClearRenderTargetView = list::clear_render_target_view;
+            vtbl.DiscardResource = list::discard_resource;
+
+            ComObject::create::<d3d12::ID3D12GraphicsCommandList>(
+                vtbl,
+                &[
+                    d3d12::ID3D12CommandList::uuidof(),
+                    d3d12::ID3D12DeviceChild::uuidof(),
+                    d3d12::ID3D12Object::uuidof(),
+                ],
+                self.clone(),
+            )
+        }
+    }
+
+    pub fn create_fence(&self, initial_value: u64) -> Fence {
+        let fence = unsafe {
+            let mut vtbl: d3d12::ID3D12FenceVtbl = unimplemented_vtable();
+            vtbl.GetCompletedValue = fence::get_completed_value;
+            vtbl.SetEventOnCompletion = fence::set_event_on_completion;
+            vtbl.Signal = fence::signal;
+
+            ComObject::create::<d3d12::ID3D12Fence>(
+                vtbl,
+                &pageable(),
+                FenceState {
+                    recorder: self.clone(),
+                    value: AtomicU64::new(initial_value),
+                    pending: Mutex::new(Vec::new()),
+                },
+            )
+        };
+        self.state().fences.insert(fence.as_mut_ptr() as usize);
+        fence
+    }
+
+    fn create_descriptor_heap(
+        &self,
+        desc: d3d12::D3D12_DESCRIPTOR_HEAP_DESC,
+    ) -> WeakPtr<d3d12::ID3D12DescriptorHeap> {
+        let base = {
+            let mut state = self.state();
+            let base = 0x1000_0000 + state.next_descriptor_address;
+            state.next_descriptor_address +=
+                (desc.NumDescriptors.max(1) * DESCRIPTOR_INCREMENT) as usize;
+            base
+        };
+
+        unsafe {
+            let mut vtbl: d3d12::ID3D12DescriptorHeapVtbl = unimplemented_vtable();
+            vtbl.GetDesc = descriptor_heap::get_desc;
+            vtbl.GetCPUDescriptorHandleForHeapStart = descriptor_heap::get_cpu_start;
+            vtbl.GetGPUDescriptorHandleForHeapStart = descriptor_heap::get_gpu_start;
+
+            ComObject::create::<d3d12::ID3D12DescriptorHeap>(
+                vtbl,
+                &pageable(),
+                DescriptorHeapState { desc, base },
+            )
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn record(&self, call: Call) {
+        self.state().calls.push(call);
+    }
+
+    fn set_event(&self, event: Event) {
+        // Called without the lock held, the handler may inspect the recorder.
+        let handler = self.state().event_handler.clone();
+        match handler {
+            Some(handler) => handler(event),
+            #[cfg(windows)]
+            None => unsafe {
+                winapi::um::synchapi::SetEvent(event.0);
+            },
+            #[cfg(not(windows))]
+            None => {}
+        }
+    }
+
+    fn result(&self, method: &'static str) -> HRESULT {
+        self.state()
+            .results
+            .get_mut(method)
+            .and_then(VecDeque::pop_front)
+            .unwrap_or(S_OK)
+    }
+}
+
+fn pageable() -> [GUID; 3] {
+    [
+        d3d12::ID3D12Pageable::uuidof(),
+        d3d12::ID3D12DeviceChild::uuidof(),
+        d3d12::ID3D12Object::uuidof(),
+    ]
+}
+
+struct FenceState {
+    recorder: Recorder,
+    value: AtomicU64,
+    pending: Mutex<Vec<(u64, usize)>>,
+}
+
+impl FenceState {
+    fn set_value(&self, value: u64) {
+        self.value.store(value, Ordering::Release);
+        let reached = {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            let (reached, waiting): (Vec<_>, Vec<_>) =
+                pending.drain(..).partition(|&(target, _)| target <= value);
+            *pending = waiting;
+            reached
+        };
+        for (_, event) in reached {
+            self.recorder.set_event(Event(event as HANDLE));
+        }
+    }
+}
+
+impl Drop for FenceState {
+    fn drop(&mut self) {
+        // All registered fences are alive, apart from the one being dropped.
+        let this: *const Self = self;
+        self.recorder.state().fences.retain(|&fence| unsafe {
+            let object = FenceObject::from_interface(fence as *mut d3d12::ID3D12Fence);
+            !std::ptr::eq(object.data(), this)
+        });
+    }
+}
+
+struct DescriptorHeapState {
+    desc: d3d12::D3D12_DESCRIPTOR_HEAP_DESC,
+    base: usize,
+}
+
+type DeviceObject = ComObject<d3d12::ID3D12DeviceVtbl, Recorder>;
+type QueueObject = ComObject<d3d12::ID3D12CommandQueueVtbl, Recorder>;
+type ListObject = ComObject<d3d12::ID3D12GraphicsCommandListVtbl, Recorder>;
+type FenceObject = ComObject<d3d12::ID3D12FenceVtbl, FenceState>;
+type DescriptorHeapObject = ComObject<d3d12::ID3D12DescriptorHeapVtbl, DescriptorHeapState>;
+type StubObject = ComObject<[usize; STUB_VTABLE_LEN], ()>;
+
+// Hands out the requested interface of `object` and drops the creation reference.
+unsafe fn hand_out<I: Interface>(
+    object: WeakPtr<I>,
+    riid: REFIID,
+    out: *mut *mut c_void,
+) -> HRESULT {
+    let unknown = object.as_unknown();
+    let hr = unknown.QueryInterface(riid, out);
+    // Internal reference, bypasses the ledger.
+    unknown.Release();
+    hr
+}
+
+// Objects which aren't mocked only support reference counting.
+unsafe fn stub_object(riid: REFIID, out: *mut *mut c_void) -> HRESULT {
+    let object = StubObject::create::<IUnknown>(unimplemented_vtable(), &[*riid], ());
+    hand_out(object, riid, out)
+}
+
+unsafe fn rects(num: UINT, rects: *const d3d12::D3D12_RECT) -> Vec<[i32; 4]> {
+    if rects.is_null() {
+        return Vec::new();
+    }
+    slice::from_raw_parts(rects, num as usize)
+        .iter()
+        .map(|r| [r.left, r.top, r.right, r.bottom])
+        .collect()
+}
+
+unsafe fn barrier(raw: &d3d12::D3D12_RESOURCE_BARRIER) -> Barrier {
+    match raw.Type {
+        d3d12::D3D12_RESOURCE_BARRIER_TYPE_TRANSITION => {
+            let t = raw.u.Transition();
+            Barrier::Transition {
+                resource: t.pResource as usize,
+                subresource: t.Subresource,
+                state_before: t.StateBefore,
+                state_after: t.StateAfter,
+                flags: raw.Flags,
+            }
+        }
+        d3d12::D3D12_RESOURCE_BARRIER_TYPE_ALIASING => {
+            let a = raw.u.Aliasing();
+            Barrier::Aliasing {
+                resource_before: a.pResourceBefore as usize,
+                resource_after: a.pResourceAfter as usize,
+                flags: raw.Flags,
+            }
+        }
+        _ => Barrier::Uav {
+            resource: raw.u.UAV().pResource as usize,
+            flags: raw.Flags,
+        },
+    }
+}
+
+unsafe fn resource_info(desc: *const d3d12::D3D12_RESOURCE_DESC) -> ResourceInfo {
+    let desc = &*desc;
+    ResourceInfo {
+        dimension: desc.Dimension,
+        alignment: desc.Alignment,
+        width: desc.Width,
+        height: desc.Height,
+        depth_or_array_size: desc.DepthOrArraySize,
+        mip_levels: desc.MipLevels,
+        format: desc.Format,
+        sample_count: desc.SampleDesc.Count,
+        layout: desc.Layout,
+        flags: desc.Flags,
+    }
+}
+
+unsafe fn clear_format(clear_value: *const d3d12::D3D12_CLEAR_VALUE) -> Option<DXGI_FORMAT> {
+    clear_value.as_ref().map(|value| value.Format)
+}
+
+unsafe fn bytes(data: *const c_void, len: SIZE_T) -> Vec<u8> {
+    if data.is_null() {
+        Vec::new()
+    } else {
+        slice::from_raw_parts(data as *const u8, len).to_vec()
+    }
+}
+
+mod device {
+    use super::*;
+
+    unsafe fn recorder<'a>(this: *mut d3d12::ID3D12Device) -> &'a Recorder {
+        DeviceObject::from_interface(this).data()
+    }
+
+    pub unsafe extern "system" fn create_command_queue(
+        this: *mut d3d12::ID3D12Device,
+        desc: *const d3d12::D3D12_COMMAND_QUEUE_DESC,
+        riid: REFGUID,
+        out: *mut *mut c_void,
+    ) -> HRESULT {
+        let recorder = recorder(this);
+        let desc = &*desc;
+        recorder.record(Call::CreateCommandQueue {
+            list_type: desc.Type,
+            priority: desc.Priority,
+            flags: desc.Flags,
+            node_mask: desc.NodeMask,
+        });
+        let hr = recorder.result("CreateCommandQueue");
+        if FAILED(hr) {
+            return hr;
+        }
+        hand_out(recorder.create_command_queue(), riid, out)
+    }
+
+    pub unsafe extern "system" fn create_command_allocator(
+        this: *mut d3d12::ID3D12Device,
+        list_type: d3d12::D3D12_COMMAND_LIST_TYPE,
+        riid: REFGUID,
+        out: *mut *mut c_void,
+    ) -> HRESULT {
+        let recorder = recorder(this);
+        recorder.record(Call::CreateCommandAllocator { list_type });
+        let hr = recorder.result("CreateCommandAllocator");
+        if FAILED(hr) {
+            return hr;
+        }
+        stub_object(riid, out)
+    }
+
+    pub unsafe extern "system" fn create_graphics_pipeline_state(
+        this: *mut d3d12::ID3D12Device,
+        desc: *const d3d12::D3D12_GRAPHICS_PIPELINE_STATE_DESC,
+        riid: REFGUID,
+        out: *mut *mut c_void,
+    ) -> HRESULT {
+        let recorder = recorder(this);
+        let desc = &*desc;
+        let input_layout = if desc.InputLayout.NumElements == 0 {
+            &[]
+        } else {
+            slice::from_raw_parts(
+                desc.InputLayout.pInputElementDescs,
+                desc.InputLayout.NumElements as usize,
+            )
+        };
+        recorder.record(Call::CreateGraphicsPipelineState {
+            root_signature: desc.pRootSignature as usize,
+            vs: bytes(desc.VS.pShaderBytecode, desc.VS.BytecodeLength),
+            ps: bytes(desc.PS.pShaderBytecode, desc.PS.BytecodeLength),
+            input_layout: input_layout
+                .iter()
+                .map(|element| {
+                    (
+                        CStr::from_ptr(element.SemanticName)
+                            .to_string_lossy()
+                            .into_owned(),
+                        element.SemanticIndex,
+                        element.Format,
+                    )
+                })
+                .collect(),
+            primitive_topology_type: desc.PrimitiveTopologyType,
+            rtv_formats: desc.RTVFormats[..desc.NumRenderTargets as usize].to_vec(),
+            dsv_format: desc.DSVFormat,
+            node_mask: desc.NodeMask,
+            flags: desc.Flags,
+        });
+        let hr = recorder.result("CreateGraphicsPipelineState");
+        if FAILED(hr) {
+            return hr;
+        }
+        stub_object(riid, out)
+    }
+
+    pub unsafe extern "system" fn create_compute_pipeline_state(
+        this: *mut d3d12::ID3D12Device,
+        desc: *const d3d12::D3D12_COMPUTE_PIPELINE_STATE_DESC,
+        riid: REFGUID,
+        out: *mut *mut c_void,
+    ) -> HRESULT {
+        let recorder = recorder(this);
+        let desc = &*desc;
+        recorder.record(Call::CreateComputePipelineState {
+            root_signature: desc.pRootSignature as usize,
+            cs: bytes(desc.CS.pShaderBytecode, desc.CS.BytecodeLength),
+            node_mask: desc.NodeMask,
+            flags: desc.Flags,
+        });
+        let hr = recorder.result("CreateComputePipelineState");
+        if FAILED(hr) {
+            return hr;
+        }
+        stub_object(riid, out)
+    }
+
+    pub unsafe extern "system" fn create_command_list(
+        this: *mut d3d12::ID3D12Device,
+        node_mask: UINT,
+        list_type: d3d12::D3D12_COMMAND_LIST_TYPE,
+        allocator: *mut d3d12::ID3D12CommandAllocator,
+        initial_state: *mut d3d12::ID3D12PipelineState,
+        riid: REFGUID,
+        out: *mut *mut c_void,
+    ) -> HRESULT {
+        let recorder = recorder(this);
+        recorder.record(Call::CreateCommandList {
+            node_mask,
+            list_type,
+            allocator: allocator as usize,
+            initial_state: initial_state as usize,
+        });
+        let hr = recorder.result("CreateCommandList");
+        if FAILED(hr) {
+            return hr;
+        }
+        hand_out(recorder.create_graphics_command_list(), riid, out)
+    }
+
+    pub unsafe extern "system" fn create_descriptor_heap(
+        this: *mut d3d12::ID3D12Device,
+        desc: *const d3d12::D3D12_DESCRIPTOR_HEAP_DESC,
+        riid: REFGUID,
+        out: *mut *mut c_void,
+    ) -> HRESULT {
+        let recorder = recorder(this);
+        let desc = *desc;
+        recorder.record(Call::CreateDescriptorHeap {
+            heap_type: desc.Type,
+            num_descriptors: desc.NumDescriptors,
+            flags: DescriptorHeapFlags::from_bits_truncate(desc.Flags),
+            node_mask: desc.NodeMask,
+        });
+        let hr = recorder.result("CreateDescriptorHeap");
+        if FAILED(hr) {
+            return hr;
+        }
+        hand_out(recorder.create_descriptor_heap(desc), riid, out)
+    }
+
+    pub unsafe extern "system" fn get_descriptor_handle_increment_size(
+        this: *mut d3d12::ID3D12Device,
+        heap_type: d3d12::D3D12_DESCRIPTOR_HEAP_TYPE,
+    ) -> UINT {
+        recorder(this).record(Call::GetDescriptorHandleIncrementSize { heap_type });
+        DESCRIPTOR_INCREMENT
+    }
+
+    pub unsafe extern "system" fn copy_descriptors(
+        this: *mut d3d12::ID3D12Device,
+        num_dest_ranges: UINT,
+        dest_starts: *const d3d12::D3D12_CPU_DESCRIPTOR_HANDLE,
+        dest_sizes: *const UINT,
+        num_src_ranges: UINT,
+        src_starts: *const d3d12::D3D12_CPU_DESCRIPTOR_HANDLE,
+        src_sizes: *const UINT,
+        heap_type: d3d12::D3D12_DESCRIPTOR_HEAP_TYPE,
+    ) {
+        let ranges =
+            |num: UINT, starts: *const d3d12::D3D12_CPU_DESCRIPTOR_HANDLE, sizes: *const UINT| {
+                (0..num as usize)
+                    .map(|i| {
+                        let size = if sizes.is_null() { 1 } else { *sizes.add(i) };
+                        ((*starts.add(i)).ptr, size)
+                    })
+                    .collect()
+            };
+        recorder(this).record(Call::CopyDescriptors {
+            dest_ranges: ranges(num_dest_ranges, dest_starts, dest_sizes),
+            src_ranges: ranges(num_src_ranges, src_starts, src_sizes),
+            heap_type,
+        });
+    }
+
+    pub unsafe extern "system" fn copy_descriptors_simple(
+        this: *mut d3d12::ID3D12Device,
+        count: UINT,
+        dest: d3d12::D3D12_CPU_DESCRIPTOR_HANDLE,
+        src: d3d12::D3D12_CPU_DESCRIPTOR_HANDLE,
+        heap_type: d3d12::D3D12_DESCRIPTOR_HEAP_TYPE,
+    ) {
+        recorder(this).record(Call::CopyDescriptorsSimple {
+            count,
+            dest: dest.ptr,
+            src: src.ptr,
+            heap_type,
+        });
+    }
+
+    pub unsafe extern "system" fn create_root_signature(
+        this: *mut d3d12::ID3D12Device,
+        node_mask: UINT,
+        blob: *const c_void,
+        blob_len: SIZE_T,
+        riid: REFGUID,
+        out: *mut *mut c_void,
+    ) -> HRESULT {
+        let recorder = recorder(this);
+        recorder.record(Call::CreateRootSignature {
+            node_mask,
+            blob: bytes(blob, blob_len),
+        });
+        let hr = recorder.result("CreateRootSignature");
+        if FAILED(hr) {
+            return hr;
+        }
+        stub_object(riid, out)
+    }
+
+    pub unsafe extern "system" fn create_render_target_view(
+        this: *mut d3d12::ID3D12Device,
+        resource: *mut d3d12::ID3D12Resource,
+        desc: *const d3d12::D3D12_RENDER_TARGET_VIEW_DESC,
+        descriptor: d3d12::D3D12_CPU_DESCRIPTOR_HANDLE,
+    ) {
+        let desc = desc.as_ref();
+        recorder(this).record(Call::CreateRenderTargetView {
+            resource: resource as usize,
+            format: desc.map(|d| d.Format),
+            view_dimension: desc.map(|d| d.ViewDimension),
+            descriptor: descriptor.ptr,
+        });
+    }
+
+    pub unsafe extern "system" fn create_shader_resource_view(
+        this: *mut d3d12::ID3D12Device,
+        resource: *mut d3d12::ID3D12Resource,
+        desc: *const d3d12::D3D12_SHADER_RESOURCE_VIEW_DESC,
+        descriptor: d3d12::D3D12_CPU_DESCRIPTOR_HANDLE,
+    ) {
+        let desc = desc.as_ref();
+        recorder(this).record(Call::CreateShaderResourceView {
+            resource: resource as usize,
+            format: desc.map(|d| d.Format),
+            view_dimension: desc.map(|d| d.ViewDimension),
+            descriptor: descriptor.ptr,
+        });
+    }
+
+    pub unsafe extern "system" fn create_unordered_access_view(
+        this: *mut d3d12::ID3D12Device,
+        resource: *mut d3d12::ID3D12Resource,
+        counter: *mut d3d12::ID3D12Resource,
+        desc: *const d3d12::D3D12_UNORDERED_ACCESS_VIEW_DESC,
+        descriptor: d3d12::D3D12_CPU_DESCRIPTOR_HANDLE,
+    ) {
+        let desc = desc.as_ref();
+        recorder(this).record(Call::CreateUnorderedAccessView {
+            resource: resource as usize,
+            counter: counter as usize,
+            format: desc.map(|d| d.Format),
+            view_dimension: desc.map(|d| d.ViewDimension),
+            descriptor: descriptor.ptr,
+        });
+    }
+
+    pub unsafe extern "system" fn create_constant_buffer_view(
+        this: *mut d3d12::ID3D12Device,
+        desc: *const d3d12::D3D12_CONSTANT_BUFFER_VIEW_DESC,
+        descriptor: d3d12::D3D12_CPU_DESCRIPTOR_HANDLE,
+    ) {
+        let desc = &*desc;
+        recorder(this).record(Call::CreateConstantBufferView {
+            buffer_location: desc.BufferLocation,
+            size_in_bytes: desc.SizeInBytes,
+            descriptor: descriptor.ptr,
+        });
+    }
+
+    pub unsafe extern "system" fn create_depth_stencil_view(
+        this: *mut d3d12::ID3D12Device,
+        resource: *mut d3d12::ID3D12Resource,
+        desc: *const d3d12::D3D12_DEPTH_STENCIL_VIEW_DESC,
+        descriptor: d3d12::D3D12_CPU_DESCRIPTOR_HANDLE,
+    ) {
+        let desc = desc.as_ref();
+        recorder(this).record(Call::CreateDepthStencilView {
+            resource: resource as usize,
+            format: desc.map(|d| d.Format),
+            view_dimension: desc.map(|d| d.ViewDimension),
+            flags: desc.map(|d| d.Flags),
+            descriptor: descriptor.ptr,
+        });
+    }
+
+    pub unsafe extern "system" fn create_sampler(
+        this: *mut d3d12::ID3D12Device,
+        desc: *const d3d12::D3D12_SAMPLER_DESC,
+        descriptor: d3d12::D3D12_CPU_DESCRIPTOR_HANDLE,
+    ) {
+        let desc = &*desc;
+        recorder(this).record(Call::CreateSampler {
+            filter: desc.Filter,
+            address_mode: [desc.AddressU, desc.AddressV, desc.AddressW],
+            mip_lod_bias: desc.MipLODBias,
+            max_anisotropy: desc.MaxAnisotropy,
+            comparison_op: desc.ComparisonFunc,
+            border_color: desc.BorderColor,
+            min_lod: desc.MinLOD,
+            max_lod: desc.MaxLOD,
+            descriptor: descriptor.ptr,
+        });
+    }
+
+    pub unsafe extern "system" fn create_heap(
+        this: *mut d3d12::ID3D12Device,
+        desc: *const d3d12::D3D12_HEAP_DESC,
+        riid: REFGUID,
+        out: *mut *mut c_void,
+    ) -> HRESULT {
+        let recorder = recorder(this);
+        let desc = &*desc;
+        recorder.record(Call::CreateHeap {
+            size_in_bytes: desc.SizeInBytes,
+            heap_type: desc.Properties.Type,
+            alignment: desc.Alignment,
+            flags: HeapFlags::from_bits_truncate(desc.Flags),
+        });
+        let hr = recorder.result("CreateHeap");
+        if FAILED(hr) {
+            return hr;
+        }
+        stub_object(riid, out)
+    }
+
+    pub unsafe extern "system" fn create_committed_resource(
+        this: *mut d3d12::ID3D12Device,
+        heap_properties: *const d3d12::D3D12_HEAP_PROPERTIES,
+        heap_flags: d3d12::D3D12_HEAP_FLAGS,
+        desc: *const d3d12::D3D12_RESOURCE_DESC,
+        initial_state: d3d12::D3D12_RESOURCE_STATES,
+        clear_value: *const d3d12::D3D12_CLEAR_VALUE,
+        riid: REFGUID,
+        out: *mut *mut c_void,
+    ) -> HRESULT {
+        let recorder = recorder(this);
+        recorder.record(Call::CreateCommittedResource {
+            heap_type: (*heap_properties).Type,
+            heap_flags: HeapFlags::from_bits_truncate(heap_flags),
+            desc: resource_info(desc),
+            initial_state,
+            clear_format: clear_format(clear_value),
+        });
+        let hr = recorder.result("CreateCommittedResource");
+        if FAILED(hr) {
+            return hr;
+        }
+        stub_object(riid, out)
+    }
+
+    pub unsafe extern "system" fn create_placed_resource(
+        this: *mut d3d12::ID3D12Device,
+        heap: *mut d3d12::ID3D12Heap,
+        offset: u64,
+        desc: *const d3d12::D3D12_RESOURCE_DESC,
+        initial_state: d3d12::D3D12_RESOURCE_STATES,
+        clear_value: *const d3d12::D3D12_CLEAR_VALUE,
+        riid: REFGUID,
+        out: *mut *mut c_void,
+    ) -> HRESULT {
+        let recorder = recorder(this);
+        recorder.record(Call::CreatePlacedResource {
+            heap: heap as usize,
+            offset,
+            desc: resource_info(desc),
+            initial_state,
+            clear_format: clear_format(clear_value),
+        });
+        let hr = recorder.result("CreatePlacedResource");
+        if FAILED(hr) {
+            return hr;
+        }
+        stub_object(riid, out)
+    }
+
+    pub unsafe extern "system" fn create_reserved_resource(
+        this: *mut d3d12::ID3D12Device,
+        desc: *const d3d12::D3D12_RESOURCE_DESC,
+        initial_state: d3d12::D3D12_RESOURCE_STATES,
+        clear_value: *const d3d12::D3D12_CLEAR_VALUE,
+        riid: REFGUID,
+        out: *mut *mut c_void,
+    ) -> HRESULT {
+        let recorder = recorder(this);
+        recorder.record(Call::CreateReservedResource {
+            desc: resource_info(desc),
+            initial_state,
+            clear_format: clear_format(clear_value),
+        });
+        let hr = recorder.result("CreateReservedResource");
+        if FAILED(hr) {
+            return hr;
+        }
+        stub_object(riid, out)
+    }
+
+    pub unsafe extern "system" fn create_fence(
+        this: *mut d3d12::ID3D12Device,
+        initial_value: u64,
+        flags: d3d12::D3D12_FENCE_FLAGS,
+        riid: REFGUID,
+        out: *mut *mut c_void,
+    ) -> HRESULT {
+        let recorder = recorder(this);
+        recorder.record(Call::CreateFence {
+            initial_value,
+            flags,
+        });
+        let hr = recorder.result("CreateFence");
+        if FAILED(hr) {
+            return hr;
+        }
+        hand_out(recorder.create_fence(initial_value), riid, out)
+    }
+
+    pub unsafe extern "system" fn create_query_heap(
+        this: *mut d3d12::ID3D12Device,
+        desc: *const d3d12::D3D12_QUERY_HEAP_DESC,
+        riid: REFGUID,
+        out: *mut *mut c_void,
+    ) -> HRESULT {
+        let recorder = recorder(this);
+        let desc = &*desc;
+        recorder.record(Call::CreateQueryHeap {
+            heap_type: desc.Type,
+            count: desc.Count,
+            node_mask: desc.NodeMask,
+        });
+        let hr = recorder.result("CreateQueryHeap");
+        if FAILED(hr) {
+            return hr;
+        }
+        stub_object(riid, out)
+    }
+
+    pub unsafe extern "system" fn create_command_signature(
+        this: *mut d3d12::ID3D12Device,
+        desc: *const d3d12::D3D12_COMMAND_SIGNATURE_DESC,
+        root_signature: *mut d3d12::ID3D12RootSignature,
+        riid: REFGUID,
+        out: *mut *mut c_void,
+    ) -> HRESULT {
+        let recorder = recorder(this);
+        let desc = &*desc;
+        recorder.record(Call::CreateCommandSignature {
+            byte_stride: desc.ByteStride,
+            num_arguments: desc.NumArgumentDescs,
+            node_mask: desc.NodeMask,
+            root_signature: root_signature as usize,
+        });
+        let hr = recorder.result("CreateCommandSignature");
+        if FAILED(hr) {
+            return hr;
+        }
+        stub_object(riid, out)
+    }
+}
+
+mod queue {
+    use super::*;
+
+    unsafe fn recorder<'a>(this: *mut d3d12::ID3D12CommandQueue) -> &'a Recorder {
+        QueueObject::from_interface(this).data()
+    }
+
+    pub unsafe extern "system" fn execute_command_lists(
+        this: *mut d3d12::ID3D12CommandQueue,
+        num: UINT,
+        lists: *const *mut d3d12::ID3D12CommandList,
+    ) {
+        let lists = slice::from_raw_parts(lists, num as usize)
+            .iter()
+            .map(|&list| list as usize)
+            .collect();
+        recorder(this).record(Call::ExecuteCommandLists { lists });
+    }
+
+    pub unsafe extern "system" fn signal(
+        this: *mut d3d12::ID3D12CommandQueue,
+        fence: *mut d3d12::ID3D12Fence,
+        value: u64,
+    ) -> HRESULT {
+        let recorder = recorder(this);
+        recorder.record(Call::QueueSignal {
+            fence: fence as usize,
+            value,
+        });
+        let hr = recorder.result("Signal");
+        // Execution is immediate, so the fence is signaled right away.
+        let is_mock = recorder.state().fences.contains(&(fence as usize));
+        if !FAILED(hr) && is_mock {
+            FenceObject::from_interface(fence).data().set_value(value);
+        }
+        hr
+    }
+
+    pub unsafe extern "system" fn wait(
+        this: *mut d3d12::ID3D12CommandQueue,
+        fence: *mut d3d12::ID3D12Fence,
+        value: u64,
+    ) -> HRESULT {
+        let recorder = recorder(this);
+        recorder.record(Call::QueueWait {
+            fence: fence as usize,
+            value,
+        });
+        recorder.result("Wait")
+    }
+}
+
+mod list {
+    use super::*;
+
+    type This = *mut d3d12::ID3D12GraphicsCommandList;
+
+    unsafe fn record(this: This, call: Call) {
+        ListObject::from_interface(this).data().record(call);
+    }
+
+    pub unsafe extern "system" fn close(this: This) -> HRESULT {
+        record(this, Call::Close);
+        ListObject::from_interface(this).data().result("Close")
+    }
+
+    pub unsafe extern "system" fn reset(
+        this: This,
+        allocator: *mut d3d12::ID3D12CommandAllocator,
+        initial_state: *mut d3d12::ID3D12PipelineState,
+    ) -> HRESULT {
+        record(
+            this,
+            Call::Reset {
+                allocator: allocator as usize,
+                initial_state: initial_state as usize,
+            },
+        );
+        ListObject::from_interface(this).data().result("Reset")
+    }
+
+    pub unsafe extern "system" fn draw_instanced(
+        this: This,
+        vertex_count: UINT,
+        instance_count: UINT,
+        start_vertex: UINT,
+        start_instance: UINT,
+    ) {
+        record(
+            this,
+            Call::DrawInstanced {
+                vertex_count,
+                instance_count,
+                start_vertex,
+                start_instance,
+            },
+        );
+    }
+
+    pub unsafe extern "system" fn draw_indexed_instanced(
+        this: This,
+        index_count: UINT,
+        instance_count: UINT,
+        start_index: UINT,
+        base_vertex: INT,
+        start_instance: UINT,
+    ) {
+        record(
+            this,
+            Call::DrawIndexedInstanced {
+                index_count,
+                instance_count,
+                start_index,
+                base_vertex,
+                start_instance,
+            },
+        );
+    }
+
+    pub unsafe extern "system" fn dispatch(this: This, x: UINT, y: UINT, z: UINT) {
+        record(this, Call::Dispatch { count: [x, y, z] });
+    }
+
+    pub unsafe extern "system" fn set_blend_factor(this: This, factor: *const [FLOAT; 4]) {
+        record(this, Call::SetBlendFactor { factor: *factor });
+    }
+
+    pub unsafe extern "system" fn set_stencil_ref(this: This, reference: UINT) {
+        record(this, Call::SetStencilRef { reference });
+    }
+
+    pub unsafe extern "system" fn set_pipeline_state(
+        this: This,
+        pipeline_state: *mut d3d12::ID3D12PipelineState,
+    ) {
+        record(
+            this,
+            Call::SetPipelineState {
+                pipeline_state: pipeline_state as usize,
+            },
+        );
+    }
+
+    pub unsafe extern "system" fn resource_barrier(
+        this: This,
+        num: UINT,
+        barriers: *const d3d12::D3D12_RESOURCE_BARRIER,
+    ) {
+        let barriers = slice::from_raw_parts(barriers, num as usize)
+            .iter()
+            .map(|raw| barrier(raw))
+            .collect();
+        record(this, Call::ResourceBarrier { barriers });
+    }
+
+    pub unsafe extern "system" fn execute_bundle(this: This, bundle: This) {
+        record(
+            this,
+            Call::ExecuteBundle {
+                bundle: bundle as usize,
+            },
+        );
+    }
+
+    pub unsafe extern "system" fn set_descriptor_heaps(
+        this: This,
+        num: UINT,
+        heaps: *mut *mut d3d12::ID3D12DescriptorHeap,
+    ) {
+        let heaps = slice::from_raw_parts(heaps, num as usize)
+            .iter()
+            .map(|&heap| heap as usize)
+            .collect();
+        record(this, Call::SetDescriptorHeaps { heaps });
+    }
+
+    pub unsafe extern "system" fn set_compute_root_signature(
+        this: This,
+        root_signature: *mut d3d12::ID3D12RootSignature,
+    ) {
+        record(
+            this,
+            Call::SetComputeRootSignature {
+                root_signature: root_signature as usize,
+            },
+        );
+    }
+
+    pub unsafe extern "system" fn set_graphics_root_signature(
+        this: This,
+        root_signature: *mut d3d12::ID3D12RootSignature,
+    ) {
+        record(
+            this,
+            Call::SetGraphicsRootSignature {
+                root_signature: root_signature as usize,
+            },
+        );
+    }
+
+    pub unsafe extern "system" fn set_compute_root_descriptor_table(
+        this: This,
+        root_index: UINT,
+        base_descriptor: d3d12::D3D12_GPU_DESCRIPTOR_HANDLE,
+    ) {
+        record(
+            this,
+            Call::SetComputeRootDescriptorTable {
+                root_index,
+                base_descriptor: base_descriptor.ptr,
+            },
+        );
+    }
+
+    pub unsafe extern "system" fn set_graphics_root_descriptor_table(
+        this: This,
+        root_index: UINT,
+        base_descriptor: d3d12::D3D12_GPU_DESCRIPTOR_HANDLE,
+    ) {
+        record(
+            this,
+            Call::SetGraphicsRootDescriptorTable {
+                root_index,
+                base_descriptor: base_descriptor.ptr,
+            },
+        );
+    }
+
+    pub unsafe extern "system" fn set_compute_root_constant(
+        this: This,
+        root_index: UINT,
+        value: UINT,
+        dest_offset_words: UINT,
+    ) {
+        record(
+            this,
+            Call::SetComputeRoot32BitConstant {
+                root_index,
+                value,
+                dest_offset_words,
+            },
+        );
+    }
+
+    pub unsafe extern "system" fn set_graphics_root_constant(
+        this: This,
+        root_index: UINT,
+        value: UINT,
+        dest_offset_words: UINT,
+    ) {
+        record(
+            this,
+            Call::SetGraphicsRoot32BitConstant {
+                root_index,
+                value,
+                dest_offset_words,
+            },
+        );
+    }
+
+    pub unsafe extern "system" fn set_compute_root_cbv(
+        this: This,
+        root_index: UINT,
+        buffer_location: d3d12::D3D12_GPU_VIRTUAL_ADDRESS,
+    ) {
+        record(
+            this,
+            Call::SetComputeRootConstantBufferView {
+                root_index,
+                buffer_location,
+            },
+        );
+    }
+
+    pub unsafe extern "system" fn set_graphics_root_cbv(
+        this: This,
+        root_index: UINT,
+        buffer_location: d3d12::D3D12_GPU_VIRTUAL_ADDRESS,
+    ) {
+        record(
+            this,
+            Call::SetGraphicsRootConstantBufferView {
+                root_index,
+                buffer_location,
+            },
+        );
+    }
+
+    pub unsafe extern "system" fn set_compute_root_srv(
+        this: This,
+        root_index: UINT,
+        buffer_location: d3d12::D3D12_GPU_VIRTUAL_ADDRESS,
+    ) {
+        record(
+            this,
+            Call::SetComputeRootShaderResourceView {
+                root_index,
+                buffer_location,
+            },
+        );
+    }
+
+    pub unsafe extern "system" fn set_graphics_root_srv(
+        this: This,
+        root_index: UINT,
+        buffer_location: d3d12::D3D12_GPU_VIRTUAL_ADDRESS,
+    ) {
+        record(
+            this,
+            Call::SetGraphicsRootShaderResourceView {
+                root_index,
+                buffer_location,
+            },
+        );
+    }
+
+    pub unsafe extern "system" fn set_compute_root_uav(
+        this: This,
+        root_index: UINT,
+        buffer_location: d3d12::D3D12_GPU_VIRTUAL_ADDRESS,
+    ) {
+        record(
+            this,
+            Call::SetComputeRootUnorderedAccessView {
+                root_index,
+                buffer_location,
+            },
+        );
+    }
+
+    pub unsafe extern "system" fn set_graphics_root_uav(
+        this: This,
+        root_index: UINT,
+        buffer_location: d3d12::D3D12_GPU_VIRTUAL_ADDRESS,
+    ) {
+        record(
+            this,
+            Call::SetGraphicsRootUnorderedAccessView {
+                root_index,
+                buffer_location,
+            },
+        );
+    }
+
+    pub unsafe extern "system" fn set_index_buffer(
+        this: This,
+        view: *const d3d12::D3D12_INDEX_BUFFER_VIEW,
+    ) {
+        let view = &*view;
+        record(
+            this,
+            Call::SetIndexBuffer {
+                buffer_location: view.BufferLocation,
+                size_in_bytes: view.SizeInBytes,
+                format: view.Format,
+            },
+        );
+    }
+
+    pub unsafe extern "system" fn clear_depth_stencil_view(
+        this: This,
+        descriptor: d3d12::D3D12_CPU_DESCRIPTOR_HANDLE,
+        flags: d3d12::D3D12_CLEAR_FLAGS,
+        depth: FLOAT,
+        stencil: u8,
+        num_rects: UINT,
+        raw_rects: *const d3d12::D3D12_RECT,
+    ) {
+        record(
+            this,
+            Call::ClearDepthStencilView {
+                descriptor: descriptor.ptr,
+                flags,
+                depth,
+                stencil,
+                rects: rects(num_rects, raw_rects),
+            },
+        );
+    }
+
+    pub unsafe extern "system" fn clear_render_target_view(
+        this: This,
+        descriptor: d3d12::D3D12_CPU_DESCRIPTOR_HANDLE,
+        color: *const [FLOAT; 4],
+        num_rects: UINT,
+        raw_rects: *const d3d12::D3D12_RECT,
+    ) {
+        record(
+            this,
+            Call::ClearRenderTargetView {
+                descriptor: descriptor.ptr,
+                color: *color,
+                rects: rects(num_rects, raw_rects),
+            },
+        );
+    }
+
+    pub unsafe extern "system" fn discard_resource(
+        this: This,
+        resource: *mut d3d12::ID3D12Resource,
+        region: *const d3d12::D3D12_DISCARD_REGION,
+    ) {
+        let (raw_rects, first_subresource, num_subresources) = match region.as_ref() {
+            Some(region) => (
+                rects(region.NumRects, region.pRects),
+                region.FirstSubresource,
+                region.NumSubresources,
+            ),
+            None => (Vec::new(), 0, 0),
+        };
+        record(
+            this,
+            Call::DiscardResource {
+                resource: resource as usize,
+                rects: raw_rects,
+                first_subresource,
+                num_subresources,
+            },
+        );
+    }
+}
+
+mod fence {
+    use super::*;
+
+    type This = *mut d3d12::ID3D12Fence;
+
+    unsafe fn state<'a>(this: This) -> &'a FenceState {
+        FenceObject::from_interface(this).data()
+    }
+
+    pub unsafe extern "system" fn get_completed_value(this: This) -> u64 {
+        let state = state(this);
+        state.recorder.record(Call::GetCompletedValue);
+        state.value.load(Ordering::Acquire)
+    }
+
+    pub unsafe extern "system" fn set_event_on_completion(
+        this: This,
+        value: u64,
+        event: HANDLE,
+    ) -> HRESULT {
+        let state = state(this);
+        state.recorder.record(Call::SetEventOnCompletion { value });
+        let hr = state.recorder.result("SetEventOnCompletion");
+        if FAILED(hr) || event.is_null() {
+            return hr;
+        }
+        if value <= state.value.load(Ordering::Acquire) {
+            state.recorder.set_event(Event(event));
+        } else {
+            state
+                .pending
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push((value, event as usize));
+        }
+        hr
+    }
+
+    pub unsafe extern "system" fn signal(this: This, value: u64) -> HRESULT {
+        let state = state(this);
+        state.recorder.record(Call::FenceSignal { value });
+        let hr = state.recorder.result("Signal");
+        if !FAILED(hr) {
+            state.set_value(value);
+        }
+        hr
+    }
+}
+
+mod descriptor_heap {
+    use super::*;
+
+    type This = *mut d3d12::ID3D12DescriptorHeap;
+
+    pub unsafe extern "system" fn get_desc(
+        this: This,
+        ret: *mut d3d12::D3D12_DESCRIPTOR_HEAP_DESC,
+    ) -> *mut d3d12::D3D12_DESCRIPTOR_HEAP_DESC {
+        *ret = DescriptorHeapObject::from_interface(this).data().desc;
+        ret
+    }
+
+    pub unsafe extern "system" fn get_cpu_start(
+        this: This,
+        ret: *mut d3d12::D3D12_CPU_DESCRIPTOR_HANDLE,
+    ) -> *mut d3d12::D3D12_CPU_DESCRIPTOR_HANDLE {
+        let state = DescriptorHeapObject::from_interface(this).data();
+        (*ret).ptr = state.base;
+        ret
+    }
+
+    pub unsafe extern "system" fn get_gpu_start(
+        this: This,
+        ret: *mut d3d12::D3D12_GPU_DESCRIPTOR_HANDLE,
+    ) -> *mut d3d12::D3D12_GPU_DESCRIPTOR_HANDLE {
+        let state = DescriptorHeapObject::from_interface(this).data();
+        let shader_visible =
+            state.desc.Flags & d3d12::D3D12_DESCRIPTOR_HEAP_FLAG_SHADER_VISIBLE != 0;
+        (*ret).ptr = if shader_visible { state.base as u64 } else { 0 };
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DescriptorHeapType, ResourceBarrier};
+    use std::ptr;
+    use winapi::shared::winerror::{E_INVALIDARG, E_OUTOFMEMORY};
+
+    #[test]
+    fn create_descriptor_heap() {
+        let recorder = Recorder::new();
+        let device = recorder.create_device();
+
+        let heap = device
+            .try_create_descriptor_heap(
+                16,
+                DescriptorHeapType::CbvSrvUav,
+                DescriptorHeapFlags::SHADER_VISIBLE,
+                0,
+            )
+            .unwrap();
+        assert_eq!(
+            recorder.take_calls(),
+            [Call::CreateDescriptorHeap {
+                heap_type: d3d12::D3D12_DESCRIPTOR_HEAP_TYPE_CBV_SRV_UAV,
+                num_descriptors: 16,
+                flags: DescriptorHeapFlags::SHADER_VISIBLE,
+                node_mask: 0,
+            }]
+        );
+        assert_eq!(
+            heap.start_gpu_descriptor().ptr,
+            heap.start_cpu_descriptor().ptr as u64
+        );
+
+        recorder.push_result("CreateDescriptorHeap", E_OUTOFMEMORY);
+        let error = device
+            .try_create_descriptor_heap(4, DescriptorHeapType::Rtv, DescriptorHeapFlags::empty(), 0)
+            .unwrap_err();
+        assert_eq!(error.hresult(), E_OUTOFMEMORY);
+        assert_eq!(recorder.take_calls().len(), 1);
+
+        unsafe {
+            heap.destroy();
+            device.destroy();
+        }
+    }
+
+    #[test]
+    fn too_many_render_targets() {
+        let recorder = Recorder::new();
+        let device = recorder.create_device();
+        let formats = [crate::Format::R8G8B8A8Unorm; 9];
+        let desc = crate::GraphicsPipelineDesc {
+            rtv_formats: &formats,
+            ..Default::default()
+        };
+
+        let (pipeline, hr) = device.create_graphics_pipeline_state(&desc);
+        assert!(pipeline.is_null());
+        assert_eq!(hr, E_INVALIDARG);
+        let error = device
+            .try_create_graphics_pipeline_state(&desc)
+            .unwrap_err();
+        assert_eq!(error.hresult(), E_INVALIDARG);
+        assert!(recorder.calls().is_empty());
+
+        unsafe { device.destroy() };
+    }
+
+    #[test]
+    fn resource_barrier() {
+        let recorder = Recorder::new();
+        let list = recorder.create_graphics_command_list();
+        let resource = crate::Resource::null();
+
+        list.resource_barrier(&[
+            ResourceBarrier::transition(
+                resource,
+                d3d12::D3D12_RESOURCE_BARRIER_ALL_SUBRESOURCES,
+                d3d12::D3D12_RESOURCE_STATE_COPY_DEST,
+                d3d12::D3D12_RESOURCE_STATE_PIXEL_SHADER_RESOURCE,
+                d3d12::D3D12_RESOURCE_BARRIER_FLAG_NONE,
+            ),
+            ResourceBarrier::transition(
+                resource,
+                2,
+                d3d12::D3D12_RESOURCE_STATE_RENDER_TARGET,
+                d3d12::D3D12_RESOURCE_STATE_PRESENT,
+                d3d12::D3D12_RESOURCE_BARRIER_FLAG_BEGIN_ONLY,
+            ),
+        ]);
+        assert_eq!(
+            recorder.take_calls(),
+            [Call::ResourceBarrier {
+                barriers: vec![
+                    Barrier::Transition {
+                        resource: 0,
+                        subresource: d3d12::D3D12_RESOURCE_BARRIER_ALL_SUBRESOURCES,
+                        state_before: d3d12::D3D12_RESOURCE_STATE_COPY_DEST,
+                        state_after: d3d12::D3D12_RESOURCE_STATE_PIXEL_SHADER_RESOURCE,
+                        flags: d3d12::D3D12_RESOURCE_BARRIER_FLAG_NONE,
+                    },
+                    Barrier::Transition {
+                        resource: 0,
+                        subresource: 2,
+                        state_before: d3d12::D3D12_RESOURCE_STATE_RENDER_TARGET,
+                        state_after: d3d12::D3D12_RESOURCE_STATE_PRESENT,
+                        flags: d3d12::D3D12_RESOURCE_BARRIER_FLAG_BEGIN_ONLY,
+                    },
+                ],
+            }]
+        );
+
+        unsafe { list.destroy() };
+    }
+
+    #[test]
+    fn fence_events() {
+        let recorder = Recorder::new();
+        let signaled = Arc::new(Mutex::new(Vec::new()));
+        let log = signaled.clone();
+        recorder.set_event_handler(move |event| log.lock().unwrap().push(event.0 as usize));
+
+        let fence = recorder.create_fence(1);
+        let (first, second) = (Event(0x10 as HANDLE), Event(0x20 as HANDLE));
+        assert_eq!(fence.set_event_on_completion(first, 1), S_OK);
+        assert_eq!(*signaled.lock().unwrap(), [0x10]);
+        assert_eq!(fence.set_event_on_completion(second, 3), S_OK);
+        assert_eq!(
+            fence.set_event_on_completion(Event(ptr::null_mut()), 3),
+            S_OK
+        );
+
+        assert_eq!(fence.signal(2), S_OK);
+        assert_eq!(*signaled.lock().unwrap(), [0x10]);
+        assert_eq!(fence.signal(3), S_OK);
+        assert_eq!(*signaled.lock().unwrap(), [0x10, 0x20]);
+        assert_eq!(fence.get_value(), 3);
+
+        unsafe { fence.destroy() };
+    }
+}
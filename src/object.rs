@@ -0,0 +1,414 @@
+//! Debug names and private data of `ID3D12Object`s
+
+use crate::{com::WeakPtr, D3DResult, HRESULT};
+use std::{iter, mem, ptr};
+use winapi::{
+    ctypes::c_void,
+    shared::{
+        guiddef::GUID,
+        minwindef::UINT,
+        winerror::{E_INVALIDARG, FAILED},
+    },
+    um::{d3d12, d3dcommon, unknwnbase::IUnknown},
+    Interface,
+};
+
+/// Key under which typed private data is stored on an object.
+///
+/// ```
+/// # #[cfg(not(windows))]
+/// # extern crate winapi_headers as winapi;
+/// struct FrameIndex;
+///
+/// unsafe impl d3d12::PrivateDataKey for FrameIndex {
+///     const GUID: winapi::shared::guiddef::GUID = winapi::shared::guiddef::GUID {
+///         Data1: 0x6c3b_5f0e,
+///         Data2: 0x1d2a,
+///         Data3: 0x4b7e,
+///         Data4: [0x9a, 0x51, 0x0c, 0x3e, 0x77, 0x12, 0xb4, 0x08],
+///     };
+///     type Data = u64;
+/// }
+/// ```
+///
+/// # Safety
+///
+/// `GUID` must be unique to this key, all data stored under it has to be a `Data`.
+pub unsafe trait PrivateDataKey {
+    const GUID: GUID;
+    type Data: Copy + 'static;
+}
+
+/// Methods shared by all interfaces deriving from `ID3D12Object`.
+pub trait D3D12Object {
+    fn as_object(&self) -> &d3d12::ID3D12Object;
+
+    /// Name shown in debug layer messages and capture tools.
+    fn set_name(&self, name: &str) -> HRESULT {
+        let name = name.encode_utf16().chain(iter::once(0)).collect::<Vec<_>>();
+        unsafe { self.as_object().SetName(name.as_ptr()) }
+    }
+
+    /// Name previously assigned with `set_name`.
+    fn name(&self) -> D3DResult<String> {
+        let guid = d3dcommon::WKPDID_D3DDebugObjectNameW;
+        let mut size = 0;
+        let hr = unsafe {
+            self.as_object()
+                .GetPrivateData(&guid, &mut size, ptr::null_mut())
+        };
+        if FAILED(hr) {
+            return (String::new(), hr);
+        }
+
+        let mut name = vec![0u16; size as usize / mem::size_of::<u16>()];
+        let hr = unsafe {
+            self.as_object()
+                .GetPrivateData(&guid, &mut size, name.as_mut_ptr() as *mut c_void)
+        };
+        while name.last() == Some(&0) {
+            name.pop();
+        }
+
+        (String::from_utf16_lossy(&name), hr)
+    }
+
+    fn set_private_data<K: PrivateDataKey>(&self, data: &K::Data) -> HRESULT {
+        unsafe {
+            self.as_object().SetPrivateData(
+                &K::GUID,
+                mem::size_of::<K::Data>() as UINT,
+                data as *const K::Data as *const c_void,
+            )
+        }
+    }
+
+    /// Returns `None` if no data is stored under the key.
+    fn private_data<K: PrivateDataKey>(&self) -> D3DResult<Option<K::Data>> {
+        let mut data = mem::MaybeUninit::<K::Data>::uninit();
+        let mut size = mem::size_of::<K::Data>() as UINT;
+        let hr = unsafe {
+            self.as_object()
+                .GetPrivateData(&K::GUID, &mut size, data.as_mut_ptr() as *mut c_void)
+        };
+        if FAILED(hr) {
+            (None, hr)
+        } else if size as usize != mem::size_of::<K::Data>() {
+            (None, E_INVALIDARG)
+        } else {
+            (Some(unsafe { data.assume_init() }), hr)
+        }
+    }
+
+    fn remove_private_data<K: PrivateDataKey>(&self) -> HRESULT {
+        unsafe { self.as_object().SetPrivateData(&K::GUID, 0, ptr::null()) }
+    }
+
+    /// Store a reference to `interface`, released again when the data is replaced
+    /// or the object is destroyed.
+    fn set_private_data_interface<I: Interface>(
+        &self,
+        guid: &GUID,
+        interface: &WeakPtr<I>,
+    ) -> HRESULT {
+        unsafe {
+            self.as_object()
+                .SetPrivateDataInterface(guid, interface.as_mut_ptr() as *const IUnknown)
+        }
+    }
+
+    /// Query the interface stored with `set_private_data_interface`.
+    #[track_caller]
+    fn private_data_interface<I: Interface>(&self, guid: &GUID) -> D3DResult<WeakPtr<I>> {
+        let mut unknown = WeakPtr::<IUnknown>::null();
+        let mut size = mem::size_of::<*mut IUnknown>() as UINT;
+        let hr = unsafe {
+            self.as_object()
+                .GetPrivateData(guid, &mut size, unknown.mut_void() as *mut c_void)
+        };
+        if FAILED(hr) || unknown.is_null() {
+            return (WeakPtr::null(), hr);
+        }
+
+        let mut interface = WeakPtr::<I>::null();
+        let hr = unsafe {
+            let hr = unknown.QueryInterface(&I::uuidof(), interface.mut_void());
+            unknown.Release();
+            hr
+        };
+
+        crate::ledger::track_created(&interface);
+        (interface, hr)
+    }
+}
+
+macro_rules! impl_d3d12_object {
+    ($($interface:ty),* $(,)?) => {
+        $(
+            impl D3D12Object for WeakPtr<$interface> {
+                fn as_object(&self) -> &d3d12::ID3D12Object {
+                    debug_assert!(!self.is_null());
+                    unsafe { &*(self.as_mut_ptr() as *const d3d12::ID3D12Object) }
+                }
+            }
+        )*
+    };
+}
+
+impl_d3d12_object!(
+    d3d12::ID3D12Object,
+    d3d12::ID3D12Device,
+    d3d12::ID3D12Device2,
+    d3d12::ID3D12CommandQueue,
+    d3d12::ID3D12CommandAllocator,
+    d3d12::ID3D12CommandList,
+    d3d12::ID3D12GraphicsCommandList,
+    d3d12::ID3D12CommandSignature,
+    d3d12::ID3D12DescriptorHeap,
+    d3d12::ID3D12QueryHeap,
+    d3d12::ID3D12RootSignature,
+    d3d12::ID3D12PipelineState,
+    d3d12::ID3D12Heap,
+    d3d12::ID3D12Resource,
+    d3d12::ID3D12Fence,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::com_object::{unimplemented_vtable, ComObject};
+    use std::{cell::RefCell, slice};
+    use winapi::shared::{
+        guiddef::{IsEqualGUID, REFGUID},
+        winerror::{DXGI_ERROR_MORE_DATA, DXGI_ERROR_NOT_FOUND, S_OK},
+    };
+
+    // Key, bytes and the interface holding a reference if one was stored.
+    type Entry = (GUID, Vec<u8>, WeakPtr<IUnknown>);
+
+    // Private data of an object.
+    #[derive(Default)]
+    struct Store(RefCell<Vec<Entry>>);
+
+    impl Store {
+        fn remove(&self, guid: &GUID) {
+            let mut entries = self.0.borrow_mut();
+            if let Some(i) = entries.iter().position(|e| IsEqualGUID(&e.0, guid)) {
+                let (_, _, interface) = entries.remove(i);
+                if !interface.is_null() {
+                    unsafe { interface.Release() };
+                }
+            }
+        }
+    }
+
+    impl Drop for Store {
+        fn drop(&mut self) {
+            for (_, _, interface) in self.0.get_mut().drain(..) {
+                if !interface.is_null() {
+                    unsafe { interface.Release() };
+                }
+            }
+        }
+    }
+
+    type Object = ComObject<d3d12::ID3D12ObjectVtbl, Store>;
+
+    fn store<'a>(this: *mut d3d12::ID3D12Object) -> &'a Store {
+        unsafe { Object::from_interface(this) }.data()
+    }
+
+    unsafe extern "system" fn get_private_data(
+        this: *mut d3d12::ID3D12Object,
+        guid: REFGUID,
+        size: *mut UINT,
+        data: *mut c_void,
+    ) -> HRESULT {
+        let entries = store(this).0.borrow();
+        let (_, bytes, interface) = match entries.iter().find(|e| IsEqualGUID(&e.0, &*guid)) {
+            Some(entry) => entry,
+            None => return DXGI_ERROR_NOT_FOUND,
+        };
+        if data.is_null() {
+            *size = bytes.len() as UINT;
+            return S_OK;
+        }
+        if (*size as usize) < bytes.len() {
+            *size = bytes.len() as UINT;
+            return DXGI_ERROR_MORE_DATA;
+        }
+        ptr::copy_nonoverlapping(bytes.as_ptr(), data as *mut u8, bytes.len());
+        *size = bytes.len() as UINT;
+        if !interface.is_null() {
+            interface.AddRef();
+        }
+        S_OK
+    }
+
+    unsafe extern "system" fn set_private_data(
+        this: *mut d3d12::ID3D12Object,
+        guid: REFGUID,
+        size: UINT,
+        data: *const c_void,
+    ) -> HRESULT {
+        let store = store(this);
+        store.remove(&*guid);
+        if !data.is_null() {
+            let bytes = slice::from_raw_parts(data as *const u8, size as usize).to_vec();
+            store.0.borrow_mut().push((*guid, bytes, WeakPtr::null()));
+        }
+        S_OK
+    }
+
+    unsafe extern "system" fn set_private_data_interface(
+        this: *mut d3d12::ID3D12Object,
+        guid: REFGUID,
+        data: *const IUnknown,
+    ) -> HRESULT {
+        let store = store(this);
+        store.remove(&*guid);
+        if !data.is_null() {
+            let interface = WeakPtr::from_raw(data as *mut IUnknown);
+            interface.AddRef();
+            let bytes = (data as usize).to_ne_bytes().to_vec();
+            store.0.borrow_mut().push((*guid, bytes, interface));
+        }
+        S_OK
+    }
+
+    unsafe extern "system" fn set_name(
+        this: *mut d3d12::ID3D12Object,
+        name: *const u16,
+    ) -> HRESULT {
+        let len = (0..).take_while(|&i| *name.add(i) != 0).count() + 1;
+        set_private_data(
+            this,
+            &d3dcommon::WKPDID_D3DDebugObjectNameW,
+            (len * mem::size_of::<u16>()) as UINT,
+            name as *const c_void,
+        )
+    }
+
+    fn object() -> WeakPtr<d3d12::ID3D12Object> {
+        unsafe {
+            let mut vtbl: d3d12::ID3D12ObjectVtbl = unimplemented_vtable();
+            vtbl.GetPrivateData = get_private_data;
+            vtbl.SetPrivateData = set_private_data;
+            vtbl.SetPrivateDataInterface = set_private_data_interface;
+            vtbl.SetName = set_name;
+            Object::create::<d3d12::ID3D12Object>(vtbl, &[], Store::default())
+        }
+    }
+
+    fn ref_count(object: WeakPtr<d3d12::ID3D12Object>) -> u32 {
+        unsafe { Object::from_interface(object.as_mut_ptr()) }.ref_count()
+    }
+
+    const fn guid(data1: u32) -> GUID {
+        GUID {
+            Data1: data1,
+            Data2: 0x1d2a,
+            Data3: 0x4b7e,
+            Data4: [0x9a, 0x51, 0x0c, 0x3e, 0x77, 0x12, 0xb4, 0x08],
+        }
+    }
+
+    struct FrameIndex;
+
+    unsafe impl PrivateDataKey for FrameIndex {
+        const GUID: GUID = guid(1);
+        type Data = u64;
+    }
+
+    // Same key with a larger type.
+    struct Wide;
+
+    unsafe impl PrivateDataKey for Wide {
+        const GUID: GUID = guid(1);
+        type Data = [u64; 2];
+    }
+
+    // Same key with a smaller type.
+    struct Narrow;
+
+    unsafe impl PrivateDataKey for Narrow {
+        const GUID: GUID = guid(1);
+        type Data = u32;
+    }
+
+    #[test]
+    fn names() {
+        let object = object();
+        assert_eq!(object.name(), (String::new(), DXGI_ERROR_NOT_FOUND));
+
+        assert_eq!(object.set_name("Shadow map"), S_OK);
+        assert_eq!(object.name(), ("Shadow map".to_string(), S_OK));
+        assert_eq!(object.set_name("Ünïcödé 名前"), S_OK);
+        assert_eq!(object.name(), ("Ünïcödé 名前".to_string(), S_OK));
+        assert_eq!(object.set_name(""), S_OK);
+        assert_eq!(object.name(), (String::new(), S_OK));
+        unsafe { object.destroy() };
+    }
+
+    #[test]
+    fn private_data() {
+        let object = object();
+        assert_eq!(
+            object.private_data::<FrameIndex>(),
+            (None, DXGI_ERROR_NOT_FOUND)
+        );
+
+        assert_eq!(object.set_private_data::<FrameIndex>(&7), S_OK);
+        assert_eq!(object.private_data::<FrameIndex>(), (Some(7), S_OK));
+        assert_eq!(object.set_private_data::<FrameIndex>(&u64::MAX), S_OK);
+        assert_eq!(object.private_data::<FrameIndex>(), (Some(u64::MAX), S_OK));
+
+        // Data stored with another size isn't returned.
+        assert_eq!(object.private_data::<Wide>(), (None, E_INVALIDARG));
+        assert_eq!(
+            object.private_data::<Narrow>(),
+            (None, DXGI_ERROR_MORE_DATA)
+        );
+
+        assert_eq!(object.remove_private_data::<FrameIndex>(), S_OK);
+        assert_eq!(
+            object.private_data::<FrameIndex>(),
+            (None, DXGI_ERROR_NOT_FOUND)
+        );
+        unsafe { object.destroy() };
+    }
+
+    #[test]
+    fn private_data_interface() {
+        let stored = object();
+        let object = object();
+        let key = guid(2);
+        assert_eq!(object.set_private_data_interface(&key, &stored), S_OK);
+        assert_eq!(ref_count(stored), 2);
+
+        let (interface, hr) = object.private_data_interface::<d3d12::ID3D12Object>(&key);
+        assert_eq!(hr, S_OK);
+        assert_eq!(interface, stored);
+        assert_eq!(ref_count(stored), 3);
+        unsafe { interface.destroy() };
+
+        let (missing, hr) = object.private_data_interface::<d3d12::ID3D12Object>(&guid(3));
+        assert!(missing.is_null());
+        assert_eq!(hr, DXGI_ERROR_NOT_FOUND);
+
+        // Replacing the data releases the interface.
+        assert_eq!(object.set_private_data::<FrameIndex>(&0), S_OK);
+        assert_eq!(
+            object.set_private_data_interface(&FrameIndex::GUID, &stored),
+            S_OK
+        );
+        assert_eq!(ref_count(stored), 3);
+        assert_eq!(object.remove_private_data::<FrameIndex>(), S_OK);
+        assert_eq!(ref_count(stored), 2);
+
+        // And so does destroying the object.
+        unsafe { object.destroy() };
+        assert_eq!(ref_count(stored), 1);
+        unsafe { stored.destroy() };
+    }
+}
@@ -0,0 +1,593 @@
+//! Pipeline state streams
+
+use crate::{
+    pso::{
+        BlendDesc, CachedPSO, DepthStencilDesc, IndexBufferStripCutValue, InputElementDesc,
+        PipelineStateFlags, PipelineStateSubobject, PrimitiveTopologyType, RasterizerDesc, Shader,
+        StreamOutputDesc, Subobject,
+    },
+    Format, NodeMask, RootSignature, SampleDesc,
+};
+use std::{error, fmt, marker::PhantomData, mem, ptr, slice};
+use winapi::{
+    shared::{dxgiformat, dxgitype, minwindef::UINT},
+    um::d3d12,
+};
+
+// Not yet part of `winapi`.
+#[allow(non_camel_case_types, non_snake_case)]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct D3D12_VIEW_INSTANCING_DESC {
+    ViewInstanceCount: UINT,
+    pViewInstanceLocations: *const ViewInstanceLocation,
+    Flags: u32,
+}
+
+/// Payload of a subobject in a `PipelineStateStream`.
+///
+/// # Safety
+///
+/// `Raw` must be the structure D3D12 expects for subobjects of type `TYPE`.
+pub unsafe trait StreamSubobject<'a> {
+    const TYPE: Subobject;
+    type Raw: Copy;
+
+    /// `None` if the subobject can't be described to D3D12.
+    fn to_raw(self) -> Option<Self::Raw>;
+}
+
+macro_rules! shader_subobjects {
+    ($($name:ident => $ty:ident),* $(,)?) => {
+        $(
+            #[derive(Clone, Copy)]
+            pub struct $name<'a>(pub Shader<'a>);
+
+            unsafe impl<'a> StreamSubobject<'a> for $name<'a> {
+                const TYPE: Subobject = Subobject::$ty;
+                type Raw = d3d12::D3D12_SHADER_BYTECODE;
+
+                fn to_raw(self) -> Option<Self::Raw> {
+                    Some(*self.0)
+                }
+            }
+        )*
+    };
+}
+
+shader_subobjects! {
+    VertexShader => VS,
+    PixelShader => PS,
+    DomainShader => DS,
+    HullShader => HS,
+    GeometryShader => GS,
+    ComputeShader => CS,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SampleMask(pub u32);
+
+#[derive(Clone, Copy, Debug)]
+pub struct InputLayout<'a>(pub &'a [InputElementDesc<'a>]);
+
+/// Up to 8 render target formats.
+#[derive(Clone, Copy, Debug)]
+pub struct RenderTargetFormats<'a>(pub &'a [Format]);
+
+#[derive(Clone, Copy, Debug)]
+pub struct DepthStencilFormat(pub Format);
+
+#[derive(Clone, Copy, Debug)]
+pub struct PipelineNodeMask(pub NodeMask);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DepthStencilDesc1 {
+    pub depth_stencil: DepthStencilDesc,
+    pub depth_bounds_test_enable: bool,
+}
+
+bitflags! {
+    pub struct ViewInstancingFlags: u32 {
+        const ENABLE_VIEW_INSTANCE_MASKING = 0x1;
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ViewInstanceLocation {
+    pub viewport_array_index: u32,
+    pub render_target_array_index: u32,
+}
+
+/// View instance locations and flags, laid out as `D3D12_VIEW_INSTANCING_DESC`.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct ViewInstancingDesc<'a>(
+    D3D12_VIEW_INSTANCING_DESC,
+    PhantomData<&'a [ViewInstanceLocation]>,
+);
+
+impl<'a> ViewInstancingDesc<'a> {
+    pub fn new(locations: &'a [ViewInstanceLocation], flags: ViewInstancingFlags) -> Self {
+        ViewInstancingDesc(
+            D3D12_VIEW_INSTANCING_DESC {
+                ViewInstanceCount: locations.len() as _,
+                pViewInstanceLocations: locations.as_ptr(),
+                Flags: flags.bits(),
+            },
+            PhantomData,
+        )
+    }
+
+    pub fn locations(&self) -> &'a [ViewInstanceLocation] {
+        if self.0.ViewInstanceCount == 0 {
+            return &[];
+        }
+        unsafe {
+            slice::from_raw_parts(
+                self.0.pViewInstanceLocations,
+                self.0.ViewInstanceCount as usize,
+            )
+        }
+    }
+
+    pub fn flags(&self) -> ViewInstancingFlags {
+        ViewInstancingFlags::from_bits_truncate(self.0.Flags)
+    }
+}
+
+impl fmt::Debug for ViewInstancingDesc<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ViewInstancingDesc")
+            .field("locations", &self.locations())
+            .field("flags", &self.flags())
+            .finish()
+    }
+}
+
+unsafe impl StreamSubobject<'_> for RootSignature {
+    const TYPE: Subobject = Subobject::RootSignature;
+    type Raw = *mut d3d12::ID3D12RootSignature;
+
+    fn to_raw(self) -> Option<Self::Raw> {
+        Some(self.as_mut_ptr())
+    }
+}
+
+unsafe impl<'a> StreamSubobject<'a> for StreamOutputDesc<'a> {
+    const TYPE: Subobject = Subobject::StreamOutput;
+    type Raw = d3d12::D3D12_STREAM_OUTPUT_DESC;
+
+    fn to_raw(self) -> Option<Self::Raw> {
+        Some(StreamOutputDesc::to_raw(self))
+    }
+}
+
+unsafe impl StreamSubobject<'_> for BlendDesc {
+    const TYPE: Subobject = Subobject::Blend;
+    type Raw = d3d12::D3D12_BLEND_DESC;
+
+    fn to_raw(self) -> Option<Self::Raw> {
+        Some(BlendDesc::to_raw(self))
+    }
+}
+
+unsafe impl StreamSubobject<'_> for SampleMask {
+    const TYPE: Subobject = Subobject::SampleMask;
+    type Raw = UINT;
+
+    fn to_raw(self) -> Option<Self::Raw> {
+        Some(self.0)
+    }
+}
+
+unsafe impl StreamSubobject<'_> for RasterizerDesc {
+    const TYPE: Subobject = Subobject::Rasterizer;
+    type Raw = d3d12::D3D12_RASTERIZER_DESC;
+
+    fn to_raw(self) -> Option<Self::Raw> {
+        Some(RasterizerDesc::to_raw(self))
+    }
+}
+
+unsafe impl StreamSubobject<'_> for DepthStencilDesc {
+    const TYPE: Subobject = Subobject::DepthStencil;
+    type Raw = d3d12::D3D12_DEPTH_STENCIL_DESC;
+
+    fn to_raw(self) -> Option<Self::Raw> {
+        Some(DepthStencilDesc::to_raw(self))
+    }
+}
+
+unsafe impl<'a> StreamSubobject<'a> for InputLayout<'a> {
+    const TYPE: Subobject = Subobject::InputLayout;
+    type Raw = d3d12::D3D12_INPUT_LAYOUT_DESC;
+
+    fn to_raw(self) -> Option<Self::Raw> {
+        Some(d3d12::D3D12_INPUT_LAYOUT_DESC {
+            pInputElementDescs: self.0.as_ptr() as *const _,
+            NumElements: self.0.len() as _,
+        })
+    }
+}
+
+unsafe impl StreamSubobject<'_> for IndexBufferStripCutValue {
+    const TYPE: Subobject = Subobject::IBStripCut;
+    type Raw = d3d12::D3D12_INDEX_BUFFER_STRIP_CUT_VALUE;
+
+    fn to_raw(self) -> Option<Self::Raw> {
+        Some(self as _)
+    }
+}
+
+unsafe impl StreamSubobject<'_> for PrimitiveTopologyType {
+    const TYPE: Subobject = Subobject::PrimitiveTopology;
+    type Raw = d3d12::D3D12_PRIMITIVE_TOPOLOGY_TYPE;
+
+    fn to_raw(self) -> Option<Self::Raw> {
+        Some(self as _)
+    }
+}
+
+unsafe impl<'a> StreamSubobject<'a> for RenderTargetFormats<'a> {
+    const TYPE: Subobject = Subobject::RTFormats;
+    type Raw = d3d12::D3D12_RT_FORMAT_ARRAY;
+
+    fn to_raw(self) -> Option<Self::Raw> {
+        if self.0.len() > 8 {
+            return None;
+        }
+        let mut formats = [dxgiformat::DXGI_FORMAT_UNKNOWN; 8];
+        for (raw, &format) in formats.iter_mut().zip(self.0) {
+            *raw = format as _;
+        }
+
+        Some(d3d12::D3D12_RT_FORMAT_ARRAY {
+            RTFormats: formats,
+            NumRenderTargets: self.0.len() as _,
+        })
+    }
+}
+
+unsafe impl StreamSubobject<'_> for DepthStencilFormat {
+    const TYPE: Subobject = Subobject::DSFormat;
+    type Raw = dxgiformat::DXGI_FORMAT;
+
+    fn to_raw(self) -> Option<Self::Raw> {
+        Some(self.0 as _)
+    }
+}
+
+unsafe impl StreamSubobject<'_> for SampleDesc {
+    const TYPE: Subobject = Subobject::SampleDesc;
+    type Raw = dxgitype::DXGI_SAMPLE_DESC;
+
+    fn to_raw(self) -> Option<Self::Raw> {
+        Some(dxgitype::DXGI_SAMPLE_DESC {
+            Count: self.count,
+            Quality: self.quality,
+        })
+    }
+}
+
+unsafe impl StreamSubobject<'_> for PipelineNodeMask {
+    const TYPE: Subobject = Subobject::NodeMask;
+    type Raw = UINT;
+
+    fn to_raw(self) -> Option<Self::Raw> {
+        Some(self.0)
+    }
+}
+
+unsafe impl<'a> StreamSubobject<'a> for CachedPSO<'a> {
+    const TYPE: Subobject = Subobject::CachedPSO;
+    type Raw = d3d12::D3D12_CACHED_PIPELINE_STATE;
+
+    fn to_raw(self) -> Option<Self::Raw> {
+        Some(*self)
+    }
+}
+
+unsafe impl StreamSubobject<'_> for PipelineStateFlags {
+    const TYPE: Subobject = Subobject::Flags;
+    type Raw = d3d12::D3D12_PIPELINE_STATE_FLAGS;
+
+    fn to_raw(self) -> Option<Self::Raw> {
+        Some(self.bits())
+    }
+}
+
+unsafe impl StreamSubobject<'_> for DepthStencilDesc1 {
+    const TYPE: Subobject = Subobject::DepthStencil1;
+    type Raw = d3d12::D3D12_DEPTH_STENCIL_DESC1;
+
+    fn to_raw(self) -> Option<Self::Raw> {
+        let desc = self.depth_stencil.to_raw();
+        Some(d3d12::D3D12_DEPTH_STENCIL_DESC1 {
+            DepthEnable: desc.DepthEnable,
+            DepthWriteMask: desc.DepthWriteMask,
+            DepthFunc: desc.DepthFunc,
+            StencilEnable: desc.StencilEnable,
+            StencilReadMask: desc.StencilReadMask,
+            StencilWriteMask: desc.StencilWriteMask,
+            FrontFace: desc.FrontFace,
+            BackFace: desc.BackFace,
+            DepthBoundsTestEnable: self.depth_bounds_test_enable as _,
+        })
+    }
+}
+
+unsafe impl<'a> StreamSubobject<'a> for ViewInstancingDesc<'a> {
+    const TYPE: Subobject = Subobject::ViewInstancing;
+    type Raw = Self;
+
+    fn to_raw(self) -> Option<Self::Raw> {
+        Some(self)
+    }
+}
+
+/// Subobject which couldn't be added to a `PipelineStateStream`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StreamError {
+    /// The subobject type was already part of the stream.
+    DuplicateSubobject(Subobject),
+    /// The subobject can't be described to D3D12, e.g. more than 8 render target formats.
+    InvalidSubobject(Subobject),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StreamError::DuplicateSubobject(ty) => {
+                write!(f, "duplicate {:?} subobject in pipeline state stream", ty)
+            }
+            StreamError::InvalidSubobject(ty) => {
+                write!(f, "invalid {:?} subobject in pipeline state stream", ty)
+            }
+        }
+    }
+}
+
+impl error::Error for StreamError {}
+
+/// Offset and size in bytes of a subobject with payload `T` appended to a stream of `len` bytes.
+///
+/// Every subobject starts pointer aligned, with its payload following the type tag.
+fn subobject_layout<T>(len: usize) -> (usize, usize) {
+    let align = mem::align_of::<PipelineStateSubobject<T>>();
+    let offset = (len + align - 1) & !(align - 1);
+    (offset, mem::size_of::<PipelineStateSubobject<T>>())
+}
+
+/// Aligned byte stream of pipeline state subobjects, passed to
+/// `ID3D12Device2::CreatePipelineState`.
+///
+/// Each subobject type may be added once, payloads have to outlive `'a`.
+#[derive(Default)]
+pub struct PipelineStateStream<'a> {
+    // Pointer sized words keep every subobject aligned.
+    data: Vec<mem::MaybeUninit<usize>>,
+    len: usize,
+    present: u32,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> PipelineStateStream<'a> {
+    pub fn new() -> Self {
+        PipelineStateStream::default()
+    }
+
+    pub fn push<S: StreamSubobject<'a>>(&mut self, subobject: S) -> Result<&mut Self, StreamError> {
+        let bit = 1u32 << (S::TYPE as u32);
+        if self.present & bit != 0 {
+            return Err(StreamError::DuplicateSubobject(S::TYPE));
+        }
+        let raw = subobject
+            .to_raw()
+            .ok_or(StreamError::InvalidSubobject(S::TYPE))?;
+
+        let (offset, size) = subobject_layout::<S::Raw>(self.len);
+        // Subobjects are at least pointer aligned, so their size is a multiple of a word.
+        self.data.resize(
+            (offset + size) / mem::size_of::<usize>(),
+            mem::MaybeUninit::zeroed(),
+        );
+        unsafe {
+            let dst = (self.data.as_mut_ptr() as *mut u8).add(offset);
+            ptr::write(
+                dst as *mut PipelineStateSubobject<S::Raw>,
+                PipelineStateSubobject::new(S::TYPE, raw),
+            );
+        }
+
+        self.len = offset + size;
+        self.present |= bit;
+        Ok(self)
+    }
+
+    /// Builder style variant of `push`.
+    pub fn with<S: StreamSubobject<'a>>(mut self, subobject: S) -> Result<Self, StreamError> {
+        self.push(subobject)?;
+        Ok(self)
+    }
+
+    pub fn contains(&self, subobject: Subobject) -> bool {
+        self.present & (1 << subobject as u32) != 0
+    }
+
+    /// Size of the stream in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Raw description pointing into `self`.
+    pub fn desc(&self) -> d3d12::D3D12_PIPELINE_STATE_STREAM_DESC {
+        d3d12::D3D12_PIPELINE_STATE_STREAM_DESC {
+            SizeInBytes: self.len,
+            pPipelineStateSubobjectStream: self.data.as_ptr() as *mut _,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PipelineStateFlags;
+
+    fn read<T: Copy>(stream: &PipelineStateStream, offset: usize) -> T {
+        assert!(offset + mem::size_of::<T>() <= stream.len());
+        unsafe { ptr::read_unaligned((stream.data.as_ptr() as *const u8).add(offset) as *const T) }
+    }
+
+    // Pushes `subobject` and checks that it starts pointer aligned right after the previous
+    // one, taking up `size` bytes, the size of the matching `d3dx12.h` stream subobject.
+    fn push<'a, S: StreamSubobject<'a>>(
+        stream: &mut PipelineStateStream<'a>,
+        subobject: S,
+        size: usize,
+    ) -> usize {
+        let offset = stream.len();
+        stream.push(subobject).unwrap();
+        assert_eq!(offset % mem::align_of::<usize>(), 0, "{:?}", S::TYPE);
+        assert_eq!(stream.len(), offset + size, "{:?}", S::TYPE);
+        assert_eq!(read::<u32>(stream, offset), S::TYPE as u32);
+        assert!(stream.contains(S::TYPE));
+        offset
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn subobject_offsets() {
+        let code = [0u8; 12];
+        let formats = [Format::R8G8B8A8Unorm, Format::R16G16Float];
+        let locations = [ViewInstanceLocation {
+            viewport_array_index: 1,
+            render_target_array_index: 2,
+        }];
+
+        let mut stream = PipelineStateStream::new();
+        assert!(stream.is_empty());
+        push(&mut stream, RootSignature::null(), 16);
+        let vs = push(&mut stream, VertexShader(Shader::from_raw(&code)), 24);
+        push(&mut stream, PixelShader(Shader::null()), 24);
+        push(&mut stream, DomainShader(Shader::null()), 24);
+        push(&mut stream, HullShader(Shader::null()), 24);
+        push(&mut stream, GeometryShader(Shader::null()), 24);
+        push(&mut stream, ComputeShader(Shader::null()), 24);
+        push(&mut stream, StreamOutputDesc::default(), 40);
+        push(&mut stream, BlendDesc::default(), 336);
+        let sample_mask = push(&mut stream, SampleMask(0xf), 8);
+        push(&mut stream, RasterizerDesc::default(), 48);
+        push(&mut stream, DepthStencilDesc::default(), 56);
+        push(&mut stream, InputLayout(&[]), 24);
+        push(&mut stream, IndexBufferStripCutValue::Disabled, 8);
+        push(&mut stream, PrimitiveTopologyType::Triangle, 8);
+        let rt_formats = push(&mut stream, RenderTargetFormats(&formats), 40);
+        push(&mut stream, DepthStencilFormat(Format::D32Float), 8);
+        push(
+            &mut stream,
+            SampleDesc {
+                count: 4,
+                quality: 0,
+            },
+            16,
+        );
+        push(&mut stream, PipelineNodeMask(0), 8);
+        push(&mut stream, CachedPSO::null(), 24);
+        push(&mut stream, PipelineStateFlags::empty(), 8);
+        push(&mut stream, DepthStencilDesc1::default(), 64);
+        let view_instancing = push(
+            &mut stream,
+            ViewInstancingDesc::new(&locations, ViewInstancingFlags::empty()),
+            32,
+        );
+        assert_eq!(stream.len(), 888);
+        assert_eq!(stream.desc().SizeInBytes, 888);
+        assert_eq!(
+            stream.desc().pPipelineStateSubobjectStream as usize % mem::align_of::<usize>(),
+            0
+        );
+
+        // Payloads with pointers follow the padded type tag, others follow it directly.
+        assert_eq!(read::<usize>(&stream, vs + 8), code.as_ptr() as usize);
+        assert_eq!(read::<usize>(&stream, vs + 16), code.len());
+        assert_eq!(read::<u32>(&stream, sample_mask + 4), 0xf);
+        assert_eq!(
+            read::<[u32; 3]>(&stream, rt_formats + 4),
+            [
+                dxgiformat::DXGI_FORMAT_R8G8B8A8_UNORM,
+                dxgiformat::DXGI_FORMAT_R16G16_FLOAT,
+                dxgiformat::DXGI_FORMAT_UNKNOWN,
+            ]
+        );
+        assert_eq!(read::<u32>(&stream, rt_formats + 4 + 32), 2);
+        assert_eq!(read::<u32>(&stream, view_instancing + 8), 1);
+        assert_eq!(
+            read::<usize>(&stream, view_instancing + 16),
+            locations.as_ptr() as usize
+        );
+    }
+
+    #[test]
+    fn subobject_layout_alignment() {
+        let word = mem::size_of::<usize>();
+        assert_eq!(subobject_layout::<u32>(0), (0, word));
+        assert_eq!(subobject_layout::<u32>(4), (word, word));
+        assert_eq!(subobject_layout::<u32>(word), (word, word));
+        assert_eq!(subobject_layout::<*const u8>(12), (2 * word, 2 * word));
+    }
+
+    #[test]
+    fn duplicate_subobjects() {
+        let mut stream = PipelineStateStream::new();
+        stream.push(SampleMask(1)).unwrap();
+        stream.push(PixelShader(Shader::null())).unwrap();
+        let len = stream.len();
+
+        assert_eq!(
+            stream.push(SampleMask(2)).err(),
+            Some(StreamError::DuplicateSubobject(Subobject::SampleMask))
+        );
+        assert_eq!(
+            stream.push(PixelShader(Shader::null())).err(),
+            Some(StreamError::DuplicateSubobject(Subobject::PS))
+        );
+        assert_eq!(stream.len(), len);
+        assert_eq!(read::<u32>(&stream, 4), 1);
+
+        // Different shader stages are different subobjects.
+        assert!(!stream.contains(Subobject::VS));
+        let stream = stream.with(VertexShader(Shader::null())).unwrap();
+        assert!(stream.contains(Subobject::VS));
+        assert_eq!(
+            stream.with(SampleMask(3)).err().map(|err| err.to_string()),
+            Some("duplicate SampleMask subobject in pipeline state stream".to_string())
+        );
+    }
+
+    #[test]
+    fn too_many_render_targets() {
+        let formats = [Format::R8G8B8A8Unorm; 9];
+        let mut stream = PipelineStateStream::new();
+        stream.push(SampleMask(1)).unwrap();
+        let len = stream.len();
+
+        assert_eq!(
+            stream.push(RenderTargetFormats(&formats)).err(),
+            Some(StreamError::InvalidSubobject(Subobject::RTFormats))
+        );
+        assert_eq!(stream.len(), len);
+        assert!(!stream.contains(Subobject::RTFormats));
+        assert_eq!(
+            StreamError::InvalidSubobject(Subobject::RTFormats).to_string(),
+            "invalid RTFormats subobject in pipeline state stream"
+        );
+
+        stream.push(RenderTargetFormats(&formats[..8])).unwrap();
+        assert_eq!(read::<u32>(&stream, len + 4 + 32), 8);
+    }
+}
@@ -1,8 +1,14 @@
 //! Pipeline state
 
-use crate::{com::WeakPtr, Blob, D3DResult, Error};
-use std::{ffi, ops::Deref, ptr};
-use winapi::um::{d3d12, d3dcompiler};
+use crate::{
+    com::{ComSend, ComSync, WeakPtr},
+    Blob, D3DResult, Error, Format, NodeMask, RootSignature, SampleDesc,
+};
+use std::{ffi, fmt, marker::PhantomData, ops::Deref, ptr};
+use winapi::{
+    shared::{dxgiformat, dxgitype},
+    um::{d3d12, d3dcompiler},
+};
 
 bitflags! {
     pub struct PipelineStateFlags: u32 {
@@ -22,34 +28,54 @@ bitflags! {
     }
 }
 
+/// Shader bytecode borrowed for `'a`.
 #[derive(Copy, Clone)]
-pub struct Shader(d3d12::D3D12_SHADER_BYTECODE);
-impl Shader {
+pub struct Shader<'a>(d3d12::D3D12_SHADER_BYTECODE, PhantomData<&'a [u8]>);
+impl<'a> Shader<'a> {
     pub fn null() -> Self {
-        Shader(d3d12::D3D12_SHADER_BYTECODE {
-            BytecodeLength: 0,
-            pShaderBytecode: ptr::null(),
-        })
+        Shader(
+            d3d12::D3D12_SHADER_BYTECODE {
+                BytecodeLength: 0,
+                pShaderBytecode: ptr::null(),
+            },
+            PhantomData,
+        )
     }
 
-    pub fn from_raw(data: &[u8]) -> Self {
-        Shader(d3d12::D3D12_SHADER_BYTECODE {
-            BytecodeLength: data.len() as _,
-            pShaderBytecode: data.as_ptr() as _,
-        })
+    pub fn from_raw(data: &'a [u8]) -> Self {
+        Shader(
+            d3d12::D3D12_SHADER_BYTECODE {
+                BytecodeLength: data.len() as _,
+                pShaderBytecode: data.as_ptr() as _,
+            },
+            PhantomData,
+        )
     }
 
-    // `blob` may not be null.
-    pub fn from_blob(blob: Blob) -> Self {
-        Shader(d3d12::D3D12_SHADER_BYTECODE {
-            BytecodeLength: unsafe { blob.GetBufferSize() },
-            pShaderBytecode: unsafe { blob.GetBufferPointer() },
-        })
+    /// Bytecode of a compiled shader, `blob` may not be null.
+    ///
+    /// The shader can't outlive the blob:
+    ///
+    /// ```compile_fail,E0597
+    /// let shader = {
+    ///     let blob = d3d12::Blob::null();
+    ///     d3d12::Shader::from_blob(&blob)
+    /// };
+    /// ```
+    pub fn from_blob(blob: &'a Blob) -> Self {
+        Shader(
+            d3d12::D3D12_SHADER_BYTECODE {
+                BytecodeLength: unsafe { blob.GetBufferSize() },
+                pShaderBytecode: unsafe { blob.GetBufferPointer() },
+            },
+            PhantomData,
+        )
     }
 
     /// Compile a shader from raw HLSL.
     ///
     /// * `target`: example format: `ps_5_1`.
+    #[track_caller]
     pub fn compile(
         code: &[u8],
         target: &ffi::CStr,
@@ -75,19 +101,21 @@ impl Shader {
             )
         };
 
+        crate::ledger::track_created(&shader);
+        crate::ledger::track_created(&error);
         ((shader, error), hr)
     }
 }
 
-impl Deref for Shader {
+impl Deref for Shader<'_> {
     type Target = d3d12::D3D12_SHADER_BYTECODE;
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
 
-impl From<Option<Blob>> for Shader {
-    fn from(blob: Option<Blob>) -> Self {
+impl<'a> From<Option<&'a Blob>> for Shader<'a> {
+    fn from(blob: Option<&'a Blob>) -> Self {
         match blob {
             Some(b) => Shader::from_blob(b),
             None => Shader::null(),
@@ -95,26 +123,33 @@ impl From<Option<Blob>> for Shader {
     }
 }
 
+/// Cached pipeline state blob borrowed for `'a`.
 #[derive(Copy, Clone)]
-pub struct CachedPSO(d3d12::D3D12_CACHED_PIPELINE_STATE);
-impl CachedPSO {
+pub struct CachedPSO<'a>(d3d12::D3D12_CACHED_PIPELINE_STATE, PhantomData<&'a [u8]>);
+impl<'a> CachedPSO<'a> {
     pub fn null() -> Self {
-        CachedPSO(d3d12::D3D12_CACHED_PIPELINE_STATE {
-            CachedBlobSizeInBytes: 0,
-            pCachedBlob: ptr::null(),
-        })
+        CachedPSO(
+            d3d12::D3D12_CACHED_PIPELINE_STATE {
+                CachedBlobSizeInBytes: 0,
+                pCachedBlob: ptr::null(),
+            },
+            PhantomData,
+        )
     }
 
     // `blob` may not be null.
-    pub fn from_blob(blob: Blob) -> Self {
-        CachedPSO(d3d12::D3D12_CACHED_PIPELINE_STATE {
-            CachedBlobSizeInBytes: unsafe { blob.GetBufferSize() },
-            pCachedBlob: unsafe { blob.GetBufferPointer() },
-        })
+    pub fn from_blob(blob: &'a Blob) -> Self {
+        CachedPSO(
+            d3d12::D3D12_CACHED_PIPELINE_STATE {
+                CachedBlobSizeInBytes: unsafe { blob.GetBufferSize() },
+                pCachedBlob: unsafe { blob.GetBufferPointer() },
+            },
+            PhantomData,
+        )
     }
 }
 
-impl Deref for CachedPSO {
+impl Deref for CachedPSO<'_> {
     type Target = d3d12::D3D12_CACHED_PIPELINE_STATE;
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -123,7 +158,15 @@ impl Deref for CachedPSO {
 
 pub type PipelineState = WeakPtr<d3d12::ID3D12PipelineState>;
 
+unsafe impl ComSend for d3d12::ID3D12PipelineState {}
+unsafe impl ComSync for d3d12::ID3D12PipelineState {}
+
+// Not yet part of `winapi`, which names it `MAX_VALID`.
+const D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_VIEW_INSTANCING:
+    d3d12::D3D12_PIPELINE_STATE_SUBOBJECT_TYPE = 22;
+
 #[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Subobject {
     RootSignature = d3d12::D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_ROOT_SIGNATURE,
     VS = d3d12::D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_VS,
@@ -147,7 +190,7 @@ pub enum Subobject {
     CachedPSO = d3d12::D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_CACHED_PSO,
     Flags = d3d12::D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_FLAGS,
     DepthStencil1 = d3d12::D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_DEPTH_STENCIL1,
-    // ViewInstancing = d3d12::D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_VIEW_INSTANCING,
+    ViewInstancing = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_VIEW_INSTANCING,
 }
 
 /// Subobject of a pipeline stream description
@@ -167,3 +210,659 @@ impl<T> PipelineStateSubobject<T> {
         }
     }
 }
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ComparisonFunc {
+    Never = d3d12::D3D12_COMPARISON_FUNC_NEVER,
+    Less = d3d12::D3D12_COMPARISON_FUNC_LESS,
+    Equal = d3d12::D3D12_COMPARISON_FUNC_EQUAL,
+    LessEqual = d3d12::D3D12_COMPARISON_FUNC_LESS_EQUAL,
+    Greater = d3d12::D3D12_COMPARISON_FUNC_GREATER,
+    NotEqual = d3d12::D3D12_COMPARISON_FUNC_NOT_EQUAL,
+    GreaterEqual = d3d12::D3D12_COMPARISON_FUNC_GREATER_EQUAL,
+    Always = d3d12::D3D12_COMPARISON_FUNC_ALWAYS,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Blend {
+    Zero = d3d12::D3D12_BLEND_ZERO,
+    One = d3d12::D3D12_BLEND_ONE,
+    SrcColor = d3d12::D3D12_BLEND_SRC_COLOR,
+    InvSrcColor = d3d12::D3D12_BLEND_INV_SRC_COLOR,
+    SrcAlpha = d3d12::D3D12_BLEND_SRC_ALPHA,
+    InvSrcAlpha = d3d12::D3D12_BLEND_INV_SRC_ALPHA,
+    DestAlpha = d3d12::D3D12_BLEND_DEST_ALPHA,
+    InvDestAlpha = d3d12::D3D12_BLEND_INV_DEST_ALPHA,
+    DestColor = d3d12::D3D12_BLEND_DEST_COLOR,
+    InvDestColor = d3d12::D3D12_BLEND_INV_DEST_COLOR,
+    SrcAlphaSat = d3d12::D3D12_BLEND_SRC_ALPHA_SAT,
+    BlendFactor = d3d12::D3D12_BLEND_BLEND_FACTOR,
+    InvBlendFactor = d3d12::D3D12_BLEND_INV_BLEND_FACTOR,
+    Src1Color = d3d12::D3D12_BLEND_SRC1_COLOR,
+    InvSrc1Color = d3d12::D3D12_BLEND_INV_SRC1_COLOR,
+    Src1Alpha = d3d12::D3D12_BLEND_SRC1_ALPHA,
+    InvSrc1Alpha = d3d12::D3D12_BLEND_INV_SRC1_ALPHA,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BlendOp {
+    Add = d3d12::D3D12_BLEND_OP_ADD,
+    Subtract = d3d12::D3D12_BLEND_OP_SUBTRACT,
+    RevSubtract = d3d12::D3D12_BLEND_OP_REV_SUBTRACT,
+    Min = d3d12::D3D12_BLEND_OP_MIN,
+    Max = d3d12::D3D12_BLEND_OP_MAX,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LogicOp {
+    Clear = d3d12::D3D12_LOGIC_OP_CLEAR,
+    Set = d3d12::D3D12_LOGIC_OP_SET,
+    Copy = d3d12::D3D12_LOGIC_OP_COPY,
+    CopyInverted = d3d12::D3D12_LOGIC_OP_COPY_INVERTED,
+    Noop = d3d12::D3D12_LOGIC_OP_NOOP,
+    Invert = d3d12::D3D12_LOGIC_OP_INVERT,
+    And = d3d12::D3D12_LOGIC_OP_AND,
+    Nand = d3d12::D3D12_LOGIC_OP_NAND,
+    Or = d3d12::D3D12_LOGIC_OP_OR,
+    Nor = d3d12::D3D12_LOGIC_OP_NOR,
+    Xor = d3d12::D3D12_LOGIC_OP_XOR,
+    Equiv = d3d12::D3D12_LOGIC_OP_EQUIV,
+    AndReverse = d3d12::D3D12_LOGIC_OP_AND_REVERSE,
+    AndInverted = d3d12::D3D12_LOGIC_OP_AND_INVERTED,
+    OrReverse = d3d12::D3D12_LOGIC_OP_OR_REVERSE,
+    OrInverted = d3d12::D3D12_LOGIC_OP_OR_INVERTED,
+}
+
+bitflags! {
+    pub struct ColorWriteEnable: u8 {
+        const RED = d3d12::D3D12_COLOR_WRITE_ENABLE_RED as u8;
+        const GREEN = d3d12::D3D12_COLOR_WRITE_ENABLE_GREEN as u8;
+        const BLUE = d3d12::D3D12_COLOR_WRITE_ENABLE_BLUE as u8;
+        const ALPHA = d3d12::D3D12_COLOR_WRITE_ENABLE_ALPHA as u8;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RenderTargetBlendDesc {
+    pub blend_enable: bool,
+    pub logic_op_enable: bool,
+    pub src_blend: Blend,
+    pub dest_blend: Blend,
+    pub blend_op: BlendOp,
+    pub src_blend_alpha: Blend,
+    pub dest_blend_alpha: Blend,
+    pub blend_op_alpha: BlendOp,
+    pub logic_op: LogicOp,
+    pub write_mask: ColorWriteEnable,
+}
+
+impl Default for RenderTargetBlendDesc {
+    /// Blending and logic operations disabled, writing all channels.
+    fn default() -> Self {
+        RenderTargetBlendDesc {
+            blend_enable: false,
+            logic_op_enable: false,
+            src_blend: Blend::One,
+            dest_blend: Blend::Zero,
+            blend_op: BlendOp::Add,
+            src_blend_alpha: Blend::One,
+            dest_blend_alpha: Blend::Zero,
+            blend_op_alpha: BlendOp::Add,
+            logic_op: LogicOp::Noop,
+            write_mask: ColorWriteEnable::all(),
+        }
+    }
+}
+
+impl RenderTargetBlendDesc {
+    pub(crate) fn to_raw(self) -> d3d12::D3D12_RENDER_TARGET_BLEND_DESC {
+        d3d12::D3D12_RENDER_TARGET_BLEND_DESC {
+            BlendEnable: self.blend_enable as _,
+            LogicOpEnable: self.logic_op_enable as _,
+            SrcBlend: self.src_blend as _,
+            DestBlend: self.dest_blend as _,
+            BlendOp: self.blend_op as _,
+            SrcBlendAlpha: self.src_blend_alpha as _,
+            DestBlendAlpha: self.dest_blend_alpha as _,
+            BlendOpAlpha: self.blend_op_alpha as _,
+            LogicOp: self.logic_op as _,
+            RenderTargetWriteMask: self.write_mask.bits(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BlendDesc {
+    pub alpha_to_coverage_enable: bool,
+    /// Use a separate blend state for each render target, otherwise the first one applies to all.
+    pub independent_blend_enable: bool,
+    pub render_targets: [RenderTargetBlendDesc; 8],
+}
+
+impl BlendDesc {
+    pub(crate) fn to_raw(self) -> d3d12::D3D12_BLEND_DESC {
+        let mut render_targets = [self.render_targets[0].to_raw(); 8];
+        for (raw, desc) in render_targets.iter_mut().zip(&self.render_targets) {
+            *raw = desc.to_raw();
+        }
+
+        d3d12::D3D12_BLEND_DESC {
+            AlphaToCoverageEnable: self.alpha_to_coverage_enable as _,
+            IndependentBlendEnable: self.independent_blend_enable as _,
+            RenderTarget: render_targets,
+        }
+    }
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FillMode {
+    Wireframe = d3d12::D3D12_FILL_MODE_WIREFRAME,
+    Solid = d3d12::D3D12_FILL_MODE_SOLID,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CullMode {
+    None = d3d12::D3D12_CULL_MODE_NONE,
+    Front = d3d12::D3D12_CULL_MODE_FRONT,
+    Back = d3d12::D3D12_CULL_MODE_BACK,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RasterizerDesc {
+    pub fill_mode: FillMode,
+    pub cull_mode: CullMode,
+    pub front_counter_clockwise: bool,
+    pub depth_bias: i32,
+    pub depth_bias_clamp: f32,
+    pub slope_scaled_depth_bias: f32,
+    pub depth_clip_enable: bool,
+    pub multisample_enable: bool,
+    pub antialiased_line_enable: bool,
+    pub forced_sample_count: u32,
+    pub conservative_raster: bool,
+}
+
+impl Default for RasterizerDesc {
+    /// Solid fill, culling clockwise back faces, with depth clipping.
+    fn default() -> Self {
+        RasterizerDesc {
+            fill_mode: FillMode::Solid,
+            cull_mode: CullMode::Back,
+            front_counter_clockwise: false,
+            depth_bias: 0,
+            depth_bias_clamp: 0.0,
+            slope_scaled_depth_bias: 0.0,
+            depth_clip_enable: true,
+            multisample_enable: false,
+            antialiased_line_enable: false,
+            forced_sample_count: 0,
+            conservative_raster: false,
+        }
+    }
+}
+
+impl RasterizerDesc {
+    pub(crate) fn to_raw(self) -> d3d12::D3D12_RASTERIZER_DESC {
+        d3d12::D3D12_RASTERIZER_DESC {
+            FillMode: self.fill_mode as _,
+            CullMode: self.cull_mode as _,
+            FrontCounterClockwise: self.front_counter_clockwise as _,
+            DepthBias: self.depth_bias,
+            DepthBiasClamp: self.depth_bias_clamp,
+            SlopeScaledDepthBias: self.slope_scaled_depth_bias,
+            DepthClipEnable: self.depth_clip_enable as _,
+            MultisampleEnable: self.multisample_enable as _,
+            AntialiasedLineEnable: self.antialiased_line_enable as _,
+            ForcedSampleCount: self.forced_sample_count,
+            ConservativeRaster: if self.conservative_raster {
+                d3d12::D3D12_CONSERVATIVE_RASTERIZATION_MODE_ON
+            } else {
+                d3d12::D3D12_CONSERVATIVE_RASTERIZATION_MODE_OFF
+            },
+        }
+    }
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StencilOp {
+    Keep = d3d12::D3D12_STENCIL_OP_KEEP,
+    Zero = d3d12::D3D12_STENCIL_OP_ZERO,
+    Replace = d3d12::D3D12_STENCIL_OP_REPLACE,
+    IncrSat = d3d12::D3D12_STENCIL_OP_INCR_SAT,
+    DecrSat = d3d12::D3D12_STENCIL_OP_DECR_SAT,
+    Invert = d3d12::D3D12_STENCIL_OP_INVERT,
+    Incr = d3d12::D3D12_STENCIL_OP_INCR,
+    Decr = d3d12::D3D12_STENCIL_OP_DECR,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StencilOpDesc {
+    pub fail_op: StencilOp,
+    pub depth_fail_op: StencilOp,
+    pub pass_op: StencilOp,
+    pub func: ComparisonFunc,
+}
+
+impl Default for StencilOpDesc {
+    fn default() -> Self {
+        StencilOpDesc {
+            fail_op: StencilOp::Keep,
+            depth_fail_op: StencilOp::Keep,
+            pass_op: StencilOp::Keep,
+            func: ComparisonFunc::Always,
+        }
+    }
+}
+
+impl StencilOpDesc {
+    pub(crate) fn to_raw(self) -> d3d12::D3D12_DEPTH_STENCILOP_DESC {
+        d3d12::D3D12_DEPTH_STENCILOP_DESC {
+            StencilFailOp: self.fail_op as _,
+            StencilDepthFailOp: self.depth_fail_op as _,
+            StencilPassOp: self.pass_op as _,
+            StencilFunc: self.func as _,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DepthStencilDesc {
+    pub depth_enable: bool,
+    pub depth_write_enable: bool,
+    pub depth_func: ComparisonFunc,
+    pub stencil_enable: bool,
+    pub stencil_read_mask: u8,
+    pub stencil_write_mask: u8,
+    pub front_face: StencilOpDesc,
+    pub back_face: StencilOpDesc,
+}
+
+impl Default for DepthStencilDesc {
+    /// Depth and stencil testing disabled, matching the default `DSVFormat` of `Unknown`.
+    fn default() -> Self {
+        DepthStencilDesc {
+            depth_enable: false,
+            depth_write_enable: true,
+            depth_func: ComparisonFunc::Less,
+            stencil_enable: false,
+            stencil_read_mask: d3d12::D3D12_DEFAULT_STENCIL_READ_MASK as _,
+            stencil_write_mask: d3d12::D3D12_DEFAULT_STENCIL_WRITE_MASK as _,
+            front_face: StencilOpDesc::default(),
+            back_face: StencilOpDesc::default(),
+        }
+    }
+}
+
+impl DepthStencilDesc {
+    pub(crate) fn to_raw(self) -> d3d12::D3D12_DEPTH_STENCIL_DESC {
+        d3d12::D3D12_DEPTH_STENCIL_DESC {
+            DepthEnable: self.depth_enable as _,
+            DepthWriteMask: if self.depth_write_enable {
+                d3d12::D3D12_DEPTH_WRITE_MASK_ALL
+            } else {
+                d3d12::D3D12_DEPTH_WRITE_MASK_ZERO
+            },
+            DepthFunc: self.depth_func as _,
+            StencilEnable: self.stencil_enable as _,
+            StencilReadMask: self.stencil_read_mask,
+            StencilWriteMask: self.stencil_write_mask,
+            FrontFace: self.front_face.to_raw(),
+            BackFace: self.back_face.to_raw(),
+        }
+    }
+}
+
+/// Offset of an input element directly following the previous one.
+pub const APPEND_ALIGNED_ELEMENT: u32 = d3d12::D3D12_APPEND_ALIGNED_ELEMENT;
+
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct InputElementDesc<'a>(d3d12::D3D12_INPUT_ELEMENT_DESC, PhantomData<&'a ffi::CStr>);
+
+impl<'a> InputElementDesc<'a> {
+    pub fn per_vertex(
+        semantic_name: &'a ffi::CStr,
+        semantic_index: u32,
+        format: Format,
+        input_slot: u32,
+        aligned_byte_offset: u32,
+    ) -> Self {
+        InputElementDesc(
+            d3d12::D3D12_INPUT_ELEMENT_DESC {
+                SemanticName: semantic_name.as_ptr(),
+                SemanticIndex: semantic_index,
+                Format: format as _,
+                InputSlot: input_slot,
+                AlignedByteOffset: aligned_byte_offset,
+                InputSlotClass: d3d12::D3D12_INPUT_CLASSIFICATION_PER_VERTEX_DATA,
+                InstanceDataStepRate: 0,
+            },
+            PhantomData,
+        )
+    }
+
+    /// Element advancing once every `step_rate` instances.
+    pub fn per_instance(
+        semantic_name: &'a ffi::CStr,
+        semantic_index: u32,
+        format: Format,
+        input_slot: u32,
+        aligned_byte_offset: u32,
+        step_rate: u32,
+    ) -> Self {
+        InputElementDesc(
+            d3d12::D3D12_INPUT_ELEMENT_DESC {
+                SemanticName: semantic_name.as_ptr(),
+                SemanticIndex: semantic_index,
+                Format: format as _,
+                InputSlot: input_slot,
+                AlignedByteOffset: aligned_byte_offset,
+                InputSlotClass: d3d12::D3D12_INPUT_CLASSIFICATION_PER_INSTANCE_DATA,
+                InstanceDataStepRate: step_rate,
+            },
+            PhantomData,
+        )
+    }
+}
+
+impl fmt::Debug for InputElementDesc<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("InputElementDesc")
+            .field("semantic_name", unsafe {
+                &ffi::CStr::from_ptr(self.0.SemanticName)
+            })
+            .field("semantic_index", &self.0.SemanticIndex)
+            .field("format", &self.0.Format)
+            .field("input_slot", &self.0.InputSlot)
+            .field("aligned_byte_offset", &self.0.AlignedByteOffset)
+            .field("input_slot_class", &self.0.InputSlotClass)
+            .field("instance_data_step_rate", &self.0.InstanceDataStepRate)
+            .finish()
+    }
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct SoDeclarationEntry<'a>(
+    d3d12::D3D12_SO_DECLARATION_ENTRY,
+    PhantomData<&'a ffi::CStr>,
+);
+
+impl<'a> SoDeclarationEntry<'a> {
+    /// Without a `semantic_name` the entry leaves a gap of `component_count` components.
+    pub fn new(
+        stream: u32,
+        semantic_name: Option<&'a ffi::CStr>,
+        semantic_index: u32,
+        start_component: u8,
+        component_count: u8,
+        output_slot: u8,
+    ) -> Self {
+        SoDeclarationEntry(
+            d3d12::D3D12_SO_DECLARATION_ENTRY {
+                Stream: stream,
+                SemanticName: semantic_name.map_or(ptr::null(), |name| name.as_ptr()),
+                SemanticIndex: semantic_index,
+                StartComponent: start_component,
+                ComponentCount: component_count,
+                OutputSlot: output_slot,
+            },
+            PhantomData,
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StreamOutputDesc<'a> {
+    pub entries: &'a [SoDeclarationEntry<'a>],
+    pub buffer_strides: &'a [u32],
+    pub rasterized_stream: u32,
+}
+
+impl StreamOutputDesc<'_> {
+    pub(crate) fn to_raw(self) -> d3d12::D3D12_STREAM_OUTPUT_DESC {
+        d3d12::D3D12_STREAM_OUTPUT_DESC {
+            pSODeclaration: self.entries.as_ptr() as *const _,
+            NumEntries: self.entries.len() as _,
+            pBufferStrides: self.buffer_strides.as_ptr(),
+            NumStrides: self.buffer_strides.len() as _,
+            RasterizedStream: self.rasterized_stream,
+        }
+    }
+}
+
+impl fmt::Debug for SoDeclarationEntry<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let semantic_name = if self.0.SemanticName.is_null() {
+            None
+        } else {
+            Some(unsafe { ffi::CStr::from_ptr(self.0.SemanticName) })
+        };
+        f.debug_struct("SoDeclarationEntry")
+            .field("stream", &self.0.Stream)
+            .field("semantic_name", &semantic_name)
+            .field("semantic_index", &self.0.SemanticIndex)
+            .field("start_component", &self.0.StartComponent)
+            .field("component_count", &self.0.ComponentCount)
+            .field("output_slot", &self.0.OutputSlot)
+            .finish()
+    }
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PrimitiveTopologyType {
+    Undefined = d3d12::D3D12_PRIMITIVE_TOPOLOGY_TYPE_UNDEFINED,
+    Point = d3d12::D3D12_PRIMITIVE_TOPOLOGY_TYPE_POINT,
+    Line = d3d12::D3D12_PRIMITIVE_TOPOLOGY_TYPE_LINE,
+    Triangle = d3d12::D3D12_PRIMITIVE_TOPOLOGY_TYPE_TRIANGLE,
+    Patch = d3d12::D3D12_PRIMITIVE_TOPOLOGY_TYPE_PATCH,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IndexBufferStripCutValue {
+    Disabled = d3d12::D3D12_INDEX_BUFFER_STRIP_CUT_VALUE_DISABLED,
+    Value0xFFFF = d3d12::D3D12_INDEX_BUFFER_STRIP_CUT_VALUE_0xFFFF,
+    Value0xFFFFFFFF = d3d12::D3D12_INDEX_BUFFER_STRIP_CUT_VALUE_0xFFFFFFFF,
+}
+
+/// Description of a graphics pipeline.
+///
+/// Borrowed input layout, stream output and render target formats have to outlive
+/// the creation call. The defaults describe a triangle pipeline without render
+/// targets, depth testing or blending.
+#[derive(Clone, Copy)]
+pub struct GraphicsPipelineDesc<'a> {
+    pub root_signature: RootSignature,
+    pub vs: Shader<'a>,
+    pub ps: Shader<'a>,
+    pub ds: Shader<'a>,
+    pub hs: Shader<'a>,
+    pub gs: Shader<'a>,
+    pub stream_output: StreamOutputDesc<'a>,
+    pub blend: BlendDesc,
+    pub sample_mask: u32,
+    pub rasterizer: RasterizerDesc,
+    pub depth_stencil: DepthStencilDesc,
+    pub input_layout: &'a [InputElementDesc<'a>],
+    pub ib_strip_cut_value: IndexBufferStripCutValue,
+    pub primitive_topology_type: PrimitiveTopologyType,
+    /// Up to 8 render target formats, pipelines with more fail to be created.
+    pub rtv_formats: &'a [Format],
+    pub dsv_format: Format,
+    pub sample: SampleDesc,
+    pub node_mask: NodeMask,
+    pub cached_pso: CachedPSO<'a>,
+    pub flags: PipelineStateFlags,
+}
+
+impl Default for GraphicsPipelineDesc<'_> {
+    fn default() -> Self {
+        GraphicsPipelineDesc {
+            root_signature: RootSignature::null(),
+            vs: Shader::null(),
+            ps: Shader::null(),
+            ds: Shader::null(),
+            hs: Shader::null(),
+            gs: Shader::null(),
+            stream_output: StreamOutputDesc::default(),
+            blend: BlendDesc::default(),
+            sample_mask: !0,
+            rasterizer: RasterizerDesc::default(),
+            depth_stencil: DepthStencilDesc::default(),
+            input_layout: &[],
+            ib_strip_cut_value: IndexBufferStripCutValue::Disabled,
+            primitive_topology_type: PrimitiveTopologyType::Triangle,
+            rtv_formats: &[],
+            dsv_format: Format::Unknown,
+            sample: SampleDesc {
+                count: 1,
+                quality: 0,
+            },
+            node_mask: 0,
+            cached_pso: CachedPSO::null(),
+            flags: PipelineStateFlags::empty(),
+        }
+    }
+}
+
+impl GraphicsPipelineDesc<'_> {
+    /// Raw description pointing into `self`, `None` with more than 8 render targets.
+    pub(crate) fn to_raw(self) -> Option<d3d12::D3D12_GRAPHICS_PIPELINE_STATE_DESC> {
+        if self.rtv_formats.len() > 8 {
+            return None;
+        }
+        let mut rtv_formats = [dxgiformat::DXGI_FORMAT_UNKNOWN; 8];
+        for (raw, &format) in rtv_formats.iter_mut().zip(self.rtv_formats) {
+            *raw = format as _;
+        }
+
+        Some(d3d12::D3D12_GRAPHICS_PIPELINE_STATE_DESC {
+            pRootSignature: self.root_signature.as_mut_ptr(),
+            VS: *self.vs,
+            PS: *self.ps,
+            DS: *self.ds,
+            HS: *self.hs,
+            GS: *self.gs,
+            StreamOutput: self.stream_output.to_raw(),
+            BlendState: self.blend.to_raw(),
+            SampleMask: self.sample_mask,
+            RasterizerState: self.rasterizer.to_raw(),
+            DepthStencilState: self.depth_stencil.to_raw(),
+            InputLayout: d3d12::D3D12_INPUT_LAYOUT_DESC {
+                pInputElementDescs: self.input_layout.as_ptr() as *const _,
+                NumElements: self.input_layout.len() as _,
+            },
+            IBStripCutValue: self.ib_strip_cut_value as _,
+            PrimitiveTopologyType: self.primitive_topology_type as _,
+            NumRenderTargets: self.rtv_formats.len() as _,
+            RTVFormats: rtv_formats,
+            DSVFormat: self.dsv_format as _,
+            SampleDesc: dxgitype::DXGI_SAMPLE_DESC {
+                Count: self.sample.count,
+                Quality: self.sample.quality,
+            },
+            NodeMask: self.node_mask,
+            CachedPSO: *self.cached_pso,
+            Flags: self.flags.bits(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::com_object::{unimplemented_vtable, ComObject};
+    use winapi::{ctypes::c_void, um::d3dcommon};
+
+    type BlobObject = ComObject<d3dcommon::ID3D10BlobVtbl, Vec<u8>>;
+
+    unsafe extern "system" fn buffer_pointer(this: *mut d3dcommon::ID3D10Blob) -> *mut c_void {
+        BlobObject::from_interface(this).data().as_ptr() as *mut c_void
+    }
+
+    unsafe extern "system" fn buffer_size(this: *mut d3dcommon::ID3D10Blob) -> usize {
+        BlobObject::from_interface(this).data().len()
+    }
+
+    fn blob(data: Vec<u8>) -> Blob {
+        unsafe {
+            let mut vtbl: d3dcommon::ID3D10BlobVtbl = unimplemented_vtable();
+            vtbl.GetBufferPointer = buffer_pointer;
+            vtbl.GetBufferSize = buffer_size;
+            BlobObject::create::<d3dcommon::ID3DBlob>(vtbl, &[], data)
+        }
+    }
+
+    #[test]
+    fn from_blob() {
+        let blob = blob(vec![0x44, 0x58, 0x42, 0x43, 0]);
+        let data = unsafe { BlobObject::from_interface(blob.as_mut_ptr()) }.data();
+
+        let shader = Shader::from_blob(&blob);
+        assert_eq!(shader.pShaderBytecode, data.as_ptr() as *const c_void);
+        assert_eq!(shader.BytecodeLength, 5);
+        let shader = Shader::from(Some(&blob));
+        assert_eq!(shader.BytecodeLength, 5);
+        let shader = Shader::from(None);
+        assert!(shader.pShaderBytecode.is_null());
+        assert_eq!(shader.BytecodeLength, 0);
+
+        let cached = CachedPSO::from_blob(&blob);
+        assert_eq!(cached.pCachedBlob, data.as_ptr() as *const c_void);
+        assert_eq!(cached.CachedBlobSizeInBytes, 5);
+        unsafe { blob.destroy() };
+    }
+
+    #[test]
+    fn graphics_desc_to_raw() {
+        let vs = [0x44, 0x58, 0x42, 0x43];
+        let formats = [Format::R8G8B8A8Unorm; 8];
+        let desc = GraphicsPipelineDesc {
+            vs: Shader::from_raw(&vs),
+            rtv_formats: &formats[..2],
+            dsv_format: Format::D32Float,
+            ..GraphicsPipelineDesc::default()
+        };
+
+        let raw = desc.to_raw().unwrap();
+        assert_eq!(raw.VS.pShaderBytecode, vs.as_ptr() as *const _);
+        assert_eq!(raw.VS.BytecodeLength, 4);
+        assert!(raw.PS.pShaderBytecode.is_null());
+        assert_eq!(raw.NumRenderTargets, 2);
+        assert_eq!(
+            raw.RTVFormats[..3],
+            [
+                dxgiformat::DXGI_FORMAT_R8G8B8A8_UNORM,
+                dxgiformat::DXGI_FORMAT_R8G8B8A8_UNORM,
+                dxgiformat::DXGI_FORMAT_UNKNOWN,
+            ]
+        );
+        assert_eq!(raw.DSVFormat, dxgiformat::DXGI_FORMAT_D32_FLOAT);
+        assert_eq!(raw.SampleMask, !0);
+
+        let all = GraphicsPipelineDesc {
+            rtv_formats: &formats,
+            ..desc
+        };
+        assert_eq!(all.to_raw().unwrap().NumRenderTargets, 8);
+    }
+
+    #[test]
+    fn too_many_render_targets() {
+        let formats = [Format::R8G8B8A8Unorm; 9];
+        let desc = GraphicsPipelineDesc {
+            rtv_formats: &formats,
+            ..GraphicsPipelineDesc::default()
+        };
+        assert!(desc.to_raw().is_none());
+    }
+}
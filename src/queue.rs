@@ -1,4 +1,8 @@
-use crate::{com::WeakPtr, sync::Fence, CommandList, HRESULT};
+use crate::{
+    com::{ComSend, ComSync, WeakPtr},
+    sync::Fence,
+    CommandList, HRESULT,
+};
 use winapi::um::d3d12;
 
 #[repr(u32)]
@@ -16,6 +20,9 @@ bitflags! {
 
 pub type CommandQueue = WeakPtr<d3d12::ID3D12CommandQueue>;
 
+unsafe impl ComSend for d3d12::ID3D12CommandQueue {}
+unsafe impl ComSync for d3d12::ID3D12CommandQueue {}
+
 impl CommandQueue {
     pub fn execute_command_lists(&self, command_lists: &[CommandList]) {
         let command_lists = command_lists
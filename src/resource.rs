@@ -1,8 +1,11 @@
 //! GPU Resource
 
-use crate::{com::WeakPtr, D3DResult, Rect};
-use std::{ops::Range, ptr};
-use winapi::um::d3d12;
+use crate::{
+    com::{ComSend, ComSync, WeakPtr},
+    D3DResult, Format, Rect, SampleDesc,
+};
+use std::{error, fmt, mem, ops::Range, ptr};
+use winapi::{shared::dxgitype, um::d3d12};
 
 pub type Subresource = u32;
 
@@ -13,8 +16,10 @@ pub struct DiscardRegion<'a> {
 
 pub type Resource = WeakPtr<d3d12::ID3D12Resource>;
 
+unsafe impl ComSend for d3d12::ID3D12Resource {}
+unsafe impl ComSync for d3d12::ID3D12Resource {}
+
 impl Resource {
-    ///
     pub fn map(
         &self,
         subresource: Subresource,
@@ -51,3 +56,908 @@ impl Resource {
         unsafe { self.GetGPUVirtualAddress() }
     }
 }
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ResourceDimension {
+    Buffer = d3d12::D3D12_RESOURCE_DIMENSION_BUFFER,
+    Texture1D = d3d12::D3D12_RESOURCE_DIMENSION_TEXTURE1D,
+    Texture2D = d3d12::D3D12_RESOURCE_DIMENSION_TEXTURE2D,
+    Texture3D = d3d12::D3D12_RESOURCE_DIMENSION_TEXTURE3D,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TextureLayout {
+    Unknown = d3d12::D3D12_TEXTURE_LAYOUT_UNKNOWN,
+    RowMajor = d3d12::D3D12_TEXTURE_LAYOUT_ROW_MAJOR,
+    UndefinedSwizzle64KB = d3d12::D3D12_TEXTURE_LAYOUT_64KB_UNDEFINED_SWIZZLE,
+    StandardSwizzle64KB = d3d12::D3D12_TEXTURE_LAYOUT_64KB_STANDARD_SWIZZLE,
+}
+
+bitflags! {
+    pub struct ResourceFlags: u32 {
+        const NONE = d3d12::D3D12_RESOURCE_FLAG_NONE;
+        const ALLOW_RENDER_TARGET = d3d12::D3D12_RESOURCE_FLAG_ALLOW_RENDER_TARGET;
+        const ALLOW_DEPTH_STENCIL = d3d12::D3D12_RESOURCE_FLAG_ALLOW_DEPTH_STENCIL;
+        const ALLOW_UNORDERED_ACCESS = d3d12::D3D12_RESOURCE_FLAG_ALLOW_UNORDERED_ACCESS;
+        const DENY_SHADER_RESOURCE = d3d12::D3D12_RESOURCE_FLAG_DENY_SHADER_RESOURCE;
+        const ALLOW_CROSS_ADAPTER = d3d12::D3D12_RESOURCE_FLAG_ALLOW_CROSS_ADAPTER;
+        const ALLOW_SIMULTANEOUS_ACCESS = d3d12::D3D12_RESOURCE_FLAG_ALLOW_SIMULTANEOUS_ACCESS;
+    }
+}
+
+bitflags! {
+    pub struct ResourceStates: u32 {
+        const COMMON = d3d12::D3D12_RESOURCE_STATE_COMMON;
+        const VERTEX_AND_CONSTANT_BUFFER = d3d12::D3D12_RESOURCE_STATE_VERTEX_AND_CONSTANT_BUFFER;
+        const INDEX_BUFFER = d3d12::D3D12_RESOURCE_STATE_INDEX_BUFFER;
+        const RENDER_TARGET = d3d12::D3D12_RESOURCE_STATE_RENDER_TARGET;
+        const UNORDERED_ACCESS = d3d12::D3D12_RESOURCE_STATE_UNORDERED_ACCESS;
+        const DEPTH_WRITE = d3d12::D3D12_RESOURCE_STATE_DEPTH_WRITE;
+        const DEPTH_READ = d3d12::D3D12_RESOURCE_STATE_DEPTH_READ;
+        const NON_PIXEL_SHADER_RESOURCE = d3d12::D3D12_RESOURCE_STATE_NON_PIXEL_SHADER_RESOURCE;
+        const PIXEL_SHADER_RESOURCE = d3d12::D3D12_RESOURCE_STATE_PIXEL_SHADER_RESOURCE;
+        const STREAM_OUT = d3d12::D3D12_RESOURCE_STATE_STREAM_OUT;
+        const INDIRECT_ARGUMENT = d3d12::D3D12_RESOURCE_STATE_INDIRECT_ARGUMENT;
+        const COPY_DEST = d3d12::D3D12_RESOURCE_STATE_COPY_DEST;
+        const COPY_SOURCE = d3d12::D3D12_RESOURCE_STATE_COPY_SOURCE;
+        const RESOLVE_DEST = d3d12::D3D12_RESOURCE_STATE_RESOLVE_DEST;
+        const RESOLVE_SOURCE = d3d12::D3D12_RESOURCE_STATE_RESOLVE_SOURCE;
+        const GENERIC_READ = d3d12::D3D12_RESOURCE_STATE_GENERIC_READ;
+        const PRESENT = d3d12::D3D12_RESOURCE_STATE_PRESENT;
+        const PREDICATION = d3d12::D3D12_RESOURCE_STATE_PREDICATION;
+    }
+}
+
+/// Optimized clear value, has to match the value passed to later clears to be fast.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClearValue {
+    Color {
+        format: Format,
+        color: [f32; 4],
+    },
+    DepthStencil {
+        format: Format,
+        depth: f32,
+        stencil: u8,
+    },
+}
+
+impl ClearValue {
+    pub fn format(&self) -> Format {
+        match *self {
+            ClearValue::Color { format, .. } | ClearValue::DepthStencil { format, .. } => format,
+        }
+    }
+
+    pub(crate) fn to_raw(self) -> d3d12::D3D12_CLEAR_VALUE {
+        let mut raw = d3d12::D3D12_CLEAR_VALUE {
+            Format: self.format() as _,
+            u: unsafe { mem::zeroed() },
+        };
+        match self {
+            ClearValue::Color { color, .. } => unsafe {
+                *raw.u.Color_mut() = color;
+            },
+            ClearValue::DepthStencil { depth, stencil, .. } => unsafe {
+                *raw.u.DepthStencil_mut() = d3d12::D3D12_DEPTH_STENCIL_VALUE {
+                    Depth: depth,
+                    Stencil: stencil,
+                };
+            },
+        }
+        raw
+    }
+}
+
+/// Reason a `ResourceDesc` or `ClearValue` would be rejected by the runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResourceDescError {
+    /// Width, height, depth or array size is zero.
+    ZeroSize,
+    /// Width, height, depth or array size exceeds the limit of the dimension.
+    ExceedsLimit {
+        value: u64,
+        limit: u64,
+    },
+    /// Buffers and 3D textures can't have array layers.
+    ArrayUnsupported,
+    /// Textures need a format.
+    UnknownFormat,
+    TooManyMipLevels {
+        requested: u16,
+        max: u16,
+    },
+    InvalidAlignment(u64),
+    InvalidSampleCount(u32),
+    /// Non-zero quality levels require multisampling.
+    InvalidSampleQuality(u32),
+    /// Multisampling is restricted to single mip 2D textures, which are render targets
+    /// or depth stencils and don't allow unordered access.
+    InvalidMultisampling,
+    /// The flags can't be combined with each other or with the dimension.
+    InvalidFlags(ResourceFlags),
+    /// Depth stencils need a depth or matching typeless format, render targets a color format.
+    InvalidFormatForFlags(Format),
+    /// Top level of block compressed textures must be a multiple of the block size.
+    UnalignedBlockCompressedSize,
+    InvalidLayout(TextureLayout),
+    /// The clear value doesn't match the usage or format of the resource.
+    InvalidClearValue,
+}
+
+impl fmt::Display for ResourceDescError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ResourceDescError::ZeroSize => write!(f, "resource has a zero sized dimension"),
+            ResourceDescError::ExceedsLimit { value, limit } => {
+                write!(f, "resource size {} exceeds the limit of {}", value, limit)
+            }
+            ResourceDescError::ArrayUnsupported => {
+                write!(f, "array size set on a buffer or 3D texture")
+            }
+            ResourceDescError::UnknownFormat => write!(f, "texture has an unknown format"),
+            ResourceDescError::TooManyMipLevels { requested, max } => write!(
+                f,
+                "{} mip levels requested, the resource has at most {}",
+                requested, max
+            ),
+            ResourceDescError::InvalidAlignment(alignment) => {
+                write!(f, "invalid resource alignment {}", alignment)
+            }
+            ResourceDescError::InvalidSampleCount(count) => {
+                write!(f, "invalid sample count {}", count)
+            }
+            ResourceDescError::InvalidSampleQuality(quality) => {
+                write!(f, "sample quality {} without multisampling", quality)
+            }
+            ResourceDescError::InvalidMultisampling => {
+                write!(f, "multisampling isn't supported for this resource")
+            }
+            ResourceDescError::InvalidFlags(flags) => {
+                write!(f, "invalid resource flags {:?}", flags)
+            }
+            ResourceDescError::InvalidFormatForFlags(format) => {
+                write!(
+                    f,
+                    "format {:?} can't be used with the resource flags",
+                    format
+                )
+            }
+            ResourceDescError::UnalignedBlockCompressedSize => write!(
+                f,
+                "block compressed texture size isn't a multiple of the block size"
+            ),
+            ResourceDescError::InvalidLayout(layout) => {
+                write!(f, "layout {:?} isn't supported for this resource", layout)
+            }
+            ResourceDescError::InvalidClearValue => {
+                write!(f, "clear value doesn't match the resource")
+            }
+        }
+    }
+}
+
+impl error::Error for ResourceDescError {}
+
+/// Description of a buffer or texture.
+///
+/// ```
+/// use d3d12::{Format, ResourceDesc, ResourceFlags};
+///
+/// let desc = ResourceDesc::texture_2d(Format::R8G8B8A8Unorm, 1024, 512)
+///     .with_full_mip_chain()
+///     .with_flags(ResourceFlags::ALLOW_RENDER_TARGET);
+/// assert_eq!(desc.mip_levels(), 11);
+/// assert!(desc.validate().is_ok());
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct ResourceDesc {
+    dimension: ResourceDimension,
+    alignment: u64,
+    width: u64,
+    height: u32,
+    depth_or_array_size: u16,
+    // `with_array_size` was called on a resource which can't be an array.
+    invalid_array_size: bool,
+    mip_levels: u16,
+    format: Format,
+    sample: SampleDesc,
+    layout: TextureLayout,
+    flags: ResourceFlags,
+}
+
+impl ResourceDesc {
+    fn new(dimension: ResourceDimension, format: Format, width: u64, height: u32) -> Self {
+        ResourceDesc {
+            dimension,
+            alignment: 0,
+            width,
+            height,
+            depth_or_array_size: 1,
+            invalid_array_size: false,
+            mip_levels: 1,
+            format,
+            sample: SampleDesc {
+                count: 1,
+                quality: 0,
+            },
+            layout: TextureLayout::Unknown,
+            flags: ResourceFlags::empty(),
+        }
+    }
+
+    pub fn buffer(size_in_bytes: u64) -> Self {
+        ResourceDesc {
+            layout: TextureLayout::RowMajor,
+            ..Self::new(ResourceDimension::Buffer, Format::Unknown, size_in_bytes, 1)
+        }
+    }
+
+    pub fn texture_1d(format: Format, width: u32) -> Self {
+        Self::new(ResourceDimension::Texture1D, format, width as _, 1)
+    }
+
+    pub fn texture_2d(format: Format, width: u32, height: u32) -> Self {
+        Self::new(ResourceDimension::Texture2D, format, width as _, height)
+    }
+
+    pub fn texture_3d(format: Format, width: u32, height: u32, depth: u16) -> Self {
+        ResourceDesc {
+            depth_or_array_size: depth,
+            ..Self::new(ResourceDimension::Texture3D, format, width as _, height)
+        }
+    }
+
+    /// Number of array layers of 1D and 2D textures, cube maps use 6 layers per cube.
+    ///
+    /// Buffers and 3D textures keep their size and fail `validate`.
+    pub fn with_array_size(mut self, array_size: u16) -> Self {
+        match self.dimension {
+            ResourceDimension::Texture1D | ResourceDimension::Texture2D => {
+                self.depth_or_array_size = array_size
+            }
+            ResourceDimension::Buffer | ResourceDimension::Texture3D => {
+                self.invalid_array_size = true
+            }
+        }
+        self
+    }
+
+    pub fn with_mip_levels(mut self, mip_levels: u16) -> Self {
+        self.mip_levels = mip_levels;
+        self
+    }
+
+    /// Mip levels down to a size of 1x1x1.
+    pub fn with_full_mip_chain(mut self) -> Self {
+        self.mip_levels = self.max_mip_levels();
+        self
+    }
+
+    pub fn with_sample(mut self, sample: SampleDesc) -> Self {
+        self.sample = sample;
+        self
+    }
+
+    pub fn with_layout(mut self, layout: TextureLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    pub fn with_flags(mut self, flags: ResourceFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Placement alignment, `0` picks the default for the resource.
+    pub fn with_alignment(mut self, alignment: u64) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn dimension(&self) -> ResourceDimension {
+        self.dimension
+    }
+
+    pub fn alignment(&self) -> u64 {
+        self.alignment
+    }
+
+    pub fn width(&self) -> u64 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn depth(&self) -> u16 {
+        match self.dimension {
+            ResourceDimension::Texture3D => self.depth_or_array_size,
+            _ => 1,
+        }
+    }
+
+    pub fn array_size(&self) -> u16 {
+        match self.dimension {
+            ResourceDimension::Texture3D => 1,
+            _ => self.depth_or_array_size,
+        }
+    }
+
+    pub fn mip_levels(&self) -> u16 {
+        self.mip_levels
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    pub fn sample(&self) -> SampleDesc {
+        self.sample
+    }
+
+    pub fn layout(&self) -> TextureLayout {
+        self.layout
+    }
+
+    pub fn flags(&self) -> ResourceFlags {
+        self.flags
+    }
+
+    /// Length of the full mip chain.
+    pub fn max_mip_levels(&self) -> u16 {
+        let extent = match self.dimension {
+            ResourceDimension::Buffer => return 1,
+            ResourceDimension::Texture1D => self.width,
+            ResourceDimension::Texture2D => self.width.max(self.height as _),
+            ResourceDimension::Texture3D => self
+                .width
+                .max(self.height as _)
+                .max(self.depth_or_array_size as _),
+        };
+        (64 - extent.max(1).leading_zeros()) as _
+    }
+
+    /// Number of subresources, including all planes of planar formats.
+    pub fn subresource_count(&self) -> u32 {
+        self.mip_levels as u32 * self.array_size() as u32 * self.format.plane_count()
+    }
+
+    /// Check the restrictions the runtime places on resource descriptions.
+    pub fn validate(&self) -> Result<(), ResourceDescError> {
+        let is_buffer = self.dimension == ResourceDimension::Buffer;
+
+        if self.invalid_array_size {
+            return Err(ResourceDescError::ArrayUnsupported);
+        }
+        if self.width == 0 || self.height == 0 || self.depth_or_array_size == 0 {
+            return Err(ResourceDescError::ZeroSize);
+        }
+        let (extent_limit, depth_limit) = match self.dimension {
+            ResourceDimension::Buffer => (u64::MAX, 1),
+            ResourceDimension::Texture1D => (
+                d3d12::D3D12_REQ_TEXTURE1D_U_DIMENSION as u64,
+                d3d12::D3D12_REQ_TEXTURE1D_ARRAY_AXIS_DIMENSION as u64,
+            ),
+            ResourceDimension::Texture2D => (
+                d3d12::D3D12_REQ_TEXTURE2D_U_OR_V_DIMENSION as u64,
+                d3d12::D3D12_REQ_TEXTURE2D_ARRAY_AXIS_DIMENSION as u64,
+            ),
+            ResourceDimension::Texture3D => (
+                d3d12::D3D12_REQ_TEXTURE3D_U_V_OR_W_DIMENSION as u64,
+                d3d12::D3D12_REQ_TEXTURE3D_U_V_OR_W_DIMENSION as u64,
+            ),
+        };
+        for &(value, limit) in &[
+            (self.width, extent_limit),
+            (self.height as u64, extent_limit),
+            (self.depth_or_array_size as u64, depth_limit),
+        ] {
+            if value > limit {
+                return Err(ResourceDescError::ExceedsLimit { value, limit });
+            }
+        }
+
+        if !is_buffer && self.format == Format::Unknown {
+            return Err(ResourceDescError::UnknownFormat);
+        }
+        let max = self.max_mip_levels();
+        if self.mip_levels == 0 || self.mip_levels > max {
+            return Err(ResourceDescError::TooManyMipLevels {
+                requested: self.mip_levels,
+                max,
+            });
+        }
+
+        let multisampled = self.sample.count > 1;
+        let small = d3d12::D3D12_SMALL_RESOURCE_PLACEMENT_ALIGNMENT as u64;
+        let default = d3d12::D3D12_DEFAULT_RESOURCE_PLACEMENT_ALIGNMENT as u64;
+        let msaa = d3d12::D3D12_DEFAULT_MSAA_RESOURCE_PLACEMENT_ALIGNMENT as u64;
+        let alignment_valid = match self.alignment {
+            0 => true,
+            alignment if alignment == default => true,
+            // Render targets and depth stencils need at least the default alignment.
+            alignment if alignment == small => {
+                !is_buffer
+                    && !self.flags.intersects(
+                        ResourceFlags::ALLOW_RENDER_TARGET | ResourceFlags::ALLOW_DEPTH_STENCIL,
+                    )
+            }
+            alignment if alignment == msaa => multisampled,
+            _ => false,
+        };
+        if !alignment_valid {
+            return Err(ResourceDescError::InvalidAlignment(self.alignment));
+        }
+
+        if !self.sample.count.is_power_of_two()
+            || self.sample.count > d3d12::D3D12_MAX_MULTISAMPLE_SAMPLE_COUNT
+        {
+            return Err(ResourceDescError::InvalidSampleCount(self.sample.count));
+        }
+        if !multisampled && self.sample.quality != 0 {
+            return Err(ResourceDescError::InvalidSampleQuality(self.sample.quality));
+        }
+        if multisampled
+            && (self.dimension != ResourceDimension::Texture2D
+                || self.mip_levels != 1
+                || !self.flags.intersects(
+                    ResourceFlags::ALLOW_RENDER_TARGET | ResourceFlags::ALLOW_DEPTH_STENCIL,
+                )
+                || self.flags.contains(ResourceFlags::ALLOW_UNORDERED_ACCESS))
+        {
+            return Err(ResourceDescError::InvalidMultisampling);
+        }
+
+        let depth_stencil = self.flags.contains(ResourceFlags::ALLOW_DEPTH_STENCIL);
+        let invalid_flags = if is_buffer {
+            self.flags
+                & (ResourceFlags::ALLOW_RENDER_TARGET
+                    | ResourceFlags::ALLOW_DEPTH_STENCIL
+                    | ResourceFlags::DENY_SHADER_RESOURCE
+                    | ResourceFlags::ALLOW_SIMULTANEOUS_ACCESS)
+        } else if depth_stencil {
+            let mut invalid = self.flags
+                & (ResourceFlags::ALLOW_RENDER_TARGET
+                    | ResourceFlags::ALLOW_UNORDERED_ACCESS
+                    | ResourceFlags::ALLOW_SIMULTANEOUS_ACCESS);
+            if self.dimension == ResourceDimension::Texture3D {
+                invalid |= ResourceFlags::ALLOW_DEPTH_STENCIL;
+            }
+            invalid
+        } else {
+            self.flags & ResourceFlags::DENY_SHADER_RESOURCE
+        };
+        if !invalid_flags.is_empty() {
+            return Err(ResourceDescError::InvalidFlags(invalid_flags));
+        }
+
+        if depth_stencil && !has_depth_aspect(self.format) {
+            return Err(ResourceDescError::InvalidFormatForFlags(self.format));
+        }
+        if self.format.is_depth_stencil()
+            && self.flags.intersects(
+                ResourceFlags::ALLOW_RENDER_TARGET | ResourceFlags::ALLOW_UNORDERED_ACCESS,
+            )
+        {
+            return Err(ResourceDescError::InvalidFormatForFlags(self.format));
+        }
+
+        if self.format.is_block_compressed() {
+            let (block_width, block_height) = self.format.block_dimensions();
+            // Block dimensions are powers of two.
+            if self.width & (block_width as u64 - 1) != 0 || self.height & (block_height - 1) != 0 {
+                return Err(ResourceDescError::UnalignedBlockCompressedSize);
+            }
+        }
+
+        let layout_valid = match self.layout {
+            TextureLayout::RowMajor => {
+                is_buffer
+                    || (self.dimension == ResourceDimension::Texture2D
+                        && self.mip_levels == 1
+                        && self.depth_or_array_size == 1
+                        && !multisampled)
+            }
+            TextureLayout::Unknown => !is_buffer,
+            TextureLayout::UndefinedSwizzle64KB | TextureLayout::StandardSwizzle64KB => {
+                !is_buffer
+                    && self.alignment != d3d12::D3D12_SMALL_RESOURCE_PLACEMENT_ALIGNMENT as u64
+            }
+        };
+        if !layout_valid {
+            return Err(ResourceDescError::InvalidLayout(self.layout));
+        }
+
+        Ok(())
+    }
+
+    /// Check the optimized clear value against the usage of the resource.
+    pub fn validate_clear_value(&self, clear_value: &ClearValue) -> Result<(), ResourceDescError> {
+        let valid = match *clear_value {
+            ClearValue::Color { format, .. } => {
+                self.flags.contains(ResourceFlags::ALLOW_RENDER_TARGET)
+                    && !format.is_depth_stencil()
+                    && !format.is_typeless()
+            }
+            ClearValue::DepthStencil { format, .. } => {
+                self.flags.contains(ResourceFlags::ALLOW_DEPTH_STENCIL) && format.is_depth_stencil()
+            }
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(ResourceDescError::InvalidClearValue)
+        }
+    }
+
+    /// Effective placement alignment inside of heaps.
+    pub fn placement_alignment(&self) -> u64 {
+        match self.alignment {
+            0 if self.sample.count > 1 => {
+                d3d12::D3D12_DEFAULT_MSAA_RESOURCE_PLACEMENT_ALIGNMENT as _
+            }
+            0 => d3d12::D3D12_DEFAULT_RESOURCE_PLACEMENT_ALIGNMENT as _,
+            alignment => alignment,
+        }
+    }
+
+    pub fn to_raw(&self) -> d3d12::D3D12_RESOURCE_DESC {
+        d3d12::D3D12_RESOURCE_DESC {
+            Dimension: self.dimension as _,
+            Alignment: self.alignment,
+            Width: self.width,
+            Height: self.height,
+            DepthOrArraySize: self.depth_or_array_size,
+            MipLevels: self.mip_levels,
+            Format: self.format as _,
+            SampleDesc: dxgitype::DXGI_SAMPLE_DESC {
+                Count: self.sample.count,
+                Quality: self.sample.quality,
+            },
+            Layout: self.layout as _,
+            Flags: self.flags.bits(),
+        }
+    }
+}
+
+// Formats which can back depth stencil views.
+fn has_depth_aspect(format: Format) -> bool {
+    format.is_depth_stencil()
+        || matches!(
+            format,
+            Format::R32G8X24Typeless
+                | Format::R32Typeless
+                | Format::R24G8Typeless
+                | Format::R16Typeless
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RT: ResourceFlags = ResourceFlags::ALLOW_RENDER_TARGET;
+    const DS: ResourceFlags = ResourceFlags::ALLOW_DEPTH_STENCIL;
+    const UAV: ResourceFlags = ResourceFlags::ALLOW_UNORDERED_ACCESS;
+    const SIMULTANEOUS: ResourceFlags = ResourceFlags::ALLOW_SIMULTANEOUS_ACCESS;
+
+    fn rgba(width: u32, height: u32) -> ResourceDesc {
+        ResourceDesc::texture_2d(Format::R8G8B8A8Unorm, width, height)
+    }
+
+    fn msaa(count: u32) -> SampleDesc {
+        SampleDesc { count, quality: 0 }
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(rgba(0, 4).validate(), Err(ResourceDescError::ZeroSize));
+        assert_eq!(
+            rgba(4, 4).with_array_size(0).validate(),
+            Err(ResourceDescError::ZeroSize)
+        );
+        assert_eq!(
+            ResourceDesc::buffer(0).validate(),
+            Err(ResourceDescError::ZeroSize)
+        );
+        assert_eq!(
+            rgba(16385, 4).validate(),
+            Err(ResourceDescError::ExceedsLimit {
+                value: 16385,
+                limit: 16384,
+            })
+        );
+        assert_eq!(
+            ResourceDesc::texture_3d(Format::R8Unorm, 4, 4, 2049).validate(),
+            Err(ResourceDescError::ExceedsLimit {
+                value: 2049,
+                limit: 2048,
+            })
+        );
+        assert!(rgba(16384, 16384).with_array_size(2048).validate().is_ok());
+        assert!(ResourceDesc::buffer(1 << 40).validate().is_ok());
+    }
+
+    #[test]
+    fn array_size() {
+        let volume = ResourceDesc::texture_3d(Format::R8Unorm, 4, 4, 8);
+        assert_eq!(volume.with_array_size(2).depth(), 8);
+        assert_eq!(
+            volume.with_array_size(2).validate(),
+            Err(ResourceDescError::ArrayUnsupported)
+        );
+        assert_eq!(
+            ResourceDesc::buffer(256).with_array_size(1).validate(),
+            Err(ResourceDescError::ArrayUnsupported)
+        );
+        let array = ResourceDesc::texture_1d(Format::R8Unorm, 64).with_array_size(6);
+        assert_eq!(array.array_size(), 6);
+        assert!(array.validate().is_ok());
+    }
+
+    #[test]
+    fn format_and_mips() {
+        assert_eq!(
+            ResourceDesc::texture_2d(Format::Unknown, 4, 4).validate(),
+            Err(ResourceDescError::UnknownFormat)
+        );
+        assert_eq!(
+            rgba(8, 8).with_mip_levels(5).validate(),
+            Err(ResourceDescError::TooManyMipLevels {
+                requested: 5,
+                max: 4,
+            })
+        );
+        assert_eq!(
+            rgba(8, 8).with_mip_levels(0).validate(),
+            Err(ResourceDescError::TooManyMipLevels {
+                requested: 0,
+                max: 4,
+            })
+        );
+        assert!(rgba(8, 8).with_full_mip_chain().validate().is_ok());
+        assert_eq!(
+            ResourceDesc::texture_2d(Format::Bc1Unorm, 6, 8).validate(),
+            Err(ResourceDescError::UnalignedBlockCompressedSize)
+        );
+        assert!(ResourceDesc::texture_2d(Format::Bc1Unorm, 8, 4)
+            .with_full_mip_chain()
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn alignment() {
+        let small = d3d12::D3D12_SMALL_RESOURCE_PLACEMENT_ALIGNMENT as u64;
+        let default = d3d12::D3D12_DEFAULT_RESOURCE_PLACEMENT_ALIGNMENT as u64;
+        let msaa_alignment = d3d12::D3D12_DEFAULT_MSAA_RESOURCE_PLACEMENT_ALIGNMENT as u64;
+
+        assert!(rgba(4, 4).with_alignment(small).validate().is_ok());
+        assert!(rgba(4, 4).with_alignment(default).validate().is_ok());
+        assert_eq!(
+            ResourceDesc::buffer(256).with_alignment(small).validate(),
+            Err(ResourceDescError::InvalidAlignment(small))
+        );
+        assert_eq!(
+            rgba(4, 4).with_alignment(small).with_flags(RT).validate(),
+            Err(ResourceDescError::InvalidAlignment(small))
+        );
+        assert_eq!(
+            ResourceDesc::texture_2d(Format::D32Float, 4, 4)
+                .with_alignment(small)
+                .with_flags(DS)
+                .validate(),
+            Err(ResourceDescError::InvalidAlignment(small))
+        );
+        assert_eq!(
+            rgba(4, 4).with_alignment(msaa_alignment).validate(),
+            Err(ResourceDescError::InvalidAlignment(msaa_alignment))
+        );
+        assert_eq!(
+            rgba(4, 4).with_alignment(1000).validate(),
+            Err(ResourceDescError::InvalidAlignment(1000))
+        );
+
+        let multisampled = rgba(4, 4).with_sample(msaa(4)).with_flags(RT);
+        assert!(multisampled
+            .with_alignment(msaa_alignment)
+            .validate()
+            .is_ok());
+        assert_eq!(multisampled.placement_alignment(), msaa_alignment);
+        assert_eq!(rgba(4, 4).placement_alignment(), default);
+        assert_eq!(
+            rgba(4, 4).with_alignment(small).placement_alignment(),
+            small
+        );
+    }
+
+    #[test]
+    fn multisampling() {
+        assert_eq!(
+            rgba(4, 4).with_sample(msaa(3)).with_flags(RT).validate(),
+            Err(ResourceDescError::InvalidSampleCount(3))
+        );
+        assert_eq!(
+            rgba(4, 4).with_sample(msaa(64)).with_flags(RT).validate(),
+            Err(ResourceDescError::InvalidSampleCount(64))
+        );
+        assert_eq!(
+            rgba(4, 4)
+                .with_sample(SampleDesc {
+                    count: 1,
+                    quality: 1,
+                })
+                .validate(),
+            Err(ResourceDescError::InvalidSampleQuality(1))
+        );
+
+        // MSAA needs single mip 2D render targets or depth stencils without UAV access.
+        for &desc in &[
+            rgba(4, 4).with_sample(msaa(4)),
+            rgba(4, 4).with_sample(msaa(4)).with_flags(RT | UAV),
+            rgba(4, 4)
+                .with_sample(msaa(4))
+                .with_flags(RT)
+                .with_mip_levels(2),
+            ResourceDesc::texture_1d(Format::R8G8B8A8Unorm, 4)
+                .with_sample(msaa(4))
+                .with_flags(RT),
+            ResourceDesc::texture_3d(Format::R8G8B8A8Unorm, 4, 4, 4)
+                .with_sample(msaa(4))
+                .with_flags(RT),
+        ] {
+            assert_eq!(
+                desc.validate(),
+                Err(ResourceDescError::InvalidMultisampling),
+                "{:?}",
+                desc
+            );
+        }
+        assert!(rgba(4, 4)
+            .with_sample(msaa(8))
+            .with_flags(RT)
+            .validate()
+            .is_ok());
+        assert!(ResourceDesc::texture_2d(Format::D24UnormS8Uint, 4, 4)
+            .with_sample(msaa(4))
+            .with_flags(DS)
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn flags() {
+        let buffer = ResourceDesc::buffer(256);
+        let depth = ResourceDesc::texture_2d(Format::D32Float, 4, 4);
+        assert_eq!(
+            buffer.with_flags(SIMULTANEOUS).validate(),
+            Err(ResourceDescError::InvalidFlags(SIMULTANEOUS))
+        );
+        assert_eq!(
+            buffer.with_flags(RT | UAV).validate(),
+            Err(ResourceDescError::InvalidFlags(RT))
+        );
+        assert!(buffer.with_flags(UAV).validate().is_ok());
+        assert_eq!(
+            depth.with_flags(DS | SIMULTANEOUS).validate(),
+            Err(ResourceDescError::InvalidFlags(SIMULTANEOUS))
+        );
+        assert_eq!(
+            depth.with_flags(DS | UAV).validate(),
+            Err(ResourceDescError::InvalidFlags(UAV))
+        );
+        assert_eq!(
+            ResourceDesc::texture_3d(Format::D32Float, 4, 4, 4)
+                .with_flags(DS)
+                .validate(),
+            Err(ResourceDescError::InvalidFlags(DS))
+        );
+        assert_eq!(
+            rgba(4, 4)
+                .with_flags(ResourceFlags::DENY_SHADER_RESOURCE)
+                .validate(),
+            Err(ResourceDescError::InvalidFlags(
+                ResourceFlags::DENY_SHADER_RESOURCE
+            ))
+        );
+        assert!(depth
+            .with_flags(DS | ResourceFlags::DENY_SHADER_RESOURCE)
+            .validate()
+            .is_ok());
+        assert!(rgba(4, 4).with_flags(RT | SIMULTANEOUS).validate().is_ok());
+
+        assert_eq!(
+            rgba(4, 4).with_flags(DS).validate(),
+            Err(ResourceDescError::InvalidFormatForFlags(
+                Format::R8G8B8A8Unorm
+            ))
+        );
+        assert_eq!(
+            depth.with_flags(RT).validate(),
+            Err(ResourceDescError::InvalidFormatForFlags(Format::D32Float))
+        );
+        assert!(ResourceDesc::texture_2d(Format::R24G8Typeless, 4, 4)
+            .with_flags(DS)
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn layouts() {
+        let row_major = rgba(4, 4).with_layout(TextureLayout::RowMajor);
+        assert!(row_major.validate().is_ok());
+        for &desc in &[
+            row_major.with_mip_levels(2),
+            row_major.with_array_size(2),
+            row_major.with_sample(msaa(4)).with_flags(RT),
+            ResourceDesc::texture_1d(Format::R8Unorm, 4).with_layout(TextureLayout::RowMajor),
+            ResourceDesc::texture_3d(Format::R8Unorm, 4, 4, 4).with_layout(TextureLayout::RowMajor),
+        ] {
+            assert_eq!(
+                desc.validate(),
+                Err(ResourceDescError::InvalidLayout(TextureLayout::RowMajor)),
+                "{:?}",
+                desc
+            );
+        }
+        assert_eq!(
+            ResourceDesc::buffer(256)
+                .with_layout(TextureLayout::Unknown)
+                .validate(),
+            Err(ResourceDescError::InvalidLayout(TextureLayout::Unknown))
+        );
+        assert_eq!(
+            rgba(4, 4)
+                .with_layout(TextureLayout::StandardSwizzle64KB)
+                .with_alignment(d3d12::D3D12_SMALL_RESOURCE_PLACEMENT_ALIGNMENT as u64)
+                .validate(),
+            Err(ResourceDescError::InvalidLayout(
+                TextureLayout::StandardSwizzle64KB
+            ))
+        );
+        assert!(rgba(4, 4)
+            .with_layout(TextureLayout::UndefinedSwizzle64KB)
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn clear_values() {
+        let color = |format| ClearValue::Color {
+            format,
+            color: [0.0; 4],
+        };
+        let depth = |format| ClearValue::DepthStencil {
+            format,
+            depth: 1.0,
+            stencil: 0,
+        };
+        let target = rgba(4, 4).with_flags(RT);
+        let depth_target = ResourceDesc::texture_2d(Format::R32Typeless, 4, 4).with_flags(DS);
+
+        assert!(target
+            .validate_clear_value(&color(Format::R8G8B8A8Unorm))
+            .is_ok());
+        assert!(depth_target
+            .validate_clear_value(&depth(Format::D32Float))
+            .is_ok());
+        for &(desc, value) in &[
+            (rgba(4, 4), color(Format::R8G8B8A8Unorm)),
+            (target, color(Format::R8G8B8A8Typeless)),
+            (target, depth(Format::D32Float)),
+            (depth_target, color(Format::R32Float)),
+            (depth_target, depth(Format::R32Float)),
+        ] {
+            assert_eq!(
+                desc.validate_clear_value(&value),
+                Err(ResourceDescError::InvalidClearValue),
+                "{:?}",
+                value
+            );
+        }
+    }
+}
@@ -0,0 +1,1643 @@
+//! Owned root signature descriptions
+//!
+//! `RootSignatureDesc` owns its parameters and samplers, and can be serialized without
+//! d3d12.dll, so root signatures can be baked on machines without D3D12. The output follows
+//! the format of `D3D12SerializeVersionedRootSignature`; it's compared against blobs from
+//! d3d12.dll for flags and root descriptors, the layout of descriptor tables, root
+//! constants and static samplers is only checked against the documented structures.
+
+use crate::{
+    build_dxbc_container, com::WeakPtr, dxbc_parts, Binding, D3DError, DescriptorRangeFlags,
+    DescriptorRangeType, RootDescriptorFlags, RootSignatureFlags, RootSignatureVersion,
+    ShaderVisibility, StaticBorderColor, TextureAddressMode, ROOT_SIGNATURE_PART,
+};
+#[cfg(any(feature = "libloading", feature = "implicit-link"))]
+use crate::{
+    versioned_desc_1_1, D3DResult, DescriptorRange, DescriptorRange1, IntoResult, RootParameter,
+    RootParameter1, StaticSampler,
+};
+#[cfg(any(feature = "libloading", feature = "implicit-link"))]
+use std::mem;
+use std::{
+    convert::TryInto,
+    error::Error,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Range,
+    ptr, slice,
+};
+#[cfg(any(feature = "libloading", feature = "implicit-link"))]
+use winapi::Interface as _;
+use winapi::{shared::winerror, um::d3d12};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DescriptorRangeDesc {
+    pub ty: DescriptorRangeType,
+    pub count: u32,
+    pub base_binding: Binding,
+    /// Offset from the start of the table, `D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND` to
+    /// follow the previous range.
+    pub offset: u32,
+    /// Ignored by version 1.0.
+    pub flags: DescriptorRangeFlags,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RootParameterKind {
+    DescriptorTable(Vec<DescriptorRangeDesc>),
+    Constants {
+        binding: Binding,
+        num_values: u32,
+    },
+    /// Root descriptors, the flags are ignored by version 1.0.
+    Cbv {
+        binding: Binding,
+        flags: RootDescriptorFlags,
+    },
+    Srv {
+        binding: Binding,
+        flags: RootDescriptorFlags,
+    },
+    Uav {
+        binding: Binding,
+        flags: RootDescriptorFlags,
+    },
+}
+
+impl RootParameterKind {
+    // Root descriptor from its type, `None` for tables and constants.
+    fn descriptor(
+        ty: d3d12::D3D12_ROOT_PARAMETER_TYPE,
+        binding: Binding,
+        flags: RootDescriptorFlags,
+    ) -> Option<Self> {
+        Some(match ty {
+            d3d12::D3D12_ROOT_PARAMETER_TYPE_CBV => RootParameterKind::Cbv { binding, flags },
+            d3d12::D3D12_ROOT_PARAMETER_TYPE_SRV => RootParameterKind::Srv { binding, flags },
+            d3d12::D3D12_ROOT_PARAMETER_TYPE_UAV => RootParameterKind::Uav { binding, flags },
+            _ => return None,
+        })
+    }
+
+    fn raw_type(&self) -> d3d12::D3D12_ROOT_PARAMETER_TYPE {
+        match *self {
+            RootParameterKind::DescriptorTable(_) => {
+                d3d12::D3D12_ROOT_PARAMETER_TYPE_DESCRIPTOR_TABLE
+            }
+            RootParameterKind::Constants { .. } => d3d12::D3D12_ROOT_PARAMETER_TYPE_32BIT_CONSTANTS,
+            RootParameterKind::Cbv { .. } => d3d12::D3D12_ROOT_PARAMETER_TYPE_CBV,
+            RootParameterKind::Srv { .. } => d3d12::D3D12_ROOT_PARAMETER_TYPE_SRV,
+            RootParameterKind::Uav { .. } => d3d12::D3D12_ROOT_PARAMETER_TYPE_UAV,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RootParameterDesc {
+    pub visibility: ShaderVisibility,
+    pub kind: RootParameterKind,
+}
+
+/// Static sampler, floating point fields are compared and hashed by their bits.
+#[derive(Clone, Debug)]
+pub struct StaticSamplerDesc {
+    pub visibility: ShaderVisibility,
+    pub binding: Binding,
+    pub filter: d3d12::D3D12_FILTER,
+    pub address_mode: TextureAddressMode,
+    pub mip_lod_bias: f32,
+    pub max_anisotropy: u32,
+    pub comparison_op: d3d12::D3D12_COMPARISON_FUNC,
+    pub border_color: StaticBorderColor,
+    pub lod: Range<f32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RootSignatureDesc {
+    pub version: RootSignatureVersion,
+    pub parameters: Vec<RootParameterDesc>,
+    pub static_samplers: Vec<StaticSamplerDesc>,
+    pub flags: RootSignatureFlags,
+}
+
+impl PartialEq for StaticSamplerDesc {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_words() == other.to_words()
+    }
+}
+
+impl Eq for StaticSamplerDesc {}
+
+impl Hash for StaticSamplerDesc {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_words().hash(state);
+    }
+}
+
+impl DescriptorRangeDesc {
+    pub fn new(
+        ty: DescriptorRangeType,
+        count: u32,
+        base_binding: Binding,
+        offset: u32,
+        flags: DescriptorRangeFlags,
+    ) -> Self {
+        DescriptorRangeDesc {
+            ty,
+            count,
+            base_binding,
+            offset,
+            flags,
+        }
+    }
+}
+
+impl RootSignatureDesc {
+    /// Empty root signature.
+    ///
+    /// Parameters are added with the `with_*` methods, in the order of their root indices.
+    ///
+    /// ```
+    /// use d3d12::{
+    ///     Binding, DescriptorRangeDesc, DescriptorRangeFlags, DescriptorRangeType,
+    ///     RootDescriptorFlags, RootSignatureDesc, RootSignatureFlags, RootSignatureVersion,
+    ///     ShaderVisibility,
+    /// };
+    ///
+    /// let binding = |register| Binding { space: 0, register };
+    /// let desc = RootSignatureDesc::new(RootSignatureVersion::V1_1)
+    ///     .with_flags(RootSignatureFlags::ALLOW_IA_INPUT_LAYOUT)
+    ///     .with_constants(ShaderVisibility::VS, binding(0), 4)
+    ///     .with_cbv(ShaderVisibility::All, binding(1), RootDescriptorFlags::DATA_STATIC)
+    ///     .with_descriptor_table(
+    ///         ShaderVisibility::PS,
+    ///         vec![DescriptorRangeDesc::new(
+    ///             DescriptorRangeType::SRV,
+    ///             2,
+    ///             binding(0),
+    ///             0,
+    ///             DescriptorRangeFlags::empty(),
+    ///         )],
+    ///     );
+    /// assert_eq!(desc.parameters.len(), 3);
+    /// assert_eq!(desc.clone(), desc);
+    /// ```
+    pub fn new(version: RootSignatureVersion) -> Self {
+        RootSignatureDesc {
+            version,
+            parameters: Vec::new(),
+            static_samplers: Vec::new(),
+            flags: RootSignatureFlags::empty(),
+        }
+    }
+
+    pub fn with_flags(mut self, flags: RootSignatureFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    pub fn with_parameter(mut self, visibility: ShaderVisibility, kind: RootParameterKind) -> Self {
+        self.parameters.push(RootParameterDesc { visibility, kind });
+        self
+    }
+
+    pub fn with_descriptor_table(
+        self,
+        visibility: ShaderVisibility,
+        ranges: Vec<DescriptorRangeDesc>,
+    ) -> Self {
+        self.with_parameter(visibility, RootParameterKind::DescriptorTable(ranges))
+    }
+
+    pub fn with_constants(
+        self,
+        visibility: ShaderVisibility,
+        binding: Binding,
+        num_values: u32,
+    ) -> Self {
+        self.with_parameter(
+            visibility,
+            RootParameterKind::Constants {
+                binding,
+                num_values,
+            },
+        )
+    }
+
+    pub fn with_cbv(
+        self,
+        visibility: ShaderVisibility,
+        binding: Binding,
+        flags: RootDescriptorFlags,
+    ) -> Self {
+        self.with_parameter(visibility, RootParameterKind::Cbv { binding, flags })
+    }
+
+    pub fn with_srv(
+        self,
+        visibility: ShaderVisibility,
+        binding: Binding,
+        flags: RootDescriptorFlags,
+    ) -> Self {
+        self.with_parameter(visibility, RootParameterKind::Srv { binding, flags })
+    }
+
+    pub fn with_uav(
+        self,
+        visibility: ShaderVisibility,
+        binding: Binding,
+        flags: RootDescriptorFlags,
+    ) -> Self {
+        self.with_parameter(visibility, RootParameterKind::Uav { binding, flags })
+    }
+
+    pub fn with_static_sampler(mut self, sampler: StaticSamplerDesc) -> Self {
+        self.static_samplers.push(sampler);
+        self
+    }
+
+    /// DXBC container with the serialized root signature, in the format returned by
+    /// `D3D12SerializeVersionedRootSignature`.
+    ///
+    /// Descriptor tables, root constants and static samplers haven't been compared against
+    /// the output of d3d12.dll, serialize with `RootSignature::serialize_desc` where exact
+    /// bytes matter.
+    ///
+    /// ```
+    /// use d3d12::{RootSignatureDesc, RootSignatureVersion};
+    ///
+    /// // Serialized by d3d12.dll.
+    /// let reference: [u32; 17] = [
+    ///     0x43425844, 0xd64afc1d, 0x5dc27735, 0x9edacb4a, 0x6bd8a7fa, 0x00000001, 0x00000044,
+    ///     0x00000001, 0x00000024, 0x30535452, 0x00000018, 0x00000001, 0x00000000, 0x00000018,
+    ///     0x00000000, 0x00000018, 0x00000000,
+    /// ];
+    /// let reference: Vec<u8> = reference.iter().flat_map(|word| word.to_le_bytes().to_vec()).collect();
+    /// assert_eq!(RootSignatureDesc::new(RootSignatureVersion::V1_0).serialize(), reference);
+    /// ```
+    pub fn serialize(&self) -> Vec<u8> {
+        build_dxbc_container(&[(ROOT_SIGNATURE_PART, &self.serialize_rts0())])
+    }
+
+    /// Contents of the `RTS0` part of a serialized root signature.
+    ///
+    /// A header is followed by the parameters, their payloads and the static samplers.
+    /// Payloads and samplers are referenced by their offset in the part.
+    pub fn serialize_rts0(&self) -> Vec<u8> {
+        let v1_1 = self.version >= RootSignatureVersion::V1_1;
+        let mut words = vec![
+            self.version as u32,
+            self.parameters.len() as u32,
+            24,
+            self.static_samplers.len() as u32,
+            0,
+            self.flags.bits(),
+        ];
+
+        let parameters_start = words.len();
+        for parameter in &self.parameters {
+            words.extend_from_slice(&[parameter.kind.raw_type(), parameter.visibility as u32, 0]);
+        }
+
+        for (i, parameter) in self.parameters.iter().enumerate() {
+            let payload_offset = 4 * words.len() as u32;
+            words[parameters_start + 3 * i + 2] = payload_offset;
+            match parameter.kind {
+                RootParameterKind::DescriptorTable(ref ranges) => {
+                    words.extend_from_slice(&[ranges.len() as u32, payload_offset + 8]);
+                    for range in ranges {
+                        words.extend_from_slice(&[
+                            range.ty as u32,
+                            range.count,
+                            range.base_binding.register,
+                            range.base_binding.space,
+                        ]);
+                        if v1_1 {
+                            words.push(range.flags.bits());
+                        }
+                        words.push(range.offset);
+                    }
+                }
+                RootParameterKind::Constants {
+                    binding,
+                    num_values,
+                } => {
+                    words.extend_from_slice(&[binding.register, binding.space, num_values]);
+                }
+                RootParameterKind::Cbv { binding, flags }
+                | RootParameterKind::Srv { binding, flags }
+                | RootParameterKind::Uav { binding, flags } => {
+                    words.extend_from_slice(&[binding.register, binding.space]);
+                    if v1_1 {
+                        words.push(flags.bits());
+                    }
+                }
+            }
+        }
+
+        words[4] = 4 * words.len() as u32;
+        for sampler in &self.static_samplers {
+            words.extend_from_slice(&sampler.to_words());
+        }
+
+        let mut bytes = Vec::with_capacity(4 * words.len());
+        for word in words {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        bytes
+    }
+}
+
+impl StaticSamplerDesc {
+    // Fields in the order of `D3D12_STATIC_SAMPLER_DESC`.
+    fn to_words(&self) -> [u32; 13] {
+        [
+            self.filter,
+            self.address_mode[0],
+            self.address_mode[1],
+            self.address_mode[2],
+            self.mip_lod_bias.to_bits(),
+            self.max_anisotropy,
+            self.comparison_op,
+            self.border_color as u32,
+            self.lod.start.to_bits(),
+            self.lod.end.to_bits(),
+            self.binding.register,
+            self.binding.space,
+            self.visibility as u32,
+        ]
+    }
+
+    fn from_words(words: [u32; 13]) -> Result<Self, RootSignatureParseError> {
+        Ok(StaticSamplerDesc {
+            filter: words[0],
+            address_mode: [words[1], words[2], words[3]],
+            mip_lod_bias: f32::from_bits(words[4]),
+            max_anisotropy: words[5],
+            comparison_op: words[6],
+            border_color: StaticBorderColor::from_raw(words[7])
+                .ok_or(RootSignatureParseError::UnknownBorderColor(words[7]))?,
+            lod: f32::from_bits(words[8])..f32::from_bits(words[9]),
+            binding: Binding {
+                register: words[10],
+                space: words[11],
+            },
+            visibility: shader_visibility(words[12])?,
+        })
+    }
+}
+
+/// Error of parsing a serialized root signature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RootSignatureParseError {
+    /// The data isn't a DXBC container.
+    InvalidContainer,
+    /// The container has no `RTS0` part.
+    MissingRootSignature,
+    /// An offset or count points past the end of the data.
+    OutOfBounds(usize),
+    UnknownVersion(u32),
+    UnknownParameterType(u32),
+    UnknownShaderVisibility(u32),
+    UnknownRangeType(u32),
+    UnknownBorderColor(u32),
+    UnknownFlags(u32),
+}
+
+impl fmt::Display for RootSignatureParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RootSignatureParseError::InvalidContainer => write!(f, "not a DXBC container"),
+            RootSignatureParseError::MissingRootSignature => {
+                write!(f, "container has no root signature")
+            }
+            RootSignatureParseError::OutOfBounds(offset) => {
+                write!(f, "offset {} is out of bounds", offset)
+            }
+            RootSignatureParseError::UnknownVersion(version) => {
+                write!(f, "unknown root signature version {}", version)
+            }
+            RootSignatureParseError::UnknownParameterType(ty) => {
+                write!(f, "unknown root parameter type {}", ty)
+            }
+            RootSignatureParseError::UnknownShaderVisibility(visibility) => {
+                write!(f, "unknown shader visibility {}", visibility)
+            }
+            RootSignatureParseError::UnknownRangeType(ty) => {
+                write!(f, "unknown descriptor range type {}", ty)
+            }
+            RootSignatureParseError::UnknownBorderColor(color) => {
+                write!(f, "unknown static border color {}", color)
+            }
+            RootSignatureParseError::UnknownFlags(flags) => write!(f, "unknown flags {:#x}", flags),
+        }
+    }
+}
+
+impl Error for RootSignatureParseError {}
+
+fn shader_visibility(raw: u32) -> Result<ShaderVisibility, RootSignatureParseError> {
+    ShaderVisibility::from_raw(raw).ok_or(RootSignatureParseError::UnknownShaderVisibility(raw))
+}
+
+fn range_type(raw: u32) -> Result<DescriptorRangeType, RootSignatureParseError> {
+    DescriptorRangeType::from_raw(raw).ok_or(RootSignatureParseError::UnknownRangeType(raw))
+}
+
+fn range_flags(raw: u32) -> Result<DescriptorRangeFlags, RootSignatureParseError> {
+    DescriptorRangeFlags::from_bits(raw).ok_or(RootSignatureParseError::UnknownFlags(raw))
+}
+
+fn descriptor_flags(raw: u32) -> Result<RootDescriptorFlags, RootSignatureParseError> {
+    RootDescriptorFlags::from_bits(raw).ok_or(RootSignatureParseError::UnknownFlags(raw))
+}
+
+// Little endian words of an `RTS0` part.
+struct Rts0<'a>(&'a [u8]);
+
+impl Rts0<'_> {
+    // Word at `base + offset`, both usually read from the data.
+    fn word(&self, base: usize, offset: usize) -> Result<u32, RootSignatureParseError> {
+        base.checked_add(offset)
+            .and_then(|start| self.0.get(start..start.checked_add(4)?))
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+            .ok_or(RootSignatureParseError::OutOfBounds(
+                base.saturating_add(offset),
+            ))
+    }
+
+    fn offset(&self, base: usize, offset: usize) -> Result<usize, RootSignatureParseError> {
+        self.word(base, offset).map(|word| word as usize)
+    }
+}
+
+impl RootSignatureDesc {
+    /// Parse a DXBC container with a root signature, either serialized or embedded in
+    /// shader bytecode compiled with a `[RootSignature(...)]` attribute.
+    ///
+    /// ```
+    /// use d3d12::{
+    ///     Binding, RootDescriptorFlags, RootParameterDesc, RootParameterKind, RootSignatureDesc,
+    ///     RootSignatureVersion, ShaderVisibility,
+    /// };
+    ///
+    /// let mut desc = RootSignatureDesc::new(RootSignatureVersion::V1_1);
+    /// desc.parameters.push(RootParameterDesc {
+    ///     visibility: ShaderVisibility::All,
+    ///     kind: RootParameterKind::Cbv {
+    ///         binding: Binding { space: 0, register: 3 },
+    ///         flags: RootDescriptorFlags::DATA_STATIC,
+    ///     },
+    /// });
+    /// assert_eq!(RootSignatureDesc::deserialize(&desc.serialize()), Ok(desc));
+    /// ```
+    pub fn deserialize(data: &[u8]) -> Result<Self, RootSignatureParseError> {
+        let (_, rts0) = dxbc_parts(data)
+            .ok_or(RootSignatureParseError::InvalidContainer)?
+            .into_iter()
+            .find(|&(part, _)| part == ROOT_SIGNATURE_PART)
+            .ok_or(RootSignatureParseError::MissingRootSignature)?;
+        Self::deserialize_rts0(rts0)
+    }
+
+    /// Parse the contents of an `RTS0` part, inverse of `serialize_rts0`.
+    pub fn deserialize_rts0(data: &[u8]) -> Result<Self, RootSignatureParseError> {
+        let rts0 = Rts0(data);
+        let version = rts0.word(0, 0)?;
+        let version = RootSignatureVersion::from_raw(version)
+            .ok_or(RootSignatureParseError::UnknownVersion(version))?;
+        let v1_1 = version >= RootSignatureVersion::V1_1;
+        let flags = rts0.word(0, 20)?;
+        let mut desc = RootSignatureDesc::new(version);
+        desc.flags = RootSignatureFlags::from_bits(flags)
+            .ok_or(RootSignatureParseError::UnknownFlags(flags))?;
+
+        let parameters_offset = rts0.offset(0, 8)?;
+        for i in 0..rts0.offset(0, 4)? {
+            let offset = 12 * i;
+            let ty = rts0.word(parameters_offset, offset)?;
+            let visibility = shader_visibility(rts0.word(parameters_offset, offset + 4)?)?;
+            let payload = rts0.offset(parameters_offset, offset + 8)?;
+
+            let kind = match ty {
+                d3d12::D3D12_ROOT_PARAMETER_TYPE_DESCRIPTOR_TABLE => {
+                    let ranges_offset = rts0.offset(payload, 4)?;
+                    let stride = if v1_1 { 24 } else { 20 };
+                    let mut ranges = Vec::new();
+                    for j in 0..rts0.offset(payload, 0)? {
+                        let range = stride * j;
+                        ranges.push(DescriptorRangeDesc {
+                            ty: range_type(rts0.word(ranges_offset, range)?)?,
+                            count: rts0.word(ranges_offset, range + 4)?,
+                            base_binding: Binding {
+                                register: rts0.word(ranges_offset, range + 8)?,
+                                space: rts0.word(ranges_offset, range + 12)?,
+                            },
+                            flags: if v1_1 {
+                                range_flags(rts0.word(ranges_offset, range + 16)?)?
+                            } else {
+                                DescriptorRangeFlags::empty()
+                            },
+                            offset: rts0.word(ranges_offset, range + stride - 4)?,
+                        });
+                    }
+                    RootParameterKind::DescriptorTable(ranges)
+                }
+                d3d12::D3D12_ROOT_PARAMETER_TYPE_32BIT_CONSTANTS => RootParameterKind::Constants {
+                    binding: Binding {
+                        register: rts0.word(payload, 0)?,
+                        space: rts0.word(payload, 4)?,
+                    },
+                    num_values: rts0.word(payload, 8)?,
+                },
+                d3d12::D3D12_ROOT_PARAMETER_TYPE_CBV
+                | d3d12::D3D12_ROOT_PARAMETER_TYPE_SRV
+                | d3d12::D3D12_ROOT_PARAMETER_TYPE_UAV => {
+                    let binding = Binding {
+                        register: rts0.word(payload, 0)?,
+                        space: rts0.word(payload, 4)?,
+                    };
+                    let flags = if v1_1 {
+                        descriptor_flags(rts0.word(payload, 8)?)?
+                    } else {
+                        RootDescriptorFlags::empty()
+                    };
+                    RootParameterKind::descriptor(ty, binding, flags).unwrap()
+                }
+                _ => return Err(RootSignatureParseError::UnknownParameterType(ty)),
+            };
+            desc.parameters.push(RootParameterDesc { visibility, kind });
+        }
+
+        let samplers_offset = rts0.offset(0, 16)?;
+        for i in 0..rts0.offset(0, 12)? {
+            let mut words = [0; 13];
+            for (j, word) in words.iter_mut().enumerate() {
+                *word = rts0.word(samplers_offset, 52 * i + 4 * j)?;
+            }
+            desc.static_samplers
+                .push(StaticSamplerDesc::from_words(words)?);
+        }
+
+        Ok(desc)
+    }
+
+    /// Copy a description returned by the runtime.
+    ///
+    /// # Safety
+    ///
+    /// The pointers in `raw` must be valid for the counts next to them.
+    pub unsafe fn from_raw(
+        raw: &d3d12::D3D12_VERSIONED_ROOT_SIGNATURE_DESC,
+    ) -> Result<Self, RootSignatureParseError> {
+        let version = RootSignatureVersion::from_raw(raw.Version)
+            .ok_or(RootSignatureParseError::UnknownVersion(raw.Version))?;
+        let mut desc = RootSignatureDesc::new(version);
+
+        let (flags, samplers) = match version {
+            RootSignatureVersion::V1_0 => {
+                let raw = raw.u.Desc_1_0();
+                for parameter in raw_slice(raw.pParameters, raw.NumParameters) {
+                    let kind = match parameter.ParameterType {
+                        d3d12::D3D12_ROOT_PARAMETER_TYPE_DESCRIPTOR_TABLE => {
+                            let table = parameter.u.DescriptorTable();
+                            let ranges =
+                                raw_slice(table.pDescriptorRanges, table.NumDescriptorRanges)
+                                    .iter()
+                                    .map(|range| {
+                                        Ok(DescriptorRangeDesc {
+                                            ty: range_type(range.RangeType)?,
+                                            count: range.NumDescriptors,
+                                            base_binding: Binding {
+                                                register: range.BaseShaderRegister,
+                                                space: range.RegisterSpace,
+                                            },
+                                            offset: range.OffsetInDescriptorsFromTableStart,
+                                            flags: DescriptorRangeFlags::empty(),
+                                        })
+                                    })
+                                    .collect::<Result<_, _>>()?;
+                            RootParameterKind::DescriptorTable(ranges)
+                        }
+                        d3d12::D3D12_ROOT_PARAMETER_TYPE_32BIT_CONSTANTS => {
+                            let constants = parameter.u.Constants();
+                            RootParameterKind::Constants {
+                                binding: Binding {
+                                    register: constants.ShaderRegister,
+                                    space: constants.RegisterSpace,
+                                },
+                                num_values: constants.Num32BitValues,
+                            }
+                        }
+                        ty => {
+                            let descriptor = parameter.u.Descriptor();
+                            let binding = Binding {
+                                register: descriptor.ShaderRegister,
+                                space: descriptor.RegisterSpace,
+                            };
+                            RootParameterKind::descriptor(ty, binding, RootDescriptorFlags::empty())
+                                .ok_or(RootSignatureParseError::UnknownParameterType(ty))?
+                        }
+                    };
+                    desc.parameters.push(RootParameterDesc {
+                        visibility: shader_visibility(parameter.ShaderVisibility)?,
+                        kind,
+                    });
+                }
+                (
+                    raw.Flags,
+                    raw_slice(raw.pStaticSamplers, raw.NumStaticSamplers),
+                )
+            }
+            RootSignatureVersion::V1_1 => {
+                let raw = raw.u.Desc_1_1();
+                for parameter in raw_slice(raw.pParameters, raw.NumParameters) {
+                    let kind = match parameter.ParameterType {
+                        d3d12::D3D12_ROOT_PARAMETER_TYPE_DESCRIPTOR_TABLE => {
+                            let table = parameter.u.DescriptorTable();
+                            let ranges =
+                                raw_slice(table.pDescriptorRanges, table.NumDescriptorRanges)
+                                    .iter()
+                                    .map(|range| {
+                                        Ok(DescriptorRangeDesc {
+                                            ty: range_type(range.RangeType)?,
+                                            count: range.NumDescriptors,
+                                            base_binding: Binding {
+                                                register: range.BaseShaderRegister,
+                                                space: range.RegisterSpace,
+                                            },
+                                            offset: range.OffsetInDescriptorsFromTableStart,
+                                            flags: range_flags(range.Flags)?,
+                                        })
+                                    })
+                                    .collect::<Result<_, _>>()?;
+                            RootParameterKind::DescriptorTable(ranges)
+                        }
+                        d3d12::D3D12_ROOT_PARAMETER_TYPE_32BIT_CONSTANTS => {
+                            let constants = parameter.u.Constants();
+                            RootParameterKind::Constants {
+                                binding: Binding {
+                                    register: constants.ShaderRegister,
+                                    space: constants.RegisterSpace,
+                                },
+                                num_values: constants.Num32BitValues,
+                            }
+                        }
+                        ty => {
+                            let descriptor = parameter.u.Descriptor();
+                            let binding = Binding {
+                                register: descriptor.ShaderRegister,
+                                space: descriptor.RegisterSpace,
+                            };
+                            RootParameterKind::descriptor(
+                                ty,
+                                binding,
+                                descriptor_flags(descriptor.Flags)?,
+                            )
+                            .ok_or(RootSignatureParseError::UnknownParameterType(ty))?
+                        }
+                    };
+                    desc.parameters.push(RootParameterDesc {
+                        visibility: shader_visibility(parameter.ShaderVisibility)?,
+                        kind,
+                    });
+                }
+                (
+                    raw.Flags,
+                    raw_slice(raw.pStaticSamplers, raw.NumStaticSamplers),
+                )
+            }
+        };
+
+        desc.flags = RootSignatureFlags::from_bits(flags)
+            .ok_or(RootSignatureParseError::UnknownFlags(flags))?;
+        for sampler in samplers {
+            desc.static_samplers.push(StaticSamplerDesc::from_words([
+                sampler.Filter,
+                sampler.AddressU,
+                sampler.AddressV,
+                sampler.AddressW,
+                sampler.MipLODBias.to_bits(),
+                sampler.MaxAnisotropy,
+                sampler.ComparisonFunc,
+                sampler.BorderColor,
+                sampler.MinLOD.to_bits(),
+                sampler.MaxLOD.to_bits(),
+                sampler.ShaderRegister,
+                sampler.RegisterSpace,
+                sampler.ShaderVisibility,
+            ])?);
+        }
+
+        Ok(desc)
+    }
+
+    /// Copy a version 1.0 description built from raw parameters, to `validate` or serialize
+    /// it with `serialize_root_signature_desc`.
+    ///
+    /// # Safety
+    ///
+    /// The ranges of tables created with `RootParameter::descriptor_table` must be alive.
+    pub unsafe fn from_parameters(
+        parameters: &[crate::RootParameter],
+        static_samplers: &[crate::StaticSampler],
+        flags: RootSignatureFlags,
+    ) -> Result<Self, RootSignatureParseError> {
+        let mut raw = d3d12::D3D12_VERSIONED_ROOT_SIGNATURE_DESC {
+            Version: d3d12::D3D_ROOT_SIGNATURE_VERSION_1_0,
+            ..std::mem::zeroed()
+        };
+        *raw.u.Desc_1_0_mut() = d3d12::D3D12_ROOT_SIGNATURE_DESC {
+            NumParameters: parameters.len() as _,
+            pParameters: parameters.as_ptr() as *const _,
+            NumStaticSamplers: static_samplers.len() as _,
+            pStaticSamplers: static_samplers.as_ptr() as _,
+            Flags: flags.bits(),
+        };
+        Self::from_raw(&raw)
+    }
+
+    /// Copy a version 1.1 description built from raw parameters, to `validate` it before
+    /// passing the parameters to `serialize_root_signature_1_1`.
+    pub fn from_parameters_1_1(
+        parameters: &[crate::RootParameter1],
+        static_samplers: &[crate::StaticSampler],
+        flags: RootSignatureFlags,
+    ) -> Result<Self, RootSignatureParseError> {
+        let mut raw = d3d12::D3D12_VERSIONED_ROOT_SIGNATURE_DESC {
+            Version: d3d12::D3D_ROOT_SIGNATURE_VERSION_1_1,
+            ..unsafe { std::mem::zeroed() }
+        };
+        unsafe {
+            *raw.u.Desc_1_1_mut() = d3d12::D3D12_ROOT_SIGNATURE_DESC1 {
+                NumParameters: parameters.len() as _,
+                pParameters: parameters.as_ptr() as *const _,
+                NumStaticSamplers: static_samplers.len() as _,
+                pStaticSamplers: static_samplers.as_ptr() as _,
+                Flags: flags.bits(),
+            };
+            // The ranges of the tables are borrowed by the parameters.
+            Self::from_raw(&raw)
+        }
+    }
+}
+
+// Empty arrays may come with null pointers.
+unsafe fn raw_slice<'a, T>(data: *const T, count: u32) -> &'a [T] {
+    if count == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(data, count as usize)
+    }
+}
+
+impl RootSignatureDesc {
+    /// Call `f` with the description in the layout of the runtime, which borrows the
+    /// ranges and samplers kept alive for the duration of the call.
+    #[cfg(any(feature = "libloading", feature = "implicit-link"))]
+    pub(crate) fn with_raw<R>(
+        &self,
+        f: impl FnOnce(&d3d12::D3D12_VERSIONED_ROOT_SIGNATURE_DESC) -> R,
+    ) -> R {
+        let static_samplers: Vec<_> = self
+            .static_samplers
+            .iter()
+            .map(|sampler| {
+                StaticSampler::new(
+                    sampler.visibility,
+                    sampler.binding,
+                    sampler.filter,
+                    sampler.address_mode,
+                    sampler.mip_lod_bias,
+                    sampler.max_anisotropy,
+                    sampler.comparison_op,
+                    sampler.border_color,
+                    sampler.lod.clone(),
+                )
+            })
+            .collect();
+        let tables = self
+            .parameters
+            .iter()
+            .map(|parameter| match parameter.kind {
+                RootParameterKind::DescriptorTable(ref ranges) => &ranges[..],
+                _ => &[],
+            });
+
+        match self.version {
+            RootSignatureVersion::V1_0 => {
+                let ranges: Vec<Vec<_>> = tables
+                    .map(|ranges| {
+                        ranges
+                            .iter()
+                            .map(|range| {
+                                DescriptorRange::new(
+                                    range.ty,
+                                    range.count,
+                                    range.base_binding,
+                                    range.offset,
+                                )
+                            })
+                            .collect()
+                    })
+                    .collect();
+                let parameters: Vec<_> = self
+                    .parameters
+                    .iter()
+                    .zip(&ranges)
+                    .map(|(parameter, ranges)| match parameter.kind {
+                        RootParameterKind::DescriptorTable(_) => {
+                            RootParameter::borrowed_table(parameter.visibility, ranges)
+                        }
+                        RootParameterKind::Constants {
+                            binding,
+                            num_values,
+                        } => RootParameter::constants(parameter.visibility, binding, num_values),
+                        RootParameterKind::Cbv { binding, .. }
+                        | RootParameterKind::Srv { binding, .. }
+                        | RootParameterKind::Uav { binding, .. } => RootParameter::descriptor(
+                            parameter.kind.raw_type(),
+                            parameter.visibility,
+                            binding,
+                        ),
+                    })
+                    .collect();
+
+                let mut desc = d3d12::D3D12_VERSIONED_ROOT_SIGNATURE_DESC {
+                    Version: d3d12::D3D_ROOT_SIGNATURE_VERSION_1_0,
+                    ..unsafe { mem::zeroed() }
+                };
+                *unsafe { desc.u.Desc_1_0_mut() } = d3d12::D3D12_ROOT_SIGNATURE_DESC {
+                    NumParameters: parameters.len() as _,
+                    pParameters: parameters.as_ptr() as *const _,
+                    NumStaticSamplers: static_samplers.len() as _,
+                    pStaticSamplers: static_samplers.as_ptr() as _,
+                    Flags: self.flags.bits(),
+                };
+                f(&desc)
+            }
+            RootSignatureVersion::V1_1 => {
+                let ranges: Vec<Vec<_>> = tables
+                    .map(|ranges| {
+                        ranges
+                            .iter()
+                            .map(|range| {
+                                DescriptorRange1::new(
+                                    range.ty,
+                                    range.count,
+                                    range.base_binding,
+                                    range.offset,
+                                    range.flags,
+                                )
+                            })
+                            .collect()
+                    })
+                    .collect();
+                let parameters: Vec<_> = self
+                    .parameters
+                    .iter()
+                    .zip(&ranges)
+                    .map(|(parameter, ranges)| match parameter.kind {
+                        RootParameterKind::DescriptorTable(_) => {
+                            RootParameter1::descriptor_table(parameter.visibility, ranges)
+                        }
+                        RootParameterKind::Constants {
+                            binding,
+                            num_values,
+                        } => RootParameter1::constants(parameter.visibility, binding, num_values),
+                        RootParameterKind::Cbv { binding, flags }
+                        | RootParameterKind::Srv { binding, flags }
+                        | RootParameterKind::Uav { binding, flags } => RootParameter1::descriptor(
+                            parameter.kind.raw_type(),
+                            parameter.visibility,
+                            binding,
+                            flags,
+                        ),
+                    })
+                    .collect();
+
+                f(&versioned_desc_1_1(
+                    &parameters,
+                    &static_samplers,
+                    self.flags,
+                ))
+            }
+        }
+    }
+}
+
+pub type RootSignatureDeserializer = WeakPtr<d3d12::ID3D12VersionedRootSignatureDeserializer>;
+
+#[cfg(feature = "libloading")]
+impl crate::D3D12Lib {
+    /// Deserializer for a serialized root signature or shader bytecode with one.
+    #[track_caller]
+    pub fn create_root_signature_deserializer(
+        &self,
+        data: &[u8],
+    ) -> Result<D3DResult<RootSignatureDeserializer>, libloading::Error> {
+        type Fun = extern "system" fn(
+            *const winapi::ctypes::c_void,
+            winapi::shared::basetsd::SIZE_T,
+            winapi::shared::guiddef::REFIID,
+            *mut *mut winapi::ctypes::c_void,
+        ) -> crate::HRESULT;
+
+        let mut deserializer = RootSignatureDeserializer::null();
+        let hr = unsafe {
+            let func: libloading::Symbol<Fun> = self
+                .lib
+                .get(b"D3D12CreateVersionedRootSignatureDeserializer")?;
+            func(
+                data.as_ptr() as *const _,
+                data.len(),
+                &d3d12::ID3D12VersionedRootSignatureDeserializer::uuidof(),
+                deserializer.mut_void(),
+            )
+        };
+
+        crate::ledger::track_created(&deserializer);
+        Ok((deserializer, hr))
+    }
+
+    #[track_caller]
+    pub fn try_create_root_signature_deserializer(
+        &self,
+        data: &[u8],
+    ) -> Result<Result<RootSignatureDeserializer, D3DError>, libloading::Error> {
+        self.create_root_signature_deserializer(data)
+            .map(|result| result.into_result("D3D12CreateVersionedRootSignatureDeserializer"))
+    }
+}
+
+impl RootSignatureDeserializer {
+    /// Deserializer for a serialized root signature or shader bytecode with one.
+    #[cfg(feature = "implicit-link")]
+    #[track_caller]
+    pub fn create(data: &[u8]) -> D3DResult<Self> {
+        let mut deserializer = RootSignatureDeserializer::null();
+        let hr = unsafe {
+            d3d12::D3D12CreateVersionedRootSignatureDeserializer(
+                data.as_ptr() as *const _,
+                data.len(),
+                &d3d12::ID3D12VersionedRootSignatureDeserializer::uuidof(),
+                deserializer.mut_void(),
+            )
+        };
+
+        crate::ledger::track_created(&deserializer);
+        (deserializer, hr)
+    }
+
+    #[cfg(feature = "implicit-link")]
+    #[track_caller]
+    pub fn try_create(data: &[u8]) -> Result<Self, D3DError> {
+        Self::create(data).into_result("D3D12CreateVersionedRootSignatureDeserializer")
+    }
+
+    /// Description converted to `version` by the runtime.
+    pub fn desc(&self, version: RootSignatureVersion) -> Result<RootSignatureDesc, D3DError> {
+        let call = "ID3D12VersionedRootSignatureDeserializer::GetRootSignatureDescAtVersion";
+        let mut raw = ptr::null_mut();
+        let hr = unsafe { self.GetRootSignatureDescAtVersion(version as _, &mut raw) };
+        if !winerror::SUCCEEDED(hr) {
+            return Err(D3DError::new(call, hr));
+        }
+        unsafe { RootSignatureDesc::from_raw(&*raw) }.map_err(|error| {
+            D3DError::new(call, winerror::E_UNEXPECTED).with_message(error.to_string())
+        })
+    }
+
+    /// Description in the version it was serialized with.
+    pub fn unconverted_desc(&self) -> Result<RootSignatureDesc, D3DError> {
+        unsafe { RootSignatureDesc::from_raw(&*self.GetUnconvertedRootSignatureDesc()) }.map_err(
+            |error| {
+                D3DError::new(
+                    "ID3D12VersionedRootSignatureDeserializer::GetUnconvertedRootSignatureDesc",
+                    winerror::E_UNEXPECTED,
+                )
+                .with_message(error.to_string())
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dxbc_checksum;
+
+    // Root signatures serialized by d3d12.dll, from the root signature tests of vkd3d.
+    const EMPTY_1_0: [u32; 17] = [
+        0x43425844, 0xd64afc1d, 0x5dc27735, 0x9edacb4a, 0x6bd8a7fa, 0x00000001, 0x00000044,
+        0x00000001, 0x00000024, 0x30535452, 0x00000018, 0x00000001, 0x00000000, 0x00000018,
+        0x00000000, 0x00000018, 0x00000000,
+    ];
+    const EMPTY_1_1: [u32; 17] = [
+        0x43425844, 0x791882cb, 0x83c1db39, 0x327edc93, 0x3163085b, 0x00000001, 0x00000044,
+        0x00000001, 0x00000024, 0x30535452, 0x00000018, 0x00000002, 0x00000000, 0x00000018,
+        0x00000000, 0x00000018, 0x00000000,
+    ];
+    // RootFlags(ALLOW_INPUT_ASSEMBLER_INPUT_LAYOUT)
+    const IA_LAYOUT_1_0: [u32; 17] = [
+        0x43425844, 0x05bbd62e, 0xc74d3646, 0xde1407a5, 0x0d99273d, 0x00000001, 0x00000044,
+        0x00000001, 0x00000024, 0x30535452, 0x00000018, 0x00000001, 0x00000000, 0x00000018,
+        0x00000000, 0x00000018, 0x00000001,
+    ];
+    // RootFlags(DENY_PIXEL_SHADER_ROOT_ACCESS)
+    const DENY_PS_1_0: [u32; 17] = [
+        0x43425844, 0xfad3a4ce, 0xf246286e, 0xeaa9e176, 0x278d5137, 0x00000001, 0x00000044,
+        0x00000001, 0x00000024, 0x30535452, 0x00000018, 0x00000001, 0x00000000, 0x00000018,
+        0x00000000, 0x00000018, 0x00000020,
+    ];
+    // CBV(b3)
+    const CBV_1_0: [u32; 22] = [
+        0x43425844, 0x8dc5087e, 0x5cb9bf0d, 0x2e465ae3, 0x6291e0e0, 0x00000001, 0x00000058,
+        0x00000001, 0x00000024, 0x30535452, 0x0000002c, 0x00000001, 0x00000001, 0x00000018,
+        0x00000000, 0x0000002c, 0x00000000, 0x00000002, 0x00000000, 0x00000024, 0x00000003,
+        0x00000000,
+    ];
+
+    fn bytes(words: &[u32]) -> Vec<u8> {
+        words
+            .iter()
+            .flat_map(|word| word.to_le_bytes().to_vec())
+            .collect()
+    }
+
+    fn binding(register: u32) -> Binding {
+        Binding { space: 0, register }
+    }
+
+    #[test]
+    fn reference_blobs() {
+        let cases = [
+            (
+                RootSignatureDesc::new(RootSignatureVersion::V1_0),
+                &EMPTY_1_0[..],
+            ),
+            (
+                RootSignatureDesc::new(RootSignatureVersion::V1_1),
+                &EMPTY_1_1[..],
+            ),
+            (
+                RootSignatureDesc::new(RootSignatureVersion::V1_0)
+                    .with_flags(RootSignatureFlags::ALLOW_IA_INPUT_LAYOUT),
+                &IA_LAYOUT_1_0[..],
+            ),
+            (
+                RootSignatureDesc::new(RootSignatureVersion::V1_0)
+                    .with_flags(RootSignatureFlags::DENY_PS_ROOT_ACCESS),
+                &DENY_PS_1_0[..],
+            ),
+            (
+                RootSignatureDesc::new(RootSignatureVersion::V1_0).with_cbv(
+                    ShaderVisibility::All,
+                    binding(3),
+                    RootDescriptorFlags::empty(),
+                ),
+                &CBV_1_0[..],
+            ),
+        ];
+        for (desc, reference) in cases.iter() {
+            let reference = bytes(reference);
+            // The checksum of the copied blob still matches, so it wasn't altered.
+            assert_eq!(dxbc_checksum(&reference[20..]), reference[4..20]);
+            assert_eq!(desc.serialize(), reference, "{:?}", desc);
+        }
+    }
+
+    // Layouts below have no blob from d3d12.dll to compare against, the expected words follow
+    // the `D3D12_ROOT_SIGNATURE_DESC` and `D3D12_ROOT_SIGNATURE_DESC1` structures.
+
+    fn table() -> Vec<DescriptorRangeDesc> {
+        vec![
+            DescriptorRangeDesc::new(
+                DescriptorRangeType::CBV,
+                1,
+                Binding {
+                    space: 7,
+                    register: 1,
+                },
+                d3d12::D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND,
+                DescriptorRangeFlags::DESCRIPTORS_VOLATILE,
+            ),
+            DescriptorRangeDesc::new(
+                DescriptorRangeType::SRV,
+                8,
+                binding(16),
+                d3d12::D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND,
+                DescriptorRangeFlags::DESCRIPTORS_VOLATILE | DescriptorRangeFlags::DATA_VOLATILE,
+            ),
+            DescriptorRangeDesc::new(
+                DescriptorRangeType::UAV,
+                !0,
+                binding(3),
+                44,
+                DescriptorRangeFlags::DATA_STATIC_WHILE_SET_AT_EXECUTE,
+            ),
+        ]
+    }
+
+    #[test]
+    fn descriptor_table() {
+        let desc = RootSignatureDesc::new(RootSignatureVersion::V1_0)
+            .with_descriptor_table(ShaderVisibility::All, table());
+        #[rustfmt::skip]
+        let expected = [
+            1, 1, 24, 0, 104, 0,
+            0, 0, 36,
+            3, 44,
+            2, 1, 1, 7, !0,
+            0, 8, 16, 0, !0,
+            1, !0, 3, 0, 44,
+        ];
+        assert_eq!(desc.serialize_rts0(), bytes(&expected));
+
+        let desc = RootSignatureDesc {
+            version: RootSignatureVersion::V1_1,
+            ..desc
+        };
+        #[rustfmt::skip]
+        let expected = [
+            2, 1, 24, 0, 116, 0,
+            0, 0, 36,
+            3, 44,
+            2, 1, 1, 7, 0x1, !0,
+            0, 8, 16, 0, 0x3, !0,
+            1, !0, 3, 0, 0x4, 44,
+        ];
+        assert_eq!(desc.serialize_rts0(), bytes(&expected));
+    }
+
+    #[test]
+    fn root_constants() {
+        let desc = RootSignatureDesc::new(RootSignatureVersion::V1_0)
+            .with_constants(ShaderVisibility::All, binding(4), 3)
+            .with_constants(
+                ShaderVisibility::VS,
+                Binding {
+                    space: 3,
+                    register: 5,
+                },
+                4,
+            );
+        #[rustfmt::skip]
+        let expected = [
+            1, 2, 24, 0, 72, 0,
+            1, 0, 48,
+            1, 1, 60,
+            4, 0, 3,
+            5, 3, 4,
+        ];
+        assert_eq!(desc.serialize_rts0(), bytes(&expected));
+    }
+
+    #[test]
+    fn root_descriptors() {
+        let desc = RootSignatureDesc::new(RootSignatureVersion::V1_1)
+            .with_flags(RootSignatureFlags::ALLOW_IA_INPUT_LAYOUT)
+            .with_cbv(
+                ShaderVisibility::All,
+                binding(0),
+                RootDescriptorFlags::DATA_STATIC,
+            )
+            .with_srv(
+                ShaderVisibility::PS,
+                Binding {
+                    space: 2,
+                    register: 1,
+                },
+                RootDescriptorFlags::DATA_VOLATILE,
+            )
+            .with_uav(
+                ShaderVisibility::All,
+                binding(2),
+                RootDescriptorFlags::empty(),
+            );
+        #[rustfmt::skip]
+        let expected = [
+            2, 3, 24, 0, 96, 1,
+            2, 0, 60,
+            3, 5, 72,
+            4, 0, 84,
+            0, 0, 8,
+            1, 2, 2,
+            2, 0, 0,
+        ];
+        assert_eq!(desc.serialize_rts0(), bytes(&expected));
+
+        // Version 1.0 drops the flags.
+        let desc = RootSignatureDesc {
+            version: RootSignatureVersion::V1_0,
+            ..desc
+        };
+        #[rustfmt::skip]
+        let expected = [
+            1, 3, 24, 0, 84, 1,
+            2, 0, 60,
+            3, 5, 68,
+            4, 0, 76,
+            0, 0,
+            1, 2,
+            2, 0,
+        ];
+        assert_eq!(desc.serialize_rts0(), bytes(&expected));
+    }
+
+    #[test]
+    fn static_samplers() {
+        let desc = RootSignatureDesc::new(RootSignatureVersion::V1_0)
+            .with_cbv(
+                ShaderVisibility::All,
+                binding(3),
+                RootDescriptorFlags::empty(),
+            )
+            .with_static_sampler(StaticSamplerDesc {
+                visibility: ShaderVisibility::PS,
+                binding: Binding {
+                    space: 1,
+                    register: 2,
+                },
+                filter: d3d12::D3D12_FILTER_ANISOTROPIC,
+                address_mode: [
+                    d3d12::D3D12_TEXTURE_ADDRESS_MODE_WRAP,
+                    d3d12::D3D12_TEXTURE_ADDRESS_MODE_CLAMP,
+                    d3d12::D3D12_TEXTURE_ADDRESS_MODE_WRAP,
+                ],
+                mip_lod_bias: 0.5,
+                max_anisotropy: 16,
+                comparison_op: d3d12::D3D12_COMPARISON_FUNC_LESS_EQUAL,
+                border_color: StaticBorderColor::OpaqueWhite,
+                lod: 0.0..1000.0,
+            });
+        #[rustfmt::skip]
+        let expected = [
+            1, 1, 24, 1, 44, 0,
+            2, 0, 36,
+            3, 0,
+            0x55, 1, 3, 1, 0x3f00_0000, 16, 4, 2, 0, 0x447a_0000, 2, 1, 5,
+        ];
+        assert_eq!(desc.serialize_rts0(), bytes(&expected));
+        // Samplers follow the payloads, as in `CBV_1_0` where they are empty.
+        assert_eq!(desc.serialize().len(), CBV_1_0.len() * 4 + 13 * 4);
+    }
+
+    fn parsed() -> RootSignatureDesc {
+        RootSignatureDesc::new(RootSignatureVersion::V1_1)
+            .with_descriptor_table(
+                ShaderVisibility::PS,
+                vec![DescriptorRangeDesc::new(
+                    DescriptorRangeType::SRV,
+                    2,
+                    binding(0),
+                    0,
+                    DescriptorRangeFlags::DATA_VOLATILE,
+                )],
+            )
+            .with_cbv(
+                ShaderVisibility::All,
+                binding(1),
+                RootDescriptorFlags::DATA_STATIC,
+            )
+            .with_static_sampler(StaticSamplerDesc {
+                visibility: ShaderVisibility::All,
+                binding: binding(0),
+                filter: d3d12::D3D12_FILTER_MIN_MAG_MIP_LINEAR,
+                address_mode: [d3d12::D3D12_TEXTURE_ADDRESS_MODE_WRAP; 3],
+                mip_lod_bias: 0.0,
+                max_anisotropy: 1,
+                comparison_op: d3d12::D3D12_COMPARISON_FUNC_LESS_EQUAL,
+                border_color: StaticBorderColor::OpaqueBlack,
+                lod: 0.0..f32::MAX,
+            })
+    }
+
+    // Words of `parsed()`, with one of them replaced.
+    //
+    //  0..6   header: version, parameters, offset 24, samplers, offset 92, flags
+    //  6..12  parameters: table with payload at 48, CBV with payload at 80
+    // 12..14  table: one range at 56
+    // 14..20  range: type, count, register, space, flags, offset
+    // 20..23  CBV: register, space, flags
+    // 23..36  sampler, border color at 30
+    fn patched(index: usize, value: u32) -> Vec<u8> {
+        let mut data = parsed().serialize_rts0();
+        assert_eq!(data.len(), 36 * 4);
+        data[4 * index..4 * index + 4].copy_from_slice(&value.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn round_trip() {
+        let desc = parsed();
+        assert_eq!(RootSignatureDesc::deserialize(&desc.serialize()), Ok(desc));
+        for reference in [&EMPTY_1_0[..], &EMPTY_1_1[..], &CBV_1_0[..]].iter() {
+            let reference = bytes(reference);
+            let desc = RootSignatureDesc::deserialize(&reference).unwrap();
+            assert_eq!(desc.serialize(), reference);
+        }
+    }
+
+    #[test]
+    fn truncated() {
+        let rts0 = parsed().serialize_rts0();
+        for len in 0..rts0.len() {
+            assert!(
+                matches!(
+                    RootSignatureDesc::deserialize_rts0(&rts0[..len]),
+                    Err(RootSignatureParseError::OutOfBounds(_))
+                ),
+                "{} bytes",
+                len
+            );
+        }
+
+        let container = parsed().serialize();
+        for len in 0..container.len() {
+            assert_eq!(
+                RootSignatureDesc::deserialize(&container[..len]),
+                Err(RootSignatureParseError::InvalidContainer),
+                "{} bytes",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn out_of_range_offsets() {
+        let end = 36 * 4;
+        let cases = [
+            // Parameters, table payload, ranges, CBV payload and samplers.
+            (2, end, end),
+            (2, !0, !0),
+            (8, end - 4, end),
+            (13, end - 20, end),
+            (13, 0x8000_0000, 0x8000_0000),
+            (11, end - 8, end),
+            (4, end - 48, end),
+        ];
+        for &(index, value, offset) in cases.iter() {
+            assert_eq!(
+                RootSignatureDesc::deserialize_rts0(&patched(index, value)),
+                Err(RootSignatureParseError::OutOfBounds(offset as usize)),
+                "word {} set to {:#x}",
+                index,
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn huge_counts() {
+        let end = 36 * 4;
+        // A third parameter is read from the table payload, its visibility is the ranges offset.
+        assert_eq!(
+            RootSignatureDesc::deserialize_rts0(&patched(1, !0)),
+            Err(RootSignatureParseError::UnknownShaderVisibility(56))
+        );
+        // More ranges are read from the CBV payload and the sampler, up to its maximum LOD.
+        assert_eq!(
+            RootSignatureDesc::deserialize_rts0(&patched(12, !0)),
+            Err(RootSignatureParseError::UnknownRangeType(
+                f32::MAX.to_bits()
+            ))
+        );
+        assert_eq!(
+            RootSignatureDesc::deserialize_rts0(&patched(3, !0)),
+            Err(RootSignatureParseError::OutOfBounds(end))
+        );
+        // Counts past the data fail before anything is allocated for them.
+        let mut data = patched(3, !0);
+        data[4 * 4..4 * 5].copy_from_slice(&(end as u32).to_le_bytes());
+        assert_eq!(
+            RootSignatureDesc::deserialize_rts0(&data),
+            Err(RootSignatureParseError::OutOfBounds(end))
+        );
+    }
+
+    #[test]
+    fn unknown_values() {
+        let cases = [
+            (0, 3, RootSignatureParseError::UnknownVersion(3)),
+            (5, 0x1000, RootSignatureParseError::UnknownFlags(0x1000)),
+            (6, 5, RootSignatureParseError::UnknownParameterType(5)),
+            (7, 8, RootSignatureParseError::UnknownShaderVisibility(8)),
+            (14, 4, RootSignatureParseError::UnknownRangeType(4)),
+            (18, 0x20, RootSignatureParseError::UnknownFlags(0x20)),
+            (22, 1, RootSignatureParseError::UnknownFlags(1)),
+            (30, 3, RootSignatureParseError::UnknownBorderColor(3)),
+            (35, 9, RootSignatureParseError::UnknownShaderVisibility(9)),
+        ];
+        for &(index, value, error) in cases.iter() {
+            assert_eq!(
+                RootSignatureDesc::deserialize_rts0(&patched(index, value)),
+                Err(error),
+                "word {} set to {:#x}",
+                index,
+                value
+            );
+        }
+
+        // Version 1.0 has no flags for ranges and root descriptors.
+        let desc = RootSignatureDesc {
+            version: RootSignatureVersion::V1_0,
+            ..parsed()
+        };
+        let mut expected = desc.clone();
+        for parameter in &mut expected.parameters {
+            match parameter.kind {
+                RootParameterKind::DescriptorTable(ref mut ranges) => {
+                    ranges[0].flags = DescriptorRangeFlags::empty()
+                }
+                RootParameterKind::Cbv { ref mut flags, .. } => {
+                    *flags = RootDescriptorFlags::empty()
+                }
+                _ => unreachable!(),
+            }
+        }
+        assert_eq!(
+            RootSignatureDesc::deserialize_rts0(&desc.serialize_rts0()),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn containers() {
+        assert_eq!(
+            RootSignatureDesc::deserialize(b"not a container"),
+            Err(RootSignatureParseError::InvalidContainer)
+        );
+        let mut container = parsed().serialize();
+        container[0] = b'X';
+        assert_eq!(
+            RootSignatureDesc::deserialize(&container),
+            Err(RootSignatureParseError::InvalidContainer)
+        );
+        let shader = build_dxbc_container(&[(*b"SHEX", &[0; 8])]);
+        assert_eq!(
+            RootSignatureDesc::deserialize(&shader),
+            Err(RootSignatureParseError::MissingRootSignature)
+        );
+    }
+
+    // Part layout of a pixel shader compiled by fxc with a `[RootSignature(...)]` attribute.
+    // Only the root signature part has real contents, the others are placeholders.
+    #[test]
+    fn shader_container() {
+        let desc = parsed();
+        let rts0 = desc.serialize_rts0();
+        let placeholder = [0u8; 16];
+        let shader = build_dxbc_container(&[
+            (*b"RDEF", &placeholder),
+            (*b"ISGN", &placeholder),
+            (*b"OSGN", &placeholder),
+            (*b"SHEX", &placeholder),
+            (*b"STAT", &placeholder),
+            (ROOT_SIGNATURE_PART, &rts0),
+        ]);
+        assert_eq!(
+            crate::find_dxbc_part(&shader, ROOT_SIGNATURE_PART),
+            Some(&rts0[..])
+        );
+        assert_eq!(RootSignatureDesc::deserialize(&shader), Ok(desc));
+    }
+
+    #[test]
+    fn from_parameters() {
+        use crate::{
+            DescriptorRange, DescriptorRange1, RootParameter, RootParameter1,
+            RootSignatureDiagnostic, RootSignatureIssue, RootSignatureLocation, StaticSampler,
+        };
+
+        let samplers = [StaticSampler::new(
+            ShaderVisibility::All,
+            binding(0),
+            d3d12::D3D12_FILTER_MIN_MAG_MIP_LINEAR,
+            [d3d12::D3D12_TEXTURE_ADDRESS_MODE_WRAP; 3],
+            0.0,
+            1,
+            d3d12::D3D12_COMPARISON_FUNC_LESS_EQUAL,
+            StaticBorderColor::OpaqueBlack,
+            0.0..f32::MAX,
+        )];
+        let ranges = [DescriptorRange1::new(
+            DescriptorRangeType::SRV,
+            2,
+            binding(0),
+            0,
+            DescriptorRangeFlags::DATA_VOLATILE,
+        )];
+        let parameters = [
+            RootParameter1::descriptor_table(ShaderVisibility::PS, &ranges),
+            RootParameter1::cbv_descriptor(
+                ShaderVisibility::All,
+                binding(1),
+                RootDescriptorFlags::DATA_STATIC,
+            ),
+        ];
+        assert_eq!(
+            RootSignatureDesc::from_parameters_1_1(
+                &parameters,
+                &samplers,
+                RootSignatureFlags::empty()
+            ),
+            Ok(parsed())
+        );
+
+        let ranges = [DescriptorRange::new(
+            DescriptorRangeType::SRV,
+            2,
+            binding(0),
+            0,
+        )];
+        let parameters = [
+            RootParameter::borrowed_table(ShaderVisibility::PS, &ranges),
+            RootParameter::cbv_descriptor(ShaderVisibility::All, binding(1)),
+            RootParameter::cbv_descriptor(ShaderVisibility::VS, binding(1)),
+        ];
+        let desc = unsafe {
+            RootSignatureDesc::from_parameters(
+                &parameters,
+                &[],
+                RootSignatureFlags::ALLOW_IA_INPUT_LAYOUT,
+            )
+        }
+        .unwrap();
+        assert_eq!(desc.version, RootSignatureVersion::V1_0);
+        assert_eq!(desc.flags, RootSignatureFlags::ALLOW_IA_INPUT_LAYOUT);
+        assert_eq!(
+            desc.validate(),
+            [RootSignatureDiagnostic {
+                location: RootSignatureLocation::Parameter(2),
+                issue: RootSignatureIssue::OverlappingRegisters {
+                    ty: DescriptorRangeType::CBV,
+                    space: 0,
+                    other: RootSignatureLocation::Parameter(1),
+                },
+            }]
+        );
+
+        let unknown = [RootParameter::descriptor(
+            99,
+            ShaderVisibility::All,
+            binding(0),
+        )];
+        assert_eq!(
+            unsafe {
+                RootSignatureDesc::from_parameters(&unknown, &[], RootSignatureFlags::empty())
+            },
+            Err(RootSignatureParseError::UnknownParameterType(99))
+        );
+    }
+
+    #[cfg(any(feature = "libloading", feature = "implicit-link"))]
+    #[test]
+    fn raw_round_trip() {
+        let round_trip = |desc: &RootSignatureDesc| {
+            desc.with_raw(|raw| {
+                assert_eq!(raw.Version, desc.version as u32);
+                unsafe { RootSignatureDesc::from_raw(raw) }
+            })
+        };
+
+        let desc = parsed()
+            .with_flags(RootSignatureFlags::ALLOW_IA_INPUT_LAYOUT)
+            .with_constants(ShaderVisibility::VS, binding(2), 4)
+            .with_descriptor_table(ShaderVisibility::All, table())
+            .with_descriptor_table(ShaderVisibility::DS, Vec::new())
+            .with_uav(
+                ShaderVisibility::All,
+                binding(5),
+                RootDescriptorFlags::DATA_VOLATILE,
+            );
+        assert_eq!(round_trip(&desc), Ok(desc.clone()));
+        assert_eq!(
+            round_trip(&RootSignatureDesc::new(RootSignatureVersion::V1_0)),
+            Ok(RootSignatureDesc::new(RootSignatureVersion::V1_0))
+        );
+
+        // Version 1.0 has no flags for ranges and root descriptors.
+        let desc_1_0 = RootSignatureDesc {
+            version: RootSignatureVersion::V1_0,
+            ..desc
+        };
+        let mut expected = desc_1_0.clone();
+        for parameter in &mut expected.parameters {
+            match parameter.kind {
+                RootParameterKind::DescriptorTable(ref mut ranges) => {
+                    for range in ranges {
+                        range.flags = DescriptorRangeFlags::empty();
+                    }
+                }
+                RootParameterKind::Cbv { ref mut flags, .. }
+                | RootParameterKind::Srv { ref mut flags, .. }
+                | RootParameterKind::Uav { ref mut flags, .. } => {
+                    *flags = RootDescriptorFlags::empty()
+                }
+                RootParameterKind::Constants { .. } => {}
+            }
+        }
+        assert_eq!(round_trip(&desc_1_0), Ok(expected));
+    }
+}
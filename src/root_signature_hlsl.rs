@@ -0,0 +1,1415 @@
+//! Parser for root signatures written in HLSL
+//!
+//! Accepts the string of a `[RootSignature(...)]` attribute, without the quotes, and
+//! produces a `RootSignatureDesc`. Errors point at the line and column of the offending
+//! token.
+
+use crate::{
+    Binding, ComparisonFunc, DescriptorRangeDesc, DescriptorRangeFlags, DescriptorRangeType,
+    RootDescriptorFlags, RootParameterDesc, RootParameterKind, RootSignatureDesc,
+    RootSignatureFlags, RootSignatureVersion, ShaderVisibility, StaticBorderColor,
+    StaticSamplerDesc,
+};
+use std::{error::Error, fmt};
+use winapi::um::d3d12;
+
+/// Error in the source of a root signature, lines and columns start at 1.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RootSignatureSyntaxError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for RootSignatureSyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl Error for RootSignatureSyntaxError {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TokenKind<'a> {
+    Ident(&'a str),
+    Number(&'a str),
+    Punct(char),
+    End,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Token<'a> {
+    kind: TokenKind<'a>,
+    line: usize,
+    column: usize,
+}
+
+impl fmt::Display for TokenKind<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TokenKind::Ident(text) | TokenKind::Number(text) => write!(f, "`{}`", text),
+            TokenKind::Punct(c) => write!(f, "`{}`", c),
+            TokenKind::End => write!(f, "end of input"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token<'_>>, RootSignatureSyntaxError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    let (mut line, mut column) = (1, 1);
+
+    while let Some(&(start, c)) = chars.peek() {
+        let (token_line, token_column) = (line, column);
+        let mut end = start + c.len_utf8();
+        chars.next();
+        column += 1;
+
+        let kind = if c == '\n' {
+            line += 1;
+            column = 1;
+            continue;
+        } else if c.is_whitespace() {
+            continue;
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || c == '_') {
+                    break;
+                }
+                end = i + 1;
+                chars.next();
+                column += 1;
+            }
+            TokenKind::Ident(&source[start..end])
+        } else if c.is_ascii_digit() || "+-.".contains(c) {
+            let mut previous = c;
+            while let Some(&(i, c)) = chars.peek() {
+                let exponent_sign = "+-".contains(c) && "eE".contains(previous);
+                if !(c.is_ascii_alphanumeric() || c == '.' || exponent_sign) {
+                    break;
+                }
+                previous = c;
+                end = i + 1;
+                chars.next();
+                column += 1;
+            }
+            TokenKind::Number(&source[start..end])
+        } else if "(),=|".contains(c) {
+            TokenKind::Punct(c)
+        } else {
+            return Err(RootSignatureSyntaxError {
+                line: token_line,
+                column: token_column,
+                message: format!("unexpected character `{}`", c),
+            });
+        };
+
+        tokens.push(Token {
+            kind,
+            line: token_line,
+            column: token_column,
+        });
+    }
+
+    tokens.push(Token {
+        kind: TokenKind::End,
+        line,
+        column,
+    });
+    Ok(tokens)
+}
+
+const ROOT_FLAGS: &[(&str, u32)] = &[
+    (
+        "ALLOW_INPUT_ASSEMBLER_INPUT_LAYOUT",
+        RootSignatureFlags::ALLOW_IA_INPUT_LAYOUT.bits(),
+    ),
+    (
+        "DENY_VERTEX_SHADER_ROOT_ACCESS",
+        RootSignatureFlags::DENY_VS_ROOT_ACCESS.bits(),
+    ),
+    (
+        "DENY_HULL_SHADER_ROOT_ACCESS",
+        RootSignatureFlags::DENY_HS_ROOT_ACCESS.bits(),
+    ),
+    (
+        "DENY_DOMAIN_SHADER_ROOT_ACCESS",
+        RootSignatureFlags::DENY_DS_ROOT_ACCESS.bits(),
+    ),
+    (
+        "DENY_GEOMETRY_SHADER_ROOT_ACCESS",
+        RootSignatureFlags::DENY_GS_ROOT_ACCESS.bits(),
+    ),
+    (
+        "DENY_PIXEL_SHADER_ROOT_ACCESS",
+        RootSignatureFlags::DENY_PS_ROOT_ACCESS.bits(),
+    ),
+    (
+        "DENY_AMPLIFICATION_SHADER_ROOT_ACCESS",
+        RootSignatureFlags::DENY_AS_ROOT_ACCESS.bits(),
+    ),
+    (
+        "DENY_MESH_SHADER_ROOT_ACCESS",
+        RootSignatureFlags::DENY_MS_ROOT_ACCESS.bits(),
+    ),
+    (
+        "ALLOW_STREAM_OUTPUT",
+        RootSignatureFlags::ALLOW_STREAM_OUTPUT.bits(),
+    ),
+    (
+        "LOCAL_ROOT_SIGNATURE",
+        RootSignatureFlags::LOCAL_ROOT_SIGNATURE.bits(),
+    ),
+    (
+        "CBV_SRV_UAV_HEAP_DIRECTLY_INDEXED",
+        RootSignatureFlags::CBV_SRV_UAV_HEAP_DIRECTLY_INDEXED.bits(),
+    ),
+    (
+        "SAMPLER_HEAP_DIRECTLY_INDEXED",
+        RootSignatureFlags::SAMPLER_HEAP_DIRECTLY_INDEXED.bits(),
+    ),
+];
+
+const DESCRIPTOR_FLAGS: &[(&str, u32)] = &[
+    ("DATA_VOLATILE", RootDescriptorFlags::DATA_VOLATILE.bits()),
+    (
+        "DATA_STATIC_WHILE_SET_AT_EXECUTE",
+        RootDescriptorFlags::DATA_STATIC_WHILE_SET_AT_EXECUTE.bits(),
+    ),
+    ("DATA_STATIC", RootDescriptorFlags::DATA_STATIC.bits()),
+];
+
+const RANGE_FLAGS: &[(&str, u32)] = &[
+    (
+        "DESCRIPTORS_VOLATILE",
+        DescriptorRangeFlags::DESCRIPTORS_VOLATILE.bits(),
+    ),
+    ("DATA_VOLATILE", DescriptorRangeFlags::DATA_VOLATILE.bits()),
+    (
+        "DATA_STATIC_WHILE_SET_AT_EXECUTE",
+        DescriptorRangeFlags::DATA_STATIC_WHILE_SET_AT_EXECUTE.bits(),
+    ),
+    ("DATA_STATIC", DescriptorRangeFlags::DATA_STATIC.bits()),
+    (
+        "DESCRIPTORS_STATIC_KEEPING_BUFFER_BOUNDS_CHECKS",
+        DescriptorRangeFlags::DESCRIPTORS_STATIC_KEEPING_BUFFER_BOUNDS_CHECKS.bits(),
+    ),
+];
+
+const VISIBILITIES: &[(&str, ShaderVisibility)] = &[
+    ("SHADER_VISIBILITY_ALL", ShaderVisibility::All),
+    ("SHADER_VISIBILITY_VERTEX", ShaderVisibility::VS),
+    ("SHADER_VISIBILITY_HULL", ShaderVisibility::HS),
+    ("SHADER_VISIBILITY_DOMAIN", ShaderVisibility::DS),
+    ("SHADER_VISIBILITY_GEOMETRY", ShaderVisibility::GS),
+    ("SHADER_VISIBILITY_PIXEL", ShaderVisibility::PS),
+    ("SHADER_VISIBILITY_AMPLIFICATION", ShaderVisibility::AS),
+    ("SHADER_VISIBILITY_MESH", ShaderVisibility::MS),
+];
+
+const FILTERS: &[(&str, u32)] = &[
+    ("MIN_MAG_MIP_POINT", d3d12::D3D12_FILTER_MIN_MAG_MIP_POINT),
+    (
+        "MIN_MAG_POINT_MIP_LINEAR",
+        d3d12::D3D12_FILTER_MIN_MAG_POINT_MIP_LINEAR,
+    ),
+    (
+        "MIN_POINT_MAG_LINEAR_MIP_POINT",
+        d3d12::D3D12_FILTER_MIN_POINT_MAG_LINEAR_MIP_POINT,
+    ),
+    (
+        "MIN_POINT_MAG_MIP_LINEAR",
+        d3d12::D3D12_FILTER_MIN_POINT_MAG_MIP_LINEAR,
+    ),
+    (
+        "MIN_LINEAR_MAG_MIP_POINT",
+        d3d12::D3D12_FILTER_MIN_LINEAR_MAG_MIP_POINT,
+    ),
+    (
+        "MIN_LINEAR_MAG_POINT_MIP_LINEAR",
+        d3d12::D3D12_FILTER_MIN_LINEAR_MAG_POINT_MIP_LINEAR,
+    ),
+    (
+        "MIN_MAG_LINEAR_MIP_POINT",
+        d3d12::D3D12_FILTER_MIN_MAG_LINEAR_MIP_POINT,
+    ),
+    ("MIN_MAG_MIP_LINEAR", d3d12::D3D12_FILTER_MIN_MAG_MIP_LINEAR),
+    ("ANISOTROPIC", d3d12::D3D12_FILTER_ANISOTROPIC),
+];
+
+// Reduction prefixes of filters, the reduction type is stored from bit 7 on.
+const FILTER_REDUCTIONS: &[(&str, u32)] = &[
+    ("FILTER_COMPARISON_", 1 << 7),
+    ("FILTER_MINIMUM_", 2 << 7),
+    ("FILTER_MAXIMUM_", 3 << 7),
+    ("FILTER_", 0),
+];
+
+const ADDRESS_MODES: &[(&str, u32)] = &[
+    (
+        "TEXTURE_ADDRESS_WRAP",
+        d3d12::D3D12_TEXTURE_ADDRESS_MODE_WRAP,
+    ),
+    (
+        "TEXTURE_ADDRESS_MIRROR",
+        d3d12::D3D12_TEXTURE_ADDRESS_MODE_MIRROR,
+    ),
+    (
+        "TEXTURE_ADDRESS_CLAMP",
+        d3d12::D3D12_TEXTURE_ADDRESS_MODE_CLAMP,
+    ),
+    (
+        "TEXTURE_ADDRESS_BORDER",
+        d3d12::D3D12_TEXTURE_ADDRESS_MODE_BORDER,
+    ),
+    (
+        "TEXTURE_ADDRESS_MIRROR_ONCE",
+        d3d12::D3D12_TEXTURE_ADDRESS_MODE_MIRROR_ONCE,
+    ),
+];
+
+const COMPARISON_FUNCS: &[(&str, ComparisonFunc)] = &[
+    ("COMPARISON_NEVER", ComparisonFunc::Never),
+    ("COMPARISON_LESS", ComparisonFunc::Less),
+    ("COMPARISON_EQUAL", ComparisonFunc::Equal),
+    ("COMPARISON_LESS_EQUAL", ComparisonFunc::LessEqual),
+    ("COMPARISON_GREATER", ComparisonFunc::Greater),
+    ("COMPARISON_NOT_EQUAL", ComparisonFunc::NotEqual),
+    ("COMPARISON_GREATER_EQUAL", ComparisonFunc::GreaterEqual),
+    ("COMPARISON_ALWAYS", ComparisonFunc::Always),
+];
+
+const BORDER_COLORS: &[(&str, StaticBorderColor)] = &[
+    (
+        "STATIC_BORDER_COLOR_TRANSPARENT_BLACK",
+        StaticBorderColor::TransparentBlack,
+    ),
+    (
+        "STATIC_BORDER_COLOR_OPAQUE_BLACK",
+        StaticBorderColor::OpaqueBlack,
+    ),
+    (
+        "STATIC_BORDER_COLOR_OPAQUE_WHITE",
+        StaticBorderColor::OpaqueWhite,
+    ),
+];
+
+// `name = value | value` argument of a clause.
+struct NamedArgument<'a> {
+    name: Token<'a>,
+    values: Vec<Token<'a>>,
+}
+
+// Register and named arguments of a clause, in any order.
+struct Arguments<'a> {
+    clause: Token<'a>,
+    register: Option<u32>,
+    named: Vec<NamedArgument<'a>>,
+}
+
+impl<'a> Arguments<'a> {
+    fn take(&mut self, name: &str) -> Option<NamedArgument<'a>> {
+        let position = self
+            .named
+            .iter()
+            .position(|argument| match argument.name.kind {
+                TokenKind::Ident(text) => text.eq_ignore_ascii_case(name),
+                _ => false,
+            })?;
+        Some(self.named.remove(position))
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    position: usize,
+    version: RootSignatureVersion,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Token<'a> {
+        self.tokens[self.position]
+    }
+
+    fn next(&mut self) -> Token<'a> {
+        let token = self.peek();
+        if token.kind != TokenKind::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn error<T>(&self, token: Token, message: String) -> Result<T, RootSignatureSyntaxError> {
+        Err(RootSignatureSyntaxError {
+            line: token.line,
+            column: token.column,
+            message,
+        })
+    }
+
+    fn expect(&mut self, punct: char) -> Result<(), RootSignatureSyntaxError> {
+        let token = self.next();
+        if token.kind == TokenKind::Punct(punct) {
+            Ok(())
+        } else {
+            self.error(token, format!("expected `{}`, found {}", punct, token.kind))
+        }
+    }
+
+    fn ident(&mut self) -> Result<(&'a str, Token<'a>), RootSignatureSyntaxError> {
+        let token = self.next();
+        match token.kind {
+            TokenKind::Ident(text) => Ok((text, token)),
+            kind => self.error(token, format!("expected an identifier, found {}", kind)),
+        }
+    }
+
+    // `value | value | ...`
+    fn values(&mut self) -> Result<Vec<Token<'a>>, RootSignatureSyntaxError> {
+        let mut values = Vec::new();
+        loop {
+            let token = self.next();
+            match token.kind {
+                TokenKind::Ident(_) | TokenKind::Number(_) => values.push(token),
+                kind => return self.error(token, format!("expected a value, found {}", kind)),
+            }
+            if self.peek().kind != TokenKind::Punct('|') {
+                return Ok(values);
+            }
+            self.next();
+        }
+    }
+
+    fn register(
+        &self,
+        token: Token,
+        text: &str,
+        class: char,
+    ) -> Result<u32, RootSignatureSyntaxError> {
+        let mut chars = text.chars();
+        let prefix = chars.next().unwrap_or_default();
+        let number = chars.as_str();
+        if !prefix.eq_ignore_ascii_case(&class)
+            || number.is_empty()
+            || !number.bytes().all(|b| b.is_ascii_digit())
+        {
+            return self.error(
+                token,
+                format!("expected a `{}` register, found `{}`", class, text),
+            );
+        }
+        match number.parse() {
+            Ok(register) => Ok(register),
+            Err(_) => self.error(token, format!("register `{}` is out of range", text)),
+        }
+    }
+
+    // `(register, name = value, ...)`, the opening parenthesis is already consumed.
+    fn arguments(
+        &mut self,
+        clause: Token<'a>,
+        class: Option<char>,
+    ) -> Result<Arguments<'a>, RootSignatureSyntaxError> {
+        let mut arguments = Arguments {
+            clause,
+            register: None,
+            named: Vec::new(),
+        };
+        if self.peek().kind == TokenKind::Punct(')') {
+            self.next();
+            return Ok(arguments);
+        }
+
+        loop {
+            let (text, token) = self.ident()?;
+            if self.peek().kind == TokenKind::Punct('=') {
+                self.next();
+                let duplicate = arguments
+                    .named
+                    .iter()
+                    .any(|argument| match argument.name.kind {
+                        TokenKind::Ident(name) => name.eq_ignore_ascii_case(text),
+                        _ => false,
+                    });
+                if duplicate {
+                    return self.error(token, format!("duplicate argument `{}`", text));
+                }
+                let values = self.values()?;
+                arguments.named.push(NamedArgument {
+                    name: token,
+                    values,
+                });
+            } else {
+                match class {
+                    Some(_) if arguments.register.is_some() => {
+                        return self.error(token, "duplicate register".to_string());
+                    }
+                    Some(class) => arguments.register = Some(self.register(token, text, class)?),
+                    None => return self.error(token, format!("expected `=` after `{}`", text)),
+                }
+            }
+
+            let token = self.next();
+            match token.kind {
+                TokenKind::Punct(',') => {}
+                TokenKind::Punct(')') => return Ok(arguments),
+                kind => return self.error(token, format!("expected `,` or `)`, found {}", kind)),
+            }
+        }
+    }
+
+    fn finish(&self, arguments: Arguments<'a>) -> Result<(), RootSignatureSyntaxError> {
+        match arguments.named.first() {
+            Some(argument) => self.error(
+                argument.name,
+                format!(
+                    "unknown argument {} for {}",
+                    argument.name.kind, arguments.clause.kind
+                ),
+            ),
+            None => Ok(()),
+        }
+    }
+
+    fn required_register(&self, arguments: &Arguments) -> Result<u32, RootSignatureSyntaxError> {
+        match arguments.register {
+            Some(register) => Ok(register),
+            None => self.error(
+                arguments.clause,
+                format!("missing register for {}", arguments.clause.kind),
+            ),
+        }
+    }
+
+    fn single(&self, argument: &NamedArgument<'a>) -> Result<Token<'a>, RootSignatureSyntaxError> {
+        match argument.values[..] {
+            [value] => Ok(value),
+            _ => self.error(
+                argument.values[1],
+                format!("{} takes a single value", argument.name.kind),
+            ),
+        }
+    }
+
+    fn uint(&self, argument: &NamedArgument<'a>) -> Result<u32, RootSignatureSyntaxError> {
+        let token = self.single(argument)?;
+        let parsed = match token.kind {
+            TokenKind::Number(text) if text.starts_with("0x") || text.starts_with("0X") => {
+                u32::from_str_radix(&text[2..], 16).ok()
+            }
+            TokenKind::Number(text) => text.parse().ok(),
+            _ => None,
+        };
+        match parsed {
+            Some(value) => Ok(value),
+            None => self.error(
+                token,
+                format!("expected an unsigned integer, found {}", token.kind),
+            ),
+        }
+    }
+
+    fn float(&self, argument: &NamedArgument<'a>) -> Result<f32, RootSignatureSyntaxError> {
+        let token = self.single(argument)?;
+        let parsed = match token.kind {
+            TokenKind::Number(text) => text.trim_end_matches(&['f', 'F'][..]).parse().ok(),
+            _ => None,
+        };
+        match parsed {
+            Some(value) => Ok(value),
+            None => self.error(token, format!("expected a number, found {}", token.kind)),
+        }
+    }
+
+    fn enumeration<T: Copy>(
+        &self,
+        argument: &NamedArgument<'a>,
+        table: &[(&str, T)],
+    ) -> Result<T, RootSignatureSyntaxError> {
+        let token = self.single(argument)?;
+        if let TokenKind::Ident(text) = token.kind {
+            if let Some(&(_, value)) = table
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(text))
+            {
+                return Ok(value);
+            }
+        }
+        self.error(
+            token,
+            format!("invalid value {} for {}", token.kind, argument.name.kind),
+        )
+    }
+
+    // Flags combined with `|`, `0` for none.
+    fn flags(
+        &self,
+        values: &[Token<'a>],
+        table: &[(&str, u32)],
+    ) -> Result<u32, RootSignatureSyntaxError> {
+        let mut flags = 0;
+        for &token in values {
+            flags |= match token.kind {
+                TokenKind::Number("0") => 0,
+                TokenKind::Ident(text) => {
+                    match table
+                        .iter()
+                        .find(|(name, _)| name.eq_ignore_ascii_case(text))
+                    {
+                        Some(&(_, flag)) => flag,
+                        None => return self.error(token, format!("unknown flag `{}`", text)),
+                    }
+                }
+                kind => return self.error(token, format!("unknown flag {}", kind)),
+            };
+        }
+        Ok(flags)
+    }
+
+    // Flags of root descriptors and ranges, only allowed by version 1.1.
+    fn versioned_flags(
+        &self,
+        argument: Option<NamedArgument<'a>>,
+        table: &[(&str, u32)],
+    ) -> Result<Option<u32>, RootSignatureSyntaxError> {
+        match argument {
+            Some(argument) if self.version < RootSignatureVersion::V1_1 => self.error(
+                argument.name,
+                "flags require root signature version 1.1".to_string(),
+            ),
+            Some(argument) => self.flags(&argument.values, table).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn space(&self, arguments: &mut Arguments<'a>) -> Result<u32, RootSignatureSyntaxError> {
+        match arguments.take("space") {
+            Some(argument) => self.uint(&argument),
+            None => Ok(0),
+        }
+    }
+
+    fn visibility(
+        &self,
+        arguments: &mut Arguments<'a>,
+    ) -> Result<ShaderVisibility, RootSignatureSyntaxError> {
+        match arguments.take("visibility") {
+            Some(argument) => self.enumeration(&argument, VISIBILITIES),
+            None => Ok(ShaderVisibility::All),
+        }
+    }
+
+    fn root_constants(
+        &mut self,
+        clause: Token<'a>,
+    ) -> Result<RootParameterDesc, RootSignatureSyntaxError> {
+        let mut arguments = self.arguments(clause, Some('b'))?;
+        let register = self.required_register(&arguments)?;
+        let num_values = match arguments.take("num32BitConstants") {
+            Some(argument) => self.uint(&argument)?,
+            None => return self.error(clause, "missing `num32BitConstants`".to_string()),
+        };
+        let binding = Binding {
+            register,
+            space: self.space(&mut arguments)?,
+        };
+        let visibility = self.visibility(&mut arguments)?;
+        self.finish(arguments)?;
+        Ok(RootParameterDesc {
+            visibility,
+            kind: RootParameterKind::Constants {
+                binding,
+                num_values,
+            },
+        })
+    }
+
+    fn root_descriptor(
+        &mut self,
+        clause: Token<'a>,
+        ty: DescriptorRangeType,
+    ) -> Result<RootParameterDesc, RootSignatureSyntaxError> {
+        let mut arguments = self.arguments(clause, Some(register_class(ty)))?;
+        let binding = Binding {
+            register: self.required_register(&arguments)?,
+            space: self.space(&mut arguments)?,
+        };
+        let visibility = self.visibility(&mut arguments)?;
+        let flags = self.versioned_flags(arguments.take("flags"), DESCRIPTOR_FLAGS)?;
+        self.finish(arguments)?;
+
+        let flags = match flags {
+            Some(flags) => RootDescriptorFlags::from_bits_truncate(flags),
+            None if self.version < RootSignatureVersion::V1_1 => RootDescriptorFlags::empty(),
+            None if ty == DescriptorRangeType::UAV => RootDescriptorFlags::DATA_VOLATILE,
+            None => RootDescriptorFlags::DATA_STATIC_WHILE_SET_AT_EXECUTE,
+        };
+        let kind = match ty {
+            DescriptorRangeType::CBV => RootParameterKind::Cbv { binding, flags },
+            DescriptorRangeType::SRV => RootParameterKind::Srv { binding, flags },
+            _ => RootParameterKind::Uav { binding, flags },
+        };
+        Ok(RootParameterDesc { visibility, kind })
+    }
+
+    fn descriptor_range(
+        &mut self,
+        clause: Token<'a>,
+        ty: DescriptorRangeType,
+    ) -> Result<DescriptorRangeDesc, RootSignatureSyntaxError> {
+        let mut arguments = self.arguments(clause, Some(register_class(ty)))?;
+        let register = self.required_register(&arguments)?;
+        let count = match arguments.take("numDescriptors") {
+            Some(ref argument) if is_ident(argument, "unbounded") => !0,
+            Some(argument) => self.uint(&argument)?,
+            None => 1,
+        };
+        let offset = match arguments.take("offset") {
+            Some(ref argument) if is_ident(argument, "DESCRIPTOR_RANGE_OFFSET_APPEND") => {
+                d3d12::D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND
+            }
+            Some(argument) => self.uint(&argument)?,
+            None => d3d12::D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND,
+        };
+        let base_binding = Binding {
+            register,
+            space: self.space(&mut arguments)?,
+        };
+        let flags = self.versioned_flags(arguments.take("flags"), RANGE_FLAGS)?;
+        self.finish(arguments)?;
+
+        let flags = match flags {
+            Some(flags) => DescriptorRangeFlags::from_bits_truncate(flags),
+            None if self.version < RootSignatureVersion::V1_1 => DescriptorRangeFlags::empty(),
+            None => match ty {
+                DescriptorRangeType::Sampler => DescriptorRangeFlags::empty(),
+                DescriptorRangeType::UAV => DescriptorRangeFlags::DATA_VOLATILE,
+                _ => DescriptorRangeFlags::DATA_STATIC_WHILE_SET_AT_EXECUTE,
+            },
+        };
+        Ok(DescriptorRangeDesc {
+            ty,
+            count,
+            base_binding,
+            offset,
+            flags,
+        })
+    }
+
+    fn descriptor_table(&mut self) -> Result<RootParameterDesc, RootSignatureSyntaxError> {
+        let mut ranges = Vec::new();
+        let mut visibility = None;
+        if self.peek().kind == TokenKind::Punct(')') {
+            self.next();
+        } else {
+            loop {
+                let (text, token) = self.ident()?;
+                if text.eq_ignore_ascii_case("visibility") {
+                    if visibility.is_some() {
+                        return self.error(token, "duplicate argument `visibility`".to_string());
+                    }
+                    self.expect('=')?;
+                    let argument = NamedArgument {
+                        name: token,
+                        values: self.values()?,
+                    };
+                    visibility = Some(self.enumeration(&argument, VISIBILITIES)?);
+                } else {
+                    let ty = match range_type(text) {
+                        Some(ty) => ty,
+                        None => {
+                            return self
+                                .error(token, format!("unknown descriptor range `{}`", text))
+                        }
+                    };
+                    self.expect('(')?;
+                    ranges.push(self.descriptor_range(token, ty)?);
+                }
+
+                let token = self.next();
+                match token.kind {
+                    TokenKind::Punct(',') => {}
+                    TokenKind::Punct(')') => break,
+                    kind => {
+                        return self.error(token, format!("expected `,` or `)`, found {}", kind))
+                    }
+                }
+            }
+        }
+
+        Ok(RootParameterDesc {
+            visibility: visibility.unwrap_or(ShaderVisibility::All),
+            kind: RootParameterKind::DescriptorTable(ranges),
+        })
+    }
+
+    fn static_sampler(
+        &mut self,
+        clause: Token<'a>,
+    ) -> Result<StaticSamplerDesc, RootSignatureSyntaxError> {
+        let mut arguments = self.arguments(clause, Some('s'))?;
+        let register = self.required_register(&arguments)?;
+
+        let filter = match arguments.take("filter") {
+            Some(argument) => {
+                let token = self.single(&argument)?;
+                let filter = match token.kind {
+                    TokenKind::Ident(text) => filter_value(text),
+                    _ => None,
+                };
+                match filter {
+                    Some(filter) => filter,
+                    None => {
+                        return self
+                            .error(token, format!("invalid value {} for filter", token.kind))
+                    }
+                }
+            }
+            None => d3d12::D3D12_FILTER_ANISOTROPIC,
+        };
+        let mut address_mode = [d3d12::D3D12_TEXTURE_ADDRESS_MODE_WRAP; 3];
+        for (mode, name) in address_mode
+            .iter_mut()
+            .zip(&["addressU", "addressV", "addressW"])
+        {
+            if let Some(argument) = arguments.take(name) {
+                *mode = self.enumeration(&argument, ADDRESS_MODES)?;
+            }
+        }
+        let mip_lod_bias = match arguments.take("mipLODBias") {
+            Some(argument) => self.float(&argument)?,
+            None => 0.0,
+        };
+        let max_anisotropy = match arguments.take("maxAnisotropy") {
+            Some(argument) => self.uint(&argument)?,
+            None => 16,
+        };
+        let comparison_op = match arguments.take("comparisonFunc") {
+            Some(argument) => self.enumeration(&argument, COMPARISON_FUNCS)?,
+            None => ComparisonFunc::LessEqual,
+        };
+        let border_color = match arguments.take("borderColor") {
+            Some(argument) => self.enumeration(&argument, BORDER_COLORS)?,
+            None => StaticBorderColor::OpaqueWhite,
+        };
+        let min_lod = match arguments.take("minLOD") {
+            Some(argument) => self.float(&argument)?,
+            None => 0.0,
+        };
+        let max_lod = match arguments.take("maxLOD") {
+            Some(argument) => self.float(&argument)?,
+            None => d3d12::D3D12_FLOAT32_MAX,
+        };
+        let binding = Binding {
+            register,
+            space: self.space(&mut arguments)?,
+        };
+        let visibility = self.visibility(&mut arguments)?;
+        self.finish(arguments)?;
+
+        Ok(StaticSamplerDesc {
+            visibility,
+            binding,
+            filter,
+            address_mode,
+            mip_lod_bias,
+            max_anisotropy,
+            comparison_op: comparison_op as _,
+            border_color,
+            lod: min_lod..max_lod,
+        })
+    }
+
+    fn root_signature(&mut self) -> Result<RootSignatureDesc, RootSignatureSyntaxError> {
+        let mut desc = RootSignatureDesc::new(self.version);
+        let mut has_flags = false;
+        if self.peek().kind == TokenKind::End {
+            return Ok(desc);
+        }
+
+        loop {
+            let (text, token) = self.ident()?;
+            self.expect('(')?;
+            if text.eq_ignore_ascii_case("RootFlags") {
+                if has_flags {
+                    return self.error(token, "duplicate `RootFlags`".to_string());
+                }
+                has_flags = true;
+                let values = self.values()?;
+                desc.flags =
+                    RootSignatureFlags::from_bits_truncate(self.flags(&values, ROOT_FLAGS)?);
+                self.expect(')')?;
+            } else if text.eq_ignore_ascii_case("RootConstants") {
+                desc.parameters.push(self.root_constants(token)?);
+            } else if text.eq_ignore_ascii_case("DescriptorTable") {
+                desc.parameters.push(self.descriptor_table()?);
+            } else if text.eq_ignore_ascii_case("StaticSampler") {
+                desc.static_samplers.push(self.static_sampler(token)?);
+            } else {
+                match range_type(text) {
+                    Some(DescriptorRangeType::Sampler) | None => {
+                        return self.error(token, format!("unknown root parameter `{}`", text))
+                    }
+                    Some(ty) => desc.parameters.push(self.root_descriptor(token, ty)?),
+                }
+            }
+
+            let token = self.next();
+            match token.kind {
+                TokenKind::Punct(',') => {}
+                TokenKind::End => return Ok(desc),
+                kind => return self.error(token, format!("expected `,`, found {}", kind)),
+            }
+        }
+    }
+}
+
+fn is_ident(argument: &NamedArgument, name: &str) -> bool {
+    match argument.values[..] {
+        [Token {
+            kind: TokenKind::Ident(text),
+            ..
+        }] => text.eq_ignore_ascii_case(name),
+        _ => false,
+    }
+}
+
+fn range_type(name: &str) -> Option<DescriptorRangeType> {
+    [
+        ("CBV", DescriptorRangeType::CBV),
+        ("SRV", DescriptorRangeType::SRV),
+        ("UAV", DescriptorRangeType::UAV),
+        ("Sampler", DescriptorRangeType::Sampler),
+    ]
+    .iter()
+    .find(|(range, _)| range.eq_ignore_ascii_case(name))
+    .map(|&(_, ty)| ty)
+}
+
+fn register_class(ty: DescriptorRangeType) -> char {
+    match ty {
+        DescriptorRangeType::CBV => 'b',
+        DescriptorRangeType::SRV => 't',
+        DescriptorRangeType::UAV => 'u',
+        DescriptorRangeType::Sampler => 's',
+    }
+}
+
+fn filter_value(name: &str) -> Option<u32> {
+    let upper = name.to_ascii_uppercase();
+    FILTER_REDUCTIONS.iter().find_map(|&(prefix, reduction)| {
+        let base = upper.strip_prefix(prefix)?;
+        FILTERS
+            .iter()
+            .find(|&&(filter, _)| filter == base)
+            .map(|&(_, filter)| filter | reduction)
+    })
+}
+
+impl RootSignatureDesc {
+    /// Parse a root signature in HLSL syntax.
+    ///
+    /// Unspecified arguments take the defaults of the HLSL compiler, including the flags of
+    /// version 1.1 ranges and root descriptors.
+    ///
+    /// ```
+    /// use d3d12::{RootParameterKind, RootSignatureDesc, RootSignatureFlags, RootSignatureVersion};
+    ///
+    /// let desc = RootSignatureDesc::parse_hlsl(
+    ///     "RootFlags(ALLOW_INPUT_ASSEMBLER_INPUT_LAYOUT), CBV(b0), \
+    ///      DescriptorTable(SRV(t0, numDescriptors = 4), visibility = SHADER_VISIBILITY_PIXEL), \
+    ///      StaticSampler(s0, filter = FILTER_MIN_MAG_MIP_LINEAR)",
+    ///     RootSignatureVersion::V1_1,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(desc.flags, RootSignatureFlags::ALLOW_IA_INPUT_LAYOUT);
+    /// assert_eq!(desc.parameters.len(), 2);
+    /// match desc.parameters[1].kind {
+    ///     RootParameterKind::DescriptorTable(ref ranges) => assert_eq!(ranges[0].count, 4),
+    ///     _ => unreachable!(),
+    /// }
+    ///
+    /// let error = RootSignatureDesc::parse_hlsl("CBV(b0),\nSRV(b1)", RootSignatureVersion::V1_1)
+    ///     .unwrap_err();
+    /// assert_eq!((error.line, error.column), (2, 5));
+    /// ```
+    pub fn parse_hlsl(
+        source: &str,
+        version: RootSignatureVersion,
+    ) -> Result<Self, RootSignatureSyntaxError> {
+        Parser {
+            tokens: tokenize(source)?,
+            position: 0,
+            version,
+        }
+        .root_signature()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> RootSignatureDesc {
+        RootSignatureDesc::parse_hlsl(source, RootSignatureVersion::V1_1).unwrap()
+    }
+
+    // Line, column and message of the error for `source`.
+    fn error(source: &str, version: RootSignatureVersion) -> (usize, usize, String) {
+        let error = RootSignatureDesc::parse_hlsl(source, version).unwrap_err();
+        (error.line, error.column, error.message)
+    }
+
+    // Column of the last occurrence of `needle` in a single line `source`.
+    fn column(source: &str, needle: &str) -> usize {
+        source[..source.rfind(needle).unwrap()].chars().count() + 1
+    }
+
+    fn binding(space: u32, register: u32) -> Binding {
+        Binding { space, register }
+    }
+
+    fn range(
+        ty: DescriptorRangeType,
+        count: u32,
+        base_binding: Binding,
+        offset: u32,
+        flags: DescriptorRangeFlags,
+    ) -> DescriptorRangeDesc {
+        DescriptorRangeDesc::new(ty, count, base_binding, offset, flags)
+    }
+
+    const APPEND: u32 = d3d12::D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND;
+
+    #[test]
+    fn clauses() {
+        let desc = parse(
+            "RootFlags(ALLOW_INPUT_ASSEMBLER_INPUT_LAYOUT | DENY_PIXEL_SHADER_ROOT_ACCESS),
+             RootConstants(num32BitConstants = 4, b2, space = 1, visibility = SHADER_VISIBILITY_VERTEX),
+             CBV(b0, flags = DATA_STATIC),
+             SRV(t1, space = 2, visibility = SHADER_VISIBILITY_PIXEL, flags = DATA_VOLATILE),
+             UAV(u3, flags = 0),
+             DescriptorTable(CBV(b4), SRV(t5, numDescriptors = 2), UAV(u6, flags = DATA_STATIC),
+                             visibility = SHADER_VISIBILITY_ALL),
+             DescriptorTable(Sampler(s0, numDescriptors = 3, space = 1, offset = 2)),
+             StaticSampler(s1, filter = FILTER_MIN_MAG_MIP_POINT,
+                           addressU = TEXTURE_ADDRESS_CLAMP, addressV = TEXTURE_ADDRESS_BORDER,
+                           addressW = TEXTURE_ADDRESS_MIRROR_ONCE, mipLODBias = -1.5,
+                           maxAnisotropy = 8, comparisonFunc = COMPARISON_ALWAYS,
+                           borderColor = STATIC_BORDER_COLOR_TRANSPARENT_BLACK,
+                           minLOD = 1, maxLOD = 8.0f, space = 3,
+                           visibility = SHADER_VISIBILITY_PIXEL)",
+        );
+
+        let expected = RootSignatureDesc::new(RootSignatureVersion::V1_1)
+            .with_flags(
+                RootSignatureFlags::ALLOW_IA_INPUT_LAYOUT | RootSignatureFlags::DENY_PS_ROOT_ACCESS,
+            )
+            .with_constants(ShaderVisibility::VS, binding(1, 2), 4)
+            .with_cbv(
+                ShaderVisibility::All,
+                binding(0, 0),
+                RootDescriptorFlags::DATA_STATIC,
+            )
+            .with_srv(
+                ShaderVisibility::PS,
+                binding(2, 1),
+                RootDescriptorFlags::DATA_VOLATILE,
+            )
+            .with_uav(
+                ShaderVisibility::All,
+                binding(0, 3),
+                RootDescriptorFlags::empty(),
+            )
+            .with_descriptor_table(
+                ShaderVisibility::All,
+                vec![
+                    range(
+                        DescriptorRangeType::CBV,
+                        1,
+                        binding(0, 4),
+                        APPEND,
+                        DescriptorRangeFlags::DATA_STATIC_WHILE_SET_AT_EXECUTE,
+                    ),
+                    range(
+                        DescriptorRangeType::SRV,
+                        2,
+                        binding(0, 5),
+                        APPEND,
+                        DescriptorRangeFlags::DATA_STATIC_WHILE_SET_AT_EXECUTE,
+                    ),
+                    range(
+                        DescriptorRangeType::UAV,
+                        1,
+                        binding(0, 6),
+                        APPEND,
+                        DescriptorRangeFlags::DATA_STATIC,
+                    ),
+                ],
+            )
+            .with_descriptor_table(
+                ShaderVisibility::All,
+                vec![range(
+                    DescriptorRangeType::Sampler,
+                    3,
+                    binding(1, 0),
+                    2,
+                    DescriptorRangeFlags::empty(),
+                )],
+            )
+            .with_static_sampler(StaticSamplerDesc {
+                visibility: ShaderVisibility::PS,
+                binding: binding(3, 1),
+                filter: d3d12::D3D12_FILTER_MIN_MAG_MIP_POINT,
+                address_mode: [
+                    d3d12::D3D12_TEXTURE_ADDRESS_MODE_CLAMP,
+                    d3d12::D3D12_TEXTURE_ADDRESS_MODE_BORDER,
+                    d3d12::D3D12_TEXTURE_ADDRESS_MODE_MIRROR_ONCE,
+                ],
+                mip_lod_bias: -1.5,
+                max_anisotropy: 8,
+                comparison_op: d3d12::D3D12_COMPARISON_FUNC_ALWAYS,
+                border_color: StaticBorderColor::TransparentBlack,
+                lod: 1.0..8.0,
+            });
+        assert_eq!(desc, expected);
+    }
+
+    #[test]
+    fn empty_and_defaults() {
+        assert_eq!(
+            parse(""),
+            RootSignatureDesc::new(RootSignatureVersion::V1_1)
+        );
+        assert_eq!(
+            parse(" \n\t"),
+            RootSignatureDesc::new(RootSignatureVersion::V1_1)
+        );
+
+        let desc = parse("RootConstants(b0, num32BitConstants = 1), StaticSampler(s0)");
+        assert_eq!(desc.parameters[0].visibility, ShaderVisibility::All);
+        assert_eq!(
+            desc.static_samplers,
+            [StaticSamplerDesc {
+                visibility: ShaderVisibility::All,
+                binding: binding(0, 0),
+                filter: d3d12::D3D12_FILTER_ANISOTROPIC,
+                address_mode: [d3d12::D3D12_TEXTURE_ADDRESS_MODE_WRAP; 3],
+                mip_lod_bias: 0.0,
+                max_anisotropy: 16,
+                comparison_op: d3d12::D3D12_COMPARISON_FUNC_LESS_EQUAL,
+                border_color: StaticBorderColor::OpaqueWhite,
+                lod: 0.0..d3d12::D3D12_FLOAT32_MAX,
+            }]
+        );
+    }
+
+    #[test]
+    fn version_flag_defaults() {
+        let source = "CBV(b0), SRV(t0), UAV(u0), \
+                      DescriptorTable(CBV(b1), SRV(t1), UAV(u1)), \
+                      DescriptorTable(Sampler(s1))";
+        let flags = |version| {
+            let desc = RootSignatureDesc::parse_hlsl(source, version).unwrap();
+            let mut descriptors = Vec::new();
+            let mut ranges = Vec::new();
+            for parameter in desc.parameters {
+                match parameter.kind {
+                    RootParameterKind::Cbv { flags, .. }
+                    | RootParameterKind::Srv { flags, .. }
+                    | RootParameterKind::Uav { flags, .. } => descriptors.push(flags),
+                    RootParameterKind::DescriptorTable(table) => {
+                        ranges.extend(table.iter().map(|range| range.flags))
+                    }
+                    RootParameterKind::Constants { .. } => unreachable!(),
+                }
+            }
+            (descriptors, ranges)
+        };
+
+        let (descriptors, ranges) = flags(RootSignatureVersion::V1_0);
+        assert!(descriptors.iter().all(|flags| flags.is_empty()));
+        assert!(ranges.iter().all(|flags| flags.is_empty()));
+
+        let (descriptors, ranges) = flags(RootSignatureVersion::V1_1);
+        assert_eq!(
+            descriptors,
+            [
+                RootDescriptorFlags::DATA_STATIC_WHILE_SET_AT_EXECUTE,
+                RootDescriptorFlags::DATA_STATIC_WHILE_SET_AT_EXECUTE,
+                RootDescriptorFlags::DATA_VOLATILE,
+            ]
+        );
+        assert_eq!(
+            ranges,
+            [
+                DescriptorRangeFlags::DATA_STATIC_WHILE_SET_AT_EXECUTE,
+                DescriptorRangeFlags::DATA_STATIC_WHILE_SET_AT_EXECUTE,
+                DescriptorRangeFlags::DATA_VOLATILE,
+                DescriptorRangeFlags::empty(),
+            ]
+        );
+    }
+
+    #[test]
+    fn literals() {
+        let desc = parse(
+            "RootConstants(num32BitConstants = 0x10, b0, space = 0XA), \
+             StaticSampler(s0, mipLODBias = -0.5f, minLOD = .25, maxLOD = 1e3, maxAnisotropy = 0x4), \
+             StaticSampler(s1, mipLODBias = +2, minLOD = 1.5E-1, maxLOD = 16.F)",
+        );
+        assert_eq!(
+            desc.parameters[0].kind,
+            RootParameterKind::Constants {
+                binding: binding(10, 0),
+                num_values: 16,
+            }
+        );
+        let sampler = &desc.static_samplers[0];
+        assert_eq!(sampler.mip_lod_bias, -0.5);
+        assert_eq!(sampler.lod, 0.25..1000.0);
+        assert_eq!(sampler.max_anisotropy, 4);
+        let sampler = &desc.static_samplers[1];
+        assert_eq!(sampler.mip_lod_bias, 2.0);
+        assert_eq!(sampler.lod, 0.15..16.0);
+
+        let source = "RootConstants(num32BitConstants = 0xZZ, b0)";
+        assert_eq!(
+            error(source, RootSignatureVersion::V1_1),
+            (
+                1,
+                column(source, "0xZZ"),
+                "expected an unsigned integer, found `0xZZ`".to_string()
+            )
+        );
+        let source = "CBV(b0, space = -1)";
+        assert_eq!(
+            error(source, RootSignatureVersion::V1_1),
+            (
+                1,
+                column(source, "-1"),
+                "expected an unsigned integer, found `-1`".to_string()
+            )
+        );
+        let source = "StaticSampler(s0, minLOD = 1.0.0)";
+        assert_eq!(
+            error(source, RootSignatureVersion::V1_1),
+            (
+                1,
+                column(source, "1.0.0"),
+                "expected a number, found `1.0.0`".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn filters() {
+        let filters = [
+            (
+                "FILTER_MIN_MAG_MIP_POINT",
+                d3d12::D3D12_FILTER_MIN_MAG_MIP_POINT,
+            ),
+            (
+                "FILTER_COMPARISON_MIN_MAG_MIP_LINEAR",
+                d3d12::D3D12_FILTER_COMPARISON_MIN_MAG_MIP_LINEAR,
+            ),
+            (
+                "FILTER_MINIMUM_ANISOTROPIC",
+                d3d12::D3D12_FILTER_MINIMUM_ANISOTROPIC,
+            ),
+            (
+                "FILTER_MAXIMUM_MIN_MAG_MIP_POINT",
+                d3d12::D3D12_FILTER_MAXIMUM_MIN_MAG_MIP_POINT,
+            ),
+            (
+                "filter_min_mag_mip_linear",
+                d3d12::D3D12_FILTER_MIN_MAG_MIP_LINEAR,
+            ),
+        ];
+        for &(name, filter) in filters.iter() {
+            let desc = parse(&format!("StaticSampler(s0, filter = {})", name));
+            assert_eq!(desc.static_samplers[0].filter, filter, "{}", name);
+        }
+
+        for name in ["MIN_MAG_MIP_POINT", "FILTER_MEDIAN_ANISOTROPIC", "FILTER_"].iter() {
+            let source = format!("StaticSampler(s0, filter = {})", name);
+            assert_eq!(
+                error(&source, RootSignatureVersion::V1_1),
+                (
+                    1,
+                    column(&source, name),
+                    format!("invalid value `{}` for filter", name)
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn unbounded_and_append() {
+        let desc = parse(
+            "DescriptorTable(SRV(t0, numDescriptors = 4, offset = 8), \
+             SRV(t4, numDescriptors = unbounded, offset = DESCRIPTOR_RANGE_OFFSET_APPEND), \
+             UAV(u0, numDescriptors = UNBOUNDED, space = 1))",
+        );
+        let ranges = match desc.parameters[0].kind {
+            RootParameterKind::DescriptorTable(ref ranges) => ranges,
+            _ => unreachable!(),
+        };
+        let counts_and_offsets: Vec<_> = ranges
+            .iter()
+            .map(|range| (range.count, range.offset))
+            .collect();
+        assert_eq!(counts_and_offsets, [(4, 8), (!0, APPEND), (!0, APPEND)]);
+
+        let source = "DescriptorTable(SRV(t0, numDescriptors = unbounded | 1))";
+        assert_eq!(
+            error(source, RootSignatureVersion::V1_1),
+            (
+                1,
+                column(source, "1)"),
+                "`numDescriptors` takes a single value".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn tokenizer_errors() {
+        let source = "CBV(b0),\n  SRV(t0) # comment";
+        assert_eq!(
+            error(source, RootSignatureVersion::V1_1),
+            (2, 11, "unexpected character `#`".to_string())
+        );
+        // Columns count characters, not bytes.
+        let source = "CBV(b0,\tspace = é)";
+        assert_eq!(
+            error(source, RootSignatureVersion::V1_1),
+            (1, 17, "unexpected character `é`".to_string())
+        );
+        let source = "RootFlags(0),\r\n\"CBV(b0)\"";
+        assert_eq!(
+            error(source, RootSignatureVersion::V1_1),
+            (2, 1, "unexpected character `\"`".to_string())
+        );
+    }
+
+    #[test]
+    fn argument_errors() {
+        let cases = [
+            (
+                "CBV(b0, space = 1, SPACE = 2)",
+                "SPACE",
+                "duplicate argument `SPACE`",
+            ),
+            ("SRV(t0, t1)", "t1", "duplicate register"),
+            (
+                "DescriptorTable(visibility = SHADER_VISIBILITY_ALL, visibility = SHADER_VISIBILITY_PIXEL)",
+                "visibility",
+                "duplicate argument `visibility`",
+            ),
+            (
+                "RootFlags(0), RootFlags(ALLOW_STREAM_OUTPUT)",
+                "RootFlags",
+                "duplicate `RootFlags`",
+            ),
+            ("SRV(b1)", "b1", "expected a `t` register, found `b1`"),
+            (
+                "DescriptorTable(UAV(t0))",
+                "t0",
+                "expected a `u` register, found `t0`",
+            ),
+            (
+                "StaticSampler(b0)",
+                "b0",
+                "expected a `s` register, found `b0`",
+            ),
+            ("CBV(b)", "b)", "expected a `b` register, found `b`"),
+            ("CBV(space = 1)", "CBV", "missing register for `CBV`"),
+            (
+                "RootConstants(b0)",
+                "RootConstants",
+                "missing `num32BitConstants`",
+            ),
+            (
+                "CBV(b0, numDescriptors = 2)",
+                "numDescriptors",
+                "unknown argument `numDescriptors` for `CBV`",
+            ),
+            (
+                "Sampler(s0)",
+                "Sampler",
+                "unknown root parameter `Sampler`",
+            ),
+            (
+                "CBV(b0, visibility = SHADER_VISIBILITY_TESSELLATION)",
+                "SHADER_VISIBILITY_TESSELLATION",
+                "invalid value `SHADER_VISIBILITY_TESSELLATION` for `visibility`",
+            ),
+            ("RootFlags(DENY_ALL)", "DENY_ALL", "unknown flag `DENY_ALL`"),
+        ];
+        for &(source, at, message) in cases.iter() {
+            assert_eq!(
+                error(source, RootSignatureVersion::V1_1),
+                (1, column(source, at), message.to_string()),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn flags_require_version_1_1() {
+        for source in [
+            "CBV(b0, flags = DATA_STATIC)",
+            "DescriptorTable(SRV(t0, flags = DESCRIPTORS_VOLATILE))",
+            "UAV(u0, flags = 0)",
+        ]
+        .iter()
+        {
+            assert_eq!(
+                error(source, RootSignatureVersion::V1_0),
+                (
+                    1,
+                    column(source, "flags"),
+                    "flags require root signature version 1.1".to_string()
+                ),
+                "{}",
+                source
+            );
+            assert!(RootSignatureDesc::parse_hlsl(source, RootSignatureVersion::V1_1).is_ok());
+        }
+    }
+
+    #[test]
+    fn trailing_commas() {
+        let cases = [
+            ("CBV(b0),", 9, "expected an identifier, found end of input"),
+            ("CBV(b0,)", 8, "expected an identifier, found `)`"),
+            (
+                "DescriptorTable(SRV(t0),)",
+                25,
+                "expected an identifier, found `)`",
+            ),
+            (
+                "RootFlags(ALLOW_STREAM_OUTPUT |)",
+                32,
+                "expected a value, found `)`",
+            ),
+        ];
+        for &(source, column, message) in cases.iter() {
+            assert_eq!(
+                error(source, RootSignatureVersion::V1_1),
+                (1, column, message.to_string()),
+                "{}",
+                source
+            );
+        }
+        assert_eq!(
+            error("CBV(b0),\n", RootSignatureVersion::V1_1),
+            (
+                2,
+                1,
+                "expected an identifier, found end of input".to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,411 @@
+//! Root signature layouts
+//!
+//! Maps shader registers to the root arguments which bind them, so the root index and
+//! table offset of a resource don't have to be counted out by hand.
+
+use crate::{
+    Binding, DescriptorRangeType, RootIndex, RootParameterKind, RootSignatureDesc, ShaderVisibility,
+};
+use winapi::um::d3d12;
+
+/// Root argument a register is bound by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RootSlot {
+    /// Descriptor `offset` of the table at `root_index`.
+    Table { root_index: RootIndex, offset: u32 },
+    /// Constant buffer made of root constants.
+    Constants { root_index: RootIndex },
+    /// Root CBV, SRV or UAV.
+    Descriptor { root_index: RootIndex },
+    /// Static sampler with this index, which needs no argument.
+    StaticSampler(usize),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Entry {
+    ty: DescriptorRangeType,
+    space: u32,
+    visibility: ShaderVisibility,
+    // Exclusive end, `1 << 32` for unbounded ranges.
+    registers: (u64, u64),
+    slot: RootSlot,
+}
+
+/// Register locations of a root signature, built from its description.
+///
+/// ```
+/// use d3d12::{
+///     Binding, DescriptorRangeType, RootSignatureDesc, RootSignatureLayout,
+///     RootSignatureVersion, RootSlot,
+/// };
+///
+/// let desc = RootSignatureDesc::parse_hlsl(
+///     "CBV(b0), DescriptorTable(SRV(t0, numDescriptors=2), SRV(t0, space=1, numDescriptors=4))",
+///     RootSignatureVersion::V1_1,
+/// )
+/// .unwrap();
+/// let layout = RootSignatureLayout::new(&desc);
+///
+/// let t3 = Binding { register: 3, space: 1 };
+/// assert_eq!(
+///     layout.resolve(DescriptorRangeType::SRV, t3),
+///     Some(RootSlot::Table { root_index: 1, offset: 5 })
+/// );
+/// assert_eq!(layout.table_size(1), Some(6));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RootSignatureLayout {
+    entries: Vec<Entry>,
+    // Descriptors in each table, `None` for other parameters.
+    table_sizes: Vec<Option<u32>>,
+}
+
+impl RootSignatureLayout {
+    pub fn new(desc: &RootSignatureDesc) -> Self {
+        let mut entries = Vec::new();
+        let mut table_sizes = Vec::with_capacity(desc.parameters.len());
+
+        for (root_index, parameter) in desc.parameters.iter().enumerate() {
+            let root_index = root_index as RootIndex;
+            let mut push = |ty, binding: Binding, count: u32, slot| {
+                let start = binding.register as u64;
+                let end = if count == !0 {
+                    1 << 32
+                } else {
+                    start + count as u64
+                };
+                entries.push(Entry {
+                    ty,
+                    space: binding.space,
+                    visibility: parameter.visibility,
+                    registers: (start, end),
+                    slot,
+                });
+            };
+
+            let (ty, binding, slot) = match parameter.kind {
+                RootParameterKind::DescriptorTable(ref ranges) => {
+                    // Appended ranges follow the previous range, which doesn't have to end
+                    // the table.
+                    let (mut size, mut previous_end) = (0u32, 0u32);
+                    for range in ranges {
+                        let offset = match range.offset {
+                            d3d12::D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND => previous_end,
+                            offset => offset,
+                        };
+                        push(
+                            range.ty,
+                            range.base_binding,
+                            range.count,
+                            RootSlot::Table { root_index, offset },
+                        );
+                        previous_end = offset.saturating_add(range.count);
+                        size = size.max(previous_end);
+                    }
+                    table_sizes.push(Some(size));
+                    continue;
+                }
+                RootParameterKind::Constants { binding, .. } => (
+                    DescriptorRangeType::CBV,
+                    binding,
+                    RootSlot::Constants { root_index },
+                ),
+                RootParameterKind::Cbv { binding, .. } => (
+                    DescriptorRangeType::CBV,
+                    binding,
+                    RootSlot::Descriptor { root_index },
+                ),
+                RootParameterKind::Srv { binding, .. } => (
+                    DescriptorRangeType::SRV,
+                    binding,
+                    RootSlot::Descriptor { root_index },
+                ),
+                RootParameterKind::Uav { binding, .. } => (
+                    DescriptorRangeType::UAV,
+                    binding,
+                    RootSlot::Descriptor { root_index },
+                ),
+            };
+            push(ty, binding, 1, slot);
+            table_sizes.push(None);
+        }
+
+        for (index, sampler) in desc.static_samplers.iter().enumerate() {
+            let register = sampler.binding.register as u64;
+            entries.push(Entry {
+                ty: DescriptorRangeType::Sampler,
+                space: sampler.binding.space,
+                visibility: sampler.visibility,
+                registers: (register, register + 1),
+                slot: RootSlot::StaticSampler(index),
+            });
+        }
+
+        RootSignatureLayout {
+            entries,
+            table_sizes,
+        }
+    }
+
+    fn find(
+        &self,
+        ty: DescriptorRangeType,
+        binding: Binding,
+        visible: impl Fn(ShaderVisibility) -> bool,
+    ) -> Option<RootSlot> {
+        let register = binding.register as u64;
+        self.entries
+            .iter()
+            .find(|entry| {
+                entry.ty == ty
+                    && entry.space == binding.space
+                    && entry.registers.0 <= register
+                    && register < entry.registers.1
+                    && visible(entry.visibility)
+            })
+            .map(|entry| match entry.slot {
+                RootSlot::Table { root_index, offset } => RootSlot::Table {
+                    root_index,
+                    offset: offset.saturating_add((register - entry.registers.0) as u32),
+                },
+                slot => slot,
+            })
+    }
+
+    /// Location of a register, seen from any shader stage.
+    ///
+    /// Returns the first parameter binding it if several do for different stages.
+    pub fn resolve(&self, ty: DescriptorRangeType, binding: Binding) -> Option<RootSlot> {
+        self.find(ty, binding, |_| true)
+    }
+
+    /// Location of a register as seen by the shader stage `visibility`.
+    pub fn resolve_for_stage(
+        &self,
+        ty: DescriptorRangeType,
+        binding: Binding,
+        visibility: ShaderVisibility,
+    ) -> Option<RootSlot> {
+        self.find(ty, binding, |entry| {
+            entry == ShaderVisibility::All || entry == visibility
+        })
+    }
+
+    /// Descriptors needed for the table at `root_index`, `None` if it isn't a table.
+    ///
+    /// Unbounded ranges make this saturate at `u32::MAX`.
+    pub fn table_size(&self, root_index: RootIndex) -> Option<u32> {
+        self.table_sizes
+            .get(root_index as usize)
+            .and_then(|&size| size)
+    }
+}
+
+impl RootSignatureDesc {
+    pub fn layout(&self) -> RootSignatureLayout {
+        RootSignatureLayout::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        DescriptorRangeDesc, DescriptorRangeFlags, RootDescriptorFlags, RootSignatureVersion,
+        StaticBorderColor, StaticSamplerDesc,
+    };
+
+    const APPEND: u32 = d3d12::D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND;
+
+    fn binding(space: u32, register: u32) -> Binding {
+        Binding { space, register }
+    }
+
+    fn srv(register: u32, count: u32, offset: u32) -> DescriptorRangeDesc {
+        DescriptorRangeDesc::new(
+            DescriptorRangeType::SRV,
+            count,
+            binding(0, register),
+            offset,
+            DescriptorRangeFlags::empty(),
+        )
+    }
+
+    fn table(root_index: RootIndex, offset: u32) -> Option<RootSlot> {
+        Some(RootSlot::Table { root_index, offset })
+    }
+
+    fn table_layout(ranges: Vec<DescriptorRangeDesc>) -> RootSignatureLayout {
+        RootSignatureDesc::new(RootSignatureVersion::V1_1)
+            .with_descriptor_table(ShaderVisibility::All, ranges)
+            .layout()
+    }
+
+    #[test]
+    fn explicit_offsets() {
+        let layout = table_layout(vec![srv(0, 2, 4), srv(10, 3, 0)]);
+        let t = |register| layout.resolve(DescriptorRangeType::SRV, binding(0, register));
+        assert_eq!(t(0), table(0, 4));
+        assert_eq!(t(1), table(0, 5));
+        assert_eq!(t(2), None);
+        assert_eq!(t(10), table(0, 0));
+        assert_eq!(t(12), table(0, 2));
+        assert_eq!(t(13), None);
+        assert_eq!(layout.table_size(0), Some(6));
+    }
+
+    #[test]
+    fn append() {
+        let layout = table_layout(vec![srv(0, 2, APPEND), srv(2, 3, APPEND)]);
+        let t = |register| layout.resolve(DescriptorRangeType::SRV, binding(0, register));
+        assert_eq!(t(1), table(0, 1));
+        assert_eq!(t(2), table(0, 2));
+        assert_eq!(t(4), table(0, 4));
+        assert_eq!(layout.table_size(0), Some(5));
+
+        // Appended after the previous range, not after the end of the table.
+        let layout = table_layout(vec![srv(0, 2, 4), srv(10, 2, 0), srv(20, 1, APPEND)]);
+        assert_eq!(
+            layout.resolve(DescriptorRangeType::SRV, binding(0, 20)),
+            table(0, 2)
+        );
+        assert_eq!(layout.table_size(0), Some(6));
+    }
+
+    #[test]
+    fn unbounded() {
+        let layout = table_layout(vec![srv(0, 4, 2), srv(8, !0, APPEND)]);
+        let t = |register| layout.resolve(DescriptorRangeType::SRV, binding(0, register));
+        assert_eq!(t(4), None);
+        assert_eq!(t(8), table(0, 6));
+        assert_eq!(t(1000), table(0, 998));
+        // The registers of an unbounded range end at `1 << 32`.
+        assert_eq!(t(u32::MAX), table(0, u32::MAX - 2));
+        assert_eq!(layout.table_size(0), Some(u32::MAX));
+    }
+
+    #[test]
+    fn parameters() {
+        let desc = RootSignatureDesc::new(RootSignatureVersion::V1_1)
+            .with_constants(ShaderVisibility::All, binding(0, 0), 4)
+            .with_cbv(
+                ShaderVisibility::All,
+                binding(0, 1),
+                RootDescriptorFlags::empty(),
+            )
+            .with_uav(
+                ShaderVisibility::All,
+                binding(2, 0),
+                RootDescriptorFlags::empty(),
+            )
+            .with_descriptor_table(ShaderVisibility::All, Vec::new());
+        let layout = desc.layout();
+        assert_eq!(
+            layout.resolve(DescriptorRangeType::CBV, binding(0, 0)),
+            Some(RootSlot::Constants { root_index: 0 })
+        );
+        assert_eq!(
+            layout.resolve(DescriptorRangeType::CBV, binding(0, 1)),
+            Some(RootSlot::Descriptor { root_index: 1 })
+        );
+        assert_eq!(
+            layout.resolve(DescriptorRangeType::UAV, binding(2, 0)),
+            Some(RootSlot::Descriptor { root_index: 2 })
+        );
+        assert_eq!(
+            layout.resolve(DescriptorRangeType::UAV, binding(0, 0)),
+            None
+        );
+        assert_eq!(
+            layout.resolve(DescriptorRangeType::SRV, binding(2, 0)),
+            None
+        );
+        assert_eq!(layout.table_size(1), None);
+        assert_eq!(layout.table_size(3), Some(0));
+        assert_eq!(layout.table_size(4), None);
+    }
+
+    #[test]
+    fn stage_visibility() {
+        let desc = RootSignatureDesc::new(RootSignatureVersion::V1_1)
+            .with_cbv(
+                ShaderVisibility::VS,
+                binding(0, 0),
+                RootDescriptorFlags::empty(),
+            )
+            .with_cbv(
+                ShaderVisibility::PS,
+                binding(0, 0),
+                RootDescriptorFlags::empty(),
+            )
+            .with_descriptor_table(ShaderVisibility::All, vec![srv(0, 1, 0)]);
+        let layout = desc.layout();
+        let b0 = |stage| layout.resolve_for_stage(DescriptorRangeType::CBV, binding(0, 0), stage);
+        assert_eq!(
+            b0(ShaderVisibility::VS),
+            Some(RootSlot::Descriptor { root_index: 0 })
+        );
+        assert_eq!(
+            b0(ShaderVisibility::PS),
+            Some(RootSlot::Descriptor { root_index: 1 })
+        );
+        assert_eq!(b0(ShaderVisibility::GS), None);
+        // `resolve` picks the first parameter.
+        assert_eq!(
+            layout.resolve(DescriptorRangeType::CBV, binding(0, 0)),
+            Some(RootSlot::Descriptor { root_index: 0 })
+        );
+        assert_eq!(
+            layout.resolve_for_stage(
+                DescriptorRangeType::SRV,
+                binding(0, 0),
+                ShaderVisibility::GS
+            ),
+            table(2, 0)
+        );
+    }
+
+    #[test]
+    fn static_samplers() {
+        let sampler = |visibility, register| StaticSamplerDesc {
+            visibility,
+            binding: binding(1, register),
+            filter: d3d12::D3D12_FILTER_ANISOTROPIC,
+            address_mode: [d3d12::D3D12_TEXTURE_ADDRESS_MODE_WRAP; 3],
+            mip_lod_bias: 0.0,
+            max_anisotropy: 16,
+            comparison_op: d3d12::D3D12_COMPARISON_FUNC_LESS_EQUAL,
+            border_color: StaticBorderColor::OpaqueWhite,
+            lod: 0.0..d3d12::D3D12_FLOAT32_MAX,
+        };
+        let desc = RootSignatureDesc::new(RootSignatureVersion::V1_1)
+            .with_descriptor_table(
+                ShaderVisibility::All,
+                vec![DescriptorRangeDesc::new(
+                    DescriptorRangeType::Sampler,
+                    2,
+                    binding(0, 0),
+                    APPEND,
+                    DescriptorRangeFlags::empty(),
+                )],
+            )
+            .with_static_sampler(sampler(ShaderVisibility::All, 0))
+            .with_static_sampler(sampler(ShaderVisibility::PS, 3));
+        let layout = desc.layout();
+        let s = |space, register| {
+            layout.resolve(DescriptorRangeType::Sampler, binding(space, register))
+        };
+        assert_eq!(s(0, 1), table(0, 1));
+        assert_eq!(s(1, 0), Some(RootSlot::StaticSampler(0)));
+        assert_eq!(s(1, 3), Some(RootSlot::StaticSampler(1)));
+        assert_eq!(s(1, 1), None);
+        assert_eq!(
+            layout.resolve_for_stage(
+                DescriptorRangeType::Sampler,
+                binding(1, 3),
+                ShaderVisibility::VS
+            ),
+            None
+        );
+    }
+}
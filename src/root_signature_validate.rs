@@ -0,0 +1,693 @@
+//! Validation of root signature descriptions
+//!
+//! Catches the mistakes which otherwise only surface as a failing `create_root_signature`,
+//! reporting each of them with the location of the offending parameter.
+
+use crate::{
+    DescriptorRangeFlags, DescriptorRangeType, RootDescriptorFlags, RootParameterKind,
+    RootSignatureDesc, RootSignatureFlags, RootSignatureVersion, ShaderVisibility,
+};
+use std::fmt;
+use winapi::um::d3d12;
+
+/// Size limit of a root signature in DWORDs.
+pub const MAX_ROOT_COST: u32 = 64;
+
+/// Element of a root signature a diagnostic refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RootSignatureLocation {
+    Parameter(usize),
+    /// Range of the descriptor table at `parameter`.
+    Range {
+        parameter: usize,
+        range: usize,
+    },
+    StaticSampler(usize),
+}
+
+impl fmt::Display for RootSignatureLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RootSignatureLocation::Parameter(index) => write!(f, "parameter {}", index),
+            RootSignatureLocation::Range { parameter, range } => {
+                write!(f, "range {} of parameter {}", range, parameter)
+            }
+            RootSignatureLocation::StaticSampler(index) => write!(f, "static sampler {}", index),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RootSignatureIssue {
+    /// The parameters up to and including this one exceed `MAX_ROOT_COST`.
+    CostExceeded {
+        cost: u32,
+    },
+    /// Registers are also bound at `other`, for a shader stage seeing both.
+    OverlappingRegisters {
+        ty: DescriptorRangeType,
+        space: u32,
+        other: RootSignatureLocation,
+    },
+    /// The last register is past `u32::MAX`.
+    RegisterOverflow,
+    /// An unbounded range is followed by a range appended to it.
+    UnboundedRangeNotLast,
+    /// Sampler ranges can't share a table with other ranges.
+    MixedSamplerTable,
+    EmptyDescriptorTable,
+    FlagsRequireVersion1_1,
+    InvalidRangeFlags(DescriptorRangeFlags),
+    InvalidDescriptorFlags(RootDescriptorFlags),
+    /// The only stage the element is visible to is denied root access.
+    DeniedVisibility(ShaderVisibility),
+}
+
+/// Problem found by `RootSignatureDesc::validate`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RootSignatureDiagnostic {
+    pub location: RootSignatureLocation,
+    pub issue: RootSignatureIssue,
+}
+
+impl fmt::Display for RootSignatureDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: ", self.location)?;
+        match self.issue {
+            RootSignatureIssue::CostExceeded { cost } => write!(
+                f,
+                "root signature costs {} DWORDs, the limit is {}",
+                cost, MAX_ROOT_COST
+            ),
+            RootSignatureIssue::OverlappingRegisters { ty, space, other } => write!(
+                f,
+                "{:?} registers in space {} overlap with {}",
+                ty, space, other
+            ),
+            RootSignatureIssue::RegisterOverflow => write!(f, "registers exceed the maximum"),
+            RootSignatureIssue::UnboundedRangeNotLast => {
+                write!(f, "unbounded range is followed by an appended range")
+            }
+            RootSignatureIssue::MixedSamplerTable => {
+                write!(f, "descriptor table mixes samplers with other descriptors")
+            }
+            RootSignatureIssue::EmptyDescriptorTable => write!(f, "descriptor table has no ranges"),
+            RootSignatureIssue::FlagsRequireVersion1_1 => {
+                write!(f, "flags require root signature version 1.1")
+            }
+            RootSignatureIssue::InvalidRangeFlags(flags) => {
+                write!(f, "invalid descriptor range flags {:?}", flags)
+            }
+            RootSignatureIssue::InvalidDescriptorFlags(flags) => {
+                write!(f, "invalid root descriptor flags {:?}", flags)
+            }
+            RootSignatureIssue::DeniedVisibility(visibility) => write!(
+                f,
+                "visible to {:?} only, which is denied root access",
+                visibility
+            ),
+        }
+    }
+}
+
+// Registers bound by an element of the root signature.
+struct Registers {
+    location: RootSignatureLocation,
+    ty: DescriptorRangeType,
+    space: u32,
+    visibility: ShaderVisibility,
+    // Exclusive end, `1 << 32` for unbounded ranges.
+    registers: (u64, u64),
+}
+
+impl Registers {
+    fn overlaps(&self, other: &Registers) -> bool {
+        let visible = self.visibility == other.visibility
+            || self.visibility == ShaderVisibility::All
+            || other.visibility == ShaderVisibility::All;
+        self.ty == other.ty
+            && self.space == other.space
+            && visible
+            && self.registers.0 < other.registers.1
+            && other.registers.0 < self.registers.1
+    }
+}
+
+const DATA_RANGE_FLAGS: DescriptorRangeFlags = DescriptorRangeFlags::from_bits_truncate(
+    DescriptorRangeFlags::DATA_VOLATILE.bits()
+        | DescriptorRangeFlags::DATA_STATIC_WHILE_SET_AT_EXECUTE.bits()
+        | DescriptorRangeFlags::DATA_STATIC.bits(),
+);
+
+fn valid_range_flags(ty: DescriptorRangeType, flags: DescriptorRangeFlags) -> bool {
+    if ty == DescriptorRangeType::Sampler {
+        return (flags - DescriptorRangeFlags::DESCRIPTORS_VOLATILE).is_empty();
+    }
+    (flags & DATA_RANGE_FLAGS).bits().count_ones() <= 1
+        && !flags.contains(
+            DescriptorRangeFlags::DESCRIPTORS_VOLATILE | DescriptorRangeFlags::DATA_STATIC,
+        )
+        && !flags.contains(
+            DescriptorRangeFlags::DESCRIPTORS_VOLATILE
+                | DescriptorRangeFlags::DESCRIPTORS_STATIC_KEEPING_BUFFER_BOUNDS_CHECKS,
+        )
+}
+
+fn denied(flags: RootSignatureFlags, visibility: ShaderVisibility) -> bool {
+    let deny = match visibility {
+        ShaderVisibility::All => return false,
+        ShaderVisibility::VS => RootSignatureFlags::DENY_VS_ROOT_ACCESS,
+        ShaderVisibility::HS => RootSignatureFlags::DENY_HS_ROOT_ACCESS,
+        ShaderVisibility::DS => RootSignatureFlags::DENY_DS_ROOT_ACCESS,
+        ShaderVisibility::GS => RootSignatureFlags::DENY_GS_ROOT_ACCESS,
+        ShaderVisibility::PS => RootSignatureFlags::DENY_PS_ROOT_ACCESS,
+        ShaderVisibility::AS => RootSignatureFlags::DENY_AS_ROOT_ACCESS,
+        ShaderVisibility::MS => RootSignatureFlags::DENY_MS_ROOT_ACCESS,
+    };
+    flags.contains(deny)
+}
+
+impl RootParameterKind {
+    /// Size of the parameter in the root signature in DWORDs.
+    pub fn cost(&self) -> u32 {
+        match *self {
+            RootParameterKind::DescriptorTable(_) => 1,
+            RootParameterKind::Constants { num_values, .. } => num_values,
+            RootParameterKind::Cbv { .. }
+            | RootParameterKind::Srv { .. }
+            | RootParameterKind::Uav { .. } => 2,
+        }
+    }
+}
+
+impl RootSignatureDesc {
+    /// Size of the root arguments in DWORDs, saturating at `u32::MAX`.
+    ///
+    /// Root signatures costing more than `MAX_ROOT_COST` fail to be created.
+    pub fn cost(&self) -> u32 {
+        self.parameters.iter().fold(0, |cost: u32, parameter| {
+            cost.saturating_add(parameter.kind.cost())
+        })
+    }
+
+    /// Problems which would make root signature creation fail, or leave parameters
+    /// inaccessible, in the order of the parameters.
+    ///
+    /// Lists of raw `RootParameter`s or `RootParameter1`s are checked by converting them with
+    /// `RootSignatureDesc::from_parameters` or `from_parameters_1_1` first.
+    ///
+    /// ```
+    /// use d3d12::{
+    ///     RootSignatureDesc, RootSignatureIssue, RootSignatureLocation, RootSignatureVersion,
+    /// };
+    ///
+    /// let desc =
+    ///     RootSignatureDesc::parse_hlsl("CBV(b0), CBV(b0)", RootSignatureVersion::V1_1).unwrap();
+    /// let diagnostics = desc.validate();
+    /// assert_eq!(diagnostics[0].location, RootSignatureLocation::Parameter(1));
+    /// match diagnostics[0].issue {
+    ///     RootSignatureIssue::OverlappingRegisters { other, .. } => {
+    ///         assert_eq!(other, RootSignatureLocation::Parameter(0))
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn validate(&self) -> Vec<RootSignatureDiagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut bindings = Vec::<Registers>::new();
+        let mut cost = 0u32;
+        let v1_1 = self.version >= RootSignatureVersion::V1_1;
+
+        let mut add_binding = |diagnostics: &mut Vec<RootSignatureDiagnostic>,
+                               binding: Registers| {
+            if binding.registers.1 > 1 << 32 {
+                diagnostics.push(RootSignatureDiagnostic {
+                    location: binding.location,
+                    issue: RootSignatureIssue::RegisterOverflow,
+                });
+            }
+            if let Some(other) = bindings.iter().find(|other| other.overlaps(&binding)) {
+                diagnostics.push(RootSignatureDiagnostic {
+                    location: binding.location,
+                    issue: RootSignatureIssue::OverlappingRegisters {
+                        ty: binding.ty,
+                        space: binding.space,
+                        other: other.location,
+                    },
+                });
+            }
+            bindings.push(binding);
+        };
+
+        for (index, parameter) in self.parameters.iter().enumerate() {
+            let location = RootSignatureLocation::Parameter(index);
+            let mut report = |issue| diagnostics.push(RootSignatureDiagnostic { location, issue });
+
+            let previous_cost = cost;
+            cost = cost.saturating_add(parameter.kind.cost());
+            if cost > MAX_ROOT_COST && previous_cost <= MAX_ROOT_COST {
+                report(RootSignatureIssue::CostExceeded { cost: self.cost() });
+            }
+            if denied(self.flags, parameter.visibility) {
+                report(RootSignatureIssue::DeniedVisibility(parameter.visibility));
+            }
+
+            let (ty, binding, flags) = match parameter.kind {
+                RootParameterKind::DescriptorTable(ref ranges) => {
+                    if ranges.is_empty() {
+                        report(RootSignatureIssue::EmptyDescriptorTable);
+                    }
+                    let samplers = ranges
+                        .iter()
+                        .filter(|range| range.ty == DescriptorRangeType::Sampler)
+                        .count();
+                    if samplers != 0 && samplers != ranges.len() {
+                        report(RootSignatureIssue::MixedSamplerTable);
+                    }
+
+                    for (i, range) in ranges.iter().enumerate() {
+                        let location = RootSignatureLocation::Range {
+                            parameter: index,
+                            range: i,
+                        };
+                        let mut report =
+                            |issue| diagnostics.push(RootSignatureDiagnostic { location, issue });
+
+                        let unbounded = range.count == !0;
+                        let next_appended = matches!(
+                            ranges.get(i + 1),
+                            Some(next) if next.offset == d3d12::D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND
+                        );
+                        if unbounded && next_appended {
+                            report(RootSignatureIssue::UnboundedRangeNotLast);
+                        }
+                        if !v1_1 && !range.flags.is_empty() {
+                            report(RootSignatureIssue::FlagsRequireVersion1_1);
+                        } else if !valid_range_flags(range.ty, range.flags) {
+                            report(RootSignatureIssue::InvalidRangeFlags(range.flags));
+                        }
+
+                        let start = range.base_binding.register as u64;
+                        let end = if unbounded {
+                            1 << 32
+                        } else {
+                            start + range.count as u64
+                        };
+                        add_binding(
+                            &mut diagnostics,
+                            Registers {
+                                location,
+                                ty: range.ty,
+                                space: range.base_binding.space,
+                                visibility: parameter.visibility,
+                                registers: (start, end),
+                            },
+                        );
+                    }
+                    continue;
+                }
+                RootParameterKind::Constants { binding, .. } => (
+                    DescriptorRangeType::CBV,
+                    binding,
+                    RootDescriptorFlags::empty(),
+                ),
+                RootParameterKind::Cbv { binding, flags } => {
+                    (DescriptorRangeType::CBV, binding, flags)
+                }
+                RootParameterKind::Srv { binding, flags } => {
+                    (DescriptorRangeType::SRV, binding, flags)
+                }
+                RootParameterKind::Uav { binding, flags } => {
+                    (DescriptorRangeType::UAV, binding, flags)
+                }
+            };
+
+            if !v1_1 && !flags.is_empty() {
+                report(RootSignatureIssue::FlagsRequireVersion1_1);
+            } else if flags.bits().count_ones() > 1 {
+                report(RootSignatureIssue::InvalidDescriptorFlags(flags));
+            }
+            add_binding(
+                &mut diagnostics,
+                Registers {
+                    location,
+                    ty,
+                    space: binding.space,
+                    visibility: parameter.visibility,
+                    registers: (binding.register as u64, binding.register as u64 + 1),
+                },
+            );
+        }
+
+        for (index, sampler) in self.static_samplers.iter().enumerate() {
+            let location = RootSignatureLocation::StaticSampler(index);
+            if denied(self.flags, sampler.visibility) {
+                diagnostics.push(RootSignatureDiagnostic {
+                    location,
+                    issue: RootSignatureIssue::DeniedVisibility(sampler.visibility),
+                });
+            }
+            add_binding(
+                &mut diagnostics,
+                Registers {
+                    location,
+                    ty: DescriptorRangeType::Sampler,
+                    space: sampler.binding.space,
+                    visibility: sampler.visibility,
+                    registers: (
+                        sampler.binding.register as u64,
+                        sampler.binding.register as u64 + 1,
+                    ),
+                },
+            );
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, DescriptorRangeDesc, StaticBorderColor, StaticSamplerDesc};
+
+    const APPEND: u32 = d3d12::D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND;
+
+    fn binding(space: u32, register: u32) -> Binding {
+        Binding { space, register }
+    }
+
+    fn srv(space: u32, register: u32, count: u32, offset: u32) -> DescriptorRangeDesc {
+        DescriptorRangeDesc::new(
+            DescriptorRangeType::SRV,
+            count,
+            binding(space, register),
+            offset,
+            DescriptorRangeFlags::empty(),
+        )
+    }
+
+    fn sampler(visibility: ShaderVisibility, register: u32) -> StaticSamplerDesc {
+        StaticSamplerDesc {
+            visibility,
+            binding: binding(0, register),
+            filter: d3d12::D3D12_FILTER_ANISOTROPIC,
+            address_mode: [d3d12::D3D12_TEXTURE_ADDRESS_MODE_WRAP; 3],
+            mip_lod_bias: 0.0,
+            max_anisotropy: 16,
+            comparison_op: d3d12::D3D12_COMPARISON_FUNC_LESS_EQUAL,
+            border_color: StaticBorderColor::OpaqueWhite,
+            lod: 0.0..d3d12::D3D12_FLOAT32_MAX,
+        }
+    }
+
+    fn diagnostic(
+        location: RootSignatureLocation,
+        issue: RootSignatureIssue,
+    ) -> RootSignatureDiagnostic {
+        RootSignatureDiagnostic { location, issue }
+    }
+
+    fn parameter(index: usize) -> RootSignatureLocation {
+        RootSignatureLocation::Parameter(index)
+    }
+
+    fn range(parameter: usize, range: usize) -> RootSignatureLocation {
+        RootSignatureLocation::Range { parameter, range }
+    }
+
+    fn v1_1() -> RootSignatureDesc {
+        RootSignatureDesc::new(RootSignatureVersion::V1_1)
+    }
+
+    #[test]
+    fn valid() {
+        let desc = RootSignatureDesc::parse_hlsl(
+            "RootConstants(b0, num32BitConstants = 60), CBV(b1), \
+             DescriptorTable(SRV(t0, numDescriptors = 4), SRV(t4, numDescriptors = unbounded)), \
+             StaticSampler(s0)",
+            RootSignatureVersion::V1_1,
+        )
+        .unwrap();
+        assert_eq!(desc.cost(), 63);
+        assert_eq!(desc.validate(), []);
+    }
+
+    #[test]
+    fn cost_exceeded() {
+        let desc = v1_1()
+            .with_constants(ShaderVisibility::All, binding(0, 0), 63)
+            .with_cbv(
+                ShaderVisibility::All,
+                binding(0, 1),
+                RootDescriptorFlags::empty(),
+            )
+            .with_descriptor_table(ShaderVisibility::All, vec![srv(0, 0, 1, 0)]);
+        assert_eq!(desc.cost(), 66);
+        // Reported once, at the parameter crossing the limit, with the total cost.
+        assert_eq!(
+            desc.validate(),
+            [diagnostic(
+                parameter(1),
+                RootSignatureIssue::CostExceeded { cost: 66 }
+            )]
+        );
+
+        let desc = v1_1()
+            .with_constants(ShaderVisibility::All, binding(0, 0), !0)
+            .with_constants(ShaderVisibility::All, binding(0, 1), 2);
+        assert_eq!(desc.cost(), u32::MAX);
+        assert_eq!(
+            desc.validate(),
+            [diagnostic(
+                parameter(0),
+                RootSignatureIssue::CostExceeded { cost: u32::MAX }
+            )]
+        );
+    }
+
+    #[test]
+    fn overlapping_registers() {
+        let desc = v1_1()
+            .with_cbv(
+                ShaderVisibility::VS,
+                binding(0, 0),
+                RootDescriptorFlags::empty(),
+            )
+            // Visible to different stages.
+            .with_cbv(
+                ShaderVisibility::PS,
+                binding(0, 0),
+                RootDescriptorFlags::empty(),
+            )
+            // Different space or type.
+            .with_cbv(
+                ShaderVisibility::All,
+                binding(1, 0),
+                RootDescriptorFlags::empty(),
+            )
+            .with_srv(
+                ShaderVisibility::All,
+                binding(1, 0),
+                RootDescriptorFlags::empty(),
+            )
+            .with_descriptor_table(ShaderVisibility::PS, vec![srv(1, 0, !0, 0)])
+            .with_constants(ShaderVisibility::All, binding(0, 0), 1)
+            .with_static_sampler(sampler(ShaderVisibility::All, 0))
+            .with_static_sampler(sampler(ShaderVisibility::GS, 0));
+        let overlap =
+            |ty, space, other| RootSignatureIssue::OverlappingRegisters { ty, space, other };
+        assert_eq!(
+            desc.validate(),
+            [
+                diagnostic(
+                    range(4, 0),
+                    overlap(DescriptorRangeType::SRV, 1, parameter(3))
+                ),
+                diagnostic(
+                    parameter(5),
+                    overlap(DescriptorRangeType::CBV, 0, parameter(0))
+                ),
+                diagnostic(
+                    RootSignatureLocation::StaticSampler(1),
+                    overlap(
+                        DescriptorRangeType::Sampler,
+                        0,
+                        RootSignatureLocation::StaticSampler(0)
+                    )
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn register_overflow() {
+        let desc = v1_1().with_descriptor_table(
+            ShaderVisibility::All,
+            vec![srv(0, !0, 1, 0), srv(1, !0, 2, APPEND)],
+        );
+        assert_eq!(
+            desc.validate(),
+            [diagnostic(
+                range(0, 1),
+                RootSignatureIssue::RegisterOverflow
+            )]
+        );
+    }
+
+    #[test]
+    fn unbounded_range_not_last() {
+        // Only a range appended right after the unbounded one is a problem.
+        let desc = v1_1()
+            .with_descriptor_table(
+                ShaderVisibility::All,
+                vec![srv(0, 0, !0, 0), srv(1, 0, 1, 8), srv(2, 0, 1, APPEND)],
+            )
+            .with_descriptor_table(
+                ShaderVisibility::All,
+                vec![srv(3, 0, 1, 0), srv(4, 0, !0, APPEND), srv(5, 0, 1, APPEND)],
+            );
+        assert_eq!(
+            desc.validate(),
+            [diagnostic(
+                range(1, 1),
+                RootSignatureIssue::UnboundedRangeNotLast
+            )]
+        );
+    }
+
+    #[test]
+    fn table_contents() {
+        let sampler_range = DescriptorRangeDesc::new(
+            DescriptorRangeType::Sampler,
+            1,
+            binding(0, 0),
+            APPEND,
+            DescriptorRangeFlags::empty(),
+        );
+        let desc = v1_1()
+            .with_descriptor_table(ShaderVisibility::All, Vec::new())
+            .with_descriptor_table(ShaderVisibility::All, vec![srv(0, 0, 1, 0), sampler_range])
+            .with_descriptor_table(
+                ShaderVisibility::PS,
+                vec![DescriptorRangeDesc {
+                    base_binding: binding(0, 1),
+                    ..sampler_range
+                }],
+            );
+        assert_eq!(
+            desc.validate(),
+            [
+                diagnostic(parameter(0), RootSignatureIssue::EmptyDescriptorTable),
+                diagnostic(parameter(1), RootSignatureIssue::MixedSamplerTable),
+            ]
+        );
+    }
+
+    #[test]
+    fn flags() {
+        let mut volatile = srv(0, 0, 1, 0);
+        volatile.flags = DescriptorRangeFlags::DATA_VOLATILE;
+        let desc = RootSignatureDesc::new(RootSignatureVersion::V1_0)
+            .with_descriptor_table(ShaderVisibility::All, vec![volatile])
+            .with_uav(
+                ShaderVisibility::All,
+                binding(0, 0),
+                RootDescriptorFlags::DATA_VOLATILE,
+            )
+            .with_uav(
+                ShaderVisibility::All,
+                binding(0, 1),
+                RootDescriptorFlags::empty(),
+            );
+        assert_eq!(
+            desc.validate(),
+            [
+                diagnostic(range(0, 0), RootSignatureIssue::FlagsRequireVersion1_1),
+                diagnostic(parameter(1), RootSignatureIssue::FlagsRequireVersion1_1),
+            ]
+        );
+        assert_eq!(
+            RootSignatureDesc {
+                version: RootSignatureVersion::V1_1,
+                ..desc
+            }
+            .validate(),
+            []
+        );
+
+        let data_flags = DescriptorRangeFlags::DATA_VOLATILE | DescriptorRangeFlags::DATA_STATIC;
+        let mut conflicting = srv(0, 0, 1, 0);
+        conflicting.flags = data_flags;
+        let sampler_range = DescriptorRangeDesc::new(
+            DescriptorRangeType::Sampler,
+            1,
+            binding(0, 0),
+            APPEND,
+            DescriptorRangeFlags::DATA_VOLATILE,
+        );
+        let descriptor_flags =
+            RootDescriptorFlags::DATA_VOLATILE | RootDescriptorFlags::DATA_STATIC;
+        let desc = v1_1()
+            .with_descriptor_table(ShaderVisibility::All, vec![conflicting])
+            .with_descriptor_table(ShaderVisibility::All, vec![sampler_range])
+            .with_cbv(ShaderVisibility::All, binding(0, 0), descriptor_flags);
+        assert_eq!(
+            desc.validate(),
+            [
+                diagnostic(
+                    range(0, 0),
+                    RootSignatureIssue::InvalidRangeFlags(data_flags)
+                ),
+                diagnostic(
+                    range(1, 0),
+                    RootSignatureIssue::InvalidRangeFlags(DescriptorRangeFlags::DATA_VOLATILE)
+                ),
+                diagnostic(
+                    parameter(2),
+                    RootSignatureIssue::InvalidDescriptorFlags(descriptor_flags)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn denied_visibility() {
+        let desc = v1_1()
+            .with_flags(RootSignatureFlags::DENY_PS_ROOT_ACCESS)
+            .with_cbv(
+                ShaderVisibility::PS,
+                binding(0, 0),
+                RootDescriptorFlags::empty(),
+            )
+            .with_cbv(
+                ShaderVisibility::All,
+                binding(0, 1),
+                RootDescriptorFlags::empty(),
+            )
+            .with_cbv(
+                ShaderVisibility::VS,
+                binding(0, 2),
+                RootDescriptorFlags::empty(),
+            )
+            .with_static_sampler(sampler(ShaderVisibility::PS, 0));
+        let diagnostics = desc.validate();
+        assert_eq!(
+            diagnostics,
+            [
+                diagnostic(
+                    parameter(0),
+                    RootSignatureIssue::DeniedVisibility(ShaderVisibility::PS)
+                ),
+                diagnostic(
+                    RootSignatureLocation::StaticSampler(0),
+                    RootSignatureIssue::DeniedVisibility(ShaderVisibility::PS)
+                ),
+            ]
+        );
+        assert_eq!(
+            diagnostics[1].to_string(),
+            "static sampler 0: visible to PS only, which is denied root access"
+        );
+    }
+}
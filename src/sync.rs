@@ -1,4 +1,7 @@
-use crate::{com::WeakPtr, HRESULT};
+use crate::{
+    com::{ComSend, ComSync, WeakPtr},
+    HRESULT,
+};
 use std::ptr;
 use winapi::um::{d3d12, synchapi, winnt};
 
@@ -24,6 +27,9 @@ impl Event {
 }
 
 pub type Fence = WeakPtr<d3d12::ID3D12Fence>;
+
+unsafe impl ComSend for d3d12::ID3D12Fence {}
+unsafe impl ComSync for d3d12::ID3D12Fence {}
 impl Fence {
     pub fn set_event_on_completion(&self, event: Event, value: u64) -> HRESULT {
         unsafe { self.SetEventOnCompletion(value, event.0) }
@@ -0,0 +1,630 @@
+//! Resource view descriptions
+
+use crate::Format;
+use std::{mem, ops::Range};
+use winapi::um::d3d12;
+
+// Not yet part of `winapi`.
+const D3D12_SRV_DIMENSION_RAYTRACING_ACCELERATION_STRUCTURE: d3d12::D3D12_SRV_DIMENSION = 11;
+
+/// Source of a component returned by shader resource view reads.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ShaderComponent {
+    Component0 = d3d12::D3D12_SHADER_COMPONENT_MAPPING_FROM_MEMORY_COMPONENT_0,
+    Component1 = d3d12::D3D12_SHADER_COMPONENT_MAPPING_FROM_MEMORY_COMPONENT_1,
+    Component2 = d3d12::D3D12_SHADER_COMPONENT_MAPPING_FROM_MEMORY_COMPONENT_2,
+    Component3 = d3d12::D3D12_SHADER_COMPONENT_MAPPING_FROM_MEMORY_COMPONENT_3,
+    Zero = d3d12::D3D12_SHADER_COMPONENT_MAPPING_FORCE_VALUE_0,
+    One = d3d12::D3D12_SHADER_COMPONENT_MAPPING_FORCE_VALUE_1,
+}
+
+/// Swizzle of the red, green, blue and alpha components.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ComponentMapping(pub [ShaderComponent; 4]);
+
+impl ComponentMapping {
+    pub const IDENTITY: Self = ComponentMapping([
+        ShaderComponent::Component0,
+        ShaderComponent::Component1,
+        ShaderComponent::Component2,
+        ShaderComponent::Component3,
+    ]);
+
+    /// Equivalent to `D3D12_ENCODE_SHADER_4_COMPONENT_MAPPING`.
+    pub fn encode(self) -> u32 {
+        let [r, g, b, a] = self.0;
+        (r as u32)
+            | (a as u32) << (3 * d3d12::D3D12_SHADER_COMPONENT_MAPPING_SHIFT)
+            | (b as u32) << (2 * d3d12::D3D12_SHADER_COMPONENT_MAPPING_SHIFT)
+            | (g as u32) << d3d12::D3D12_SHADER_COMPONENT_MAPPING_SHIFT
+            | d3d12::D3D12_SHADER_COMPONENT_MAPPING_ALWAYS_SET_BIT_AVOIDING_ZEROMEM_MISTAKES
+    }
+}
+
+impl Default for ComponentMapping {
+    fn default() -> Self {
+        ComponentMapping::IDENTITY
+    }
+}
+
+#[repr(transparent)]
+pub struct ShaderResourceViewDesc(pub(crate) d3d12::D3D12_SHADER_RESOURCE_VIEW_DESC);
+
+impl ShaderResourceViewDesc {
+    fn new(format: Format, dimension: d3d12::D3D12_SRV_DIMENSION) -> Self {
+        ShaderResourceViewDesc(d3d12::D3D12_SHADER_RESOURCE_VIEW_DESC {
+            Format: format as _,
+            ViewDimension: dimension,
+            Shader4ComponentMapping: ComponentMapping::IDENTITY.encode(),
+            ..unsafe { mem::zeroed() }
+        })
+    }
+
+    fn buffer_raw(format: Format, elements: Range<u64>, stride: u32, flags: u32) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_SRV_DIMENSION_BUFFER);
+        *unsafe { desc.0.u.Buffer_mut() } = d3d12::D3D12_BUFFER_SRV {
+            FirstElement: elements.start,
+            NumElements: (elements.end - elements.start) as _,
+            StructureByteStride: stride,
+            Flags: flags,
+        };
+        desc
+    }
+
+    /// Typed buffer view, elements are counted in units of `format`.
+    pub fn buffer(format: Format, elements: Range<u64>) -> Self {
+        Self::buffer_raw(format, elements, 0, d3d12::D3D12_BUFFER_SRV_FLAG_NONE)
+    }
+
+    pub fn structured_buffer(elements: Range<u64>, stride: u32) -> Self {
+        Self::buffer_raw(
+            Format::Unknown,
+            elements,
+            stride,
+            d3d12::D3D12_BUFFER_SRV_FLAG_NONE,
+        )
+    }
+
+    /// `ByteAddressBuffer` view, elements are 32 bit words.
+    pub fn raw_buffer(elements: Range<u64>) -> Self {
+        Self::buffer_raw(
+            Format::R32Typeless,
+            elements,
+            0,
+            d3d12::D3D12_BUFFER_SRV_FLAG_RAW,
+        )
+    }
+
+    /// `mip_levels` of `!0` selects all mips starting from `most_detailed_mip`.
+    pub fn texture_1d(format: Format, most_detailed_mip: u32, mip_levels: u32) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_SRV_DIMENSION_TEXTURE1D);
+        *unsafe { desc.0.u.Texture1D_mut() } = d3d12::D3D12_TEX1D_SRV {
+            MostDetailedMip: most_detailed_mip,
+            MipLevels: mip_levels,
+            ResourceMinLODClamp: 0.0,
+        };
+        desc
+    }
+
+    pub fn texture_1d_array(
+        format: Format,
+        most_detailed_mip: u32,
+        mip_levels: u32,
+        array: Range<u32>,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_SRV_DIMENSION_TEXTURE1DARRAY);
+        *unsafe { desc.0.u.Texture1DArray_mut() } = d3d12::D3D12_TEX1D_ARRAY_SRV {
+            MostDetailedMip: most_detailed_mip,
+            MipLevels: mip_levels,
+            FirstArraySlice: array.start,
+            ArraySize: array.end - array.start,
+            ResourceMinLODClamp: 0.0,
+        };
+        desc
+    }
+
+    pub fn texture_2d(
+        format: Format,
+        most_detailed_mip: u32,
+        mip_levels: u32,
+        plane_slice: u32,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_SRV_DIMENSION_TEXTURE2D);
+        *unsafe { desc.0.u.Texture2D_mut() } = d3d12::D3D12_TEX2D_SRV {
+            MostDetailedMip: most_detailed_mip,
+            MipLevels: mip_levels,
+            PlaneSlice: plane_slice,
+            ResourceMinLODClamp: 0.0,
+        };
+        desc
+    }
+
+    pub fn texture_2d_array(
+        format: Format,
+        most_detailed_mip: u32,
+        mip_levels: u32,
+        array: Range<u32>,
+        plane_slice: u32,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_SRV_DIMENSION_TEXTURE2DARRAY);
+        *unsafe { desc.0.u.Texture2DArray_mut() } = d3d12::D3D12_TEX2D_ARRAY_SRV {
+            MostDetailedMip: most_detailed_mip,
+            MipLevels: mip_levels,
+            FirstArraySlice: array.start,
+            ArraySize: array.end - array.start,
+            PlaneSlice: plane_slice,
+            ResourceMinLODClamp: 0.0,
+        };
+        desc
+    }
+
+    pub fn texture_2d_ms(format: Format) -> Self {
+        Self::new(format, d3d12::D3D12_SRV_DIMENSION_TEXTURE2DMS)
+    }
+
+    pub fn texture_2d_ms_array(format: Format, array: Range<u32>) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_SRV_DIMENSION_TEXTURE2DMSARRAY);
+        *unsafe { desc.0.u.Texture2DMSArray_mut() } = d3d12::D3D12_TEX2DMS_ARRAY_SRV {
+            FirstArraySlice: array.start,
+            ArraySize: array.end - array.start,
+        };
+        desc
+    }
+
+    pub fn texture_3d(format: Format, most_detailed_mip: u32, mip_levels: u32) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_SRV_DIMENSION_TEXTURE3D);
+        *unsafe { desc.0.u.Texture3D_mut() } = d3d12::D3D12_TEX3D_SRV {
+            MostDetailedMip: most_detailed_mip,
+            MipLevels: mip_levels,
+            ResourceMinLODClamp: 0.0,
+        };
+        desc
+    }
+
+    pub fn texture_cube(format: Format, most_detailed_mip: u32, mip_levels: u32) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_SRV_DIMENSION_TEXTURECUBE);
+        *unsafe { desc.0.u.TextureCube_mut() } = d3d12::D3D12_TEXCUBE_SRV {
+            MostDetailedMip: most_detailed_mip,
+            MipLevels: mip_levels,
+            ResourceMinLODClamp: 0.0,
+        };
+        desc
+    }
+
+    /// `first_face` is the array layer of the first face of the first cube.
+    pub fn texture_cube_array(
+        format: Format,
+        most_detailed_mip: u32,
+        mip_levels: u32,
+        first_face: u32,
+        num_cubes: u32,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_SRV_DIMENSION_TEXTURECUBEARRAY);
+        *unsafe { desc.0.u.TextureCubeArray_mut() } = d3d12::D3D12_TEXCUBE_ARRAY_SRV {
+            MostDetailedMip: most_detailed_mip,
+            MipLevels: mip_levels,
+            First2DArrayFace: first_face,
+            NumCubes: num_cubes,
+            ResourceMinLODClamp: 0.0,
+        };
+        desc
+    }
+
+    /// View of the acceleration structure at `location`, created without a resource.
+    pub fn raytracing_acceleration_structure(location: u64) -> Self {
+        let mut desc = Self::new(
+            Format::Unknown,
+            D3D12_SRV_DIMENSION_RAYTRACING_ACCELERATION_STRUCTURE,
+        );
+        // `D3D12_RAYTRACING_ACCELERATION_STRUCTURE_SRV` only holds the location.
+        unsafe { *(&mut desc.0.u as *mut _ as *mut u64) = location };
+        desc
+    }
+
+    pub fn with_component_mapping(mut self, mapping: ComponentMapping) -> Self {
+        self.0.Shader4ComponentMapping = mapping.encode();
+        self
+    }
+
+    /// Clamp sampling to mips at or below `clamp`, ignored by views without mips.
+    pub fn with_min_lod_clamp(mut self, clamp: f32) -> Self {
+        unsafe {
+            match self.0.ViewDimension {
+                d3d12::D3D12_SRV_DIMENSION_TEXTURE1D => {
+                    self.0.u.Texture1D_mut().ResourceMinLODClamp = clamp
+                }
+                d3d12::D3D12_SRV_DIMENSION_TEXTURE1DARRAY => {
+                    self.0.u.Texture1DArray_mut().ResourceMinLODClamp = clamp
+                }
+                d3d12::D3D12_SRV_DIMENSION_TEXTURE2D => {
+                    self.0.u.Texture2D_mut().ResourceMinLODClamp = clamp
+                }
+                d3d12::D3D12_SRV_DIMENSION_TEXTURE2DARRAY => {
+                    self.0.u.Texture2DArray_mut().ResourceMinLODClamp = clamp
+                }
+                d3d12::D3D12_SRV_DIMENSION_TEXTURE3D => {
+                    self.0.u.Texture3D_mut().ResourceMinLODClamp = clamp
+                }
+                d3d12::D3D12_SRV_DIMENSION_TEXTURECUBE => {
+                    self.0.u.TextureCube_mut().ResourceMinLODClamp = clamp
+                }
+                d3d12::D3D12_SRV_DIMENSION_TEXTURECUBEARRAY => {
+                    self.0.u.TextureCubeArray_mut().ResourceMinLODClamp = clamp
+                }
+                _ => {}
+            }
+        }
+        self
+    }
+
+    pub(crate) fn is_acceleration_structure(&self) -> bool {
+        self.0.ViewDimension == D3D12_SRV_DIMENSION_RAYTRACING_ACCELERATION_STRUCTURE
+    }
+}
+
+#[repr(transparent)]
+pub struct UnorderedAccessViewDesc(pub(crate) d3d12::D3D12_UNORDERED_ACCESS_VIEW_DESC);
+
+impl UnorderedAccessViewDesc {
+    fn new(format: Format, dimension: d3d12::D3D12_UAV_DIMENSION) -> Self {
+        UnorderedAccessViewDesc(d3d12::D3D12_UNORDERED_ACCESS_VIEW_DESC {
+            Format: format as _,
+            ViewDimension: dimension,
+            ..unsafe { mem::zeroed() }
+        })
+    }
+
+    fn buffer_raw(
+        format: Format,
+        elements: Range<u64>,
+        stride: u32,
+        counter_offset: u64,
+        flags: u32,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_UAV_DIMENSION_BUFFER);
+        *unsafe { desc.0.u.Buffer_mut() } = d3d12::D3D12_BUFFER_UAV {
+            FirstElement: elements.start,
+            NumElements: (elements.end - elements.start) as _,
+            StructureByteStride: stride,
+            CounterOffsetInBytes: counter_offset,
+            Flags: flags,
+        };
+        desc
+    }
+
+    /// Typed buffer view, elements are counted in units of `format`.
+    pub fn buffer(format: Format, elements: Range<u64>) -> Self {
+        Self::buffer_raw(format, elements, 0, 0, d3d12::D3D12_BUFFER_UAV_FLAG_NONE)
+    }
+
+    /// Structured buffer view, the hidden counter is located at `counter_offset` in the
+    /// counter resource passed to `Device::create_unordered_access_view`.
+    ///
+    /// Panics if the offset isn't a multiple of `D3D12_UAV_COUNTER_PLACEMENT_ALIGNMENT`.
+    pub fn structured_buffer(elements: Range<u64>, stride: u32, counter_offset: u64) -> Self {
+        assert_eq!(
+            counter_offset & (d3d12::D3D12_UAV_COUNTER_PLACEMENT_ALIGNMENT as u64 - 1),
+            0,
+            "misaligned UAV counter offset"
+        );
+        Self::buffer_raw(
+            Format::Unknown,
+            elements,
+            stride,
+            counter_offset,
+            d3d12::D3D12_BUFFER_UAV_FLAG_NONE,
+        )
+    }
+
+    /// `RWByteAddressBuffer` view, elements are 32 bit words.
+    pub fn raw_buffer(elements: Range<u64>) -> Self {
+        Self::buffer_raw(
+            Format::R32Typeless,
+            elements,
+            0,
+            0,
+            d3d12::D3D12_BUFFER_UAV_FLAG_RAW,
+        )
+    }
+
+    pub fn texture_1d(format: Format, mip_slice: u32) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_UAV_DIMENSION_TEXTURE1D);
+        *unsafe { desc.0.u.Texture1D_mut() } = d3d12::D3D12_TEX1D_UAV {
+            MipSlice: mip_slice,
+        };
+        desc
+    }
+
+    pub fn texture_1d_array(format: Format, mip_slice: u32, array: Range<u32>) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_UAV_DIMENSION_TEXTURE1DARRAY);
+        *unsafe { desc.0.u.Texture1DArray_mut() } = d3d12::D3D12_TEX1D_ARRAY_UAV {
+            MipSlice: mip_slice,
+            FirstArraySlice: array.start,
+            ArraySize: array.end - array.start,
+        };
+        desc
+    }
+
+    pub fn texture_2d(format: Format, mip_slice: u32, plane_slice: u32) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_UAV_DIMENSION_TEXTURE2D);
+        *unsafe { desc.0.u.Texture2D_mut() } = d3d12::D3D12_TEX2D_UAV {
+            MipSlice: mip_slice,
+            PlaneSlice: plane_slice,
+        };
+        desc
+    }
+
+    pub fn texture_2d_array(
+        format: Format,
+        mip_slice: u32,
+        array: Range<u32>,
+        plane_slice: u32,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_UAV_DIMENSION_TEXTURE2DARRAY);
+        *unsafe { desc.0.u.Texture2DArray_mut() } = d3d12::D3D12_TEX2D_ARRAY_UAV {
+            MipSlice: mip_slice,
+            FirstArraySlice: array.start,
+            ArraySize: array.end - array.start,
+            PlaneSlice: plane_slice,
+        };
+        desc
+    }
+
+    pub fn texture_3d(format: Format, mip_slice: u32, w_slices: Range<u32>) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_UAV_DIMENSION_TEXTURE3D);
+        *unsafe { desc.0.u.Texture3D_mut() } = d3d12::D3D12_TEX3D_UAV {
+            MipSlice: mip_slice,
+            FirstWSlice: w_slices.start,
+            WSize: w_slices.end - w_slices.start,
+        };
+        desc
+    }
+}
+
+#[repr(transparent)]
+pub struct ConstantBufferViewDesc(pub(crate) d3d12::D3D12_CONSTANT_BUFFER_VIEW_DESC);
+
+impl ConstantBufferViewDesc {
+    /// Panics if `size_in_bytes` or the location isn't a multiple of
+    /// `D3D12_CONSTANT_BUFFER_DATA_PLACEMENT_ALIGNMENT`.
+    pub fn new(buffer_location: u64, size_in_bytes: u32) -> Self {
+        let alignment = d3d12::D3D12_CONSTANT_BUFFER_DATA_PLACEMENT_ALIGNMENT;
+        assert_eq!(
+            buffer_location & (alignment as u64 - 1),
+            0,
+            "misaligned constant buffer location"
+        );
+        assert_eq!(
+            size_in_bytes & (alignment - 1),
+            0,
+            "misaligned constant buffer size"
+        );
+        ConstantBufferViewDesc(d3d12::D3D12_CONSTANT_BUFFER_VIEW_DESC {
+            BufferLocation: buffer_location,
+            SizeInBytes: size_in_bytes,
+        })
+    }
+}
+
+#[repr(transparent)]
+pub struct RenderTargetViewDesc(pub(crate) d3d12::D3D12_RENDER_TARGET_VIEW_DESC);
+
+impl RenderTargetViewDesc {
+    fn new(format: Format, dimension: d3d12::D3D12_RTV_DIMENSION) -> Self {
+        RenderTargetViewDesc(d3d12::D3D12_RENDER_TARGET_VIEW_DESC {
+            Format: format as _,
+            ViewDimension: dimension,
+            ..unsafe { mem::zeroed() }
+        })
+    }
+
+    pub fn buffer(format: Format, elements: Range<u64>) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_RTV_DIMENSION_BUFFER);
+        *unsafe { desc.0.u.Buffer_mut() } = d3d12::D3D12_BUFFER_RTV {
+            FirstElement: elements.start,
+            NumElements: (elements.end - elements.start) as _,
+        };
+        desc
+    }
+
+    pub fn texture_1d(format: Format, mip_slice: u32) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_RTV_DIMENSION_TEXTURE1D);
+        *unsafe { desc.0.u.Texture1D_mut() } = d3d12::D3D12_TEX1D_RTV {
+            MipSlice: mip_slice,
+        };
+        desc
+    }
+
+    pub fn texture_1d_array(format: Format, mip_slice: u32, array: Range<u32>) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_RTV_DIMENSION_TEXTURE1DARRAY);
+        *unsafe { desc.0.u.Texture1DArray_mut() } = d3d12::D3D12_TEX1D_ARRAY_RTV {
+            MipSlice: mip_slice,
+            FirstArraySlice: array.start,
+            ArraySize: array.end - array.start,
+        };
+        desc
+    }
+
+    pub fn texture_2d(format: Format, mip_slice: u32, plane_slice: u32) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_RTV_DIMENSION_TEXTURE2D);
+        *unsafe { desc.0.u.Texture2D_mut() } = d3d12::D3D12_TEX2D_RTV {
+            MipSlice: mip_slice,
+            PlaneSlice: plane_slice,
+        };
+        desc
+    }
+
+    pub fn texture_2d_array(
+        format: Format,
+        mip_slice: u32,
+        array: Range<u32>,
+        plane_slice: u32,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_RTV_DIMENSION_TEXTURE2DARRAY);
+        *unsafe { desc.0.u.Texture2DArray_mut() } = d3d12::D3D12_TEX2D_ARRAY_RTV {
+            MipSlice: mip_slice,
+            FirstArraySlice: array.start,
+            ArraySize: array.end - array.start,
+            PlaneSlice: plane_slice,
+        };
+        desc
+    }
+
+    pub fn texture_2d_ms(format: Format) -> Self {
+        Self::new(format, d3d12::D3D12_RTV_DIMENSION_TEXTURE2DMS)
+    }
+
+    pub fn texture_2d_ms_array(format: Format, array: Range<u32>) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_RTV_DIMENSION_TEXTURE2DMSARRAY);
+        *unsafe { desc.0.u.Texture2DMSArray_mut() } = d3d12::D3D12_TEX2DMS_ARRAY_RTV {
+            FirstArraySlice: array.start,
+            ArraySize: array.end - array.start,
+        };
+        desc
+    }
+
+    pub fn texture_3d(format: Format, mip_slice: u32, w_slices: Range<u32>) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_RTV_DIMENSION_TEXTURE3D);
+        *unsafe { desc.0.u.Texture3D_mut() } = d3d12::D3D12_TEX3D_RTV {
+            MipSlice: mip_slice,
+            FirstWSlice: w_slices.start,
+            WSize: w_slices.end - w_slices.start,
+        };
+        desc
+    }
+}
+
+bitflags! {
+    pub struct DepthStencilViewFlags: u32 {
+        const READ_ONLY_DEPTH = d3d12::D3D12_DSV_FLAG_READ_ONLY_DEPTH;
+        const READ_ONLY_STENCIL = d3d12::D3D12_DSV_FLAG_READ_ONLY_STENCIL;
+    }
+}
+
+#[repr(transparent)]
+pub struct DepthStencilViewDesc(pub(crate) d3d12::D3D12_DEPTH_STENCIL_VIEW_DESC);
+
+impl DepthStencilViewDesc {
+    fn new(format: Format, dimension: d3d12::D3D12_DSV_DIMENSION) -> Self {
+        DepthStencilViewDesc(d3d12::D3D12_DEPTH_STENCIL_VIEW_DESC {
+            Format: format as _,
+            ViewDimension: dimension,
+            Flags: d3d12::D3D12_DSV_FLAG_NONE,
+            ..unsafe { mem::zeroed() }
+        })
+    }
+
+    pub fn texture_1d(format: Format, mip_slice: u32) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_DSV_DIMENSION_TEXTURE1D);
+        *unsafe { desc.0.u.Texture1D_mut() } = d3d12::D3D12_TEX1D_DSV {
+            MipSlice: mip_slice,
+        };
+        desc
+    }
+
+    pub fn texture_1d_array(format: Format, mip_slice: u32, array: Range<u32>) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_DSV_DIMENSION_TEXTURE1DARRAY);
+        *unsafe { desc.0.u.Texture1DArray_mut() } = d3d12::D3D12_TEX1D_ARRAY_DSV {
+            MipSlice: mip_slice,
+            FirstArraySlice: array.start,
+            ArraySize: array.end - array.start,
+        };
+        desc
+    }
+
+    pub fn texture_2d(format: Format, mip_slice: u32) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_DSV_DIMENSION_TEXTURE2D);
+        *unsafe { desc.0.u.Texture2D_mut() } = d3d12::D3D12_TEX2D_DSV {
+            MipSlice: mip_slice,
+        };
+        desc
+    }
+
+    pub fn texture_2d_array(format: Format, mip_slice: u32, array: Range<u32>) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_DSV_DIMENSION_TEXTURE2DARRAY);
+        *unsafe { desc.0.u.Texture2DArray_mut() } = d3d12::D3D12_TEX2D_ARRAY_DSV {
+            MipSlice: mip_slice,
+            FirstArraySlice: array.start,
+            ArraySize: array.end - array.start,
+        };
+        desc
+    }
+
+    pub fn texture_2d_ms(format: Format) -> Self {
+        Self::new(format, d3d12::D3D12_DSV_DIMENSION_TEXTURE2DMS)
+    }
+
+    pub fn texture_2d_ms_array(format: Format, array: Range<u32>) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_DSV_DIMENSION_TEXTURE2DMSARRAY);
+        *unsafe { desc.0.u.Texture2DMSArray_mut() } = d3d12::D3D12_TEX2DMS_ARRAY_DSV {
+            FirstArraySlice: array.start,
+            ArraySize: array.end - array.start,
+        };
+        desc
+    }
+
+    /// Read-only views can be bound while the resource is in a depth read state.
+    pub fn with_flags(mut self, flags: DepthStencilViewFlags) -> Self {
+        self.0.Flags = flags.bits();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn component_mapping() {
+        // `D3D12_DEFAULT_SHADER_4_COMPONENT_MAPPING`, which `winapi` leaves out.
+        assert_eq!(ComponentMapping::IDENTITY.encode(), 0x1688);
+        assert_eq!(ComponentMapping::default(), ComponentMapping::IDENTITY);
+        let desc = ShaderResourceViewDesc::texture_2d(Format::R8G8B8A8Unorm, 0, 1, 0);
+        assert_eq!(desc.0.Shader4ComponentMapping, 0x1688);
+
+        let swizzle = ComponentMapping([
+            ShaderComponent::One,
+            ShaderComponent::Zero,
+            ShaderComponent::Component0,
+            ShaderComponent::Component3,
+        ]);
+        assert_eq!(swizzle.encode(), 5 | 4 << 3 | 3 << 9 | 1 << 12);
+    }
+
+    #[test]
+    fn raytracing_acceleration_structure() {
+        let desc = ShaderResourceViewDesc::raytracing_acceleration_structure(0x1234_5678_9abc);
+        assert!(desc.is_acceleration_structure());
+        assert_eq!(desc.0.Format, Format::Unknown as u32);
+        assert_eq!(desc.0.ViewDimension, 11);
+        // Shares its offset with the first element of the other views.
+        assert_eq!(unsafe { desc.0.u.Buffer().FirstElement }, 0x1234_5678_9abc);
+        assert!(!ShaderResourceViewDesc::raw_buffer(0..4).is_acceleration_structure());
+    }
+
+    #[test]
+    fn constant_buffer() {
+        let desc = ConstantBufferViewDesc::new(0x1_0100, 512);
+        assert_eq!(desc.0.BufferLocation, 0x1_0100);
+        assert_eq!(desc.0.SizeInBytes, 512);
+    }
+
+    #[test]
+    #[should_panic(expected = "misaligned constant buffer location")]
+    fn misaligned_constant_buffer_location() {
+        ConstantBufferViewDesc::new(0x1_0080, 256);
+    }
+
+    #[test]
+    #[should_panic(expected = "misaligned constant buffer size")]
+    fn misaligned_constant_buffer_size() {
+        ConstantBufferViewDesc::new(0x1_0000, 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "misaligned UAV counter offset")]
+    fn misaligned_counter() {
+        UnorderedAccessViewDesc::structured_buffer(0..16, 16, 2048);
+    }
+}